@@ -0,0 +1,79 @@
+//! Compare the time taken to print and diff files with different options.
+//!
+//! The files default to test binaries that are too small for the times to mean
+//! much, so pass larger files on the command line:
+//!
+//! ```text
+//! cargo bench -p ddbug -- <file-a> <file-b>
+//! ```
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: usize = 5;
+
+fn main() {
+    let mut paths = std::env::args().skip(1).filter(|arg| !arg.starts_with('-'));
+    let path_a = paths.next().unwrap_or_else(|| "tests/bin/diff1".into());
+    let path_b = paths.next().unwrap_or_else(|| "tests/bin/diff2".into());
+    let file_a = ddbug::File::parse(path_a.clone()).unwrap();
+    let file_b = ddbug::File::parse(path_b.clone()).unwrap();
+
+    let mut options = ddbug::Options {
+        category_type: true,
+        category_function: true,
+        category_variable: true,
+        ..Default::default()
+    };
+    ddbug::assign_ids(file_a.file(), &options);
+
+    // Printers write their output in many small pieces.
+    bench(&format!("print {} unbuffered", path_a), || {
+        let mut w = null();
+        print(file_a.file(), &mut w, &options);
+    });
+    bench(&format!("print {} buffered", path_a), || {
+        let mut w = BufWriter::new(null());
+        print(file_a.file(), &mut w, &options);
+    });
+
+    ddbug::assign_merged_ids(file_a.file(), file_b.file(), &options);
+    for threads in [1, 0] {
+        options.diff_threads = threads;
+        bench(
+            &format!("diff {} {} threads={}", path_a, path_b, threads),
+            || {
+                let mut w = BufWriter::new(null());
+                let mut printer = ddbug::TextPrinter::new(&mut w, &options);
+                ddbug::diff(&mut printer, file_a.file(), file_b.file(), &options).unwrap();
+                ddbug::Printer::flush(&mut printer).unwrap();
+            },
+        );
+    }
+}
+
+fn null() -> fs::File {
+    fs::OpenOptions::new()
+        .write(true)
+        .open("/dev/null")
+        .unwrap()
+}
+
+fn print(file: &ddbug::File, w: &mut dyn Write, options: &ddbug::Options) {
+    let mut printer = ddbug::TextPrinter::new(w, options);
+    ddbug::print(file, &mut printer, options).unwrap();
+    ddbug::Printer::flush(&mut printer).unwrap();
+}
+
+// Print the median time of several runs of `f`.
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    let mut times: Vec<Duration> = (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    println!("{}: {:?}", name, times[ITERATIONS / 2]);
+}
@@ -76,11 +76,18 @@ impl<'code> Code<'code> {
         calls(self, range).unwrap_or(Vec::new())
     }
 
+    pub(crate) fn instruction_count(&self, range: Range) -> Option<usize> {
+        let bytes = self.range(range)?;
+        let cs = Capstone::new_raw(self.arch, self.mode, capstone::NO_EXTRA_MODE, None).ok()?;
+        let insns = cs.disasm_all(bytes, range.begin).ok()?;
+        Some(insns.len())
+    }
+
     pub(crate) fn disassembler(&self) -> Option<Disassembler> {
         Disassembler::new(self.arch, self.mode)
     }
 
-    fn range(&self, range: Range) -> Option<&'code [u8]> {
+    pub(crate) fn range(&self, range: Range) -> Option<&'code [u8]> {
         for region in &self.regions {
             if range.begin >= region.address
                 && range.end <= region.address + region.code.len() as u64
@@ -266,6 +273,10 @@ impl<'a> Instruction<'a> {
                                     write!(w, "+{:x}", imm - range.begin)?;
                                     continue;
                                 }
+                                if is_jump(&detail) || is_call(&detail) {
+                                    w.address(imm)?;
+                                    continue;
+                                }
                             }
                         } else {
                             debug!("operand count mismatch {:x}", self.insn.address());
@@ -305,19 +316,23 @@ impl<'a> Instruction<'a> {
                 // TODO: lookup variables too
                 if let Some(function) = state.hash().functions_by_address.get(&imm) {
                     state.instruction(None, "", |w, _hash| {
-                        write!(w, "0x{:x} = ", imm)?;
+                        w.address(imm)?;
+                        write!(w, " = ")?;
                         print::function::print_ref(function, w)
                     })?;
                 } else if let Some(symbol) = code.plt(imm) {
                     state.instruction(None, "", |w, _hash| {
                         // TODO: link to symbol
-                        write!(w, "0x{:x} = {}@plt", imm, symbol)?;
+                        w.address(imm)?;
+                        write!(w, " = {}@plt", symbol)?;
                         Ok(())
                     })?;
                 } else if let Some(symbol) = code.relocation(imm) {
                     state.instruction(None, "", |w, _hash| {
                         // TODO: link to symbol
-                        write!(w, "[0x{:x}] = {}", imm, symbol)?;
+                        write!(w, "[")?;
+                        w.address(imm)?;
+                        write!(w, "] = {}", symbol)?;
                         Ok(())
                     })?;
                 }
@@ -415,7 +430,8 @@ impl<'a> Instruction<'a> {
                     })?;
                 } else if let Some(value) = code.read_mem(address, size) {
                     state.instruction(None, "", |w, hash| {
-                        write!(w, "[ip + 0x{:x}] = 0x{:x}", offset, value)?;
+                        write!(w, "[ip + 0x{:x}] = ", offset)?;
+                        w.address(value)?;
                         if let Some(function) = hash.functions_by_address.get(&value) {
                             write!(w, " = ")?;
                             print::function::print_ref(function, w)?;
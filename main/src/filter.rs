@@ -1,9 +1,10 @@
 use std::cmp;
 use std::collections::HashSet;
+use std::ptr;
 
 use parser::{
-    BaseType, EnumerationType, File, FileHash, Function, StructType, Type, TypeDef, TypeKind,
-    TypeOffset, UnionType, Unit, UnspecifiedType, Variable,
+    BaseType, EnumerationType, File, FileHash, Function, Namespace, StructType, Type, TypeDef,
+    TypeKind, TypeOffset, UnionType, Unit, UnspecifiedType, Variable,
 };
 
 use crate::Options;
@@ -29,10 +30,70 @@ pub(crate) fn enumerate_and_filter_units<'input, 'file>(
         .collect()
 }
 
+/// Call `f` for each function that matches the filter options.
+///
+/// This is the same as `File::for_each_function`, but skips the functions that
+/// are not printed.
+pub fn for_each_function<'input, F>(file: &File<'input>, options: &Options, mut f: F)
+where
+    F: FnMut(&Unit<'input>, &Function<'input>),
+{
+    file.for_each_function(|unit, function| {
+        if filter_unit(unit, options) && filter_function(function, options) {
+            f(unit, function);
+        }
+    });
+}
+
+/// Call `f` for each type that matches the filter options.
+///
+/// This is the same as `File::for_each_type`, but skips the types that are not
+/// printed. `hash` must have been created for `file`.
+pub fn for_each_type<'input, F>(file: &File<'input>, hash: &FileHash, options: &Options, mut f: F)
+where
+    F: FnMut(&Unit<'input>, &Type<'input>),
+{
+    // The unit filter and inline types only change when the unit does.
+    let mut current: Option<(*const Unit, Option<HashSet<TypeOffset>>)> = None;
+    file.for_each_type(|unit, ty| {
+        let inline_types = match current {
+            Some((ptr, ref inline_types)) if ptr::eq(ptr, unit) => inline_types,
+            _ => {
+                let inline_types = filter_unit(unit, options).then(|| inline_types(unit, hash));
+                &current.insert((unit, inline_types)).1
+            }
+        };
+        if let Some(inline_types) = inline_types {
+            if filter_type(ty, hash, options, false, inline_types) {
+                f(unit, ty);
+            }
+        }
+    });
+}
+
+/// Call `f` for each variable that matches the filter options.
+///
+/// This is the same as `File::for_each_variable`, but skips the variables that
+/// are not printed. `hash` must have been created for `file`.
+pub fn for_each_variable<'input, F>(
+    file: &File<'input>,
+    hash: &FileHash,
+    options: &Options,
+    mut f: F,
+) where
+    F: FnMut(&Unit<'input>, &Variable<'input>),
+{
+    file.for_each_variable(|unit, variable| {
+        if filter_unit(unit, options) && filter_variable(variable, hash, options) {
+            f(unit, variable);
+        }
+    });
+}
+
 /// Return true if this unit matches the filter options.
 fn filter_unit(unit: &Unit, options: &Options) -> bool {
     if let Some(filter) = options.filter_unit.as_ref() {
-        let (prefix, suffix) = options.prefix_map(unit.name().unwrap_or(""));
+        let (prefix, suffix) = options.prefix_map(options.unit_name(unit).unwrap_or(""));
         let iter = prefix.bytes().chain(suffix.bytes());
         iter.cmp(filter.bytes()) == cmp::Ordering::Equal
     } else {
@@ -72,7 +133,7 @@ pub(crate) fn filter_types<'input, 'unit>(
     let inline_types = inline_types(unit, hash);
     unit.types()
         .iter()
-        .filter(|a| filter_type(a, options, diff, &inline_types))
+        .filter(|a| filter_type(a, hash, options, diff, &inline_types))
         .collect()
 }
 
@@ -86,7 +147,7 @@ pub(crate) fn enumerate_and_filter_types<'input, 'unit>(
     unit.types()
         .iter()
         .enumerate()
-        .filter(|a| filter_type(a.1, options, diff, &inline_types))
+        .filter(|a| filter_type(a.1, hash, options, diff, &inline_types))
         .collect()
 }
 
@@ -113,22 +174,24 @@ pub(crate) fn enumerate_and_filter_functions<'input, 'unit>(
 
 pub(crate) fn filter_variables<'input, 'unit>(
     unit: &'unit Unit<'input>,
+    hash: &FileHash,
     options: &Options,
 ) -> Vec<&'unit Variable<'input>> {
     unit.variables()
         .iter()
-        .filter(|a| filter_variable(a, options))
+        .filter(|a| filter_variable(a, hash, options))
         .collect()
 }
 
 pub(crate) fn enumerate_and_filter_variables<'input, 'unit>(
     unit: &'unit Unit<'input>,
+    hash: &FileHash,
     options: &Options,
 ) -> Vec<(usize, &'unit Variable<'input>)> {
     unit.variables()
         .iter()
         .enumerate()
-        .filter(|a| filter_variable(a.1, options))
+        .filter(|a| filter_variable(a.1, hash, options))
         .collect()
 }
 
@@ -139,21 +202,28 @@ fn filter_function(f: &Function, options: &Options) -> bool {
         // TODO: make this configurable?
         return false;
     }
-    options.filter_name(f.name())
+    options.filter_name(f.namespace(), f.name())
         && options.filter_namespace(f.namespace())
+        && options.filter_glob(f.namespace(), f.name())
         && options.filter_function_inline(f.is_inline())
 }
 
-fn filter_variable(v: &Variable, options: &Options) -> bool {
+fn filter_variable(v: &Variable, hash: &FileHash, options: &Options) -> bool {
     if !v.is_declaration() && v.address().is_none() {
         // TODO: make this configurable?
         return false;
     }
-    options.filter_name(v.name()) && options.filter_namespace(v.namespace())
+    if !options.show_declarations && hash.has_variable_definition(v) {
+        return false;
+    }
+    options.filter_name(v.namespace(), v.name())
+        && options.filter_namespace(v.namespace())
+        && options.filter_glob(v.namespace(), v.name())
 }
 
 fn filter_type(
     ty: &Type,
+    hash: &FileHash,
     options: &Options,
     diff: bool,
     inline_types: &HashSet<TypeOffset>,
@@ -171,7 +241,11 @@ fn filter_type(
         | TypeKind::Function(..)
         | TypeKind::PointerToMember(..)
         | TypeKind::Modifier(..)
-        | TypeKind::Subrange(..) => options.filter_name.is_none(),
+        | TypeKind::Subrange(..) => {
+            options.filter_name.is_none()
+                && options.filter_glob.is_none()
+                && options.filter_include.is_empty()
+        }
     } {
         return false;
     }
@@ -196,30 +270,202 @@ fn filter_type(
         | TypeKind::Modifier(..)
         | TypeKind::Subrange(..) => return false,
     }
+    // Filter out declarations that are redundant with a definition.
+    if !options.show_declarations && hash.has_type_definition(ty) {
+        return false;
+    }
     // Filter out inline types.
     ty.offset().is_some() && !inline_types.contains(&ty.offset())
 }
 
 fn filter_base(ty: &BaseType, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(None)
+    options.filter_name(None, ty.name())
+        && options.filter_namespace(None)
+        && options.filter_glob(None, ty.name())
 }
 
 fn filter_type_def(ty: &TypeDef, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(ty.namespace())
+    options.filter_name(ty.namespace(), ty.name())
+        && options.filter_namespace(ty.namespace())
+        && options.filter_glob(ty.namespace(), ty.name())
 }
 
 fn filter_struct(ty: &StructType, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(ty.namespace())
+    options.filter_name(ty.namespace(), ty.name())
+        && options.filter_namespace(ty.namespace())
+        && options.filter_glob(ty.namespace(), ty.name())
 }
 
 fn filter_union(ty: &UnionType, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(ty.namespace())
+    options.filter_name(ty.namespace(), ty.name())
+        && options.filter_namespace(ty.namespace())
+        && options.filter_glob(ty.namespace(), ty.name())
 }
 
 fn filter_enumeration(ty: &EnumerationType, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(ty.namespace())
+    options.filter_name(ty.namespace(), ty.name())
+        && options.filter_namespace(ty.namespace())
+        && options.filter_glob(ty.namespace(), ty.name())
 }
 
 fn filter_unspecified(ty: &UnspecifiedType, options: &Options) -> bool {
-    options.filter_name(ty.name()) && options.filter_namespace(ty.namespace())
+    options.filter_name(ty.namespace(), ty.name())
+        && options.filter_namespace(ty.namespace())
+        && options.filter_glob(ty.namespace(), ty.name())
+}
+
+/// The name of an entry including its namespaces, separated by `::`.
+///
+/// Anonymous names are given as `<anon>`.
+pub(crate) fn qualified_name(namespace: Option<&Namespace>, name: Option<&str>) -> String {
+    fn push_namespace(namespace: &Namespace, s: &mut String) {
+        if let Some(parent) = namespace.parent() {
+            push_namespace(parent, s);
+        }
+        s.push_str(namespace.name().unwrap_or("<anon>"));
+        s.push_str("::");
+    }
+
+    let mut s = String::new();
+    if let Some(namespace) = namespace {
+        push_namespace(namespace, &mut s);
+    }
+    s.push_str(name.unwrap_or("<anon>"));
+    s
+}
+
+/// Compare the qualified names of two entries for diff matching.
+///
+/// If `options.canonical_names` is set, then compiler generated discriminators
+/// are ignored. Otherwise this is the same as `Namespace::cmp_ns_and_name`.
+pub(crate) fn cmp_ns_and_name(
+    ns_a: Option<&Namespace>,
+    name_a: Option<&str>,
+    ns_b: Option<&Namespace>,
+    name_b: Option<&str>,
+    options: &Options,
+) -> cmp::Ordering {
+    if options.canonical_names {
+        let a = canonical_name(&qualified_name(ns_a, name_a));
+        let b = canonical_name(&qualified_name(ns_b, name_b));
+        a.cmp(&b)
+    } else {
+        Namespace::cmp_ns_and_name(ns_a, name_a, ns_b, name_b)
+    }
+}
+
+/// Remove compiler generated discriminators from a qualified name.
+///
+/// Closures and lambdas are numbered in the order they appear in their parent,
+/// so `{closure#1}` becomes `{closure}` and `{lambda(int)#2}` becomes
+/// `{lambda(int)}`. Anonymous namespaces are given as `<anon>`.
+fn canonical_name(name: &str) -> String {
+    let name = name
+        .split("::")
+        .map(|component| {
+            if component == "(anonymous namespace)" || component.starts_with("_GLOBAL__N_") {
+                "<anon>"
+            } else {
+                component
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("::");
+
+    let mut s = String::with_capacity(name.len());
+    let mut depth = 0;
+    let mut chars = name.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '#' if depth > 0 => {
+                let rest = &name[index + 1..];
+                let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+                if digits != 0 && rest[digits..].starts_with('}') {
+                    chars.nth(digits - 1);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        s.push(c);
+    }
+    s
+}
+
+/// Replace the generic arguments in a qualified name with `_`.
+///
+/// For example, `alloc::vec::Vec<u8>::push<u8>` becomes `alloc::vec::Vec<_>::push<_>`.
+/// Returns `None` if the name has no generic arguments.
+pub(crate) fn erase_generics(name: &str) -> Option<String> {
+    let mut s = String::with_capacity(name.len());
+    let mut depth = 0;
+    let mut erased = false;
+    let mut prev = None;
+    for c in name.chars() {
+        match c {
+            // Don't mistake `operator<` and similar for generic arguments.
+            '<' if depth == 0 && (s.ends_with("operator") || s.ends_with("operator<")) => {
+                s.push(c);
+            }
+            '<' => {
+                if depth == 0 {
+                    s.push_str("<_");
+                    erased = true;
+                }
+                depth += 1;
+            }
+            // Ignore the `->` in function pointer types.
+            '>' if depth > 0 && prev != Some('-') => {
+                depth -= 1;
+                if depth == 0 {
+                    s.push(c);
+                }
+            }
+            _ if depth > 0 => {}
+            _ => s.push(c),
+        }
+        prev = Some(c);
+    }
+    if erased {
+        Some(s)
+    } else {
+        None
+    }
+}
+
+/// Match `name` against a shell style glob.
+///
+/// `*` matches any sequence of characters, including `::`, and `?` matches
+/// a single character.
+pub(crate) fn glob_match(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut g, mut n) = (0, 0);
+    // The position of the last `*` in the glob, and the position in the name
+    // that it currently matches up to.
+    let mut star = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_g, star_n)) => {
+                    // Let the `*` match one more character.
+                    g = star_g + 1;
+                    n = star_n + 1;
+                    star = Some((star_g, n));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
 }
@@ -9,18 +9,23 @@
 #[macro_use]
 extern crate log;
 
-use parser::Namespace;
+use parser::{Namespace, Source, Unit};
 
 pub use parser::{Error, File, Result};
 
+// Re-export the debuginfo model so that library users can analyze it directly.
+pub use parser;
+
 mod code;
 mod filter;
+pub use self::filter::{for_each_function, for_each_type, for_each_variable};
 
 mod print;
 pub use self::print::file::{
-    assign_ids, assign_merged_ids, diff, diff_id, parent_id, print, print_id,
+    abi_check, assign_ids, assign_merged_ids, compare, diff, diff_id, diff_symbols, parent_id,
+    print, print_archive, print_id,
 };
-pub use self::print::{DiffPrefix, HtmlPrinter, Id, Printer, TextPrinter};
+pub use self::print::{DiffPrefix, HtmlPrinter, Id, MarkdownPrinter, Printer, TextPrinter};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Sort {
@@ -35,20 +40,46 @@ impl Default for Sort {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    File,
+    Unit,
+    Type,
+    Function,
+    Variable,
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Options {
     pub print_source: bool,
     pub print_file_address: bool,
+    pub debug_sizes: bool,
     pub print_unit_address: bool,
     pub print_function_calls: bool,
     pub print_function_instructions: bool,
+    pub print_function_instruction_count: bool,
+    pub print_function_cfi: bool,
+    pub merge_sections: bool,
+    pub print_function_signature: bool,
     pub print_function_variables: bool,
     pub print_function_stack_frame: bool,
     pub print_inlined_function_parameters: bool,
     pub print_variable_locations: bool,
+    pub print_variable_bytes: bool,
+    pub print_variable_section: bool,
+    pub print_offset: bool,
     pub inline_depth: usize,
+    pub max_depth: usize,
+    pub member_declaration_order: bool,
+    pub decimal_addresses: bool,
+    pub load_bias: u64,
+    pub human_sizes: bool,
+    /// Print the sizes of functions and variables as a percentage of the total size
+    /// of all functions or variables in the file.
+    pub percent: bool,
     pub html: bool,
     pub http: bool,
+    pub markdown: bool,
 
     pub category_file: bool,
     pub category_unit: bool,
@@ -58,15 +89,62 @@ pub struct Options {
 
     pub filter_function_inline: Option<bool>,
     pub filter_name: Option<String>,
+    pub filter_glob: Option<String>,
+    pub filter_include: Vec<String>,
+    pub filter_exclude: Vec<String>,
     pub filter_namespace: Vec<String>,
     pub filter_unit: Option<String>,
+    pub show_declarations: bool,
 
     pub sort: Sort,
+    pub group_by_namespace: bool,
+    pub top: Option<usize>,
+    pub by_file: bool,
+    pub group_generics: bool,
+    pub coverage: bool,
+    pub icf_report: bool,
+    pub inline_report: bool,
+    pub dump: bool,
+    pub layout: bool,
+    /// Print a table of contents with the line number of each unit before the units.
+    pub toc: bool,
+    pub flamegraph: bool,
+    pub addresses: Vec<u64>,
 
+    pub diff_summary: bool,
+    /// Only print the differences that break the ABI.
+    pub abi_check: bool,
+    pub match_renamed_functions: bool,
+    /// The number of threads to use for diffing units.
+    ///
+    /// If this is 0, then the available parallelism is used.
+    pub diff_threads: usize,
+    /// The minimum similarity, as a percentage, for two items in a list to be
+    /// shown as a single changed item instead of a deletion and an addition.
+    ///
+    /// The similarity is 100% for items that are equal, and 0% for items whose
+    /// difference costs as much as deleting one and adding the other. The default
+    /// of 0 pairs any items that have something in common, and 100 pairs only
+    /// items that are equal.
+    pub similarity_threshold: u32,
+    /// The number of bytes that an item's size must change by to be significant.
+    ///
+    /// If either this or `grow_threshold_percent` is set, then changed items
+    /// whose size changed by less than the thresholds are not printed, and
+    /// items whose size grew by more than either threshold are marked.
+    pub grow_threshold_bytes: Option<u64>,
+    /// The percentage that an item's size must change by to be significant.
+    pub grow_threshold_percent: Option<u64>,
+    /// Only print items that grew by more than the grow thresholds.
+    ///
+    /// Added items are considered to have grown from a size of 0.
+    pub grow_only: bool,
+    pub canonical_names: bool,
     pub ignore_added: bool,
     pub ignore_deleted: bool,
     pub ignore_function_address: bool,
     pub ignore_function_size: bool,
+    pub ignore_function_frame_size: bool,
     pub ignore_function_inline: bool,
     pub ignore_function_linkage_name: bool,
     pub ignore_function_symbol_name: bool,
@@ -74,6 +152,10 @@ pub struct Options {
     pub ignore_variable_linkage_name: bool,
     pub ignore_variable_symbol_name: bool,
     pub prefix_map: Vec<(String, String)>,
+    /// Path prefixes to remove from the start of unit names and source paths.
+    pub strip_prefix: Vec<String>,
+    /// Print unit names and source paths relative to the unit's compilation directory.
+    pub relative: bool,
 }
 
 impl Options {
@@ -87,12 +169,55 @@ impl Options {
         self
     }
 
+    /// Print only the given category, and disable all others.
+    pub fn only(&mut self, category: Category) -> &mut Self {
+        self.category_file = category == Category::File;
+        self.category_unit = category == Category::Unit;
+        self.category_type = category == Category::Type;
+        self.category_function = category == Category::Function;
+        self.category_variable = category == Category::Variable;
+        self
+    }
+
     fn filter_function_inline(&self, inline: bool) -> bool {
         self.filter_function_inline.is_none() || self.filter_function_inline == Some(inline)
     }
 
-    fn filter_name(&self, name: Option<&str>) -> bool {
-        self.filter_name.is_none() || self.filter_name.as_ref().map(String::as_ref) == name
+    // Match `filter_name` against the name, or against the qualified name if
+    // `filter_name` contains a namespace separator.
+    fn filter_name(&self, namespace: Option<&Namespace>, name: Option<&str>) -> bool {
+        match self.filter_name {
+            None => true,
+            Some(ref filter_name) if filter_name.contains("::") => {
+                *filter_name == filter::qualified_name(namespace, name)
+            }
+            Some(ref filter_name) => Some(filter_name.as_str()) == name,
+        }
+    }
+
+    // Match the qualified name against `filter_glob`, `filter_include` and `filter_exclude`.
+    fn filter_glob(&self, namespace: Option<&Namespace>, name: Option<&str>) -> bool {
+        if self.filter_glob.is_none()
+            && self.filter_include.is_empty()
+            && self.filter_exclude.is_empty()
+        {
+            return true;
+        }
+        let name = filter::qualified_name(namespace, name);
+        if let Some(ref glob) = self.filter_glob {
+            if !filter::glob_match(glob, &name) {
+                return false;
+            }
+        }
+        (self.filter_include.is_empty()
+            || self
+                .filter_include
+                .iter()
+                .any(|glob| filter::glob_match(glob, &name)))
+            && !self
+                .filter_exclude
+                .iter()
+                .any(|glob| filter::glob_match(glob, &name))
     }
 
     fn filter_namespace(&self, namespace: Option<&Namespace>) -> bool {
@@ -104,6 +229,34 @@ impl Options {
         }
     }
 
+    // Remove the longest matching prefix from a unit name or source path.
+    //
+    // The prefixes are those in `strip_prefix`, and also `dir` if `relative` is set.
+    fn strip_path<'path>(&self, path: &'path str, dir: Option<&str>) -> &'path str {
+        fn strip<'path>(path: &'path str, prefix: &str) -> Option<&'path str> {
+            // Only match whole path components.
+            let rest = path.strip_prefix(prefix.trim_end_matches('/'))?;
+            rest.strip_prefix('/').filter(|rest| !rest.is_empty())
+        }
+
+        let dir = if self.relative { dir } else { None };
+        dir.into_iter()
+            .chain(self.strip_prefix.iter().map(String::as_str))
+            .filter_map(|prefix| strip(path, prefix))
+            .min_by_key(|rest| rest.len())
+            .unwrap_or(path)
+    }
+
+    fn unit_name<'unit>(&self, unit: &'unit Unit) -> Option<&'unit str> {
+        unit.name().map(|name| self.strip_path(name, unit.dir()))
+    }
+
+    fn source_path(&self, source: &Source, unit: &Unit) -> Option<String> {
+        source
+            .path(unit)
+            .map(|path| self.strip_path(&path, unit.dir()).to_string())
+    }
+
     fn prefix_map<'name>(&self, name: &'name str) -> (&str, &'name str) {
         for (old, new) in &self.prefix_map {
             if name.starts_with(&*old) {
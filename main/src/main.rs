@@ -15,18 +15,21 @@ extern crate clap;
 extern crate log;
 
 use std::io::{BufWriter, Write};
+use std::path::PathBuf;
 
 use warp::Filter;
 
 // Mode
 const OPT_FILE: &str = "file";
 const OPT_DIFF: &str = "diff";
+const OPT_DIFF_SYMBOLS: &str = "diff-symbols";
 
 // Print format
 const OPT_OUTPUT: &str = "format";
 const OPT_OUTPUT_TEXT: &str = "text";
 const OPT_OUTPUT_HTML: &str = "html";
 const OPT_OUTPUT_HTTP: &str = "http";
+const OPT_OUTPUT_MARKDOWN: &str = "markdown";
 
 // Print categories
 const OPT_CATEGORY: &str = "category";
@@ -35,6 +38,7 @@ const OPT_CATEGORY_UNIT: &str = "unit";
 const OPT_CATEGORY_TYPE: &str = "type";
 const OPT_CATEGORY_FUNCTION: &str = "function";
 const OPT_CATEGORY_VARIABLE: &str = "variable";
+const OPT_ONLY: &str = "only";
 
 // Print fields
 const OPT_PRINT: &str = "print";
@@ -45,28 +49,73 @@ const OPT_PRINT_FILE_ADDRESS: &str = "file-address";
 const OPT_PRINT_UNIT_ADDRESS: &str = "unit-address";
 const OPT_PRINT_FUNCTION_CALLS: &str = "function-calls";
 const OPT_PRINT_FUNCTION_INSTRUCTIONS: &str = "function-instructions";
+const OPT_PRINT_FUNCTION_INSTRUCTION_COUNT: &str = "function-instruction-count";
+const OPT_PRINT_FUNCTION_SIGNATURE: &str = "function-signature";
 const OPT_PRINT_FUNCTION_VARIABLES: &str = "function-variables";
 const OPT_PRINT_FUNCTION_STACK_FRAME: &str = "function-stack-frame";
 const OPT_PRINT_INLINED_FUNCTION_PARAMETERS: &str = "inlined-function-parameters";
 const OPT_PRINT_VARIABLE_LOCATIONS: &str = "variable-locations";
+const OPT_PRINT_VARIABLE_BYTES: &str = "variable-bytes";
+const OPT_PRINT_VARIABLE_SECTION: &str = "variable-section";
+const OPT_PRINT_OFFSET: &str = "offset";
 
 // Print parameters
 const OPT_INLINE_DEPTH: &str = "inline-depth";
+const OPT_MAX_DEPTH: &str = "max-depth";
+const OPT_NO_CHILDREN: &str = "no-children";
+const OPT_DECIMAL_ADDRESSES: &str = "decimal-addresses";
+const OPT_LOAD_BIAS: &str = "load-bias";
+const OPT_HUMAN_SIZES: &str = "human-sizes";
+const OPT_PERCENT: &str = "percent";
+const OPT_STRIP_PREFIX: &str = "strip-prefix";
+const OPT_RELATIVE: &str = "relative";
+const OPT_DEBUG_SIZES: &str = "debug-sizes";
+const OPT_MERGE_SECTIONS: &str = "merge-sections";
+const OPT_CFI: &str = "cfi";
 
 // Filters
 const OPT_FILTER: &str = "filter";
 const OPT_FILTER_INLINE: &str = "inline";
 const OPT_FILTER_FUNCTION_INLINE: &str = "function-inline";
 const OPT_FILTER_NAME: &str = "name";
+const OPT_FILTER_GLOB: &str = "glob";
 const OPT_FILTER_NAMESPACE: &str = "namespace";
 const OPT_FILTER_UNIT: &str = "unit";
+const OPT_INCLUDE: &str = "include";
+const OPT_EXCLUDE: &str = "exclude";
+const OPT_SHOW_DECLARATIONS: &str = "show-declarations";
 
 // Sorting
 const OPT_SORT: &str = "sort";
 const OPT_SORT_SIZE: &str = "size";
 const OPT_SORT_NAME: &str = "name";
+const OPT_MEMBER_ORDER: &str = "member-order";
+const OPT_MEMBER_ORDER_OFFSET: &str = "offset";
+const OPT_MEMBER_ORDER_DECLARATION: &str = "declaration";
+
+// Grouping
+const OPT_BY_NAMESPACE: &str = "by-namespace";
+const OPT_TOP: &str = "top";
+const OPT_BY_FILE: &str = "by-file";
+const OPT_GROUP_GENERICS: &str = "group-generics";
+const OPT_COVERAGE: &str = "coverage";
+const OPT_ICF_REPORT: &str = "icf-report";
+const OPT_INLINE_REPORT: &str = "inline-report";
+const OPT_DUMP: &str = "dump";
+const OPT_LAYOUT: &str = "layout";
+const OPT_FLAMEGRAPH: &str = "flamegraph";
+const OPT_ADDRESSES: &str = "addresses";
+const OPT_TOC: &str = "toc";
 
 // Diff options
+const OPT_NO_SUMMARY: &str = "no-summary";
+const OPT_MATCH_RENAMED: &str = "match-renamed";
+const OPT_SIMILARITY_THRESHOLD: &str = "similarity-threshold";
+const OPT_GROW_THRESHOLD: &str = "grow-threshold";
+const OPT_GROW_ONLY: &str = "grow-only";
+const OPT_ABI_CHECK: &str = "abi-check";
+const OPT_THREADS: &str = "threads";
+const OPT_CANONICAL_NAMES: &str = "canonical-names";
 const OPT_IGNORE: &str = "ignore";
 const OPT_IGNORE_ADDED: &str = "added";
 const OPT_IGNORE_DELETED: &str = "deleted";
@@ -75,14 +124,25 @@ const OPT_IGNORE_LINKAGE_NAME: &str = "linkage-name";
 const OPT_IGNORE_SYMBOL_NAME: &str = "symbol-name";
 const OPT_IGNORE_FUNCTION_ADDRESS: &str = "function-address";
 const OPT_IGNORE_FUNCTION_SIZE: &str = "function-size";
+const OPT_IGNORE_FUNCTION_FRAME_SIZE: &str = "function-frame-size";
 const OPT_IGNORE_FUNCTION_INLINE: &str = "function-inline";
 const OPT_IGNORE_FUNCTION_SYMBOL_NAME: &str = "function-symbol-name";
 const OPT_IGNORE_VARIABLE_ADDRESS: &str = "variable-address";
 const OPT_IGNORE_VARIABLE_SYMBOL_NAME: &str = "variable-symbol-name";
 const OPT_PREFIX_MAP: &str = "prefix-map";
+const OPT_EXIT_CODE: &str = "exit-code";
+const OPT_STRICT: &str = "strict";
+const OPT_PROGRESS: &str = "progress";
+const OPT_CACHE_DIR: &str = "cache-dir";
+const OPT_SYMTAB: &str = "symtab";
+const OPT_SYMTAB_UNIT: &str = "unit";
+const OPT_SYMTAB_RANGES: &str = "ranges";
+const OPT_SYMTAB_NONE: &str = "none";
+const OPT_SECTIONS: &str = "sections";
 
 fn main() {
-    env_logger::init();
+    // Show parse warnings by default.
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
 
     let matches = clap::App::new("ddbug")
         .version(crate_version!())
@@ -99,8 +159,22 @@ fn main() {
             clap::Arg::with_name(OPT_DIFF)
                 .short("d")
                 .long(OPT_DIFF)
-                .help("Print difference between two files")
-                .value_names(&["FILE", "FILE"]),
+                .help(
+                    "Print difference between two files, \
+                     or compare the sizes of entries in more than two files",
+                )
+                .value_name("FILE")
+                .multiple(true)
+                .min_values(2),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_DIFF_SYMBOLS)
+                .long(OPT_DIFF_SYMBOLS)
+                .help(
+                    "Print the functions and variables of a file that are described by \
+                     debuginfo but have no symbol, or have a symbol but no debuginfo",
+                )
+                .conflicts_with(OPT_DIFF),
         )
         .arg(
             clap::Arg::with_name(OPT_OUTPUT)
@@ -109,7 +183,12 @@ fn main() {
                 .help("Output format")
                 .takes_value(true)
                 .value_name("FORMAT")
-                .possible_values(&[OPT_OUTPUT_TEXT, OPT_OUTPUT_HTML, OPT_OUTPUT_HTTP]),
+                .possible_values(&[
+                    OPT_OUTPUT_TEXT,
+                    OPT_OUTPUT_HTML,
+                    OPT_OUTPUT_HTTP,
+                    OPT_OUTPUT_MARKDOWN,
+                ]),
         )
         .arg(
             clap::Arg::with_name(OPT_CATEGORY)
@@ -128,6 +207,20 @@ fn main() {
                     OPT_CATEGORY_VARIABLE,
                 ]),
         )
+        .arg(
+            clap::Arg::with_name(OPT_ONLY)
+                .long(OPT_ONLY)
+                .help("Print only entries of a single category")
+                .value_name("CATEGORY")
+                .conflicts_with(OPT_CATEGORY)
+                .possible_values(&[
+                    OPT_CATEGORY_FILE,
+                    OPT_CATEGORY_UNIT,
+                    OPT_CATEGORY_TYPE,
+                    OPT_CATEGORY_FUNCTION,
+                    OPT_CATEGORY_VARIABLE,
+                ]),
+        )
         .arg(
             clap::Arg::with_name(OPT_PRINT)
                 .short("p")
@@ -145,10 +238,15 @@ fn main() {
                     OPT_PRINT_UNIT_ADDRESS,
                     OPT_PRINT_FUNCTION_CALLS,
                     OPT_PRINT_FUNCTION_INSTRUCTIONS,
+                    OPT_PRINT_FUNCTION_INSTRUCTION_COUNT,
+                    OPT_PRINT_FUNCTION_SIGNATURE,
                     OPT_PRINT_FUNCTION_VARIABLES,
                     OPT_PRINT_FUNCTION_STACK_FRAME,
                     OPT_PRINT_INLINED_FUNCTION_PARAMETERS,
                     OPT_PRINT_VARIABLE_LOCATIONS,
+                    OPT_PRINT_VARIABLE_BYTES,
+                    OPT_PRINT_VARIABLE_SECTION,
+                    OPT_PRINT_OFFSET,
                 ]),
         )
         .arg(
@@ -157,6 +255,83 @@ fn main() {
                 .help("Depth of inlined function calls to print (defaults to 1, 0 to disable)")
                 .value_name("DEPTH"),
         )
+        .arg(
+            clap::Arg::with_name(OPT_MAX_DEPTH)
+                .long(OPT_MAX_DEPTH)
+                .help("Depth of nested types to print members for (defaults to 0, unlimited)")
+                .value_name("DEPTH"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_NO_CHILDREN)
+                .long(OPT_NO_CHILDREN)
+                .help("Don't print members of nested types (same as '--max-depth 1')")
+                .conflicts_with(OPT_MAX_DEPTH),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_DECIMAL_ADDRESSES)
+                .long(OPT_DECIMAL_ADDRESSES)
+                .help("Print addresses in decimal instead of hexadecimal"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_LOAD_BIAS)
+                .long(OPT_LOAD_BIAS)
+                .help(
+                    "Add a value to all printed addresses, such as the load address of a \
+                     position independent executable (in hexadecimal if prefixed by '0x')",
+                )
+                .value_name("ADDRESS"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_HUMAN_SIZES)
+                .long(OPT_HUMAN_SIZES)
+                .help("Print sizes in human readable units such as KiB"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_PERCENT)
+                .long(OPT_PERCENT)
+                .help(
+                    "Print the sizes of functions and variables as a percentage of the total \
+                     size of functions or variables",
+                ),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_STRIP_PREFIX)
+                .long(OPT_STRIP_PREFIX)
+                .help("Remove a path prefix from unit names and source paths")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("PATH"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_RELATIVE)
+                .long(OPT_RELATIVE)
+                .help(
+                    "Print unit names and source paths relative to the compilation directory \
+                     of the unit",
+                ),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_DEBUG_SIZES)
+                .long(OPT_DEBUG_SIZES)
+                .help("Print the total size of the debuginfo sections of the file"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_CFI)
+                .long(OPT_CFI)
+                .help(
+                    "Print a summary of the unwind information of functions: the CFA and \
+                     return address at entry, and the registers saved by the function",
+                ),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_MERGE_SECTIONS)
+                .long(OPT_MERGE_SECTIONS)
+                .help(
+                    "Merge sections with the same name, and print the merged sections \
+                     of all members of an archive",
+                ),
+        )
         .arg(
             clap::Arg::with_name(OPT_FILTER)
                 .short("f")
@@ -167,6 +342,29 @@ fn main() {
                 .require_delimiter(true)
                 .value_name("FILTER"),
         )
+        .arg(
+            clap::Arg::with_name(OPT_INCLUDE)
+                .long(OPT_INCLUDE)
+                .help("Print only entries whose name, including namespaces, matches a pattern")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("PATTERN"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_EXCLUDE)
+                .long(OPT_EXCLUDE)
+                .help("Don't print entries whose name, including namespaces, matches a pattern")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .value_name("PATTERN"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_SHOW_DECLARATIONS)
+                .long(OPT_SHOW_DECLARATIONS)
+                .help("Print declarations of types and variables that are defined elsewhere"),
+        )
         .arg(
             clap::Arg::with_name(OPT_SORT)
                 .short("s")
@@ -176,6 +374,233 @@ fn main() {
                 .value_name("KEY")
                 .possible_values(&[OPT_SORT_NAME, OPT_SORT_SIZE]),
         )
+        .arg(
+            clap::Arg::with_name(OPT_MEMBER_ORDER)
+                .long(OPT_MEMBER_ORDER)
+                .help(
+                    "Order of struct members: by offset with padding, \
+                     or in declaration order without padding",
+                )
+                .takes_value(true)
+                .value_name("ORDER")
+                .possible_values(&[OPT_MEMBER_ORDER_OFFSET, OPT_MEMBER_ORDER_DECLARATION])
+                .default_value(OPT_MEMBER_ORDER_OFFSET),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_BY_NAMESPACE)
+                .long(OPT_BY_NAMESPACE)
+                .help("Group entries by namespace"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_TOP)
+                .long(OPT_TOP)
+                .help(
+                    "Print only the N largest types, functions and variables, \
+                     and the N units with the most fragmented addresses",
+                )
+                .value_name("N")
+                .conflicts_with(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_BY_FILE)
+                .long(OPT_BY_FILE)
+                .help(
+                    "Print the size of functions and variables for each source file and directory",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_TOP),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_GROUP_GENERICS)
+                .long(OPT_GROUP_GENERICS)
+                .help(
+                    "Print the total size and count of the instantiations of each generic function \
+                     (limited to N groups by '--top N')",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_COVERAGE)
+                .long(OPT_COVERAGE)
+                .help(
+                    "Print the percentage of each executable section that is covered by \
+                     functions in the debuginfo",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_ICF_REPORT)
+                .long(OPT_ICF_REPORT)
+                .help(
+                    "Print groups of functions with identical code, and the size that could be \
+                     saved by folding them",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_INLINE_REPORT)
+                .long(OPT_INLINE_REPORT)
+                .help(
+                    "Print the functions that are inlined the most, with the number and total \
+                     size of their inlined instances (limited to N functions by '--top N')",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_DUMP)
+                .long(OPT_DUMP)
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Print the raw DWARF entries for the entries with the given name")
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT)
+                .conflicts_with(OPT_INLINE_REPORT),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_LAYOUT)
+                .long(OPT_LAYOUT)
+                .takes_value(true)
+                .value_name("NAME")
+                .help("Print a diagram of the member layout of the structs with the given name")
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT)
+                .conflicts_with(OPT_INLINE_REPORT)
+                .conflicts_with(OPT_DUMP),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_FLAMEGRAPH)
+                .long(OPT_FLAMEGRAPH)
+                .help(
+                    "Print the size of each function in the folded stack format used by \
+                     flamegraph tools, with the namespaces of the function as the stack",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT)
+                .conflicts_with(OPT_INLINE_REPORT)
+                .conflicts_with(OPT_DUMP)
+                .conflicts_with(OPT_LAYOUT),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_ADDRESSES)
+                .long(OPT_ADDRESSES)
+                .takes_value(true)
+                .multiple(true)
+                .require_delimiter(true)
+                .value_name("ADDRESS")
+                .help(
+                    "Print the function and source location for each address, including \
+                     inlined functions",
+                )
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT)
+                .conflicts_with(OPT_INLINE_REPORT)
+                .conflicts_with(OPT_DUMP)
+                .conflicts_with(OPT_LAYOUT)
+                .conflicts_with(OPT_FLAMEGRAPH),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_TOC)
+                .long(OPT_TOC)
+                .help("Print a table of contents with the line number of each unit")
+                .conflicts_with(OPT_DIFF)
+                .conflicts_with(OPT_TOP)
+                .conflicts_with(OPT_BY_FILE)
+                .conflicts_with(OPT_GROUP_GENERICS)
+                .conflicts_with(OPT_COVERAGE)
+                .conflicts_with(OPT_ICF_REPORT)
+                .conflicts_with(OPT_INLINE_REPORT)
+                .conflicts_with(OPT_DUMP)
+                .conflicts_with(OPT_LAYOUT)
+                .conflicts_with(OPT_FLAMEGRAPH)
+                .conflicts_with(OPT_ADDRESSES),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_NO_SUMMARY)
+                .long(OPT_NO_SUMMARY)
+                .help("Don't print the summary of changes at the end of a diff")
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_MATCH_RENAMED)
+                .long(OPT_MATCH_RENAMED)
+                .help("Match deleted and added functions that have the same size and code")
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_SIMILARITY_THRESHOLD)
+                .long(OPT_SIMILARITY_THRESHOLD)
+                .takes_value(true)
+                .value_name("PERCENT")
+                .help(
+                    "Print list items as deleted and added instead of changed unless they are \
+                     more than PERCENT similar [default: 0]",
+                )
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_GROW_THRESHOLD)
+                .long(OPT_GROW_THRESHOLD)
+                .takes_value(true)
+                .multiple(true)
+                .require_delimiter(true)
+                .value_name("BYTES|PERCENT%")
+                .help(
+                    "Hide changed items whose size changed by less than the given thresholds, \
+                     and mark items that grew by more",
+                )
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_GROW_ONLY)
+                .long(OPT_GROW_ONLY)
+                .help("Only print items that grew by more than the grow thresholds")
+                .requires(OPT_GROW_THRESHOLD),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_THREADS)
+                .long(OPT_THREADS)
+                .takes_value(true)
+                .value_name("N")
+                .help("Use N threads to diff units [default: the number of CPUs]")
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_ABI_CHECK)
+                .long(OPT_ABI_CHECK)
+                .help(
+                    "Only print differences that break the ABI, and exit with status 1 if there \
+                     are any, or 2 on error",
+                )
+                .requires(OPT_DIFF),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_CANONICAL_NAMES)
+                .long(OPT_CANONICAL_NAMES)
+                .help("Ignore lambda, closure and anonymous namespace discriminators when matching")
+                .requires(OPT_DIFF),
+        )
         .arg(
             clap::Arg::with_name(OPT_IGNORE)
                 .short("i")
@@ -194,6 +619,7 @@ fn main() {
                     OPT_IGNORE_SYMBOL_NAME,
                     OPT_IGNORE_FUNCTION_ADDRESS,
                     OPT_IGNORE_FUNCTION_SIZE,
+                    OPT_IGNORE_FUNCTION_FRAME_SIZE,
                     OPT_IGNORE_FUNCTION_INLINE,
                     OPT_IGNORE_FUNCTION_SYMBOL_NAME,
                     OPT_IGNORE_VARIABLE_ADDRESS,
@@ -210,15 +636,74 @@ fn main() {
                 .require_delimiter(true)
                 .value_name("OLD>=<NEW"),
         )
+        .arg(
+            clap::Arg::with_name(OPT_EXIT_CODE)
+                .long(OPT_EXIT_CODE)
+                .help("Exit with status 1 if there are differences, or 2 on error"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_STRICT)
+                .long(OPT_STRICT)
+                .help("Fail if any part of the debug info cannot be parsed"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_PROGRESS)
+                .long(OPT_PROGRESS)
+                .help("Write parsing progress to stderr"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_CACHE_DIR)
+                .long(OPT_CACHE_DIR)
+                .help("Cache parsed files in the given directory to speed up later runs")
+                .value_name("DIR"),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_SYMTAB)
+                .long(OPT_SYMTAB)
+                .help(
+                    "How to handle symbols without debuginfo: list them in a <symtab> unit, \
+                     only include their ranges in the file size, or ignore them",
+                )
+                .takes_value(true)
+                .possible_values(&[OPT_SYMTAB_UNIT, OPT_SYMTAB_RANGES, OPT_SYMTAB_NONE])
+                .default_value(OPT_SYMTAB_UNIT),
+        )
+        .arg(
+            clap::Arg::with_name(OPT_SECTIONS)
+                .long(OPT_SECTIONS)
+                .help("Only include functions, variables and ranges within the given sections")
+                .takes_value(true)
+                .multiple(true)
+                .require_delimiter(true)
+                .value_name("SECTION"),
+        )
         .after_help(concat!(
             "FILTERS:\n",
             "    function-inline=<yes|no>        Match function 'inline' value\n",
-            "    name=<string>                   Match entries with the given name\n",
+            "    name=<string>                   Match entries with the given name, including\n",
+            "                                    namespaces if it contains '::'\n",
+            "    glob=<pattern>                  Match entries whose name, including namespaces,\n",
+            "                                    matches the given glob ('*' and '?')\n",
             "    namespace=<string>              Match entries within the given namespace\n",
+            "                                    ('<anon>' matches an anonymous namespace)\n",
             "    unit=<string>                   Match entries within the given unit\n"
         ))
         .get_matches();
 
+    if matches.is_present(OPT_EXIT_CODE)
+        && !matches.is_present(OPT_DIFF)
+        && !matches.is_present(OPT_DIFF_SYMBOLS)
+    {
+        clap::Error::with_description(
+            &format!(
+                "--{} requires --{} or --{}",
+                OPT_EXIT_CODE, OPT_DIFF, OPT_DIFF_SYMBOLS
+            ),
+            clap::ErrorKind::MissingRequiredArgument,
+        )
+        .exit();
+    }
+
     let mut options = ddbug::Options::default();
     options.inline_depth = 1;
 
@@ -234,6 +719,7 @@ fn main() {
                 options.print_function_calls = true;
                 options.print_function_instructions = true;
             }
+            OPT_OUTPUT_MARKDOWN => options.markdown = true,
             _ => clap::Error::with_description(
                 &format!("invalid {} value: {}", OPT_OUTPUT, value),
                 clap::ErrorKind::InvalidValue,
@@ -254,6 +740,47 @@ fn main() {
             }
         }
     }
+    if let Some(max_depth) = matches.value_of(OPT_MAX_DEPTH) {
+        match max_depth.parse::<usize>() {
+            Ok(max_depth) => options.max_depth = max_depth,
+            Err(_) => {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_MAX_DEPTH, max_depth),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+    if matches.is_present(OPT_NO_CHILDREN) {
+        options.max_depth = 1;
+    }
+    options.decimal_addresses = matches.is_present(OPT_DECIMAL_ADDRESSES);
+    if let Some(load_bias) = matches.value_of(OPT_LOAD_BIAS) {
+        let value = match load_bias.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => load_bias.parse::<u64>(),
+        };
+        match value {
+            Ok(load_bias) => options.load_bias = load_bias,
+            Err(_) => {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_LOAD_BIAS, load_bias),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+    options.human_sizes = matches.is_present(OPT_HUMAN_SIZES);
+    options.percent = matches.is_present(OPT_PERCENT);
+    if let Some(values) = matches.values_of(OPT_STRIP_PREFIX) {
+        options.strip_prefix = values.map(String::from).collect();
+    }
+    options.relative = matches.is_present(OPT_RELATIVE);
+    options.debug_sizes = matches.is_present(OPT_DEBUG_SIZES);
+    options.merge_sections = matches.is_present(OPT_MERGE_SECTIONS);
+    options.print_function_cfi = matches.is_present(OPT_CFI);
 
     if let Some(values) = matches.values_of(OPT_CATEGORY) {
         for value in values {
@@ -270,6 +797,19 @@ fn main() {
                 .exit(),
             }
         }
+    } else if let Some(value) = matches.value_of(OPT_ONLY) {
+        options.only(match value {
+            OPT_CATEGORY_FILE => ddbug::Category::File,
+            OPT_CATEGORY_UNIT => ddbug::Category::Unit,
+            OPT_CATEGORY_TYPE => ddbug::Category::Type,
+            OPT_CATEGORY_FUNCTION => ddbug::Category::Function,
+            OPT_CATEGORY_VARIABLE => ddbug::Category::Variable,
+            _ => clap::Error::with_description(
+                &format!("invalid {} value: {}", OPT_ONLY, value),
+                clap::ErrorKind::InvalidValue,
+            )
+            .exit(),
+        });
     } else {
         options.category_file = true;
         options.category_unit = true;
@@ -287,10 +827,14 @@ fn main() {
                     options.print_source = true;
                     options.print_function_calls = true;
                     options.print_function_instructions = true;
+                    options.print_function_instruction_count = true;
+                    options.print_function_signature = true;
                     options.print_function_variables = true;
                     options.print_function_stack_frame = true;
                     options.print_inlined_function_parameters = true;
                     options.print_variable_locations = true;
+                    options.print_variable_bytes = true;
+                    options.print_variable_section = true;
                 }
                 OPT_PRINT_ADDRESS => {
                     options.print_file_address = true;
@@ -301,12 +845,19 @@ fn main() {
                 OPT_PRINT_UNIT_ADDRESS => options.print_unit_address = true,
                 OPT_PRINT_FUNCTION_CALLS => options.print_function_calls = true,
                 OPT_PRINT_FUNCTION_INSTRUCTIONS => options.print_function_instructions = true,
+                OPT_PRINT_FUNCTION_INSTRUCTION_COUNT => {
+                    options.print_function_instruction_count = true
+                }
+                OPT_PRINT_FUNCTION_SIGNATURE => options.print_function_signature = true,
                 OPT_PRINT_FUNCTION_VARIABLES => options.print_function_variables = true,
                 OPT_PRINT_FUNCTION_STACK_FRAME => options.print_function_stack_frame = true,
                 OPT_PRINT_INLINED_FUNCTION_PARAMETERS => {
                     options.print_inlined_function_parameters = true
                 }
                 OPT_PRINT_VARIABLE_LOCATIONS => options.print_variable_locations = true,
+                OPT_PRINT_VARIABLE_BYTES => options.print_variable_bytes = true,
+                OPT_PRINT_VARIABLE_SECTION => options.print_variable_section = true,
+                OPT_PRINT_OFFSET => options.print_offset = true,
                 _ => clap::Error::with_description(
                     &format!("invalid {} value: {}", OPT_PRINT, value),
                     clap::ErrorKind::InvalidValue,
@@ -334,6 +885,7 @@ fn main() {
                         };
                     }
                     OPT_FILTER_NAME => options.filter_name = Some(value.into()),
+                    OPT_FILTER_GLOB => options.filter_glob = Some(value.into()),
                     OPT_FILTER_NAMESPACE => {
                         options.filter_namespace = value.split("::").map(String::from).collect()
                     }
@@ -354,6 +906,14 @@ fn main() {
         }
     }
 
+    if let Some(values) = matches.values_of(OPT_INCLUDE) {
+        options.filter_include = values.map(String::from).collect();
+    }
+    if let Some(values) = matches.values_of(OPT_EXCLUDE) {
+        options.filter_exclude = values.map(String::from).collect();
+    }
+    options.show_declarations = matches.is_present(OPT_SHOW_DECLARATIONS);
+
     options.sort = match matches.value_of(OPT_SORT) {
         Some(OPT_SORT_NAME) => ddbug::Sort::Name,
         Some(OPT_SORT_SIZE) => ddbug::Sort::Size,
@@ -364,6 +924,105 @@ fn main() {
         .exit(),
         _ => ddbug::Sort::None,
     };
+    options.member_declaration_order =
+        matches.value_of(OPT_MEMBER_ORDER) == Some(OPT_MEMBER_ORDER_DECLARATION);
+
+    options.group_by_namespace = matches.is_present(OPT_BY_NAMESPACE);
+    options.by_file = matches.is_present(OPT_BY_FILE);
+    options.group_generics = matches.is_present(OPT_GROUP_GENERICS);
+    options.coverage = matches.is_present(OPT_COVERAGE);
+    options.icf_report = matches.is_present(OPT_ICF_REPORT);
+    options.inline_report = matches.is_present(OPT_INLINE_REPORT);
+    if let Some(name) = matches.value_of(OPT_DUMP) {
+        options.dump = true;
+        options.filter_name = Some(name.into());
+    }
+    if let Some(name) = matches.value_of(OPT_LAYOUT) {
+        options.layout = true;
+        options.filter_name = Some(name.into());
+    }
+    options.flamegraph = matches.is_present(OPT_FLAMEGRAPH);
+    options.toc = matches.is_present(OPT_TOC);
+    if let Some(values) = matches.values_of(OPT_ADDRESSES) {
+        for value in values {
+            let address = match value.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => value.parse::<u64>(),
+            };
+            match address {
+                Ok(address) => options.addresses.push(address),
+                Err(_) => {
+                    clap::Error::with_description(
+                        &format!("invalid {} value: {}", OPT_ADDRESSES, value),
+                        clap::ErrorKind::InvalidValue,
+                    )
+                    .exit();
+                }
+            }
+        }
+    }
+
+    if let Some(top) = matches.value_of(OPT_TOP) {
+        match top.parse::<usize>() {
+            Ok(top) => options.top = Some(top),
+            Err(_) => {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_TOP, top),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+
+    options.diff_summary = !matches.is_present(OPT_NO_SUMMARY);
+    options.match_renamed_functions = matches.is_present(OPT_MATCH_RENAMED);
+    if let Some(threshold) = matches.value_of(OPT_SIMILARITY_THRESHOLD) {
+        match threshold.parse::<u32>() {
+            Ok(threshold) if threshold <= 100 => options.similarity_threshold = threshold,
+            _ => {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_SIMILARITY_THRESHOLD, threshold),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+    if let Some(threads) = matches.value_of(OPT_THREADS) {
+        match threads.parse::<usize>() {
+            Ok(threads) if threads > 0 => options.diff_threads = threads,
+            _ => {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_THREADS, threads),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+    if let Some(values) = matches.values_of(OPT_GROW_THRESHOLD) {
+        for value in values {
+            let threshold = match value.strip_suffix('%') {
+                Some(percent) => percent
+                    .parse::<u64>()
+                    .map(|percent| options.grow_threshold_percent = Some(percent)),
+                None => value
+                    .parse::<u64>()
+                    .map(|bytes| options.grow_threshold_bytes = Some(bytes)),
+            };
+            if threshold.is_err() {
+                clap::Error::with_description(
+                    &format!("invalid {} value: {}", OPT_GROW_THRESHOLD, value),
+                    clap::ErrorKind::InvalidValue,
+                )
+                .exit();
+            }
+        }
+    }
+    options.grow_only = matches.is_present(OPT_GROW_ONLY);
+    options.abi_check = matches.is_present(OPT_ABI_CHECK);
+    options.canonical_names = matches.is_present(OPT_CANONICAL_NAMES);
 
     if let Some(values) = matches.values_of(OPT_IGNORE) {
         for value in values {
@@ -384,6 +1043,7 @@ fn main() {
                 }
                 OPT_IGNORE_FUNCTION_ADDRESS => options.ignore_function_address = true,
                 OPT_IGNORE_FUNCTION_SIZE => options.ignore_function_size = true,
+                OPT_IGNORE_FUNCTION_FRAME_SIZE => options.ignore_function_frame_size = true,
                 OPT_IGNORE_FUNCTION_INLINE => options.ignore_function_inline = true,
                 OPT_IGNORE_FUNCTION_SYMBOL_NAME => options.ignore_function_symbol_name = true,
                 OPT_IGNORE_VARIABLE_ADDRESS => options.ignore_variable_address = true,
@@ -414,32 +1074,166 @@ fn main() {
         options.prefix_map.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
     }
 
-    if let Some(mut paths) = matches.values_of(OPT_DIFF) {
+    let strict = matches.is_present(OPT_STRICT);
+    let progress = matches.is_present(OPT_PROGRESS);
+    let mut parse_options = ddbug::parser::ParseOptions {
+        cache_dir: matches.value_of(OPT_CACHE_DIR).map(PathBuf::from),
+        ..Default::default()
+    };
+    match matches.value_of(OPT_SYMTAB) {
+        Some(OPT_SYMTAB_RANGES) => parse_options.symtab = false,
+        Some(OPT_SYMTAB_NONE) => {
+            parse_options.symtab = false;
+            parse_options.symtab_ranges = false;
+        }
+        _ => {}
+    }
+    if let Some(values) = matches.values_of(OPT_SECTIONS) {
+        parse_options.sections = values.map(String::from).collect();
+    }
+
+    if let Some(paths) = matches.values_of(OPT_DIFF).filter(|paths| paths.len() > 2) {
+        if options.http {
+            error!("{} output can only compare two files", OPT_OUTPUT_HTTP);
+            return;
+        }
+        if options.abi_check {
+            error!("--{} can only compare two files", OPT_ABI_CHECK);
+            std::process::exit(2);
+        }
+        let exit_code = matches.is_present(OPT_EXIT_CODE);
+        let mut files = Vec::new();
+        for path in paths {
+            match parse_file(path, strict, progress, &parse_options) {
+                Ok(file) => files.push(file),
+                Err(e) => {
+                    error!("{}: {}", path, e);
+                    exit_error(exit_code);
+                    return;
+                }
+            }
+        }
+        let files: Vec<_> = files.iter().map(|file| file.file()).collect();
+        match compare_files(&files, &options) {
+            Ok(changed) => {
+                if changed && exit_code {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                error!("{}", e);
+                exit_error(exit_code);
+            }
+        }
+    } else if let Some(mut paths) = matches.values_of(OPT_DIFF) {
         let path_a = paths.next().unwrap();
         let path_b = paths.next().unwrap();
+        if options.http && options.abi_check {
+            error!("{} output can't print ABI differences", OPT_OUTPUT_HTTP);
+            return;
+        }
+        let exit_code = matches.is_present(OPT_EXIT_CODE) || options.abi_check;
 
-        match ddbug::File::parse(path_a.to_string()) {
-            Err(e) => error!("{}: {}", path_a, e),
-            Ok(file_a) => match ddbug::File::parse(path_b.to_string()) {
-                Err(e) => error!("{}: {}", path_b, e),
+        match parse_file(path_a, strict, progress, &parse_options) {
+            Err(e) => {
+                error!("{}: {}", path_a, e);
+                exit_error(exit_code);
+            }
+            Ok(file_a) => match parse_file(path_b, strict, progress, &parse_options) {
+                Err(e) => {
+                    error!("{}: {}", path_b, e);
+                    exit_error(exit_code);
+                }
                 Ok(file_b) => {
-                    if let Err(e) = {
-                        let ids = ddbug::assign_merged_ids(file_a.file(), file_b.file(), &options);
-                        if options.http {
-                            serve_diff_file(file_a, file_b, options, ids)
-                        } else {
-                            diff_file(file_a.file(), file_b.file(), &options)
+                    let ids = ddbug::assign_merged_ids(file_a.file(), file_b.file(), &options);
+                    let result = if options.http {
+                        serve_diff_file(file_a, file_b, options, ids).map(|_| false)
+                    } else if options.abi_check {
+                        abi_check(file_a.file(), file_b.file(), &options)
+                    } else {
+                        diff_file(file_a.file(), file_b.file(), &options)
+                    };
+                    match result {
+                        Ok(changed) => {
+                            if changed && exit_code {
+                                std::process::exit(1);
+                            }
+                        }
+                        Err(e) => {
+                            error!("{}", e);
+                            exit_error(exit_code);
                         }
-                    } {
-                        error!("{}", e);
                     }
                 }
             },
         }
+    } else if matches.is_present(OPT_DIFF_SYMBOLS) {
+        let path = matches.value_of(OPT_FILE).unwrap();
+        if options.http {
+            error!("{} output can't print symbol differences", OPT_OUTPUT_HTTP);
+            return;
+        }
+        let exit_code = matches.is_present(OPT_EXIT_CODE);
+        let debuginfo_options = ddbug::parser::ParseOptions {
+            symtab: false,
+            symtab_ranges: false,
+            ..parse_options.clone()
+        };
+        let symbols_options = ddbug::parser::ParseOptions {
+            debuginfo: false,
+            ..parse_options
+        };
+        let result = parse_file(path, strict, progress, &debuginfo_options).and_then(|file_a| {
+            let file_b = parse_file(path, strict, false, &symbols_options)?;
+            diff_symbols(file_a.file(), file_b.file(), &options)
+        });
+        match result {
+            Ok(changed) => {
+                if changed && exit_code {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                error!("{}: {}", path, e);
+                exit_error(exit_code);
+            }
+        }
     } else {
         let path = matches.value_of(OPT_FILE).unwrap();
 
-        if let Err(e) = ddbug::File::parse(path.to_string()).and_then(|file| {
+        match ddbug::File::parse_archive(path.to_string(), &parse_options) {
+            Ok(Some(members)) => {
+                if options.http {
+                    error!("{} output can't print archives", OPT_OUTPUT_HTTP);
+                    return;
+                }
+                if options.merge_sections && options.category_file {
+                    let files: Vec<_> = members.iter().map(|member| member.file()).collect();
+                    if let Err(e) = format(&options, |printer| {
+                        ddbug::print_archive(path, &files, printer, &options)
+                    }) {
+                        error!("{}: {}", path, e);
+                    }
+                }
+                for member in &members {
+                    let file = member.file();
+                    if let Err(e) = check_warnings(file, strict).and_then(|_| {
+                        ddbug::assign_ids(file, &options);
+                        print_file(file, &options)
+                    }) {
+                        error!("{}: {}", file.path(), e);
+                    }
+                }
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                error!("{}: {}", path, e);
+                return;
+            }
+        }
+
+        if let Err(e) = parse_file(path, strict, progress, &parse_options).and_then(|file| {
             let ids = ddbug::assign_ids(file.file(), &options);
             if options.http {
                 serve_print_file(file, options, ids)
@@ -452,17 +1246,107 @@ fn main() {
     }
 }
 
+// Like diff(1), a failed comparison must not be mistaken for an unchanged one.
+fn exit_error(exit_code: bool) {
+    if exit_code {
+        std::process::exit(2);
+    }
+}
+
+fn parse_file(
+    path: &str,
+    strict: bool,
+    progress: bool,
+    parse_options: &ddbug::parser::ParseOptions,
+) -> ddbug::Result<ddbug::parser::FileContext> {
+    let file = if progress {
+        // Only write when the percentage changes, to limit the output for
+        // files with many units.
+        let mut last = None;
+        let file = ddbug::File::parse_with_options(
+            path.to_string(),
+            parse_options,
+            &mut |count, total| {
+                let percent = (count * 100).checked_div(total).unwrap_or(100);
+                if last != Some(percent) {
+                    last = Some(percent);
+                    eprint!(
+                        "\r{}: parsed {}/{} units ({}%)",
+                        path, count, total, percent
+                    );
+                }
+            },
+        );
+        if last.is_some() {
+            eprintln!();
+        }
+        file?
+    } else {
+        ddbug::File::parse_with_options(path.to_string(), parse_options, &mut |_, _| {})?
+    };
+    check_warnings(file.file(), strict)?;
+    Ok(file)
+}
+
+fn check_warnings(file: &ddbug::File, strict: bool) -> ddbug::Result<()> {
+    let warnings = file.warnings();
+    if strict {
+        if let Some(warning) = warnings.first() {
+            return Err(warning.clone().into());
+        }
+    }
+    for warning in warnings {
+        warn!("{}: {}", file.path(), warning);
+    }
+    Ok(())
+}
+
 fn diff_file(
     file_a: &ddbug::File,
     file_b: &ddbug::File,
     options: &ddbug::Options,
-) -> ddbug::Result<()> {
+) -> ddbug::Result<bool> {
+    let mut changed = false;
     format(options, |printer| {
-        if let Err(e) = ddbug::diff(printer, file_a, file_b, options) {
-            error!("{}", e);
-        }
+        changed = ddbug::diff(printer, file_a, file_b, options)?;
         Ok(())
-    })
+    })?;
+    Ok(changed)
+}
+
+fn abi_check(
+    file_a: &ddbug::File,
+    file_b: &ddbug::File,
+    options: &ddbug::Options,
+) -> ddbug::Result<bool> {
+    let mut breaking = false;
+    format(options, |printer| {
+        breaking = ddbug::abi_check(printer, file_a, file_b, options)?;
+        Ok(())
+    })?;
+    Ok(breaking)
+}
+
+fn diff_symbols(
+    file_a: &ddbug::File,
+    file_b: &ddbug::File,
+    options: &ddbug::Options,
+) -> ddbug::Result<bool> {
+    let mut changed = false;
+    format(options, |printer| {
+        changed = ddbug::diff_symbols(printer, file_a, file_b, options)?;
+        Ok(())
+    })?;
+    Ok(changed)
+}
+
+fn compare_files(files: &[&ddbug::File], options: &ddbug::Options) -> ddbug::Result<bool> {
+    let mut changed = false;
+    format(options, |printer| {
+        changed = ddbug::compare(printer, files, options)?;
+        Ok(())
+    })?;
+    Ok(changed)
 }
 
 fn print_file(file: &ddbug::File, options: &ddbug::Options) -> ddbug::Result<()> {
@@ -479,10 +1363,16 @@ where
         let mut printer = ddbug::HtmlPrinter::new(&mut writer, options);
         printer.begin()?;
         f(&mut printer)?;
-        printer.end()
+        printer.end()?;
+        ddbug::Printer::flush(&mut printer)
+    } else if options.markdown {
+        let mut printer = ddbug::MarkdownPrinter::new(&mut writer, options);
+        f(&mut printer)?;
+        ddbug::Printer::flush(&mut printer)
     } else {
         let mut printer = ddbug::TextPrinter::new(&mut writer, options);
-        f(&mut printer)
+        f(&mut printer)?;
+        ddbug::Printer::flush(&mut printer)
     }
 }
 
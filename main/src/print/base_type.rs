@@ -47,7 +47,7 @@ impl<'input> PrintHeader for BaseType<'input> {
 
 fn print_byte_size(ty: &BaseType, w: &mut dyn ValuePrinter, _hash: &FileHash) -> Result<()> {
     if let Some(size) = ty.byte_size() {
-        write!(w, "{}", size)?;
+        w.size(size)?;
     } else {
         debug!("base type with no size");
     }
@@ -2,30 +2,51 @@ use std::cmp;
 
 use parser::{EnumerationType, Enumerator, FileHash, Unit};
 
+use crate::print::types::AnonName;
 use crate::print::{self, DiffList, DiffState, Print, PrintHeader, PrintState, ValuePrinter};
-use crate::Result;
+use crate::{Options, Result};
 
-fn print_name(ty: &EnumerationType, w: &mut dyn ValuePrinter) -> Result<()> {
+fn print_name(
+    ty: &EnumerationType,
+    w: &mut dyn ValuePrinter,
+    anon_name: Option<&str>,
+) -> Result<()> {
     write!(w, "enum ")?;
     if let Some(namespace) = ty.namespace() {
         print::namespace::print(namespace, w)?;
     }
-    w.name(ty.name().unwrap_or("<anon>"))?;
+    match ty.name() {
+        Some(name) => w.name(name)?,
+        None => w.name(anon_name.unwrap_or("<anon>"))?,
+    }
     Ok(())
 }
 
-pub(crate) fn print_ref(ty: &EnumerationType, w: &mut dyn ValuePrinter, id: usize) -> Result<()> {
-    w.link(id, &mut |w| print_name(ty, w))
+fn anon_label(ty: &EnumerationType, hash: &FileHash) -> Option<String> {
+    print::types::anon_label(ty.offset(), ty.source(), hash, AnonName::Derived)
+}
+
+pub(crate) fn print_ref(
+    ty: &EnumerationType,
+    w: &mut dyn ValuePrinter,
+    id: usize,
+    anon_name: Option<&str>,
+) -> Result<()> {
+    w.link(id, &mut |w| print_name(ty, w, anon_name))
 }
 
 impl<'input> PrintHeader for EnumerationType<'input> {
     fn print_header(&self, state: &mut PrintState) -> Result<()> {
-        state.line(|w, _state| print_name(self, w))
+        state.line(|w, hash| {
+            let anon_name = anon_label(self, hash);
+            print_name(self, w, anon_name.as_deref())
+        })
     }
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
         }
         state.field("declaration", |w, _state| print_declaration(self, w))?;
         state.field("size", |w, state| print_byte_size(self, w, state))?;
@@ -34,7 +55,10 @@ impl<'input> PrintHeader for EnumerationType<'input> {
     }
 
     fn diff_header(state: &mut DiffState, a: &Self, b: &Self) -> Result<()> {
-        state.line(a, b, |w, _state, x| print_name(x, w))
+        state.line(a, b, |w, hash, x| {
+            let anon_name = anon_label(x, hash);
+            print_name(x, w, anon_name.as_deref())
+        })
     }
 
     fn diff_body(
@@ -44,12 +68,13 @@ impl<'input> PrintHeader for EnumerationType<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
         state.field("declaration", a, b, |w, _state, x| print_declaration(x, w))?;
@@ -63,8 +88,13 @@ impl<'input> PrintHeader for EnumerationType<'input> {
     }
 }
 
-fn print_source(ty: &EnumerationType, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(ty.source(), w, unit)
+fn print_source(
+    ty: &EnumerationType,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(ty.source(), w, unit, options)
 }
 
 fn print_declaration(ty: &EnumerationType, w: &mut dyn ValuePrinter) -> Result<()> {
@@ -76,7 +106,7 @@ fn print_declaration(ty: &EnumerationType, w: &mut dyn ValuePrinter) -> Result<(
 
 fn print_byte_size(ty: &EnumerationType, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
     if let Some(size) = ty.byte_size(hash) {
-        write!(w, "{}", size)?;
+        w.size(size)?;
     } else {
         debug!("enum with no size");
     }
@@ -1,11 +1,20 @@
-use parser::{File, FileHash, Function, Type, Unit, Variable};
+use std::borrow::Cow;
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use parser::{
+    File, FileHash, Function, InlinedFunction, Member, Namespace, RangeList, RawEntry, Section,
+    Type, TypeKind, Unit, Variable,
+};
 
 use crate::code::Code;
 use crate::filter;
+use crate::print::text::TextValuePrinter;
 use crate::print::{
-    DiffState, Id, MergeIterator, MergeResult, PrintHeader, PrintState, Printer, SortList,
+    struct_type, Change, CompareState, DiffCounts, DiffState, Id, MergeIterator, MergeResult,
+    Print, PrintHeader, PrintState, Printer, SortList,
 };
-use crate::{Error, Options, Result};
+use crate::{Error, Options, Result, Sort};
 
 pub fn assign_ids(file: &File, options: &Options) -> Vec<Id> {
     let mut ids = Vec::new();
@@ -157,7 +166,7 @@ fn assign_merged_ids_in_unit(
     let mut types_b = filter::enumerate_and_filter_types(unit_b, hash_b, options, true);
     types_b.sort_by(|x, y| Type::cmp_id_for_sort(hash_b, x.1, hash_b, y.1, options));
     let types = MergeIterator::new(types_a.into_iter(), types_b.into_iter(), |a, b| {
-        Type::cmp_id(hash_a, a.1, hash_b, b.1)
+        <Type as SortList>::cmp_id(hash_a, a.1, hash_b, b.1, options)
     });
     for ty in types {
         match ty {
@@ -257,9 +266,9 @@ fn assign_merged_ids_in_unit(
         }
     }
 
-    let mut variables_a = filter::enumerate_and_filter_variables(unit_a, options);
+    let mut variables_a = filter::enumerate_and_filter_variables(unit_a, hash_a, options);
     variables_a.sort_by(|x, y| Variable::cmp_id_for_sort(hash_a, x.1, hash_a, y.1, options));
-    let mut variables_b = filter::enumerate_and_filter_variables(unit_b, options);
+    let mut variables_b = filter::enumerate_and_filter_variables(unit_b, hash_b, options);
     variables_b.sort_by(|x, y| Variable::cmp_id_for_sort(hash_b, x.1, hash_b, y.1, options));
     let variables = MergeIterator::new(variables_a.into_iter(), variables_b.into_iter(), |a, b| {
         <Variable as SortList>::cmp_id(hash_a, a.1, hash_b, b.1, options)
@@ -326,39 +335,721 @@ pub fn print(file: &File, printer: &mut dyn Printer, options: &Options) -> Resul
     let code = Code::new(file);
     let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
 
-    if options.category_file {
+    // The folded stack format has no room for the file header.
+    if options.flamegraph {
+        return print_flamegraph(&mut state, file, options);
+    }
+
+    if !options.addresses.is_empty() {
+        return print_addresses(&mut state, file, options);
+    }
+
+    if options.toc {
+        return print_toc(&mut state, file, options);
+    }
+
+    print_file_header(&mut state, file, options)?;
+
+    if options.by_file {
+        return print_by_file(&mut state, file, options);
+    }
+
+    if options.group_generics {
+        return print_group_generics(&mut state, file, options);
+    }
+
+    if options.coverage {
+        return print_coverage(&mut state, file, options);
+    }
+
+    if options.icf_report {
+        return print_icf_report(&mut state, file, options);
+    }
+
+    if options.inline_report {
+        return print_inline_report(&mut state, file, options);
+    }
+
+    if options.dump {
+        return print_dump(&mut state, file, options);
+    }
+
+    if options.layout {
+        return print_layouts(&mut state, file, options);
+    }
+
+    if let Some(top) = options.top {
+        let hash = state.hash();
+        let units = filter::filter_units(file, options);
+        if options.category_unit {
+            super::unit::print_top_fragmented(&mut state, &units, top)?;
+        }
+        if options.category_type {
+            let mut types = Vec::new();
+            for unit in &units {
+                for ty in filter::filter_types(unit, hash, options, false) {
+                    types.push((*unit, ty));
+                }
+            }
+            print_top(&mut state, &mut types, |ty, hash| ty.byte_size(hash), top)?;
+        }
+        if options.category_function {
+            let mut functions = Vec::new();
+            for unit in &units {
+                for function in filter::filter_functions(unit, options) {
+                    functions.push((*unit, function));
+                }
+            }
+            print_top(&mut state, &mut functions, |f, _hash| f.size(), top)?;
+        }
+        if options.category_variable {
+            let mut variables = Vec::new();
+            for unit in &units {
+                for variable in filter::filter_variables(unit, hash, options) {
+                    variables.push((*unit, variable));
+                }
+            }
+            print_top(&mut state, &mut variables, |v, hash| v.byte_size(hash), top)?;
+        }
+        return Ok(());
+    }
+
+    state.sort_list(&(), &mut filter::filter_units(file, options))
+}
+
+// Print the summary of the file that is output before its units.
+fn print_file_header(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    if !options.category_file {
+        return Ok(());
+    }
+    state.collapsed(
+        |state| {
+            state.line(|w, _hash| {
+                write!(w, "file {}", file.path())?;
+                Ok(())
+            })
+        },
+        |state| {
+            let ranges = file.ranges(state.hash());
+            let size = ranges.size();
+            let fn_size = file.function_size();
+            let var_size = file.variable_size(state.hash());
+            // TODO: fix our calculations so that the function and variable sizes
+            // can't exceed the total size.
+            let other_size = size.saturating_sub(fn_size).saturating_sub(var_size);
+            if options.print_file_address {
+                state.field_collapsed("addresses", |state| state.list(&(), ranges.list()))?;
+            }
+            state.field_size("size", size)?;
+            state.field_size("fn size", fn_size)?;
+            state.field_size("var size", var_size)?;
+            state.field_size("other size", other_size)?;
+            if options.debug_sizes {
+                state.field_size("debug size", file.debug_size())?;
+            }
+            let unresolved_types = file.unresolved_types(state.hash()).len() as u64;
+            if unresolved_types != 0 {
+                state.field_u64("unresolved types", unresolved_types)?;
+            }
+            if options.merge_sections {
+                let sections = Section::merge(file.sections());
+                state.field_collapsed("sections", |state| state.list(&(), &sections))?;
+            } else {
+                state.field_collapsed("sections", |state| state.list(&(), file.sections()))?;
+            }
+            state.field_collapsed("producers", |state| {
+                state.list(&(), &super::unit::producers(file))
+            })?;
+            Ok(())
+        },
+    )?;
+    state.line_break()
+}
+
+// Print a table of contents of the units, followed by the units.
+fn print_toc(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let header = state.buffer(|state| print_file_header(state, file, options))?;
+    state.toc_list(&header, &(), &mut filter::filter_units(file, options))
+}
+
+// Print the largest `top` items across all units, in order of decreasing size.
+fn print_top<'input, T, F>(
+    state: &mut PrintState,
+    list: &mut Vec<(&Unit<'input>, &T)>,
+    size: F,
+    top: usize,
+) -> Result<()>
+where
+    T: SortList<Arg = Unit<'input>>,
+    F: Fn(&T, &FileHash) -> Option<u64>,
+{
+    let hash = state.hash();
+    let options = state.options();
+    list.sort_by(|a, b| {
+        size(b.1, hash)
+            .cmp(&size(a.1, hash))
+            .then_with(|| T::cmp_id_for_sort(hash, a.1, hash, b.1, options))
+    });
+    // Items such as types may be duplicated in multiple units.
+    list.dedup_by(|a, b| T::cmp_id(hash, a.1, hash, b.1, options) == cmp::Ordering::Equal);
+    for (unit, item) in list.iter().take(top) {
+        item.print(state, unit)?;
+    }
+    Ok(())
+}
+
+// Print the size of functions and variables for each source file, and the
+// total for each directory containing those files.
+//
+// Functions and variables are assigned to the file that declares them if known,
+// otherwise to the primary source file of their unit.
+fn print_by_file(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let hash = state.hash();
+    // Map from path to function size and variable size.
+    // Directories end with '/', so they sort before their contents.
+    let mut sizes: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    let mut add = |path: &str, fn_size: u64, var_size: u64| {
+        let dirs = path.match_indices('/').map(|(i, _)| &path[..=i]);
+        for key in dirs.chain(Some(path)) {
+            let size = sizes.entry(key.to_string()).or_default();
+            size.0 += fn_size;
+            size.1 += var_size;
+        }
+    };
+    for unit in filter::filter_units(file, options) {
+        let unit_path = match (unit.dir(), unit.name()) {
+            (Some(dir), Some(name)) if !dir.is_empty() && !name.starts_with('/') => {
+                format!("{}/{}", dir, name)
+            }
+            (_, name) => name.unwrap_or("<unknown>").to_string(),
+        };
+        let unit_path = options.strip_path(&unit_path, unit.dir());
+        if options.category_function {
+            for function in filter::filter_functions(unit, options) {
+                let size: u64 = function.ranges().iter().map(|range| range.size()).sum();
+                if size != 0 {
+                    let path = options.source_path(function.source(), unit);
+                    add(path.as_deref().unwrap_or(unit_path), size, 0);
+                }
+            }
+        }
+        if options.category_variable {
+            for variable in filter::filter_variables(unit, hash, options) {
+                if let Some(range) = variable.range(hash) {
+                    let path = options.source_path(variable.source(), unit);
+                    add(path.as_deref().unwrap_or(unit_path), 0, range.size());
+                }
+            }
+        }
+    }
+
+    let mut sizes: Vec<_> = sizes.into_iter().collect();
+    if options.sort == Sort::Size {
+        sizes.sort_by_key(|(_, (fn_size, var_size))| cmp::Reverse(fn_size + var_size));
+    }
+    for (path, (fn_size, var_size)) in sizes {
         state.collapsed(
             |state| {
                 state.line(|w, _hash| {
-                    write!(w, "file {}", file.path())?;
+                    write!(w, "{}", path)?;
                     Ok(())
                 })
             },
             |state| {
-                let ranges = file.ranges(state.hash());
-                let size = ranges.size();
-                let fn_size = file.function_size();
-                let var_size = file.variable_size(state.hash());
-                let other_size = size.checked_sub(fn_size + var_size).unwrap_or_else(|| {
-                    // TODO: fix our calculations so this doesn't happen
-                    debug!("function or variable sizes are too large");
-                    0
-                });
-                if options.print_file_address {
-                    state.field_collapsed("addresses", |state| state.list(&(), ranges.list()))?;
-                }
-                state.field_u64("size", size)?;
-                state.field_u64("fn size", fn_size)?;
-                state.field_u64("var size", var_size)?;
-                state.field_u64("other size", other_size)?;
-                state.field_collapsed("sections", |state| state.list(&(), file.sections()))?;
+                state.field_size("size", fn_size + var_size)?;
+                state.field_size("fn size", fn_size)?;
+                state.field_size("var size", var_size)?;
                 Ok(())
             },
         )?;
         state.line_break()?;
     }
+    Ok(())
+}
 
-    state.sort_list(&(), &mut filter::filter_units(file, options))
+// Print the total size and count of the instantiations of each generic function.
+//
+// Functions are grouped by their qualified name with the generic arguments erased,
+// in order of decreasing total size.
+fn print_group_generics(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    // Map from erased name to total size and count.
+    let mut groups: BTreeMap<String, (u64, u64)> = BTreeMap::new();
+    for unit in filter::filter_units(file, options) {
+        for function in filter::filter_functions(unit, options) {
+            let size = match function.size() {
+                Some(size) => size,
+                None => continue,
+            };
+            let name = filter::qualified_name(function.namespace(), function.name());
+            if let Some(name) = filter::erase_generics(&name) {
+                let group = groups.entry(name).or_default();
+                group.0 += size;
+                group.1 += 1;
+            }
+        }
+    }
+
+    let mut groups: Vec<_> = groups.into_iter().collect();
+    groups.sort_by(|(name_a, (size_a, _)), (name_b, (size_b, _))| {
+        size_b.cmp(size_a).then_with(|| name_a.cmp(name_b))
+    });
+    if let Some(top) = options.top {
+        groups.truncate(top);
+    }
+    for (name, (size, count)) in groups {
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "{}", name)?;
+                    Ok(())
+                })
+            },
+            |state| {
+                state.field_size("size", size)?;
+                state.field_u64("instantiations", count)?;
+                Ok(())
+            },
+        )?;
+        state.line_break()?;
+    }
+    Ok(())
+}
+
+// Print the functions that are inlined the most, ordered by the total size of
+// their inlined instances, with the number of instances and the number of
+// functions that they are inlined into.
+//
+// Instances are grouped by name, so that the same function described by
+// multiple units is counted once.
+fn print_inline_report(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    #[derive(Default)]
+    struct Inlined {
+        size: u64,
+        count: u64,
+        callers: BTreeSet<String>,
+    }
+
+    fn visit(
+        caller: &str,
+        inlined_functions: &[InlinedFunction],
+        hash: &FileHash,
+        report: &mut BTreeMap<String, Inlined>,
+    ) {
+        for inlined_function in inlined_functions {
+            if let Some(origin) = inlined_function.abstract_origin(hash) {
+                let name = filter::qualified_name(origin.namespace(), origin.name());
+                let inlined = report.entry(name).or_default();
+                inlined.size += inlined_function.size().unwrap_or(0);
+                inlined.count += 1;
+                inlined.callers.insert(caller.to_string());
+            }
+            visit(caller, inlined_function.inlined_functions(), hash, report);
+        }
+    }
+
+    let hash = state.hash();
+    let mut report = BTreeMap::new();
+    for unit in filter::filter_units(file, options) {
+        for function in filter::filter_functions(unit, options) {
+            // Functions without an address only contain the abstract instances.
+            if function.address().is_none() {
+                continue;
+            }
+            let caller = filter::qualified_name(function.namespace(), function.name());
+            let details = function.details(hash);
+            visit(&caller, details.inlined_functions(), hash, &mut report);
+        }
+    }
+
+    let mut report: Vec<_> = report.into_iter().collect();
+    report.sort_by(|(name_a, a), (name_b, b)| {
+        b.size
+            .cmp(&a.size)
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| name_a.cmp(name_b))
+    });
+    if let Some(top) = options.top {
+        report.truncate(top);
+    }
+    for (name, inlined) in report {
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "{}", name)?;
+                    Ok(())
+                })
+            },
+            |state| {
+                state.field_size("size", inlined.size)?;
+                state.field_u64("instances", inlined.count)?;
+                state.field_u64("callers", inlined.callers.len() as u64)?;
+                Ok(())
+            },
+        )?;
+        state.line_break()?;
+    }
+    Ok(())
+}
+
+// Print the fraction of the bytes in each executable section that are covered
+// by functions described in the debuginfo, and the total for all executable sections.
+//
+// Functions that were only found in the symbol table are not counted.
+// Alignment padding between functions is counted as covered.
+fn print_coverage(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let mut functions = RangeList::default();
+    for unit in filter::filter_units(file, options) {
+        for function in filter::filter_functions(unit, options) {
+            if function.offset().get().is_none() {
+                continue;
+            }
+            for range in function.ranges() {
+                functions.push(range);
+            }
+        }
+    }
+    functions.sort();
+
+    let mut total_size = 0;
+    let mut total_covered = 0;
+    for section in file.sections() {
+        let range = match section.address() {
+            Some(range) if section.is_executable() && range.size() != 0 => range,
+            _ => continue,
+        };
+        let mut ranges = RangeList::default();
+        ranges.push(range);
+        let size = range.size();
+        let covered = size.saturating_sub(ranges.subtract(&functions).size());
+        let name = format!("section {}", section.name().unwrap_or("<anon>"));
+        print_coverage_entry(state, &name, size, covered)?;
+        total_size += size;
+        total_covered += covered;
+    }
+    if total_size != 0 {
+        print_coverage_entry(state, "total", total_size, total_covered)?;
+    }
+    Ok(())
+}
+
+fn print_coverage_entry(state: &mut PrintState, name: &str, size: u64, covered: u64) -> Result<()> {
+    state.collapsed(
+        |state| {
+            state.line(|w, _hash| {
+                write!(w, "{}", name)?;
+                Ok(())
+            })
+        },
+        |state| {
+            state.field_size("size", size)?;
+            state.field_size("covered", covered)?;
+            state.field("coverage", |w, _hash| {
+                write!(w, "{:.1}%", covered as f64 * 100.0 / size as f64)?;
+                Ok(())
+            })
+        },
+    )?;
+    state.line_break()
+}
+
+// Print groups of functions with identical code, and the number of bytes that
+// would be saved by folding each group into a single function.
+//
+// Functions that share an address have already been folded by the linker, and
+// are printed separately after the groups. Relocations are not considered, so
+// functions in relocatable objects may be reported as identical even though
+// they refer to different symbols.
+fn print_icf_report(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let hash = state.hash();
+    // Map from address to the functions at that address.
+    let mut addresses: BTreeMap<u64, Vec<&Function>> = BTreeMap::new();
+    for unit in filter::filter_units(file, options) {
+        for function in filter::filter_functions(unit, options) {
+            if let Some(address) = function.address() {
+                let functions = addresses.entry(address).or_default();
+                // The same function may be described by multiple units.
+                if !functions
+                    .iter()
+                    .any(|f| Function::cmp_id(hash, f, hash, function) == cmp::Ordering::Equal)
+                {
+                    functions.push(function);
+                }
+            }
+        }
+    }
+
+    // Map from code to the addresses of the functions with that code.
+    let mut codes: HashMap<Vec<u8>, Vec<u64>> = HashMap::new();
+    for (address, functions) in &addresses {
+        let bytes: Option<Vec<&[u8]>> = functions[0]
+            .ranges()
+            .into_iter()
+            .map(|range| file.segment_bytes(range))
+            .collect();
+        let bytes = match bytes {
+            Some(bytes) => bytes.concat(),
+            None => continue,
+        };
+        if !bytes.is_empty() {
+            codes.entry(bytes).or_default().push(*address);
+        }
+    }
+
+    let mut groups: Vec<_> = codes
+        .into_iter()
+        .filter(|(_, group)| group.len() > 1)
+        .map(|(bytes, group)| {
+            let size = bytes.len() as u64;
+            (size * (group.len() as u64 - 1), size, group)
+        })
+        .collect();
+    groups.sort_by(|(savings_a, _, group_a), (savings_b, _, group_b)| {
+        savings_b.cmp(savings_a).then_with(|| group_a.cmp(group_b))
+    });
+    let mut total_savings = 0;
+    for (savings, size, group) in &groups {
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "identical functions")?;
+                    Ok(())
+                })
+            },
+            |state| {
+                state.field_size("size", *size)?;
+                state.field_u64("copies", group.len() as u64)?;
+                state.field_size("savings", *savings)?;
+                state.field_collapsed("functions", |state| {
+                    for address in group {
+                        print_icf_functions(state, &addresses[address])?;
+                    }
+                    Ok(())
+                })
+            },
+        )?;
+        state.line_break()?;
+        total_savings += savings;
+    }
+
+    for (address, functions) in &addresses {
+        if functions.len() < 2 {
+            continue;
+        }
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "folded functions")?;
+                    Ok(())
+                })
+            },
+            |state| {
+                state.field("address", |w, _hash| w.address(*address))?;
+                if let Some(size) = functions[0].size() {
+                    state.field_size("size", size)?;
+                }
+                state.field_collapsed("functions", |state| print_icf_functions(state, functions))
+            },
+        )?;
+        state.line_break()?;
+    }
+
+    if !groups.is_empty() {
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "total")?;
+                    Ok(())
+                })
+            },
+            |state| state.field_size("savings", total_savings),
+        )?;
+        state.line_break()?;
+    }
+    Ok(())
+}
+
+fn print_icf_functions(state: &mut PrintState, functions: &[&Function]) -> Result<()> {
+    for function in functions {
+        state.line(|w, _hash| {
+            write!(w, "fn ")?;
+            super::function::print_ref(function, w)
+        })?;
+    }
+    Ok(())
+}
+
+// Print the raw debugging information entries for the types, functions and
+// variables that match the filters.
+fn print_dump(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let hash = state.hash();
+    let mut offsets = BTreeSet::new();
+    for unit in filter::filter_units(file, options) {
+        if options.category_type {
+            for ty in filter::filter_types(unit, hash, options, false) {
+                offsets.extend(ty.offset().get());
+            }
+        }
+        if options.category_function {
+            for function in filter::filter_functions(unit, options) {
+                offsets.extend(function.offset().get());
+            }
+        }
+        if options.category_variable {
+            for variable in filter::filter_variables(unit, hash, options) {
+                offsets.extend(variable.offset().get());
+            }
+        }
+    }
+    for offset in offsets {
+        if let Some(entry) = file.raw_entry(offset) {
+            print_raw_entry(state, &entry)?;
+            state.line_break()?;
+        }
+    }
+    Ok(())
+}
+
+fn print_raw_entry(state: &mut PrintState, entry: &RawEntry) -> Result<()> {
+    state.collapsed(
+        |state| {
+            state.line(|w, _hash| {
+                write!(w, "<0x{:x}> {}", entry.offset(), entry.tag())?;
+                Ok(())
+            })
+        },
+        |state| {
+            for attribute in entry.attributes() {
+                let label = format!("{} {}", attribute.name(), attribute.form());
+                state.field(&label, |w, _hash| {
+                    write!(w, "{}", attribute.value())?;
+                    Ok(())
+                })?;
+            }
+            for child in entry.children() {
+                print_raw_entry(state, child)?;
+            }
+            Ok(())
+        },
+    )
+}
+
+// Print a layout diagram for each struct definition that matches the name filter.
+fn print_layouts(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    let hash = state.hash();
+    for unit in filter::filter_units(file, options) {
+        for ty in filter::filter_types(unit, hash, options, false) {
+            if let TypeKind::Struct(ref t) = *ty.kind() {
+                if !t.is_declaration() {
+                    struct_type::print_layout(t, state)?;
+                    state.line_break()?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// Print a line for each function in the folded stack format that is used by
+// flamegraph tools: the namespaces and name separated by ';', followed by the size.
+/// Print the sections of all members of an archive, merged by name.
+///
+/// The members are still printed individually by `print`.
+pub fn print_archive(
+    path: &str,
+    files: &[&File],
+    printer: &mut dyn Printer,
+    options: &Options,
+) -> Result<()> {
+    let file = match files.first() {
+        Some(file) => file,
+        None => return Ok(()),
+    };
+    let hash = FileHash::new(file);
+    let mut state = PrintState::new(printer, &hash, None, options);
+    let sections = Section::merge(files.iter().flat_map(|file| file.sections()));
+    state.collapsed(
+        |state| {
+            state.line(|w, _hash| {
+                write!(w, "archive {}", path)?;
+                Ok(())
+            })
+        },
+        |state| {
+            state.field_u64("members", files.len() as u64)?;
+            state.field_collapsed("sections", |state| state.list(&(), &sections))
+        },
+    )?;
+    state.line_break()
+}
+
+fn print_flamegraph(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    fn push_frame(name: Option<&str>, stack: &mut String) {
+        // ';' separates frames, and may occur in names such as Rust array types.
+        stack.push_str(&name.unwrap_or("<anon>").replace(';', ","));
+    }
+
+    fn push_namespace(namespace: &Namespace, stack: &mut String) {
+        if let Some(parent) = namespace.parent() {
+            push_namespace(parent, stack);
+        }
+        push_frame(namespace.name(), stack);
+        stack.push(';');
+    }
+
+    for unit in filter::filter_units(file, options) {
+        for function in filter::filter_functions(unit, options) {
+            let size = match function.size() {
+                Some(size) if size != 0 => size,
+                _ => continue,
+            };
+            let mut stack = String::new();
+            if let Some(namespace) = function.namespace() {
+                push_namespace(namespace, &mut stack);
+            }
+            push_frame(function.name(), &mut stack);
+            state.line(|w, _hash| {
+                write!(w, "{} {}", stack, size)?;
+                Ok(())
+            })?;
+        }
+    }
+    Ok(())
+}
+
+// Print the function and source location for each address, with inlined
+// functions first.
+fn print_addresses(state: &mut PrintState, file: &File, options: &Options) -> Result<()> {
+    for &address in &options.addresses {
+        let frames = file.symbolize(state.hash(), address.wrapping_sub(options.load_bias));
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "0x{:x}", address)?;
+                    Ok(())
+                })
+            },
+            |state| {
+                if frames.is_empty() {
+                    return state.line(|w, _hash| {
+                        write!(w, "??")?;
+                        Ok(())
+                    });
+                }
+                for frame in &frames {
+                    state.line(|w, _hash| {
+                        super::function::print_ref(frame.function(), w)?;
+                        if frame.source().is_some() {
+                            write!(w, " at ")?;
+                            super::source::print(frame.source(), w, frame.unit(), options)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            },
+        )?;
+    }
+    Ok(())
 }
 
 pub fn parent_id(id: Id, file: &File) -> Option<usize> {
@@ -445,17 +1136,21 @@ pub fn print_id(
     Ok(())
 }
 
+/// Print the differences between two files.
+///
+/// Returns true if any differences were found.
 pub fn diff(
     printer: &mut dyn Printer,
     file_a: &File,
     file_b: &File,
     options: &Options,
-) -> Result<()> {
+) -> Result<bool> {
     let hash_a = FileHash::new(file_a);
     let hash_b = FileHash::new(file_b);
     let code_a = Code::new(file_a);
     let code_b = Code::new(file_b);
 
+    let counts = DiffCounts::default();
     let mut state = DiffState::new(
         printer,
         &hash_a,
@@ -464,7 +1159,17 @@ pub fn diff(
         code_b.as_ref(),
         options,
     );
+    if options.diff_summary {
+        state.counts = Some(&counts);
+    }
+    diff_file(&mut state, file_a, file_b, options)?;
+    if options.diff_summary {
+        print_diff_summary(&mut state, &counts, file_a, file_b)?;
+    }
+    Ok(state.diff)
+}
 
+fn diff_file(state: &mut DiffState, file_a: &File, file_b: &File, options: &Options) -> Result<()> {
     if options.category_file {
         state.collapsed(
             |state| {
@@ -482,20 +1187,45 @@ pub fn diff(
                 let fn_size_b = file_b.function_size();
                 let var_size_a = file_a.variable_size(state.hash_a());
                 let var_size_b = file_b.variable_size(state.hash_b());
-                let other_size_a = size_a - fn_size_a - var_size_a;
-                let other_size_b = size_b - fn_size_b - var_size_b;
+                let other_size_a = size_a.saturating_sub(fn_size_a).saturating_sub(var_size_a);
+                let other_size_b = size_b.saturating_sub(fn_size_b).saturating_sub(var_size_b);
                 if options.print_file_address {
                     state.field_collapsed("addresses", |state| {
                         state.ord_list(&(), ranges_a.list(), &(), ranges_b.list())
                     })?;
                 }
-                state.field_u64("size", size_a, size_b)?;
-                state.field_u64("fn size", fn_size_a, fn_size_b)?;
-                state.field_u64("var size", var_size_a, var_size_b)?;
-                state.field_u64("other size", other_size_a, other_size_b)?;
-                // TODO: sort sections
+                state.field_size("size", size_a, size_b)?;
+                state.field_size("fn size", fn_size_a, fn_size_b)?;
+                state.field_size("var size", var_size_a, var_size_b)?;
+                state.field_size("other size", other_size_a, other_size_b)?;
+                if options.debug_sizes {
+                    state.field_size("debug size", file_a.debug_size(), file_b.debug_size())?;
+                }
+                let unresolved_types_a = file_a.unresolved_types(state.hash_a()).len() as u64;
+                let unresolved_types_b = file_b.unresolved_types(state.hash_b()).len() as u64;
+                if unresolved_types_a != 0 || unresolved_types_b != 0 {
+                    state.field_u64("unresolved types", unresolved_types_a, unresolved_types_b)?;
+                }
+                let (merged_a, merged_b);
+                let (mut sections_a, mut sections_b): (Vec<_>, Vec<_>) = if options.merge_sections {
+                    merged_a = Section::merge(file_a.sections());
+                    merged_b = Section::merge(file_b.sections());
+                    (merged_a.iter().collect(), merged_b.iter().collect())
+                } else {
+                    (
+                        file_a.sections().iter().collect(),
+                        file_b.sections().iter().collect(),
+                    )
+                };
+                sections_a.sort_by(|a, b| Section::cmp_id(a, b));
+                sections_b.sort_by(|a, b| Section::cmp_id(a, b));
                 state.field_collapsed("sections", |state| {
-                    state.list(&(), file_a.sections(), &(), file_b.sections())
+                    state.list(&(), &sections_a, &(), &sections_b)
+                })?;
+                let producers_a = super::unit::producers(file_a);
+                let producers_b = super::unit::producers(file_b);
+                state.field_collapsed("producers", |state| {
+                    state.ord_list(&(), &producers_a, &(), &producers_b)
                 })?;
                 Ok(())
             },
@@ -503,13 +1233,806 @@ pub fn diff(
         state.line_break()?;
     }
 
-    state.sort_list(
+    if has_debuginfo(file_a) != has_debuginfo(file_b) {
+        return diff_symbol_only(state, file_a, file_b, options);
+    }
+
+    let hash_a = state.hash_a();
+    let hash_b = state.hash_b();
+    state.sort_list_parallel(
         &(),
         &(),
-        &mut merged_units(&hash_a, file_a, &hash_b, file_b, options),
+        &mut merged_units(hash_a, file_a, hash_b, file_b, options),
     )
 }
 
+// Return true if any of the types, functions or variables in the file were
+// created from debuginfo, instead of from the symbol table.
+fn has_debuginfo(file: &File) -> bool {
+    file.units().iter().any(|unit| {
+        !unit.types().is_empty()
+            || unit.functions().iter().any(|f| f.offset().get().is_some())
+            || unit.variables().iter().any(|v| v.offset().get().is_some())
+    })
+}
+
+// Diff two files where only one of them has debuginfo.
+//
+// The entries of the other file can only come from its symbol table, so units
+// can't be paired. Instead, entries in all units are paired by their linkage
+// name, which is the symbol name, and only the fields that are known for symbols
+// are compared.
+fn diff_symbol_only(
+    state: &mut DiffState,
+    file_a: &File,
+    file_b: &File,
+    options: &Options,
+) -> Result<()> {
+    if options.category_type {
+        // Types only come from debuginfo, so there is nothing to pair them with.
+        state.line((), (), |w, _hash, ()| {
+            write!(w, "types: not compared because only one file has debuginfo")?;
+            Ok(())
+        })?;
+        state.line_break()?;
+    }
+    if options.category_function {
+        diff_named_entries(
+            state,
+            &named_functions(file_a, options),
+            &named_functions(file_b, options),
+            super::function::diff_symbol,
+        )?;
+    }
+    if options.category_variable {
+        diff_named_entries(
+            state,
+            &named_variables(file_a, state.hash_a(), options),
+            &named_variables(file_b, state.hash_b(), options),
+            super::variable::diff_symbol,
+        )?;
+    }
+    Ok(())
+}
+
+fn named_functions<'input, 'file>(
+    file: &'file File<'input>,
+    options: &Options,
+) -> Vec<(&'file str, &'file Unit<'input>, &'file Function<'input>)> {
+    named_entries(file, options, |unit| {
+        filter::filter_functions(unit, options)
+            .into_iter()
+            .filter_map(|f| Some((f.linkage_name().or_else(|| f.name())?, f)))
+            .collect()
+    })
+}
+
+fn named_variables<'input, 'file>(
+    file: &'file File<'input>,
+    hash: &FileHash,
+    options: &Options,
+) -> Vec<(&'file str, &'file Unit<'input>, &'file Variable<'input>)> {
+    named_entries(file, options, |unit| {
+        filter::filter_variables(unit, hash, options)
+            .into_iter()
+            .filter_map(|v| Some((v.linkage_name().or_else(|| v.name())?, v)))
+            .collect()
+    })
+}
+
+// Return the entries of all units, sorted by name.
+//
+// Only the first entry with each name is kept.
+fn named_entries<'input, 'file, T, F>(
+    file: &'file File<'input>,
+    options: &Options,
+    entries: F,
+) -> Vec<(&'file str, &'file Unit<'input>, &'file T)>
+where
+    F: Fn(&'file Unit<'input>) -> Vec<(&'file str, &'file T)>,
+{
+    let mut list = Vec::new();
+    for unit in filter::filter_units(file, options) {
+        list.extend(
+            entries(unit)
+                .into_iter()
+                .map(|(name, entry)| (name, unit, entry)),
+        );
+    }
+    list.sort_by_key(|entry| entry.0);
+    list.dedup_by_key(|entry| entry.0);
+    list
+}
+
+fn diff_named_entries<'input, T>(
+    state: &mut DiffState,
+    list_a: &[(&str, &Unit<'input>, &T)],
+    list_b: &[(&str, &Unit<'input>, &T)],
+    diff: fn(&mut DiffState, &T, &T) -> Result<()>,
+) -> Result<()>
+where
+    T: SortList<Arg = Unit<'input>>,
+{
+    for entry in MergeIterator::new(list_a.iter(), list_b.iter(), |a, b| a.0.cmp(b.0)) {
+        match entry {
+            MergeResult::Both((_, _, a), (_, _, b)) => {
+                let diff = state.print_if_diff(|state| diff(state, a, b))?;
+                state.count_changed::<T>(diff);
+            }
+            MergeResult::Left((_, unit, entry)) => {
+                state.count(*entry, Change::Deleted);
+                if !state.options().ignore_deleted {
+                    state.prefix_delete(|state| entry.print(state, unit))?;
+                }
+            }
+            MergeResult::Right((_, unit, entry)) => {
+                state.count(*entry, Change::Added);
+                if !state.options().ignore_added {
+                    state.prefix_add(|state| entry.print(state, unit))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Diff the functions and variables described by the debuginfo of `file_a`
+/// against the symbols of `file_b`.
+///
+/// This is intended for comparing two views of the same binary: `file_a` is
+/// parsed without a `<symtab>` unit, and `file_b` is parsed without debuginfo.
+/// Entries are matched by address, so deleted entries are not covered by a
+/// symbol, and added entries are symbols that are not covered by debuginfo.
+pub fn diff_symbols(
+    printer: &mut dyn Printer,
+    file_a: &File,
+    file_b: &File,
+    options: &Options,
+) -> Result<bool> {
+    let hash_a = FileHash::new(file_a);
+    let hash_b = FileHash::new(file_b);
+    let code_a = Code::new(file_a);
+    let code_b = Code::new(file_b);
+
+    let mut state = DiffState::new(
+        printer,
+        &hash_a,
+        &hash_b,
+        code_a.as_ref(),
+        code_b.as_ref(),
+        options,
+    );
+
+    if options.category_function {
+        let functions = |file| {
+            addressed_entries(file, options, |unit| {
+                filter::filter_functions(unit, options)
+                    .into_iter()
+                    .filter_map(|function| Some((function.address()?, function)))
+                    .collect()
+            })
+        };
+        diff_addressed_entries(&mut state, &functions(file_a), &functions(file_b))?;
+    }
+    if options.category_variable {
+        let variables = |file, hash| {
+            addressed_entries(file, options, |unit| {
+                filter::filter_variables(unit, hash, options)
+                    .into_iter()
+                    .filter_map(|variable| Some((variable.address()?, variable)))
+                    .collect()
+            })
+        };
+        diff_addressed_entries(
+            &mut state,
+            &variables(file_a, &hash_a),
+            &variables(file_b, &hash_b),
+        )?;
+    }
+    Ok(state.diff)
+}
+
+// Return the entries of all units that have an address, sorted by address.
+//
+// Only the first entry at each address is kept.
+fn addressed_entries<'input, 'file, T, F>(
+    file: &'file File<'input>,
+    options: &Options,
+    entries: F,
+) -> Vec<(u64, &'file Unit<'input>, &'file T)>
+where
+    F: Fn(&'file Unit<'input>) -> Vec<(u64, &'file T)>,
+{
+    let mut list = Vec::new();
+    for unit in filter::filter_units(file, options) {
+        list.extend(
+            entries(unit)
+                .into_iter()
+                .map(|(address, entry)| (address, unit, entry)),
+        );
+    }
+    list.sort_by_key(|entry| entry.0);
+    list.dedup_by_key(|entry| entry.0);
+    list
+}
+
+fn diff_addressed_entries<'input, T>(
+    state: &mut DiffState,
+    list_a: &[(u64, &Unit<'input>, &T)],
+    list_b: &[(u64, &Unit<'input>, &T)],
+) -> Result<()>
+where
+    T: SortList<Arg = Unit<'input>>,
+{
+    for entry in MergeIterator::new(list_a.iter(), list_b.iter(), |a, b| a.0.cmp(&b.0)) {
+        match entry {
+            MergeResult::Both(..) => {}
+            MergeResult::Left((_, unit, entry)) => {
+                state.prefix_delete(|state| entry.print(state, unit))?;
+            }
+            MergeResult::Right((_, unit, entry)) => {
+                state.prefix_add(|state| entry.print(state, unit))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Print the differences between two files that break the ABI.
+///
+/// Structs, unions and enumerations are paired by their qualified name, and
+/// external functions by their linkage name. If there are several different
+/// definitions with the same name, then each definition in the first file is
+/// paired with the most similar definition in the second file. A difference
+/// breaks the ABI if it changes the size of a type, the type, offset or size of
+/// a member, the vtable index of a virtual method, the value of an enumerator,
+/// or the signature of a function, or if it removes a member, virtual method or
+/// function. Other differences are benign and are not printed.
+///
+/// Returns true if any differences break the ABI.
+pub fn abi_check(
+    printer: &mut dyn Printer,
+    file_a: &File,
+    file_b: &File,
+    options: &Options,
+) -> Result<bool> {
+    let hash_a = FileHash::new(file_a);
+    let hash_b = FileHash::new(file_b);
+    let entries_a = abi_entries(file_a, &hash_a, options)?;
+    let entries_b = abi_entries(file_b, &hash_b, options)?;
+
+    let mut state = PrintState::new(printer, &hash_b, None, options);
+    let mut breaking = false;
+    for (key, (name, defs_a)) in &entries_a {
+        let mut changes = Vec::new();
+        match entries_b.get(key) {
+            Some((_, defs_b)) => {
+                for a in defs_a {
+                    let closest = defs_b
+                        .iter()
+                        .map(|b| a.changes(b))
+                        .min_by_key(Vec::len)
+                        .unwrap_or_default();
+                    for change in closest {
+                        if !changes.contains(&change) {
+                            changes.push(change);
+                        }
+                    }
+                }
+            }
+            None => match defs_a[0] {
+                // Types may be omitted from the debuginfo if they are unused.
+                AbiEntry::Type { .. } => continue,
+                AbiEntry::Function { .. } => changes.push("removed".to_string()),
+            },
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        breaking = true;
+        state.collapsed(
+            |state| {
+                state.line(|w, _hash| {
+                    write!(w, "{}", name)?;
+                    Ok(())
+                })
+            },
+            |state| {
+                for change in &changes {
+                    state.line(|w, _hash| {
+                        write!(w, "{}", change)?;
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            },
+        )?;
+        state.line_break()?;
+    }
+    Ok(breaking)
+}
+
+// The parts of a type or function that affect the ABI.
+#[derive(PartialEq, Eq)]
+enum AbiEntry {
+    Type {
+        size: Option<u64>,
+        members: Vec<AbiMember>,
+        // The name and vtable index of each virtual method.
+        virtual_methods: Vec<(String, Option<u64>)>,
+        // The name and value of each enumerator.
+        enumerators: Vec<(String, Option<i64>)>,
+    },
+    Function {
+        signature: String,
+    },
+}
+
+// The parts of a member that affect the ABI.
+#[derive(PartialEq, Eq)]
+struct AbiMember {
+    name: String,
+    ty: String,
+    bit_offset: u64,
+    bit_size: Option<u64>,
+}
+
+impl AbiEntry {
+    // Describe the changes from `self` to `other` that break the ABI.
+    fn changes(&self, other: &AbiEntry) -> Vec<String> {
+        fn find<'a, T>(list: &'a [(String, T)], name: &str) -> Option<&'a T> {
+            list.iter().find(|x| x.0 == name).map(|x| &x.1)
+        }
+
+        fn format_opt<T: std::fmt::Display>(val: Option<T>) -> String {
+            match val {
+                Some(val) => val.to_string(),
+                None => "??".to_string(),
+            }
+        }
+
+        let mut changes = Vec::new();
+        match (self, other) {
+            (
+                AbiEntry::Type {
+                    size: size_a,
+                    members: members_a,
+                    virtual_methods: virtual_methods_a,
+                    enumerators: enumerators_a,
+                },
+                AbiEntry::Type {
+                    size: size_b,
+                    members: members_b,
+                    virtual_methods: virtual_methods_b,
+                    enumerators: enumerators_b,
+                },
+            ) => {
+                if size_a != size_b {
+                    changes.push(format!(
+                        "size changed from {} to {}",
+                        format_opt(*size_a),
+                        format_opt(*size_b)
+                    ));
+                }
+                let mut anon_index = 0;
+                for member_a in members_a {
+                    let name = &member_a.name;
+                    let mut members_b = members_b.iter().filter(|member| member.name == *name);
+                    // Anonymous members have no name to pair them by, so pair them
+                    // by their position among the anonymous members.
+                    let member_b = if name == "<anon>" {
+                        anon_index += 1;
+                        members_b.nth(anon_index - 1)
+                    } else {
+                        members_b.next()
+                    };
+                    let member_b = match member_b {
+                        Some(member_b) => member_b,
+                        None => {
+                            changes.push(format!("member {}: removed", name));
+                            continue;
+                        }
+                    };
+                    if member_a.ty != member_b.ty {
+                        changes.push(format!(
+                            "member {}: type changed from {} to {}",
+                            name, member_a.ty, member_b.ty
+                        ));
+                    }
+                    if member_a.bit_offset != member_b.bit_offset {
+                        changes.push(format!(
+                            "member {}: offset changed from {} to {}",
+                            name,
+                            super::member::format_bit(member_a.bit_offset),
+                            super::member::format_bit(member_b.bit_offset)
+                        ));
+                    }
+                    if member_a.bit_size != member_b.bit_size {
+                        changes.push(format!(
+                            "member {}: size changed from {} to {}",
+                            name,
+                            format_opt(member_a.bit_size.map(super::member::format_bit)),
+                            format_opt(member_b.bit_size.map(super::member::format_bit))
+                        ));
+                    }
+                }
+                for (name, index_a) in virtual_methods_a {
+                    match find(virtual_methods_b, name) {
+                        Some(index_b) if index_a != index_b => changes.push(format!(
+                            "virtual {}: vtable index changed from {} to {}",
+                            name,
+                            format_opt(*index_a),
+                            format_opt(*index_b)
+                        )),
+                        Some(_) => {}
+                        None => changes.push(format!("virtual {}: removed", name)),
+                    }
+                }
+                for (name, value_a) in enumerators_a {
+                    // Removing an enumerator doesn't change the values of the others.
+                    if let Some(value_b) = find(enumerators_b, name) {
+                        if value_a != value_b {
+                            changes.push(format!(
+                                "enumerator {}: value changed from {} to {}",
+                                name,
+                                format_opt(*value_a),
+                                format_opt(*value_b)
+                            ));
+                        }
+                    }
+                }
+            }
+            (
+                AbiEntry::Function {
+                    signature: signature_a,
+                },
+                AbiEntry::Function {
+                    signature: signature_b,
+                },
+            ) if signature_a != signature_b => {
+                changes.push(format!(
+                    "signature changed from {} to {}",
+                    signature_a, signature_b
+                ));
+            }
+            _ => {}
+        }
+        changes
+    }
+}
+
+// Return the structs, unions, enumerations and external functions of all units,
+// keyed by their kind and name, with the name to print.
+//
+// Each distinct definition with the same key is kept, since the same name may be
+// used for unrelated types in different units.
+fn abi_entries(
+    file: &File,
+    hash: &FileHash,
+    options: &Options,
+) -> Result<BTreeMap<String, (String, Vec<AbiEntry>)>> {
+    fn type_name(ty: Option<Cow<Type>>, hash: &FileHash) -> Result<String> {
+        let mut name = Vec::new();
+        let mut w = TextValuePrinter::new(&mut name);
+        super::types::print_ref(ty, &mut w, hash)?;
+        Ok(String::from_utf8_lossy(&name).into_owned())
+    }
+
+    fn members(members: &[Member], hash: &FileHash) -> Result<Vec<AbiMember>> {
+        members
+            .iter()
+            .filter(|member| !member.is_static())
+            .map(|member| {
+                Ok(AbiMember {
+                    name: member.name().unwrap_or("<anon>").to_string(),
+                    ty: type_name(member.ty(hash), hash)?,
+                    bit_offset: member.bit_offset(),
+                    bit_size: member.bit_size(hash),
+                })
+            })
+            .collect()
+    }
+
+    fn insert(
+        entries: &mut BTreeMap<String, (String, Vec<AbiEntry>)>,
+        key: String,
+        name: String,
+        entry: AbiEntry,
+    ) {
+        let defs = &mut entries.entry(key).or_insert_with(|| (name, Vec::new())).1;
+        if !defs.contains(&entry) {
+            defs.push(entry);
+        }
+    }
+
+    let mut entries = BTreeMap::new();
+    for unit in filter::filter_units(file, options) {
+        if options.category_type {
+            for ty in filter::filter_types(unit, hash, options, true) {
+                let (kind, name, entry) = match *ty.kind() {
+                    TypeKind::Struct(ref val) if !val.is_declaration() => (
+                        "struct",
+                        filter::qualified_name(val.namespace(), val.name()),
+                        AbiEntry::Type {
+                            size: val.byte_size(),
+                            members: members(val.members(), hash)?,
+                            virtual_methods: val
+                                .virtual_methods(hash)
+                                .iter()
+                                .map(|f| {
+                                    (f.name().unwrap_or("<anon>").to_string(), f.vtable_index())
+                                })
+                                .collect(),
+                            enumerators: Vec::new(),
+                        },
+                    ),
+                    TypeKind::Union(ref val) if !val.is_declaration() => (
+                        "union",
+                        filter::qualified_name(val.namespace(), val.name()),
+                        AbiEntry::Type {
+                            size: val.byte_size(),
+                            members: members(val.members(), hash)?,
+                            virtual_methods: Vec::new(),
+                            enumerators: Vec::new(),
+                        },
+                    ),
+                    TypeKind::Enumeration(ref val) if !val.is_declaration() => (
+                        "enum",
+                        filter::qualified_name(val.namespace(), val.name()),
+                        AbiEntry::Type {
+                            size: val.byte_size(hash),
+                            members: Vec::new(),
+                            virtual_methods: Vec::new(),
+                            enumerators: val
+                                .enumerators(hash)
+                                .iter()
+                                .map(|e| (e.name().unwrap_or("<anon>").to_string(), e.value()))
+                                .collect(),
+                        },
+                    ),
+                    _ => continue,
+                };
+                // Anonymous types can't be paired by name.
+                if ty.is_anon() {
+                    continue;
+                }
+                let name = format!("{} {}", kind, name);
+                insert(&mut entries, name.clone(), name, entry);
+            }
+        }
+        if options.category_function {
+            for function in filter::filter_functions(unit, options) {
+                // Only functions that are defined and visible to other units are
+                // part of the ABI.
+                if !function.is_external() || function.address().is_none() {
+                    continue;
+                }
+                let key = match function.linkage_name().or_else(|| function.name()) {
+                    Some(key) => format!("fn {}", key),
+                    None => continue,
+                };
+                let mut signature = String::from("fn(");
+                for (i, parameter) in function.parameters().iter().enumerate() {
+                    if i != 0 {
+                        signature.push_str(", ");
+                    }
+                    signature.push_str(&type_name(parameter.ty(hash), hash)?);
+                }
+                if function.is_variadic() {
+                    if !function.parameters().is_empty() {
+                        signature.push_str(", ");
+                    }
+                    signature.push_str("...");
+                }
+                signature.push(')');
+                if let Some(return_type) = function.return_type(hash) {
+                    if !return_type.is_void() {
+                        signature.push_str(" -> ");
+                        signature.push_str(&type_name(Some(return_type), hash)?);
+                    }
+                }
+                let name = format!(
+                    "fn {}",
+                    filter::qualified_name(function.namespace(), function.name())
+                );
+                insert(&mut entries, key, name, AbiEntry::Function { signature });
+            }
+        }
+    }
+    Ok(entries)
+}
+
+// Print a single line summarizing the number of changed entries and the change
+// in total size.
+//
+// The counts are updated while diffing, so this must be called after that.
+fn print_diff_summary(
+    state: &mut DiffState,
+    counts: &DiffCounts,
+    file_a: &File,
+    file_b: &File,
+) -> Result<()> {
+    let hash_a = state.hash_a();
+    let hash_b = state.hash_b();
+    let options = state.options();
+    let size_a = file_a.ranges(hash_a).size();
+    let size_b = file_b.ranges(hash_b).size();
+    state.line((), (), |w, _hash, ()| {
+        write!(w, "summary: ")?;
+        if options.category_function {
+            counts.functions.print(w, "functions")?;
+        }
+        if options.category_type {
+            counts.types.print(w, "types")?;
+        }
+        if options.category_variable {
+            counts.variables.print(w, "variables")?;
+        }
+        write!(w, "total size ")?;
+        if size_b >= size_a {
+            write!(w, "+")?;
+            w.size(size_b - size_a)?;
+        } else {
+            write!(w, "-")?;
+            w.size(size_a - size_b)?;
+        }
+        Ok(())
+    })?;
+    state.line_break()
+}
+
+/// Print a comparison of the sizes of the entries in any number of files.
+///
+/// Entries are paired by name, and each entry is printed with a column
+/// of sizes for each file.
+///
+/// Returns true if any sizes differ.
+pub fn compare(printer: &mut dyn Printer, files: &[&File], options: &Options) -> Result<bool> {
+    let hashes: Vec<_> = files.iter().map(|file| FileHash::new(file)).collect();
+    let mut state = CompareState::new(printer, options);
+
+    if options.category_file {
+        state.collapsed(
+            |w| {
+                write!(w, "file ")?;
+                for (i, file) in files.iter().enumerate() {
+                    if i != 0 {
+                        write!(w, " | ")?;
+                    }
+                    write!(w, "{}", file.path())?;
+                }
+                Ok(())
+            },
+            |state| {
+                let mut sizes = Vec::new();
+                let mut fn_sizes = Vec::new();
+                let mut var_sizes = Vec::new();
+                let mut other_sizes = Vec::new();
+                for (file, hash) in files.iter().zip(&hashes) {
+                    let size = file.ranges(hash).size();
+                    let fn_size = file.function_size();
+                    let var_size = file.variable_size(hash);
+                    sizes.push(Some(Some(size)));
+                    fn_sizes.push(Some(Some(fn_size)));
+                    var_sizes.push(Some(Some(var_size)));
+                    other_sizes.push(Some(Some(
+                        size.saturating_sub(fn_size).saturating_sub(var_size),
+                    )));
+                }
+                state.field_sizes("size", &sizes)?;
+                state.field_sizes("fn size", &fn_sizes)?;
+                state.field_sizes("var size", &var_sizes)?;
+                state.field_sizes("other size", &other_sizes)?;
+                Ok(())
+            },
+        )?;
+        state.line_break()?;
+    }
+
+    if options.category_type {
+        let types = compare_sizes(files, &hashes, options, |unit, hash, f| {
+            for ty in filter::filter_types(unit, hash, options, true) {
+                // Anonymous types can't be paired by name.
+                if ty.is_anon() || super::types::kind(ty).is_err() {
+                    continue;
+                }
+                let mut name = Vec::new();
+                let mut w = TextValuePrinter::new(&mut name);
+                super::types::print_ref(Some(Cow::Borrowed(ty)), &mut w, hash)?;
+                f(name, ty.byte_size(hash));
+            }
+            Ok(())
+        })?;
+        print_compare_sizes(&mut state, "types", &types)?;
+    }
+
+    if options.category_function {
+        let functions = compare_sizes(files, &hashes, options, |unit, _hash, f| {
+            for function in filter::filter_functions(unit, options) {
+                if function.name().is_none() {
+                    continue;
+                }
+                let mut name = Vec::new();
+                let mut w = TextValuePrinter::new(&mut name);
+                super::function::print_ref(function, &mut w)?;
+                f(name, function.size());
+            }
+            Ok(())
+        })?;
+        print_compare_sizes(&mut state, "functions", &functions)?;
+    }
+
+    if options.category_variable {
+        let variables = compare_sizes(files, &hashes, options, |unit, hash, f| {
+            for variable in filter::filter_variables(unit, hash, options) {
+                if variable.name().is_none() {
+                    continue;
+                }
+                let mut name = Vec::new();
+                let mut w = TextValuePrinter::new(&mut name);
+                super::variable::print_ref(variable, &mut w)?;
+                f(name, variable.byte_size(hash));
+            }
+            Ok(())
+        })?;
+        print_compare_sizes(&mut state, "variables", &variables)?;
+    }
+
+    Ok(state.diff())
+}
+
+// Map the name of each item to its size in each file.
+//
+// `items` is called for each unit, and must call its argument with the name
+// and size of each item in the unit. If an item occurs more than once in a
+// file, such as types that are defined in multiple units, then the first
+// occurrence is used.
+fn compare_sizes<F>(
+    files: &[&File],
+    hashes: &[FileHash],
+    options: &Options,
+    mut items: F,
+) -> Result<BTreeMap<Vec<u8>, Vec<Option<Option<u64>>>>>
+where
+    F: FnMut(&Unit, &FileHash, &mut dyn FnMut(Vec<u8>, Option<u64>)) -> Result<()>,
+{
+    let mut map = BTreeMap::new();
+    for (i, (file, hash)) in files.iter().zip(hashes).enumerate() {
+        for unit in filter::filter_units(file, options) {
+            items(unit, hash, &mut |name, size| {
+                let sizes = map.entry(name).or_insert_with(|| vec![None; files.len()]);
+                if sizes[i].is_none() {
+                    sizes[i] = Some(size);
+                }
+            })?;
+        }
+    }
+    Ok(map)
+}
+
+fn print_compare_sizes(
+    state: &mut CompareState,
+    label: &str,
+    items: &BTreeMap<Vec<u8>, Vec<Option<Option<u64>>>>,
+) -> Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    state.collapsed(
+        |w| {
+            write!(w, "{}", label)?;
+            Ok(())
+        },
+        |state| {
+            for (name, sizes) in items {
+                state.field_sizes(&String::from_utf8_lossy(name), sizes)?;
+            }
+            Ok(())
+        },
+    )?;
+    state.line_break()
+}
+
 pub fn diff_id(
     id: (Id, Id),
     file_a: &File,
@@ -542,7 +2065,7 @@ pub fn diff_id(
             );
             Some(super::unit::diff_body(&mut state, unit_a, unit_b))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (Id::Unit { unit_index }, Id::None) => || -> Option<Result<()>> {
             let unit = file_a.units().get(unit_index)?;
             let hash = FileHash::new(file_a);
@@ -550,7 +2073,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(super::unit::print_body(unit, &mut state))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (Id::None, Id::Unit { unit_index }) => || -> Option<Result<()>> {
             let unit = file_b.units().get(unit_index)?;
             let hash = FileHash::new(file_b);
@@ -558,7 +2081,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(super::unit::print_body(unit, &mut state))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Type {
                 unit_index: unit_index_a,
@@ -589,7 +2112,7 @@ pub fn diff_id(
                 &mut state, unit_a, type_a, unit_b, type_b,
             ))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Type {
                 unit_index,
@@ -604,7 +2127,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(super::types::kind(ty).and_then(|kind| kind.print_body(&mut state, unit)))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::None,
             Id::Type {
@@ -619,7 +2142,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(super::types::kind(ty).and_then(|kind| kind.print_body(&mut state, unit)))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Function {
                 unit_index: unit_index_a,
@@ -650,7 +2173,7 @@ pub fn diff_id(
                 &mut state, unit_a, function_a, unit_b, function_b,
             ))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Function {
                 unit_index,
@@ -665,7 +2188,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(function.print_body(&mut state, unit))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::None,
             Id::Function {
@@ -680,7 +2203,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(function.print_body(&mut state, unit))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Variable {
                 unit_index: unit_index_a,
@@ -711,7 +2234,7 @@ pub fn diff_id(
                 &mut state, unit_a, variable_a, unit_b, variable_b,
             ))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::Variable {
                 unit_index,
@@ -726,7 +2249,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(variable.print_body(&mut state, unit))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
+        .unwrap_or(Err(Error::from("invalid id"))),
         (
             Id::None,
             Id::Variable {
@@ -741,7 +2264,7 @@ pub fn diff_id(
             let mut state = PrintState::new(printer, &hash, code.as_ref(), options);
             Some(variable.print_body(&mut state, unit))
         }()
-        .unwrap_or(Err(Error("invalid id".into()))),
-        _ => Err(Error("invalid id".into())),
+        .unwrap_or(Err(Error::from("invalid id"))),
+        _ => Err(Error::from("invalid id")),
     }
 }
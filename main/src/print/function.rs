@@ -2,12 +2,15 @@ use std::cmp;
 
 use parser::{
     Cfi, CfiDirective, FileHash, Function, FunctionDetails, InlinedFunction, LocalVariable,
-    Parameter, ParameterType, Range, Type, TypeOffset, Unit,
+    Namespace, Parameter, ParameterType, Range, Register, SectionKind, Type, TypeOffset, Unit,
+    Virtuality,
 };
 
 use crate::code::{Call, Code};
+use crate::filter;
 use crate::print::{
-    self, DiffList, DiffState, Print, PrintHeader, PrintState, SortList, ValuePrinter,
+    self, ChangeCounts, DiffCounts, DiffList, DiffState, Print, PrintHeader, PrintState, SortList,
+    ValuePrinter,
 };
 use crate::{Options, Result, Sort};
 
@@ -30,6 +33,51 @@ fn print_name(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     Ok(())
 }
 
+fn print_signature(f: &Function, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    print_name(f, w)?;
+    write!(w, "(")?;
+    let details = f.details(hash);
+    for (i, parameter) in details.parameters().iter().enumerate() {
+        if i != 0 {
+            write!(w, ", ")?;
+        }
+        print::parameter::print_decl(parameter, w, hash)?;
+    }
+    if f.is_variadic() {
+        if !details.parameters().is_empty() {
+            write!(w, ", ")?;
+        }
+        write!(w, "...")?;
+    }
+    write!(w, ")")?;
+    let ty = f.return_type(hash);
+    if ty.as_ref().map(|t| t.is_void()) != Some(true) {
+        write!(w, " -> ")?;
+        print::types::print_ref(ty, w, hash)?;
+    }
+    Ok(())
+}
+
+fn print_header(
+    f: &Function,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+    options: &Options,
+) -> Result<()> {
+    if options.print_function_signature {
+        print_signature(f, w, hash)
+    } else {
+        print_name(f, w)
+    }
+}
+
+fn print_variadic(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
+    if f.is_variadic() {
+        write!(w, "...")?;
+    }
+    Ok(())
+}
+
 fn print_linkage_name(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     if let Some(linkage_name) = f.linkage_name() {
         write!(w, "{}", linkage_name)?;
@@ -44,8 +92,13 @@ fn print_symbol_name(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     Ok(())
 }
 
-fn print_source(f: &Function, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(f.source(), w, unit)
+fn print_source(
+    f: &Function,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(f.source(), w, unit, options)
 }
 
 fn print_address(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
@@ -57,7 +110,236 @@ fn print_address(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
 
 fn print_size(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     if let Some(size) = f.size() {
-        write!(w, "{}", size)?;
+        w.size(size)?;
+    }
+    Ok(())
+}
+
+// The size of the function as a percentage of the size of all functions,
+// if `options.percent` is set.
+fn size_percent(f: &Function, hash: &FileHash, options: &Options) -> Option<f64> {
+    if !options.percent {
+        return None;
+    }
+    print::percent(f.size()?, hash.function_size())
+}
+
+// Print the number of instructions decoded from the function's code,
+// or `n/a` if the function is not within an executable section.
+fn print_instruction_count(
+    f: &Function,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+    code: Option<&Code>,
+) -> Result<()> {
+    let range = match f.range() {
+        Some(range) => range,
+        None => return Ok(()),
+    };
+    let count = code
+        .filter(|_| hash.file.classify_address(range.begin) == Some(SectionKind::Code))
+        .and_then(|code| code.instruction_count(range));
+    match count {
+        Some(count) => write!(w, "{}", count)?,
+        None => write!(w, "n/a")?,
+    }
+    Ok(())
+}
+
+// Print `<inlined>` instead of the address for a function that is only inlined.
+fn print_address_or_inlined(
+    f: &Function,
+    inlined: Option<(usize, u64)>,
+    w: &mut dyn ValuePrinter,
+) -> Result<()> {
+    if inlined.is_some() {
+        write!(w, "<inlined>")?;
+        return Ok(());
+    }
+    print_address(f, w)
+}
+
+// Print the total size of the inlined instances for a function that is only inlined.
+fn print_size_or_inlined(
+    f: &Function,
+    inlined: Option<(usize, u64)>,
+    w: &mut dyn ValuePrinter,
+) -> Result<()> {
+    if let Some((count, size)) = inlined {
+        w.size(size)?;
+        let times = if count == 1 { "time" } else { "times" };
+        write!(w, " (inlined {} {})", count, times)?;
+        return Ok(());
+    }
+    print_size(f, w)
+}
+
+/// The number and total size of the inlined instances of `f` within `unit`.
+///
+/// Returns `None` if there are no inlined instances.
+fn inlined_instances(f: &Function, unit: &Unit, hash: &FileHash) -> Option<(usize, u64)> {
+    fn visit(
+        f: &Function,
+        inlined_functions: &[InlinedFunction],
+        hash: &FileHash,
+        count: &mut usize,
+        size: &mut u64,
+    ) {
+        for inlined_function in inlined_functions {
+            if matches!(inlined_function.abstract_origin(hash), Some(origin) if std::ptr::eq(origin, f))
+            {
+                *count += 1;
+                *size += inlined_function.size().unwrap_or(0);
+            }
+            visit(f, inlined_function.inlined_functions(), hash, count, size);
+        }
+    }
+
+    let mut count = 0;
+    let mut size = 0;
+    for function in unit.functions() {
+        // Functions without an address only contain the abstract instances.
+        if function.address().is_none() {
+            continue;
+        }
+        let details = function.details(hash);
+        visit(f, details.inlined_functions(), hash, &mut count, &mut size);
+    }
+    if count == 0 {
+        None
+    } else {
+        Some((count, size))
+    }
+}
+
+fn print_frame_size(f: &Function, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(frame_size) = f.frame_size(hash) {
+        w.size(frame_size)?;
+    }
+    Ok(())
+}
+
+fn print_unwind(f: &Function, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if f.address().is_some() {
+        if f.has_cfi(hash) {
+            write!(w, "present")?;
+        } else {
+            write!(w, "absent")?;
+        }
+    }
+    Ok(())
+}
+
+// The unwind rules of a function, summarized from its CFI.
+#[derive(Debug, Default)]
+struct CfiSummary {
+    // The CFA rule at function entry.
+    cfa: Option<(Register, i64)>,
+    // The return address column, and its CFA offset at function entry if it is
+    // saved on the stack.
+    return_address: Option<(Register, Option<i64>)>,
+    // The CFA offset of each register that the function saves, in the order
+    // that they are saved.
+    saved_registers: Vec<(Register, i64)>,
+}
+
+fn cfi_summary(f: &Function, hash: &FileHash) -> Option<CfiSummary> {
+    let begin = f.address()?;
+    let cfi = f.cfi(hash);
+    if cfi.is_empty() {
+        return None;
+    }
+    let mut summary = CfiSummary::default();
+    let mut return_column = None;
+    for (address, directive) in cfi {
+        let entry = address.get().map(|x| x == begin).unwrap_or(true);
+        match directive {
+            CfiDirective::ReturnColumn(register) => return_column = Some(register),
+            CfiDirective::DefCfa(register, offset) if entry => {
+                summary.cfa = Some((register, offset));
+            }
+            CfiDirective::DefCfaRegister(register) if entry => {
+                let offset = summary.cfa.map(|cfa| cfa.1).unwrap_or(0);
+                summary.cfa = Some((register, offset));
+            }
+            CfiDirective::DefCfaOffset(offset) if entry => {
+                if let Some(cfa) = summary.cfa.as_mut() {
+                    cfa.1 = offset;
+                }
+            }
+            CfiDirective::Offset(register, offset) => {
+                if Some(register) == return_column {
+                    if entry {
+                        summary.return_address = Some((register, Some(offset)));
+                    }
+                } else if summary.saved_registers.iter().all(|x| x.0 != register) {
+                    summary.saved_registers.push((register, offset));
+                }
+            }
+            _ => {}
+        }
+    }
+    if summary.return_address.is_none() {
+        summary.return_address = return_column.map(|register| (register, None));
+    }
+    Some(summary)
+}
+
+fn print_cfa_offset(offset: i64, w: &mut dyn ValuePrinter) -> Result<()> {
+    if offset < 0 {
+        write!(w, "-0x{:x}", -offset)?;
+    } else {
+        write!(w, "+0x{:x}", offset)?;
+    }
+    Ok(())
+}
+
+fn print_cfa(
+    summary: &Option<CfiSummary>,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+) -> Result<()> {
+    if let Some((register, offset)) = summary.as_ref().and_then(|x| x.cfa) {
+        print::register::print(register, w, hash)?;
+        print_cfa_offset(offset, w)?;
+    }
+    Ok(())
+}
+
+fn print_return_address(
+    summary: &Option<CfiSummary>,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+) -> Result<()> {
+    match summary.as_ref().and_then(|x| x.return_address) {
+        Some((_, Some(offset))) => {
+            write!(w, "[cfa")?;
+            print_cfa_offset(offset, w)?;
+            write!(w, "]")?;
+        }
+        Some((register, None)) => print::register::print(register, w, hash)?,
+        None => {}
+    }
+    Ok(())
+}
+
+fn print_saved_registers(
+    summary: &Option<CfiSummary>,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+) -> Result<()> {
+    let saved_registers = match summary {
+        Some(summary) => &summary.saved_registers,
+        None => return Ok(()),
+    };
+    for (i, &(register, offset)) in saved_registers.iter().enumerate() {
+        if i != 0 {
+            write!(w, ", ")?;
+        }
+        print::register::print(register, w, hash)?;
+        write!(w, "=[cfa")?;
+        print_cfa_offset(offset, w)?;
+        write!(w, "]")?;
     }
     Ok(())
 }
@@ -69,6 +351,28 @@ fn print_inline(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     Ok(())
 }
 
+fn print_linkage(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
+    // Functions from the symbol table have no DW_AT_external.
+    if f.offset().get().is_none() {
+        return Ok(());
+    }
+    if f.is_external() {
+        write!(w, "external")?;
+    } else {
+        write!(w, "static")?;
+    }
+    Ok(())
+}
+
+fn print_virtuality(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
+    match f.virtuality() {
+        Some(Virtuality::Virtual) => write!(w, "yes")?,
+        Some(Virtuality::PureVirtual) => write!(w, "pure")?,
+        None => {}
+    }
+    Ok(())
+}
+
 fn print_declaration(f: &Function, w: &mut dyn ValuePrinter) -> Result<()> {
     if f.is_declaration() {
         write!(w, "yes")?;
@@ -89,26 +393,77 @@ fn print_return_type(f: &Function, w: &mut dyn ValuePrinter, hash: &FileHash) ->
     Ok(())
 }
 
+/// Print a function that was matched by `DiffState::match_renamed_functions`.
+pub(crate) fn diff_renamed(state: &mut DiffState, a: &Function, b: &Function) -> Result<()> {
+    state.line_modify(|w| {
+        write!(w, "renamed ")?;
+        print_ref(a, w)?;
+        write!(w, " -> ")?;
+        print_ref(b, w)?;
+        write!(w, " (unchanged)")?;
+        Ok(())
+    })?;
+    state.line_break()
+}
+
 impl<'input> PrintHeader for Function<'input> {
     fn print_header(&self, state: &mut PrintState) -> Result<()> {
-        state.line(|w, _state| print_name(self, w))
+        let options = state.options();
+        state.line(|w, state| print_header(self, w, state, options))
     }
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
+        if state.options().print_offset {
+            state.field("offset", |w, _state| {
+                print::print_offset(self.offset().get(), w)
+            })?;
+        }
         state.field("linkage name", |w, _state| print_linkage_name(self, w))?;
         state.field("symbol name", |w, _state| print_symbol_name(self, w))?;
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
         }
         state.field("address", |w, _state| print_address(self, w))?;
-        state.field("size", |w, _state| print_size(self, w))?;
+        let percent = size_percent(self, state.hash(), state.options());
+        state.field("size", |w, _state| {
+            print_size(self, w)?;
+            print::print_percent(percent, None, w)
+        })?;
+        if state.options().print_function_instruction_count {
+            let code = state.code;
+            state.field("instructions", |w, hash| {
+                print_instruction_count(self, w, hash, code)
+            })?;
+        }
+        state.field("frame size", |w, state| print_frame_size(self, w, state))?;
         state.field("inline", |w, _state| print_inline(self, w))?;
+        state.field("unwind", |w, state| print_unwind(self, w, state))?;
+        if state.options().print_function_cfi {
+            let cfi = cfi_summary(self, state.hash());
+            if cfi.is_some() {
+                state.field_collapsed("cfi", |state| {
+                    state.field("cfa", |w, hash| print_cfa(&cfi, w, hash))?;
+                    state.field("return address", |w, hash| {
+                        print_return_address(&cfi, w, hash)
+                    })?;
+                    state.field("saved registers", |w, hash| {
+                        print_saved_registers(&cfi, w, hash)
+                    })
+                })?;
+            }
+        }
+        state.field("virtual", |w, _state| print_virtuality(self, w))?;
         state.field("declaration", |w, _state| print_declaration(self, w))?;
+        state.field("linkage", |w, _state| print_linkage(self, w))?;
         state.field_expanded("return type", |state| {
             state.line(|w, state| print_return_type(self, w, state))
         })?;
         let details = self.details(state.hash());
-        state.field_expanded("parameters", |state| state.list(unit, details.parameters()))?;
+        state.field_expanded("parameters", |state| {
+            state.list(unit, details.parameters())?;
+            state.line(|w, _state| print_variadic(self, w))
+        })?;
         if state.options().print_function_variables {
             state.field_collapsed("variables", |state| state.list(unit, details.variables()))?;
         }
@@ -134,7 +489,12 @@ impl<'input> PrintHeader for Function<'input> {
     }
 
     fn diff_header(state: &mut DiffState, a: &Self, b: &Self) -> Result<()> {
-        state.line(a, b, |w, _state, x| print_name(x, w))
+        let options = state.options();
+        // The names can only differ by discriminators, which are not a change.
+        let flag = options.canonical_names && !options.print_function_signature;
+        state.ignore_diff(flag, |state| {
+            state.line(a, b, |w, state, x| print_header(x, w, state, options))
+        })
     }
 
     fn diff_body(
@@ -144,6 +504,14 @@ impl<'input> PrintHeader for Function<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
+        if state.options().print_offset {
+            // Offsets are expected to differ, so they are not a change.
+            state.ignore_diff(true, |state| {
+                state.field("offset", a, b, |w, _state, x| {
+                    print::print_offset(x.offset().get(), w)
+                })
+            })?;
+        }
         let flag = state.options().ignore_function_linkage_name;
         state.ignore_diff(flag, |state| {
             state.field("linkage name", a, b, |w, _state, x| {
@@ -154,27 +522,94 @@ impl<'input> PrintHeader for Function<'input> {
         state.ignore_diff(flag, |state| {
             state.field("symbol name", a, b, |w, _state, x| print_symbol_name(x, w))
         })?;
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
+        // A function that is out-of-line in one file may only be inlined in the
+        // other file, so report that instead of a removed address and size.
+        let (inlined_a, inlined_b) = match (a.address(), b.address()) {
+            (None, Some(_)) => (inlined_instances(a, unit_a, state.hash_a()), None),
+            (Some(_), None) => (None, inlined_instances(b, unit_b, state.hash_b())),
+            _ => (None, None),
+        };
         let flag = state.options().ignore_function_address;
         state.ignore_diff(flag, |state| {
-            state.field("address", a, b, |w, _state, x| print_address(x, w))
+            state.field(
+                "address",
+                (a, inlined_a),
+                (b, inlined_b),
+                |w, _state, (x, inlined)| print_address_or_inlined(x, inlined, w),
+            )
         })?;
-        let flag = state.options().ignore_function_size;
+        let options = state.options();
+        // A change in percentage alone doesn't mean that the function changed.
+        let flag = options.ignore_function_size
+            || (options.percent && (a.size(), inlined_a) == (b.size(), inlined_b));
+        let base = if inlined_a.is_none() {
+            size_percent(a, state.hash_a(), options)
+        } else {
+            None
+        };
         state.ignore_diff(flag, |state| {
-            state.field("size", a, b, |w, _state, x| print_size(x, w))
+            state.field(
+                "size",
+                (a, inlined_a),
+                (b, inlined_b),
+                |w, hash, (x, inlined)| {
+                    print_size_or_inlined(x, inlined, w)?;
+                    if inlined.is_none() {
+                        print::print_percent(size_percent(x, hash, options), base, w)?;
+                    }
+                    Ok(())
+                },
+            )
+        })?;
+        if state.options().print_function_instruction_count {
+            let flag = state.options().ignore_function_size;
+            state.ignore_diff(flag, |state| {
+                state.field(
+                    "instructions",
+                    (a, state.code_a),
+                    (b, state.code_b),
+                    |w, hash, (x, code)| print_instruction_count(x, w, hash, code),
+                )
+            })?;
+        }
+        let flag = state.options().ignore_function_frame_size;
+        state.ignore_diff(flag, |state| {
+            state.field("frame size", a, b, |w, state, x| {
+                print_frame_size(x, w, state)
+            })
         })?;
         let flag = state.options().ignore_function_inline;
         state.ignore_diff(flag, |state| {
             state.field("inline", a, b, |w, _state, x| print_inline(x, w))
         })?;
+        state.field("unwind", a, b, |w, state, x| print_unwind(x, w, state))?;
+        if state.options().print_function_cfi {
+            let cfi_a = cfi_summary(a, state.hash_a());
+            let cfi_b = cfi_summary(b, state.hash_b());
+            if cfi_a.is_some() || cfi_b.is_some() {
+                state.field_collapsed("cfi", |state| {
+                    state.field("cfa", &cfi_a, &cfi_b, |w, hash, x| print_cfa(x, w, hash))?;
+                    state.field("return address", &cfi_a, &cfi_b, |w, hash, x| {
+                        print_return_address(x, w, hash)
+                    })?;
+                    state.field("saved registers", &cfi_a, &cfi_b, |w, hash, x| {
+                        print_saved_registers(x, w, hash)
+                    })
+                })?;
+            }
+        }
+        state.field("virtual", a, b, |w, _state, x| print_virtuality(x, w))?;
         state.field("declaration", a, b, |w, _state, x| print_declaration(x, w))?;
+        state.field("linkage", a, b, |w, _state, x| print_linkage(x, w))?;
         state.field_expanded("return type", |state| {
             state.line(a, b, |w, state, x| print_return_type(x, w, state))
         })?;
@@ -186,7 +621,8 @@ impl<'input> PrintHeader for Function<'input> {
                 details_a.parameters(),
                 unit_b,
                 details_b.parameters(),
-            )
+            )?;
+            state.line(a, b, |w, _state, x| print_variadic(x, w))
         })?;
         if state.options().print_function_variables {
             let mut variables_a: Vec<_> = details_a.variables().iter().collect();
@@ -233,6 +669,32 @@ impl<'input> PrintHeader for Function<'input> {
     }
 }
 
+// Diff a function from the debuginfo of one file against a function from the
+// symbol table of another file.
+//
+// Only the fields that are known for symbols are compared.
+pub(crate) fn diff_symbol(state: &mut DiffState, a: &Function, b: &Function) -> Result<()> {
+    state.collapsed(
+        |state| {
+            // The symbol name may be mangled, so the names are not a change.
+            state.ignore_diff(true, |state| PrintHeader::diff_header(state, a, b))
+        },
+        |state| {
+            let flag = state.options().ignore_function_address;
+            state.ignore_diff(flag, |state| {
+                state.field("address", a, b, |w, _state, x| {
+                    print::print_address_presence(x.address(), w)
+                })
+            })?;
+            let flag = state.options().ignore_function_size;
+            state.ignore_diff(flag, |state| {
+                state.field("size", a, b, |w, _state, x| print_size(x, w))
+            })
+        },
+    )?;
+    state.line_break()
+}
+
 impl<'input> Print for Function<'input> {
     type Arg = Unit<'input>;
 
@@ -265,13 +727,13 @@ impl<'input> Print for Function<'input> {
 
 impl<'input> SortList for Function<'input> {
     fn cmp_id(
-        hash_a: &FileHash,
+        _hash_a: &FileHash,
         a: &Self,
-        hash_b: &FileHash,
+        _hash_b: &FileHash,
         b: &Self,
-        _options: &Options,
+        options: &Options,
     ) -> cmp::Ordering {
-        Function::cmp_id(hash_a, a, hash_b, b)
+        filter::cmp_ns_and_name(a.namespace(), a.name(), b.namespace(), b.name(), options)
     }
 
     // This function is a bit of a hack. We use it for sorting, but not for
@@ -283,9 +745,9 @@ impl<'input> SortList for Function<'input> {
         a: &Self,
         hash_b: &FileHash,
         b: &Self,
-        _options: &Options,
+        options: &Options,
     ) -> cmp::Ordering {
-        let ord = Function::cmp_id(hash_a, a, hash_b, b);
+        let ord = <Self as SortList>::cmp_id(hash_a, a, hash_b, b, options);
         if ord != cmp::Ordering::Equal {
             return ord;
         }
@@ -297,7 +759,10 @@ impl<'input> SortList for Function<'input> {
             }
         }
 
-        a.parameters().len().cmp(&b.parameters().len())
+        a.parameters()
+            .len()
+            .cmp(&b.parameters().len())
+            .then_with(|| a.is_variadic().cmp(&b.is_variadic()))
     }
 
     fn cmp_by(
@@ -314,6 +779,18 @@ impl<'input> SortList for Function<'input> {
             Sort::Size => a.size().cmp(&b.size()),
         }
     }
+
+    fn namespace(&self) -> Option<&Namespace<'_>> {
+        self.namespace()
+    }
+
+    fn size(&self, _hash: &FileHash) -> Option<u64> {
+        self.size()
+    }
+
+    fn counts(counts: &DiffCounts) -> Option<&ChangeCounts> {
+        Some(&counts.functions)
+    }
 }
 
 fn print_call(
@@ -326,7 +803,10 @@ fn print_call(
     if !options.ignore_function_address {
         // FIXME: it would be nice to display this in a way that doesn't clutter the output
         // when diffing
-        write!(w, "0x{:x} -> 0x{:x} ", call.from, call.to)?;
+        w.address(call.from)?;
+        write!(w, " -> ")?;
+        w.address(call.to)?;
+        write!(w, " ")?;
     }
     if let Some(function) = hash.functions_by_address.get(&call.to) {
         print_ref(function, w)?;
@@ -334,7 +814,7 @@ fn print_call(
         write!(w, "{}", plt)?;
     } else if options.ignore_function_address {
         // We haven't displayed an address yet, so we need to display something.
-        write!(w, "0x{:x}", call.to)?;
+        w.address(call.to)?;
     }
     Ok(())
 }
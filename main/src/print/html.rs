@@ -3,7 +3,7 @@ use std::io::Write;
 
 use marksman_escape::Escape;
 
-use super::{DiffPrefix, Printer, ValuePrinter};
+use super::{format_size, BufferFn, DiffPrefix, Printer, ValuePrinter};
 use crate::{Options, Result};
 
 const HEADER: &str = r##"<!DOCTYPE html>
@@ -225,20 +225,31 @@ pub struct HtmlPrinter<'w> {
     indent: usize,
     prefix: DiffPrefix,
     inline_depth: usize,
+    nested_depth: usize,
     // Hack to allow indented <ul> to be included within parent <li>.
     line_started: bool,
     http: bool,
+    decimal_addresses: bool,
+    load_bias: u64,
+    human_sizes: bool,
 }
 
 impl<'w> HtmlPrinter<'w> {
+    /// Create a printer that writes HTML to `w`.
+    ///
+    /// As for `TextPrinter::new`, `w` should normally be buffered.
     pub fn new(w: &'w mut dyn Write, options: &Options) -> Self {
         HtmlPrinter {
             w,
             indent: 0,
             prefix: DiffPrefix::None,
             inline_depth: options.inline_depth,
+            nested_depth: super::nested_depth(options),
             line_started: false,
             http: options.http,
+            decimal_addresses: options.decimal_addresses,
+            load_bias: options.load_bias,
+            human_sizes: options.human_sizes,
         }
     }
 
@@ -259,7 +270,12 @@ impl<'w> Printer for HtmlPrinter<'w> {
         buf: &mut Vec<u8>,
         f: &mut dyn FnMut(&mut dyn ValuePrinter) -> Result<()>,
     ) -> Result<()> {
-        let mut p = HtmlValuePrinter { w: buf };
+        let mut p = HtmlValuePrinter {
+            w: buf,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
+        };
         f(&mut p)
     }
 
@@ -275,13 +291,44 @@ impl<'w> Printer for HtmlPrinter<'w> {
             indent: self.indent,
             prefix: self.prefix,
             inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
             line_started: self.line_started,
             http: self.http,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
         };
         f(&mut p)?;
         Ok(())
     }
 
+    fn buffer_fn(&self) -> Option<BufferFn> {
+        let indent = self.indent;
+        let prefix = self.prefix;
+        let inline_depth = self.inline_depth;
+        let nested_depth = self.nested_depth;
+        let line_started = self.line_started;
+        let http = self.http;
+        let decimal_addresses = self.decimal_addresses;
+        let load_bias = self.load_bias;
+        let human_sizes = self.human_sizes;
+        Some(Box::new(move |buf, f| {
+            let mut p = HtmlPrinter {
+                w: buf,
+                indent,
+                prefix,
+                inline_depth,
+                nested_depth,
+                line_started,
+                http,
+                decimal_addresses,
+                load_bias,
+                human_sizes,
+            };
+            f(&mut p)
+        }))
+    }
+
     fn write_buf(&mut self, buf: &[u8]) -> Result<()> {
         self.w.write_all(buf)?;
         Ok(())
@@ -357,8 +404,12 @@ impl<'w> Printer for HtmlPrinter<'w> {
             indent: self.indent + 1,
             prefix: self.prefix,
             inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
             line_started: false,
             http: self.http,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
         };
         body(&mut printer)?;
         Ok(())
@@ -441,6 +492,19 @@ impl<'w> Printer for HtmlPrinter<'w> {
         self.inline_depth += 1;
     }
 
+    fn nested_begin(&mut self) -> bool {
+        if self.nested_depth == 0 {
+            false
+        } else {
+            self.nested_depth -= 1;
+            true
+        }
+    }
+
+    fn nested_end(&mut self) {
+        self.nested_depth += 1;
+    }
+
     fn instruction(&mut self, address: Option<u64>, mnemonic: &str, buf: &[u8]) -> Result<()> {
         write!(self.w, "<tr><td>")?;
         if let Some(address) = address {
@@ -451,6 +515,11 @@ impl<'w> Printer for HtmlPrinter<'w> {
         write!(self.w, "</td></tr>")?;
         Ok(())
     }
+
+    fn flush(&mut self) -> Result<()> {
+        self.w.flush()?;
+        Ok(())
+    }
 }
 
 fn escaped(bytes: &[u8]) -> Vec<u8> {
@@ -463,6 +532,9 @@ fn escaped_str(s: &str) -> String {
 
 struct HtmlValuePrinter<'w> {
     w: &'w mut Vec<u8>,
+    decimal_addresses: bool,
+    load_bias: u64,
+    human_sizes: bool,
 }
 
 impl<'w> Write for HtmlValuePrinter<'w> {
@@ -578,4 +650,23 @@ impl<'w> ValuePrinter for HtmlValuePrinter<'w> {
         }
         Ok(())
     }
+
+    fn address(&mut self, address: u64) -> Result<()> {
+        let address = address.wrapping_add(self.load_bias);
+        if self.decimal_addresses {
+            write!(self.w, "{}", address)?;
+        } else {
+            write!(self.w, "0x{:x}", address)?;
+        }
+        Ok(())
+    }
+
+    fn size(&mut self, size: u64) -> Result<()> {
+        if self.human_sizes {
+            write!(self.w, "{}", format_size(size))?;
+        } else {
+            write!(self.w, "{}", size)?;
+        }
+        Ok(())
+    }
 }
@@ -37,6 +37,12 @@ pub(crate) fn diff_list(
 }
 
 fn print_inherit(inherit: &Inherit, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(accessibility) = inherit.accessibility() {
+        write!(w, "{} ", accessibility.as_str())?;
+    }
+    if inherit.is_virtual() {
+        write!(w, "virtual ")?;
+    }
     print::types::print_ref(inherit.ty(hash), w, hash)
 }
 
@@ -3,7 +3,7 @@ use std::cmp;
 use parser::{FileHash, Function, InlinedFunction, LocalVariable, Unit};
 
 use crate::print::{self, DiffList, DiffState, Print, PrintState, SortList, ValuePrinter};
-use crate::Result;
+use crate::{Options, Result};
 
 fn print_size_and_decl(
     f: &InlinedFunction,
@@ -22,8 +22,13 @@ fn print_size_and_decl(
     Ok(())
 }
 
-fn print_call_source(f: &InlinedFunction, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(f.call_source(), w, unit)
+fn print_call_source(
+    f: &InlinedFunction,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(f.call_source(), w, unit, options)
 }
 
 impl<'input> Print for InlinedFunction<'input> {
@@ -35,8 +40,11 @@ impl<'input> Print for InlinedFunction<'input> {
             // which requires that there are no duplicate functions
             |state| state.line(|w, state| print_size_and_decl(self, w, state)),
             |state| {
-                if state.options().print_source {
-                    state.field("call source", |w, _state| print_call_source(self, w, unit))?;
+                let options = state.options();
+                if options.print_source {
+                    state.field("call source", |w, _state| {
+                        print_call_source(self, w, unit, options)
+                    })?;
                 }
                 if state.options().print_inlined_function_parameters {
                     state.field_expanded("parameters", |state| {
@@ -58,12 +66,13 @@ impl<'input> Print for InlinedFunction<'input> {
         state.collapsed(
             |state| state.line(a, b, |w, state, x| print_size_and_decl(x, w, state)),
             |state| {
-                if state.options().print_source {
+                let options = state.options();
+                if options.print_source {
                     state.field(
                         "call source",
                         (unit_a, a),
                         (unit_b, b),
-                        |w, _state, (unit, x)| print_call_source(x, w, unit),
+                        |w, _state, (unit, x)| print_call_source(x, w, unit, options),
                     )?;
                 }
                 if state.options().print_inlined_function_parameters {
@@ -128,8 +137,9 @@ impl<'input> DiffList for InlinedFunction<'input> {
             }
         }
 
-        let path_a = a.call_source().path(unit_a);
-        let path_b = b.call_source().path(unit_b);
+        let options = state.options();
+        let path_a = options.source_path(a.call_source(), unit_a);
+        let path_b = options.source_path(b.call_source(), unit_b);
         if path_a.cmp(&path_b) != cmp::Ordering::Equal
             || a.call_source().line().cmp(&b.call_source().line()) != cmp::Ordering::Equal
             || a.call_source().column().cmp(&b.call_source().column()) != cmp::Ordering::Equal
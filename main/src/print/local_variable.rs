@@ -27,7 +27,7 @@ fn print_size_and_decl(v: &LocalVariable, w: &mut dyn ValuePrinter, hash: &FileH
 
 fn print_address(v: &LocalVariable, w: &mut dyn ValuePrinter) -> Result<()> {
     if let Some(address) = v.address() {
-        write!(w, "0x{:x}", address)?;
+        w.address(address)?;
     }
     Ok(())
 }
@@ -0,0 +1,299 @@
+use std::io::Write;
+
+use super::text::TextValuePrinter;
+use super::{BufferFn, DiffPrefix, Printer, ValuePrinter};
+use crate::{Options, Result};
+
+/// A printer for Markdown output.
+///
+/// Each top level entry is printed as a heading, followed by a table of its fields.
+/// For diffs, the table has an extra column with the kind of change.
+pub struct MarkdownPrinter<'w> {
+    w: &'w mut dyn Write,
+    indent: usize,
+    prefix: DiffPrefix,
+    inline_depth: usize,
+    nested_depth: usize,
+    decimal_addresses: bool,
+    load_bias: u64,
+    human_sizes: bool,
+}
+
+impl<'w> MarkdownPrinter<'w> {
+    /// Create a printer that writes Markdown to `w`.
+    ///
+    /// As for `TextPrinter::new`, `w` should normally be buffered.
+    pub fn new(w: &'w mut dyn Write, options: &Options) -> Self {
+        MarkdownPrinter {
+            w,
+            indent: 0,
+            prefix: DiffPrefix::None,
+            inline_depth: options.inline_depth,
+            nested_depth: super::nested_depth(options),
+            decimal_addresses: options.decimal_addresses,
+            load_bias: options.load_bias,
+            human_sizes: options.human_sizes,
+        }
+    }
+
+    fn child<'a>(&self, w: &'a mut dyn Write, indent: usize) -> MarkdownPrinter<'a> {
+        MarkdownPrinter {
+            w,
+            indent,
+            prefix: self.prefix,
+            inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
+        }
+    }
+
+    fn sign(prefix: DiffPrefix) -> &'static str {
+        match prefix {
+            DiffPrefix::None | DiffPrefix::Equal => "",
+            DiffPrefix::Delete => "-",
+            DiffPrefix::Add => "+",
+            DiffPrefix::Modify => "~",
+        }
+    }
+
+    fn heading(&mut self, buf: &[u8]) -> Result<()> {
+        write!(self.w, "####")?;
+        let sign = Self::sign(self.prefix);
+        if !sign.is_empty() {
+            write!(self.w, " {}", sign)?;
+        }
+        write!(self.w, " ")?;
+        self.write_escaped(buf)?;
+        writeln!(self.w)?;
+        writeln!(self.w)?;
+        if self.prefix == DiffPrefix::None {
+            writeln!(self.w, "| Field | Value |")?;
+            writeln!(self.w, "|---|---|")?;
+        } else {
+            writeln!(self.w, "| | Field | Value |")?;
+            writeln!(self.w, "|---|---|---|")?;
+        }
+        Ok(())
+    }
+
+    fn row(&mut self, prefix: DiffPrefix, label: &str, value: &[u8]) -> Result<()> {
+        write!(self.w, "|")?;
+        if prefix != DiffPrefix::None {
+            write!(self.w, " {} |", Self::sign(prefix))?;
+        }
+        // Show nesting by indenting the first non-empty column.
+        let indent = self.indent.saturating_sub(1);
+        write!(self.w, " ")?;
+        if !label.is_empty() {
+            self.write_indent(indent)?;
+            self.write_escaped(label.as_bytes())?;
+        }
+        write!(self.w, " | ")?;
+        if label.is_empty() {
+            self.write_indent(indent)?;
+        }
+        self.write_escaped(value)?;
+        writeln!(self.w, " |")?;
+        Ok(())
+    }
+
+    fn write_indent(&mut self, indent: usize) -> Result<()> {
+        for _ in 0..indent {
+            write!(self.w, "&nbsp;&nbsp;&nbsp;&nbsp;")?;
+        }
+        Ok(())
+    }
+
+    fn write_escaped(&mut self, buf: &[u8]) -> Result<()> {
+        for &c in buf {
+            match c {
+                b'<' => write!(self.w, "&lt;")?,
+                b'>' => write!(self.w, "&gt;")?,
+                b'&' => write!(self.w, "&amp;")?,
+                b'\\' | b'`' | b'*' | b'_' | b'|' | b'[' | b']' => self.w.write_all(&[b'\\', c])?,
+                b'\t' => write!(self.w, " ")?,
+                _ => self.w.write_all(&[c])?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'w> Printer for MarkdownPrinter<'w> {
+    fn value(
+        &mut self,
+        buf: &mut Vec<u8>,
+        f: &mut dyn FnMut(&mut dyn ValuePrinter) -> Result<()>,
+    ) -> Result<()> {
+        let mut p = TextValuePrinter {
+            w: buf,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
+        };
+        f(&mut p)
+    }
+
+    /// Calls `f` to write to a temporary buffer.
+    fn buffer(
+        &mut self,
+        buf: &mut Vec<u8>,
+        f: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
+    ) -> Result<()> {
+        let mut p = self.child(buf, self.indent);
+        f(&mut p)
+    }
+
+    fn buffer_fn(&self) -> Option<BufferFn> {
+        let indent = self.indent;
+        let prefix = self.prefix;
+        let inline_depth = self.inline_depth;
+        let nested_depth = self.nested_depth;
+        let decimal_addresses = self.decimal_addresses;
+        let load_bias = self.load_bias;
+        let human_sizes = self.human_sizes;
+        Some(Box::new(move |buf, f| {
+            let mut p = MarkdownPrinter {
+                w: buf,
+                indent,
+                prefix,
+                inline_depth,
+                nested_depth,
+                decimal_addresses,
+                load_bias,
+                human_sizes,
+            };
+            f(&mut p)
+        }))
+    }
+
+    fn write_buf(&mut self, buf: &[u8]) -> Result<()> {
+        self.w.write_all(buf)?;
+        Ok(())
+    }
+
+    fn line_break(&mut self) -> Result<()> {
+        // A blank line ends the table, so only allow it between entries.
+        if self.indent == 0 {
+            writeln!(self.w)?;
+        }
+        Ok(())
+    }
+
+    fn line(&mut self, label: &str, buf: &[u8]) -> Result<()> {
+        if self.indent == 0 {
+            return self.heading(buf);
+        }
+        self.row(self.prefix, label, buf)
+    }
+
+    fn line_diff(&mut self, label: &str, a: &[u8], b: &[u8]) -> Result<()> {
+        let mut value = a.to_vec();
+        value.extend_from_slice(" \u{2192} ".as_bytes());
+        value.extend_from_slice(b);
+        if self.indent == 0 {
+            self.prefix = DiffPrefix::Modify;
+            return self.heading(&value);
+        }
+        self.row(DiffPrefix::Modify, label, &value)
+    }
+
+    fn indent_body(
+        &mut self,
+        buf: &mut Vec<u8>,
+        body: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
+    ) -> Result<()> {
+        let mut printer = self.child(buf, self.indent + 1);
+        body(&mut printer)
+    }
+
+    fn indent_header(
+        &mut self,
+        _collapsed: bool,
+        body: &[u8],
+        header: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
+    ) -> Result<()> {
+        header(self)?;
+        self.write_buf(body)?;
+        Ok(())
+    }
+
+    fn indent_id(
+        &mut self,
+        _id: usize,
+        header: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
+        body: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
+    ) -> Result<()> {
+        header(self)?;
+        let indent = self.indent + 1;
+        let mut printer = MarkdownPrinter {
+            w: self.w,
+            indent,
+            prefix: self.prefix,
+            inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
+        };
+        body(&mut printer)
+    }
+
+    fn indent_detail(&mut self, _id: &str, _label: &str) -> Result<()> {
+        unreachable!();
+    }
+
+    fn prefix(&mut self, prefix: DiffPrefix) {
+        self.prefix = prefix;
+    }
+
+    fn get_prefix(&self) -> DiffPrefix {
+        self.prefix
+    }
+
+    fn inline_begin(&mut self) -> bool {
+        if self.inline_depth == 0 {
+            false
+        } else {
+            self.inline_depth -= 1;
+            true
+        }
+    }
+
+    fn inline_end(&mut self) {
+        self.inline_depth += 1;
+    }
+
+    fn nested_begin(&mut self) -> bool {
+        if self.nested_depth == 0 {
+            false
+        } else {
+            self.nested_depth -= 1;
+            true
+        }
+    }
+
+    fn nested_end(&mut self) {
+        self.nested_depth += 1;
+    }
+
+    fn instruction(&mut self, address: Option<u64>, mnemonic: &str, buf: &[u8]) -> Result<()> {
+        let label = match address {
+            Some(address) => format!("{:x}", address),
+            None => String::new(),
+        };
+        let mut value = mnemonic.as_bytes().to_vec();
+        if !buf.is_empty() {
+            value.push(b' ');
+            value.extend_from_slice(buf);
+        }
+        self.row(self.prefix, &label, &value)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.w.flush()?;
+        Ok(())
+    }
+}
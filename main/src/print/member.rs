@@ -2,13 +2,20 @@ use std::borrow::Cow;
 use std::cmp;
 use std::ops::Deref;
 
-use parser::{FileHash, Inherit, Layout, LayoutItem, Member, Type, Unit, Variant, VariantPart};
+use parser::{
+    BaseTypeEncoding, FileHash, Inherit, Layout, LayoutItem, Member, Type, TypeKind, Unit, Variant,
+    VariantPart,
+};
 
+use crate::print::types::AnonName;
 use crate::print::{self, DiffList, DiffState, Print, PrintState, ValuePrinter};
 use crate::Result;
 
 fn print_member(member: &Member, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
-    write!(w, "{}", format_bit(member.bit_offset()))?;
+    // Static members have no offset.
+    if !member.is_static() {
+        write!(w, "{}", format_bit(member.bit_offset()))?;
+    }
     match member.bit_size(hash) {
         Some(bit_size) => {
             write!(w, "[{}]", format_bit(bit_size))?;
@@ -19,35 +26,105 @@ fn print_member(member: &Member, w: &mut dyn ValuePrinter, hash: &FileHash) -> R
             write!(w, "[??]")?;
         }
     }
-    write!(w, "\t{}: ", member.name().unwrap_or("<anon>"))?;
-    print::types::print_ref(member.ty(hash), w, hash)?;
+    write!(w, "\t")?;
+    print_modifiers(member, w, hash)?;
+    write!(w, "{}: ", member.name().unwrap_or("<anon>"))?;
+    // Anonymous member types are printed inline, so they don't need a name.
+    print::types::print_ref_anon(member.ty(hash), w, hash, AnonName::Omit)?;
+    Ok(())
+}
+
+// The explicit alignment of the member, if it differs from the natural alignment.
+//
+// Some compilers specify the alignment of every member, so an alignment that is
+// the same as the natural alignment is not interesting.
+fn explicit_alignment(member: &Member, hash: &FileHash) -> Option<u64> {
+    member
+        .alignment()
+        .filter(|&alignment| Some(alignment) != member.natural_alignment(hash))
+}
+
+fn print_modifiers(member: &Member, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    let alignment =
+        explicit_alignment(member, hash).map(|alignment| format!("aligned({})", alignment));
+    let mut modifiers = Vec::new();
+    if let Some(accessibility) = member.accessibility() {
+        modifiers.push(accessibility.as_str());
+    }
+    if member.is_static() {
+        modifiers.push("static");
+    }
+    if member.is_artificial() {
+        modifiers.push("artificial");
+    }
+    if let Some(ref alignment) = alignment {
+        modifiers.push(alignment);
+    }
+    if !modifiers.is_empty() {
+        write!(w, "{}: ", modifiers.join(" "))?;
+    }
     Ok(())
 }
 
 fn print_variant_part(
     layout: &Layout,
-    _variant_part: &VariantPart,
+    variant_part: &VariantPart,
+    discriminant: Option<&Member>,
     w: &mut dyn ValuePrinter,
+    hash: &FileHash,
 ) -> Result<()> {
-    // TODO: indicate which discriminant
     write!(
         w,
         "{}[{}]\t<variant part>",
         format_bit(layout.bit_offset),
         format_bit(layout.bit_size.get().unwrap_or(0)),
     )?;
+    if let Some(discriminant) = discriminant {
+        write!(w, ": ")?;
+        if variant_part.has_niche(discriminant, hash) {
+            write!(w, "niche ")?;
+        }
+        write!(w, "discriminant {}", format_bit(discriminant.bit_offset()))?;
+        if let Some(bit_size) = discriminant.bit_size(hash) {
+            write!(w, "[{}]", format_bit(bit_size))?;
+        }
+    }
     Ok(())
 }
 
-fn print_variant(variant: &Variant, w: &mut dyn ValuePrinter) -> Result<()> {
+fn print_variant(
+    variant: &Variant,
+    sign_bits: Option<u64>,
+    w: &mut dyn ValuePrinter,
+) -> Result<()> {
     if let Some(name) = variant.name() {
         write!(w, "{}: ", name)?;
     }
-    // TODO: use discriminant type to display value
-    write!(w, "<{}>", variant.discriminant_value().unwrap_or(0))?;
+    match (variant.discriminant_value(), sign_bits) {
+        (Some(value), Some(bits)) if bits > 0 && bits < 64 => {
+            let shift = 64 - bits;
+            write!(w, "<{}>", ((value << shift) as i64) >> shift)?;
+        }
+        (Some(value), Some(_)) => write!(w, "<{}>", value as i64)?,
+        (Some(value), None) => write!(w, "<{}>", value)?,
+        (None, _) => write!(w, "<default>")?,
+    }
     Ok(())
 }
 
+/// The size in bits of the discriminant if its values are signed.
+fn discriminant_sign_bits(discriminant: Option<&Member>, hash: &FileHash) -> Option<u64> {
+    let discriminant = discriminant?;
+    let ty = discriminant.ty(hash)?;
+    match ty.kind() {
+        TypeKind::Base(base) => match base.encoding() {
+            BaseTypeEncoding::Signed | BaseTypeEncoding::SignedChar => discriminant.bit_size(hash),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 fn print_inherit(
     layout: &Layout,
     inherit: &Inherit,
@@ -74,6 +151,22 @@ fn print_padding(layout: &Layout, w: &mut dyn ValuePrinter) -> Result<()> {
     Ok(())
 }
 
+/// Print a single line describing a layout item, excluding any variants.
+pub(crate) fn print_layout_item(
+    layout: &Layout,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+) -> Result<()> {
+    match layout.item {
+        LayoutItem::Padding => print_padding(layout, w),
+        LayoutItem::Member(member) => print_member(member, w, hash),
+        LayoutItem::VariantPart(variant_part, discriminant) => {
+            print_variant_part(layout, variant_part, discriminant, w, hash)
+        }
+        LayoutItem::Inherit(inherit) => print_inherit(layout, inherit, w, hash),
+    }
+}
+
 impl<'input> Print for Member<'input> {
     type Arg = Unit<'input>;
 
@@ -87,7 +180,10 @@ impl<'input> Print for Member<'input> {
         let ty = ty.as_ref().map(Cow::deref);
         state.expanded(
             |state| state.line(|w, hash| print_member(self, w, hash)),
-            |state| print::types::print_members(state, unit, ty),
+            |state| match ty {
+                Some(_) => state.nested(|state| print::types::print_members(state, unit, ty)),
+                None => Ok(()),
+            },
         )
     }
 
@@ -106,7 +202,11 @@ impl<'input> Print for Member<'input> {
         let ty_b = ty_b.as_ref().map(Cow::deref);
         state.expanded(
             |state| state.line(a, b, |w, hash, x| print_member(x, w, hash)),
-            |state| print::types::diff_members(state, unit_a, ty_a, unit_b, ty_b),
+            |state| match (ty_a, ty_b) {
+                (None, None) => Ok(()),
+                _ => state
+                    .nested(|state| print::types::diff_members(state, unit_a, ty_a, unit_b, ty_b)),
+            },
         )
     }
 }
@@ -121,6 +221,9 @@ impl<'input> DiffList for Member<'input> {
         if a.name().cmp(&b.name()) != cmp::Ordering::Equal {
             cost += 1;
         }
+        if explicit_alignment(a, state.hash_a()) != explicit_alignment(b, state.hash_b()) {
+            cost += 1;
+        }
         match (a.ty(state.hash_a()), b.ty(state.hash_b())) {
             (Some(ref ty_a), Some(ref ty_b)) => {
                 if Type::cmp_id(state.hash_a(), ty_a, state.hash_b(), ty_b) != cmp::Ordering::Equal
@@ -138,17 +241,21 @@ impl<'input> DiffList for Member<'input> {
 }
 
 impl<'input> Print for Variant<'input> {
-    type Arg = (&'input Unit<'input>, u64, Option<u64>);
+    type Arg = (&'input Unit<'input>, u64, Option<u64>, Option<u64>);
 
     fn print(
         &self,
         state: &mut PrintState,
-        (unit, bit_offset, bit_size): &Self::Arg,
+        (unit, bit_offset, bit_size, sign_bits): &Self::Arg,
     ) -> Result<()> {
         state.expanded(
-            |state| state.line(|w, _hash| print_variant(self, w)),
+            |state| state.line(|w, _hash| print_variant(self, *sign_bits, w)),
             |state| {
-                let layout = self.layout(*bit_offset, *bit_size, state.hash());
+                let layout = if state.options().member_declaration_order {
+                    self.declaration_layout(*bit_offset, *bit_size, state.hash())
+                } else {
+                    self.layout(*bit_offset, *bit_size, state.hash())
+                };
                 state.list(*unit, &layout)
             },
         )
@@ -156,16 +263,31 @@ impl<'input> Print for Variant<'input> {
 
     fn diff(
         state: &mut DiffState,
-        (unit_a, bit_offset_a, bit_size_a): &Self::Arg,
+        (unit_a, bit_offset_a, bit_size_a, sign_bits_a): &Self::Arg,
         a: &Self,
-        (unit_b, bit_offset_b, bit_size_b): &Self::Arg,
+        (unit_b, bit_offset_b, bit_size_b, sign_bits_b): &Self::Arg,
         b: &Self,
     ) -> Result<()> {
         state.expanded(
-            |state| state.line(a, b, |w, _hash, variant| print_variant(variant, w)),
             |state| {
-                let layout_a = a.layout(*bit_offset_a, *bit_size_a, state.hash_a());
-                let layout_b = b.layout(*bit_offset_b, *bit_size_b, state.hash_b());
+                state.line(
+                    (a, *sign_bits_a),
+                    (b, *sign_bits_b),
+                    |w, _hash, (variant, sign_bits)| print_variant(variant, sign_bits, w),
+                )
+            },
+            |state| {
+                let (layout_a, layout_b) = if state.options().member_declaration_order {
+                    (
+                        a.declaration_layout(*bit_offset_a, *bit_size_a, state.hash_a()),
+                        b.declaration_layout(*bit_offset_b, *bit_size_b, state.hash_b()),
+                    )
+                } else {
+                    (
+                        a.layout(*bit_offset_a, *bit_size_a, state.hash_a()),
+                        b.layout(*bit_offset_b, *bit_size_b, state.hash_b()),
+                    )
+                };
                 state.list(*unit_a, &layout_a, *unit_b, &layout_b)
             },
         )
@@ -202,15 +324,22 @@ impl<'input, 'member> Print for Layout<'input, 'member> {
         match self.item {
             LayoutItem::Padding => state.line(|w, _hash| print_padding(self, w)),
             LayoutItem::Member(member) => member.print(state, unit),
-            LayoutItem::VariantPart(variant_part) => state.expanded(
-                |state| state.line(|w, _hash| print_variant_part(self, variant_part, w)),
-                |state| {
-                    state.list(
-                        &(unit, self.bit_offset, self.bit_size.get()),
-                        variant_part.variants(),
-                    )
-                },
-            ),
+            LayoutItem::VariantPart(variant_part, discriminant) => {
+                let sign_bits = discriminant_sign_bits(discriminant, state.hash());
+                state.expanded(
+                    |state| {
+                        state.line(|w, hash| {
+                            print_variant_part(self, variant_part, discriminant, w, hash)
+                        })
+                    },
+                    |state| {
+                        state.list(
+                            &(unit, self.bit_offset, self.bit_size.get(), sign_bits),
+                            variant_part.variants(),
+                        )
+                    },
+                )
+            }
             LayoutItem::Inherit(inherit) => {
                 state.line(|w, hash| print_inherit(self, inherit, w, hash))
             }
@@ -226,27 +355,32 @@ impl<'input, 'member> Print for Layout<'input, 'member> {
                 Member::diff(state, unit_a, member_a, unit_b, member_b)
             }
             (
-                &LayoutItem::VariantPart(ref variant_part_a),
-                &LayoutItem::VariantPart(ref variant_part_b),
-            ) => state.expanded(
-                |state| {
-                    state.line(
-                        (a, variant_part_a),
-                        (b, variant_part_b),
-                        |w, _hash, (x, v)| print_variant_part(x, v, w),
-                    )
-                },
-                |state| {
-                    let variants_a = variant_part_a.variants();
-                    let variants_b = variant_part_b.variants();
-                    state.list(
-                        &(unit_a, a.bit_offset, a.bit_size.get()),
-                        variants_a,
-                        &(unit_b, b.bit_offset, b.bit_size.get()),
-                        variants_b,
-                    )
-                },
-            ),
+                &LayoutItem::VariantPart(variant_part_a, discriminant_a),
+                &LayoutItem::VariantPart(variant_part_b, discriminant_b),
+            ) => {
+                let (hash_a, hash_b) = (state.hash_a(), state.hash_b());
+                let sign_bits_a = discriminant_sign_bits(discriminant_a, hash_a);
+                let sign_bits_b = discriminant_sign_bits(discriminant_b, hash_b);
+                state.expanded(
+                    |state| {
+                        state.line(
+                            (a, variant_part_a, discriminant_a),
+                            (b, variant_part_b, discriminant_b),
+                            |w, hash, (x, variant_part, discriminant)| {
+                                print_variant_part(x, variant_part, discriminant, w, hash)
+                            },
+                        )
+                    },
+                    |state| {
+                        state.list(
+                            &(unit_a, a.bit_offset, a.bit_size.get(), sign_bits_a),
+                            variant_part_a.variants(),
+                            &(unit_b, b.bit_offset, b.bit_size.get(), sign_bits_b),
+                            variant_part_b.variants(),
+                        )
+                    },
+                )
+            }
             (&LayoutItem::Inherit(ref inherit_a), &LayoutItem::Inherit(ref inherit_b)) => state
                 .line((a, inherit_a), (b, inherit_b), |w, hash, (x, inherit)| {
                     print_inherit(x, inherit, w, hash)
@@ -270,10 +404,15 @@ impl<'input, 'member> DiffList for Layout<'input, 'member> {
             (&LayoutItem::Member(ref a), &LayoutItem::Member(ref b)) => {
                 Member::diff_cost(state, unit_a, a, unit_b, b)
             }
-            (&LayoutItem::VariantPart(ref _a), &LayoutItem::VariantPart(ref _b)) => {
+            (&LayoutItem::VariantPart(_, ref a), &LayoutItem::VariantPart(_, ref b)) => {
                 // TODO: for now we assume there is only one variant part
-                // Later we should compare `VariantPart::discr`
-                0
+                let a = a.map(|x| (x.bit_offset(), x.bit_size(state.hash_a())));
+                let b = b.map(|x| (x.bit_offset(), x.bit_size(state.hash_b())));
+                if a == b {
+                    0
+                } else {
+                    1
+                }
             }
             (&LayoutItem::Inherit(ref a), &LayoutItem::Inherit(ref b)) => {
                 Inherit::diff_cost(state, &(), a, &(), b)
@@ -283,7 +422,7 @@ impl<'input, 'member> DiffList for Layout<'input, 'member> {
     }
 }
 
-fn format_bit(val: u64) -> String {
+pub(crate) fn format_bit(val: u64) -> String {
     let byte = val / 8;
     let bit = val % 8;
     if bit == 0 {
@@ -1,9 +1,10 @@
 use std::cmp;
 use std::collections::BinaryHeap;
 use std::io::Write;
-use std::usize;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::{thread, usize};
 
-use parser::FileHash;
+use parser::{FileHash, Function, Namespace};
 
 use crate::code::Code;
 use crate::{Options, Result};
@@ -14,6 +15,9 @@ pub use self::text::TextPrinter;
 mod html;
 pub use self::html::HtmlPrinter;
 
+mod markdown;
+pub use self::markdown::MarkdownPrinter;
+
 pub(crate) mod base_type;
 pub(crate) mod enumeration;
 pub(crate) mod file;
@@ -79,6 +83,17 @@ pub trait Printer {
         buf: &mut Vec<u8>,
         f: &mut dyn FnMut(&mut dyn Printer) -> Result<()>,
     ) -> Result<()>;
+
+    /// Returns a function that calls `f` to write to a temporary buffer, in the
+    /// same way as `buffer`.
+    ///
+    /// Unlike `buffer`, the returned function can be called from other threads.
+    /// Printers that don't support this return `None`, and are only used from
+    /// the calling thread.
+    fn buffer_fn(&self) -> Option<BufferFn> {
+        None
+    }
+
     fn write_buf(&mut self, buf: &[u8]) -> Result<()>;
 
     fn line_break(&mut self) -> Result<()>;
@@ -111,9 +126,28 @@ pub trait Printer {
     fn inline_begin(&mut self) -> bool;
     fn inline_end(&mut self);
 
+    fn nested_begin(&mut self) -> bool;
+    fn nested_end(&mut self);
+
     fn instruction(&mut self, address: Option<u64>, mnemonic: &str, buf: &[u8]) -> Result<()>;
+
+    /// Flush the underlying writer.
+    ///
+    /// This should be called after printing has finished, so that output held in
+    /// a buffered writer is written and any errors are reported.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
+/// A function that calls its second argument to write to the buffer given by its
+/// first argument.
+pub type BufferFn = Box<
+    dyn Fn(&mut Vec<u8>, &mut dyn FnMut(&mut dyn Printer) -> Result<()>) -> Result<()>
+        + Send
+        + Sync,
+>;
+
 pub trait ValuePrinter: Write {
     fn link(
         &mut self,
@@ -122,6 +156,93 @@ pub trait ValuePrinter: Write {
     ) -> Result<()>;
 
     fn name(&mut self, name: &str) -> Result<()>;
+
+    fn address(&mut self, address: u64) -> Result<()>;
+
+    fn size(&mut self, size: u64) -> Result<()>;
+}
+
+/// Print the debuginfo offset of an entry, if known.
+pub(crate) fn print_offset(offset: Option<usize>, w: &mut dyn ValuePrinter) -> Result<()> {
+    if let Some(offset) = offset {
+        write!(w, "0x{:x}", offset)?;
+    }
+    Ok(())
+}
+
+/// The size as a percentage of the total, or `None` if the total is zero.
+pub(crate) fn percent(size: u64, total: u64) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some(size as f64 * 100.0 / total as f64)
+    }
+}
+
+/// Print a percentage of a total after a size, such as ` (12.3%)`.
+///
+/// If `base` is given, then also print the change in percentage points from it.
+pub(crate) fn print_percent(
+    percent: Option<f64>,
+    base: Option<f64>,
+    w: &mut dyn ValuePrinter,
+) -> Result<()> {
+    let percent = match percent {
+        Some(percent) => percent,
+        None => return Ok(()),
+    };
+    write!(w, " ({:.1}%", percent)?;
+    if let Some(base) = base {
+        // Compare the printed values, so that the change agrees with them.
+        let change = (percent * 10.0).round() - (base * 10.0).round();
+        if change != 0.0 {
+            write!(w, ", {:+.1}pp", change / 10.0)?;
+        }
+    }
+    write!(w, ")")?;
+    Ok(())
+}
+
+/// Format a size in bytes using binary units, such as `1.0 MiB`.
+pub(crate) fn format_size(size: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if size < 1024 {
+        return format!("{} B", size);
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+// Print whether there is an address, for comparisons where the address itself
+// is expected to differ.
+pub(crate) fn print_address_presence(address: Option<u64>, w: &mut dyn ValuePrinter) -> Result<()> {
+    if address.is_some() {
+        write!(w, "yes")?;
+    } else {
+        write!(w, "none")?;
+    }
+    Ok(())
+}
+
+/// The number of levels of nested types that printers may recurse into.
+///
+/// The members of top level types are always printed, and a `max_depth` of 0
+/// means there is no limit.
+pub(crate) fn nested_depth(options: &Options) -> usize {
+    match options.max_depth {
+        0 => usize::MAX,
+        max_depth => max_depth - 1,
+    }
+}
+
+fn print_nested_placeholder(w: &mut dyn ValuePrinter) -> Result<()> {
+    write!(w, "{{ ... }}")?;
+    Ok(())
 }
 
 pub(crate) struct PrintState<'a> {
@@ -276,6 +397,21 @@ impl<'a> PrintState<'a> {
         }
     }
 
+    /// Call `f` to print the members of a nested type, or print a placeholder
+    /// if `options.max_depth` has been reached.
+    pub fn nested<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut PrintState) -> Result<()>,
+    {
+        if self.printer.nested_begin() {
+            let ret = f(self);
+            self.printer.nested_end();
+            ret
+        } else {
+            self.line(|w, _hash| print_nested_placeholder(w))
+        }
+    }
+
     fn prefix(
         &mut self,
         prefix: DiffPrefix,
@@ -328,6 +464,10 @@ impl<'a> PrintState<'a> {
         })
     }
 
+    pub fn field_size(&mut self, label: &str, arg: u64) -> Result<()> {
+        self.field(label, |w, _| w.size(arg))
+    }
+
     pub fn instruction<F>(&mut self, address: Option<u64>, mnemonic: &str, mut f: F) -> Result<()>
     where
         F: FnMut(&mut dyn ValuePrinter, &FileHash) -> Result<()>,
@@ -346,13 +486,130 @@ impl<'a> PrintState<'a> {
         Ok(())
     }
 
+    // Sort then display a list of items.
+    //
+    // Items that are equal according to the sort key are ordered by their identifier
+    // (e.g. namespace and name), and then remain in their original order.
     pub fn sort_list<T: SortList>(&mut self, arg: &T::Arg, list: &mut [&T]) -> Result<()> {
-        list.sort_by(|a, b| T::cmp_by(self.hash, a, self.hash, b, self.options));
+        let hash = self.hash;
+        let options = self.options;
+        list.sort_by(|a, b| {
+            T::cmp_by(hash, a, hash, b, options)
+                .then_with(|| T::cmp_id_for_sort(hash, a, hash, b, options))
+        });
+        if self.options.group_by_namespace {
+            let mut list: Vec<_> = list
+                .iter()
+                .map(|item| (namespace::path(item.namespace()), *item))
+                .collect();
+            list.sort_by(|a, b| a.0.cmp(&b.0));
+            return self.namespace_list(arg, &list, 0);
+        }
         for item in list {
             item.print(self, arg)?;
         }
         Ok(())
     }
+
+    /// Call `f` to print to a temporary buffer, and return the buffer.
+    pub fn buffer<F>(&mut self, mut f: F) -> Result<Vec<u8>>
+    where
+        F: FnMut(&mut PrintState) -> Result<()>,
+    {
+        let hash = self.hash;
+        let code = self.code;
+        let options = self.options;
+        let mut buf = Vec::new();
+        self.printer.buffer(&mut buf, &mut |printer| {
+            let mut state = PrintState::new(printer, hash, code, options);
+            f(&mut state)
+        })?;
+        Ok(buf)
+    }
+
+    // Print a table of contents for a sorted list, followed by the items in the list.
+    //
+    // Each entry gives the line number that an item starts at, and the first line
+    // of the item, so that the item can be found in a pager. `header` is printed
+    // before the table of contents, and is included in the line numbers.
+    pub fn toc_list<T: SortList>(
+        &mut self,
+        header: &[u8],
+        arg: &T::Arg,
+        list: &mut [&T],
+    ) -> Result<()> {
+        fn count_lines(buf: &[u8]) -> usize {
+            buf.iter().filter(|&&c| c == b'\n').count()
+        }
+
+        let hash = self.hash;
+        let options = self.options;
+        list.sort_by(|a, b| {
+            T::cmp_by(hash, a, hash, b, options)
+                .then_with(|| T::cmp_id_for_sort(hash, a, hash, b, options))
+        });
+        let mut items = Vec::new();
+        for item in list {
+            let buf = self.buffer(|state| item.print(state, arg))?;
+            if !buf.is_empty() {
+                items.push(buf);
+            }
+        }
+
+        // The items start after the header, the label, the entries and a line break.
+        let line = count_lines(header) + items.len() + 3;
+        self.printer.write_buf(header)?;
+        self.field_expanded("contents", |state| {
+            let mut line = line;
+            for buf in &items {
+                let first = buf.split(|&c| c == b'\n').next().unwrap_or(&[]);
+                let first = String::from_utf8_lossy(first);
+                state.line(|w, _hash| {
+                    write!(w, "{}\t{}", line, first.trim_start())?;
+                    Ok(())
+                })?;
+                line += count_lines(buf);
+            }
+            Ok(())
+        })?;
+        self.line_break()?;
+        for buf in &items {
+            self.printer.write_buf(buf)?;
+        }
+        Ok(())
+    }
+
+    // Print a list of items that is sorted by namespace, with a nested block for
+    // each namespace below the given depth.
+    fn namespace_list<T: SortList>(
+        &mut self,
+        arg: &T::Arg,
+        list: &[(Vec<Option<&str>>, &T)],
+        depth: usize,
+    ) -> Result<()> {
+        let mut list = list;
+        while let Some((path, item)) = list.first() {
+            if path.len() <= depth {
+                item.print(self, arg)?;
+                list = &list[1..];
+                continue;
+            }
+            let name = path[depth];
+            let len = list
+                .iter()
+                .take_while(|x| x.0.len() > depth && x.0[depth] == name)
+                .count();
+            let (group, rest) = list.split_at(len);
+            self.indent_impl(
+                true,
+                false,
+                |state| state.line(|w, _state| namespace::print_header(name, w)),
+                |state| state.namespace_list(arg, group, depth + 1),
+            )?;
+            list = rest;
+        }
+        Ok(())
+    }
 }
 
 pub(crate) struct DiffState<'a> {
@@ -367,6 +624,9 @@ pub(crate) struct DiffState<'a> {
     code_a: Option<&'a Code<'a>>,
     code_b: Option<&'a Code<'a>>,
     options: &'a Options,
+
+    // The counts of changed entries for the summary, if it is enabled.
+    counts: Option<&'a DiffCounts>,
 }
 
 impl<'a> DiffState<'a> {
@@ -411,12 +671,13 @@ impl<'a> DiffState<'a> {
             code_a,
             code_b,
             options,
+            counts: None,
         }
     }
 
-    // Write output of `f` to a temporary buffer, then only
-    // output that buffer if there were any differences.
-    fn print_if_diff<F>(&mut self, mut f: F) -> Result<()>
+    // Write output of `f` to a temporary buffer, and return the buffer and
+    // whether there were any differences.
+    fn buffer<F>(&mut self, mut f: F) -> Result<(Vec<u8>, bool)>
     where
         F: FnMut(&mut DiffState) -> Result<()>,
     {
@@ -425,19 +686,107 @@ impl<'a> DiffState<'a> {
         let code_a = self.code_a;
         let code_b = self.code_b;
         let options = self.options;
+        let counts = self.counts;
         let mut buf = Vec::new();
         let mut diff = false;
         self.printer.buffer(&mut buf, &mut |printer| {
             let mut state = DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+            state.counts = counts;
             f(&mut state)?;
             diff = state.diff;
             Ok(())
         })?;
+        Ok((buf, diff))
+    }
+
+    // Write output of `f` to a temporary buffer, then only
+    // output that buffer if there were any differences.
+    //
+    // Returns true if there were any differences.
+    fn print_if_diff<F>(&mut self, f: F) -> Result<bool>
+    where
+        F: FnMut(&mut DiffState) -> Result<()>,
+    {
+        let (buf, diff) = self.buffer(f)?;
         self.diff |= diff;
-        if diff || options.html {
-            self.printer.write_buf(&*buf)?;
+        if diff || self.options.html {
+            self.printer.write_buf(&buf)?;
         }
-        Ok(())
+        Ok(diff)
+    }
+
+    // Count an item as changed for the summary, if there were any differences.
+    fn count_changed<T: SortList>(&self, diff: bool) {
+        if let Some(counts) = self.counts.and_then(T::counts) {
+            if diff {
+                counts.add(Change::Changed, 1);
+            }
+        }
+    }
+
+    // Count an item that is only in one of the files for the summary.
+    fn count<T: SortList>(&self, item: &T, change: Change) {
+        if let Some(counts) = self.counts {
+            let hash = match change {
+                Change::Added => self.hash_b,
+                _ => self.hash_a,
+            };
+            item.count(hash, self.options, counts, change);
+        }
+    }
+
+    /// Pair up deleted and added functions that have the same size and code.
+    ///
+    /// This is a secondary pass over the functions that could not be matched by name.
+    /// The matched pairs are removed from `list` and returned.
+    pub fn match_renamed_functions<'i, 'input>(
+        &self,
+        list: &mut Vec<MergeResult<&'i Function<'input>, &'i Function<'input>>>,
+    ) -> Vec<(&'i Function<'input>, &'i Function<'input>)> {
+        let mut renamed = Vec::new();
+        if !self.options.match_renamed_functions {
+            return renamed;
+        }
+
+        let code =
+            |f: &Function, code: Option<&'a Code<'a>>| code.and_then(|c| c.range(f.range()?));
+        // Functions from the symbol table all have the same offset, so track
+        // matches by their index in the list.
+        let mut matched = vec![false; list.len()];
+        for (index_a, a) in list.iter().enumerate() {
+            let a = match *a {
+                MergeResult::Left(a) => a,
+                _ => continue,
+            };
+            let size = match a.size() {
+                Some(size) if size != 0 => size,
+                _ => continue,
+            };
+            // Functions without code can't be compared.
+            let code_a = match code(a, self.code_a) {
+                Some(code_a) => code_a,
+                None => continue,
+            };
+            for (index_b, b) in list.iter().enumerate() {
+                let b = match *b {
+                    MergeResult::Right(b) => b,
+                    _ => continue,
+                };
+                if b.size() != Some(size)
+                    || matched[index_b]
+                    || code(b, self.code_b) != Some(code_a)
+                {
+                    continue;
+                }
+                matched[index_a] = true;
+                matched[index_b] = true;
+                renamed.push((a, b));
+                break;
+            }
+        }
+        let mut matched = matched.into_iter();
+        list.retain(|_| !matched.next().unwrap_or(false));
+        renamed
     }
 
     // Don't allow `f` to update self.diff if flag is true.
@@ -468,6 +817,7 @@ impl<'a> DiffState<'a> {
         let code_a = self.code_a;
         let code_b = self.code_b;
         let options = self.options;
+        let counts = self.counts;
 
         // Render the body first so that we can determine if there are differences.
         // TODO: this makes the initial HTTP load much slower than it could be.
@@ -476,6 +826,7 @@ impl<'a> DiffState<'a> {
         self.printer.indent_body(&mut body_buf, &mut |printer| {
             printer.prefix(DiffPrefix::Equal);
             let mut state = DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+            state.counts = counts;
             body(&mut state)?;
             diff |= state.diff;
             Ok(())
@@ -490,6 +841,7 @@ impl<'a> DiffState<'a> {
                     printer.prefix(DiffPrefix::Equal);
                 }
                 let mut state = DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+                state.counts = counts;
                 header(&mut state)?;
                 diff |= state.diff;
                 Ok(())
@@ -521,6 +873,7 @@ impl<'a> DiffState<'a> {
         let code_a = self.code_a;
         let code_b = self.code_b;
         let options = self.options;
+        let counts = self.counts;
 
         // Render the body first so that we can determine if there are differences
         // or if it is empty.
@@ -529,6 +882,7 @@ impl<'a> DiffState<'a> {
         self.printer.indent_body(&mut body_buf, &mut |printer| {
             printer.prefix(DiffPrefix::Equal);
             let mut state = DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+            state.counts = counts;
             body(&mut state)?;
             if state.diff {
                 diff = true;
@@ -546,6 +900,7 @@ impl<'a> DiffState<'a> {
                     }
                     let mut state =
                         DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+                    state.counts = counts;
                     header(&mut state)?;
                     if state.diff {
                         diff = true;
@@ -602,6 +957,21 @@ impl<'a> DiffState<'a> {
         }
     }
 
+    /// Call `f` to diff the members of a nested type, or print a placeholder
+    /// if `options.max_depth` has been reached.
+    pub fn nested<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut DiffState) -> Result<()>,
+    {
+        if self.printer.nested_begin() {
+            let ret = f(self);
+            self.printer.nested_end();
+            ret
+        } else {
+            self.line((), (), |w, _hash, ()| print_nested_placeholder(w))
+        }
+    }
+
     fn prefix_delete<F>(&mut self, mut f: F) -> Result<()>
     where
         F: FnMut(&mut PrintState) -> Result<()>,
@@ -633,9 +1003,11 @@ impl<'a> DiffState<'a> {
         let code_a = self.code_a;
         let code_b = self.code_b;
         let options = self.options;
+        let counts = self.counts;
         let mut buf = Vec::new();
         self.printer.buffer(&mut buf, &mut |printer| {
             let mut state = DiffState::new(printer, hash_a, hash_b, code_a, code_b, options);
+            state.counts = counts;
             state
                 .a()
                 .prefix(DiffPrefix::Delete, &mut |state| f(state, arg_a))?;
@@ -655,6 +1027,19 @@ impl<'a> DiffState<'a> {
         self.printer.line_break()
     }
 
+    // A single line that describes a change to both sides.
+    pub fn line_modify<F>(&mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&mut dyn ValuePrinter) -> Result<()>,
+    {
+        let mut buf = Vec::new();
+        self.printer.value(&mut buf, &mut |printer| f(printer))?;
+        self.printer.prefix(DiffPrefix::Modify);
+        self.printer.line("", &buf)?;
+        self.diff = true;
+        Ok(())
+    }
+
     pub fn label(&mut self, label: &str) -> Result<()> {
         if self.printer.get_prefix() != DiffPrefix::Modify {
             self.printer.prefix(DiffPrefix::Equal);
@@ -726,6 +1111,23 @@ impl<'a> DiffState<'a> {
         })
     }
 
+    pub fn field_size(&mut self, label: &str, arg_a: u64, arg_b: u64) -> Result<()> {
+        let base = arg_a;
+        self.field(label, arg_a, arg_b, |w, _hash, arg| {
+            w.size(arg)?;
+            if arg > base {
+                write!(w, " (+")?;
+                w.size(arg - base)?;
+                write!(w, ")")?;
+            } else if arg < base {
+                write!(w, " (-")?;
+                w.size(base - arg)?;
+                write!(w, ")")?;
+            }
+            Ok(())
+        })
+    }
+
     pub fn list<T: DiffList>(
         &mut self,
         arg_a: &T::Arg,
@@ -739,6 +1141,7 @@ impl<'a> DiffState<'a> {
             |a| a.step_cost(self, arg_a),
             |b| b.step_cost(self, arg_b),
             |a, b| T::diff_cost(self, arg_a, a, arg_b, b),
+            self.options.similarity_threshold,
         );
         let mut iter_a = list_a.iter();
         let mut iter_b = list_b.iter();
@@ -799,6 +1202,10 @@ impl<'a> DiffState<'a> {
     // Also, self.options controls:
     // - sort order
     // - display of added/deleted options
+    //
+    // Items that are equal according to the sort key are ordered by their identifier
+    // (e.g. namespace and name), so that the order doesn't depend on the order
+    // that the compiler emitted them in.
     pub fn sort_list<'i, T: SortList>(
         &mut self,
         arg_a: &T::Arg,
@@ -808,31 +1215,345 @@ impl<'a> DiffState<'a> {
     where
         T: 'i,
     {
+        self.sort_merged(list);
+
+        if self.options.group_by_namespace {
+            let mut list: Vec<_> = list
+                .iter()
+                .map(|item| {
+                    let namespace = match *item {
+                        MergeResult::Both(a, _) | MergeResult::Left(a) => a.namespace(),
+                        MergeResult::Right(b) => b.namespace(),
+                    };
+                    (namespace::path(namespace), item)
+                })
+                .collect();
+            list.sort_by(|a, b| a.0.cmp(&b.0));
+            return self.namespace_list(arg_a, arg_b, &list, 0);
+        }
+        for item in list {
+            self.sort_list_item(arg_a, arg_b, item)?;
+        }
+        Ok(())
+    }
+
+    /// Diff a list of items in the same way as `sort_list`, but using multiple threads.
+    ///
+    /// Each item is diffed to its own buffer, and the buffers are written in order,
+    /// so the output is the same as for `sort_list`.
+    pub fn sort_list_parallel<'i, T>(
+        &mut self,
+        arg_a: &T::Arg,
+        arg_b: &T::Arg,
+        list: &mut [MergeResult<&'i T, &'i T>],
+    ) -> Result<()>
+    where
+        T: SortList + Sync + 'i,
+        T::Arg: Sync,
+    {
+        let threads = match self.options.diff_threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let buffer = match self.printer.buffer_fn() {
+            // Grouping by namespace nests the items, so they can't be diffed independently.
+            Some(buffer) if threads > 1 && list.len() > 1 && !self.options.group_by_namespace => {
+                buffer
+            }
+            _ => return self.sort_list(arg_a, arg_b, list),
+        };
+        self.sort_merged(list);
+
+        let hash_a = self.hash_a;
+        let hash_b = self.hash_b;
+        let code_a = self.code_a;
+        let code_b = self.code_b;
+        let options = self.options;
+        let counts = self.counts;
+        let list = &*list;
+        let next = AtomicUsize::new(0);
+        let results = thread::scope(|scope| {
+            let workers: Vec<_> = (0..cmp::min(threads, list.len()))
+                .map(|_| {
+                    scope.spawn(|| -> Result<Vec<_>> {
+                        let mut results = Vec::new();
+                        // Items vary in size, so take them one at a time instead
+                        // of splitting the list into a chunk per thread.
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let item = match list.get(index) {
+                                Some(item) => item,
+                                None => return Ok(results),
+                            };
+                            let mut buf = Vec::new();
+                            let mut diff = false;
+                            buffer(&mut buf, &mut |printer| {
+                                let mut state = DiffState::new(
+                                    printer, hash_a, hash_b, code_a, code_b, options,
+                                );
+                                state.counts = counts;
+                                state.sort_list_item(arg_a, arg_b, item)?;
+                                diff = state.diff;
+                                Ok(())
+                            })?;
+                            results.push((index, buf, diff));
+                        }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+
+        let mut results: Vec<_> = results.into_iter().flatten().collect();
+        results.sort_by_key(|result| result.0);
+        for (_, buf, diff) in results {
+            self.diff |= diff;
+            self.printer.write_buf(&buf)?;
+        }
+        Ok(())
+    }
+
+    fn sort_merged<'i, T: SortList + 'i>(&self, list: &mut [MergeResult<&'i T, &'i T>]) {
         list.sort_by(|x, y| {
             MergeResult::cmp(x, y, &self.hash_a, &self.hash_b, |x, hash_x, y, hash_y| {
                 T::cmp_by(hash_x, x, hash_y, y, self.options)
+                    .then_with(|| T::cmp_id_for_sort(hash_x, x, hash_y, y, self.options))
             })
         });
+    }
 
-        for item in list {
-            match *item {
-                MergeResult::Both(a, b) => {
-                    self.print_if_diff(|state| T::diff(state, arg_a, a, arg_b, b))?;
-                }
-                MergeResult::Left(a) => {
-                    if !self.options.ignore_deleted {
-                        self.prefix_delete(|state| a.print(state, arg_a))?;
+    // Diff a list of items that is sorted by namespace, with a nested block for
+    // each namespace below the given depth.
+    //
+    // Namespaces that contain no differences are not displayed.
+    fn namespace_list<'i, T: SortList + 'i>(
+        &mut self,
+        arg_a: &T::Arg,
+        arg_b: &T::Arg,
+        list: &[(Vec<Option<&str>>, &MergeResult<&'i T, &'i T>)],
+        depth: usize,
+    ) -> Result<()> {
+        let mut list = list;
+        while let Some((path, item)) = list.first() {
+            if path.len() <= depth {
+                self.sort_list_item(arg_a, arg_b, item)?;
+                list = &list[1..];
+                continue;
+            }
+            let name = path[depth];
+            let len = list
+                .iter()
+                .take_while(|x| x.0.len() > depth && x.0[depth] == name)
+                .count();
+            let (group, rest) = list.split_at(len);
+            self.indent_impl(
+                true,
+                false,
+                |state| state.line(name, name, |w, _hash, x| namespace::print_header(x, w)),
+                |state| state.namespace_list(arg_a, arg_b, group, depth + 1),
+            )?;
+            list = rest;
+        }
+        Ok(())
+    }
+
+    fn sort_list_item<'i, T: SortList + 'i>(
+        &mut self,
+        arg_a: &T::Arg,
+        arg_b: &T::Arg,
+        item: &MergeResult<&'i T, &'i T>,
+    ) -> Result<()> {
+        // The grow thresholds only apply to items that have a size.
+        let thresholds = self.options.grow_threshold_bytes.is_some()
+            || self.options.grow_threshold_percent.is_some();
+        match *item {
+            MergeResult::Both(a, b) => {
+                let size_a = a.size(self.hash_a);
+                let size_b = b.size(self.hash_b);
+                if thresholds && (size_a.is_some() || size_b.is_some()) {
+                    if self.grew(size_a, size_b) {
+                        let diff = self.print_if_diff(|state| {
+                            state.line_grew(size_a, size_b)?;
+                            T::diff(state, arg_a, a, arg_b, b)
+                        })?;
+                        self.count_changed::<T>(diff);
+                        return Ok(());
+                    }
+                    if self.options.grow_only
+                        || (size_a != size_b && !self.size_changed(size_a, size_b))
+                    {
+                        // Hidden items are still included in the summary.
+                        if self.counts.is_some() {
+                            let (_, diff) =
+                                self.buffer(|state| T::diff(state, arg_a, a, arg_b, b))?;
+                            self.count_changed::<T>(diff);
+                        }
+                        return Ok(());
                     }
                 }
-                MergeResult::Right(b) => {
-                    if !self.options.ignore_added {
-                        self.prefix_add(|state| b.print(state, arg_b))?;
+                let diff = self.print_if_diff(|state| T::diff(state, arg_a, a, arg_b, b))?;
+                self.count_changed::<T>(diff);
+            }
+            MergeResult::Left(a) => {
+                self.count(a, Change::Deleted);
+                if self.options.ignore_deleted
+                    || (self.options.grow_only && a.size(self.hash_a).is_some())
+                {
+                    return Ok(());
+                }
+                self.prefix_delete(|state| a.print(state, arg_a))?;
+            }
+            MergeResult::Right(b) => {
+                self.count(b, Change::Added);
+                if self.options.ignore_added {
+                    return Ok(());
+                }
+                let size_b = b.size(self.hash_b);
+                if thresholds && size_b.is_some() {
+                    if self.grew(None, size_b) {
+                        self.line_grew(None, size_b)?;
+                    } else if self.options.grow_only {
+                        return Ok(());
                     }
                 }
+                self.prefix_add(|state| b.print(state, arg_b))?;
             }
         }
         Ok(())
     }
+
+    // Return true if the size grew by more than the grow thresholds.
+    fn grew(&self, size_a: Option<u64>, size_b: Option<u64>) -> bool {
+        size_b.unwrap_or(0) > size_a.unwrap_or(0) && self.size_changed(size_a, size_b)
+    }
+
+    // Return true if the size grew or shrank by more than the grow thresholds.
+    fn size_changed(&self, size_a: Option<u64>, size_b: Option<u64>) -> bool {
+        let size_a = size_a.unwrap_or(0);
+        let change = size_b.unwrap_or(0).abs_diff(size_a);
+        if change == 0 {
+            return false;
+        }
+        if let Some(bytes) = self.options.grow_threshold_bytes {
+            if change > bytes {
+                return true;
+            }
+        }
+        if let Some(percent) = self.options.grow_threshold_percent {
+            if size_a == 0 || change.saturating_mul(100) > percent.saturating_mul(size_a) {
+                return true;
+            }
+        }
+        false
+    }
+
+    // A marker for an item that grew by more than the grow thresholds.
+    fn line_grew(&mut self, size_a: Option<u64>, size_b: Option<u64>) -> Result<()> {
+        let size_a = size_a.unwrap_or(0);
+        let size_b = size_b.unwrap_or(0);
+        self.line_modify(|w| {
+            write!(w, "grew: +")?;
+            w.size(size_b - size_a)?;
+            if let Some(percent) = ((size_b - size_a) * 100).checked_div(size_a) {
+                write!(w, " (+{}%)", percent)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// The state for printing a comparison of any number of files.
+///
+/// Unlike `DiffState`, this doesn't print the differences in detail. Instead,
+/// each field is printed as a single line with one column per file.
+pub(crate) struct CompareState<'a> {
+    printer: &'a mut dyn Printer,
+
+    // True if any field had values that weren't all equal.
+    diff: bool,
+
+    options: &'a Options,
+}
+
+impl<'a> CompareState<'a> {
+    pub fn new(printer: &'a mut dyn Printer, options: &'a Options) -> Self {
+        CompareState {
+            printer,
+            diff: false,
+            options,
+        }
+    }
+
+    // Output the header with an indented body, but only if the body is not empty.
+    pub fn collapsed<FHeader, FBody>(&mut self, mut header: FHeader, mut body: FBody) -> Result<()>
+    where
+        FHeader: FnMut(&mut dyn ValuePrinter) -> Result<()>,
+        FBody: FnMut(&mut CompareState) -> Result<()>,
+    {
+        let options = self.options;
+        let mut body_buf = Vec::new();
+        let mut diff = false;
+        self.printer.indent_body(&mut body_buf, &mut |printer| {
+            let mut state = CompareState::new(printer, options);
+            body(&mut state)?;
+            diff = state.diff;
+            Ok(())
+        })?;
+        self.diff |= diff;
+        if !body_buf.is_empty() {
+            self.printer
+                .indent_header(true, &body_buf, &mut |printer| {
+                    let mut buf = Vec::new();
+                    printer.value(&mut buf, &mut header)?;
+                    printer.line("", &buf)
+                })?;
+        }
+        Ok(())
+    }
+
+    pub fn line_break(&mut self) -> Result<()> {
+        self.printer.line_break()
+    }
+
+    /// Print the sizes for each file as a column, and flag the line if they differ.
+    ///
+    /// `None` means the item is missing from that file, and `Some(None)`
+    /// means it is present but its size is unknown.
+    pub fn field_sizes(&mut self, label: &str, sizes: &[Option<Option<u64>>]) -> Result<()> {
+        let differs = sizes.windows(2).any(|w| w[0] != w[1]);
+        let mut buf = Vec::new();
+        self.printer.value(&mut buf, &mut |w| {
+            for (i, size) in sizes.iter().enumerate() {
+                if i != 0 {
+                    write!(w, " | ")?;
+                }
+                match size {
+                    Some(Some(size)) => w.size(*size)?,
+                    Some(None) => write!(w, "?")?,
+                    None => write!(w, "-")?,
+                }
+            }
+            if differs {
+                write!(w, " (differs)")?;
+            }
+            Ok(())
+        })?;
+        self.printer.line(label, &buf)?;
+        self.diff |= differs;
+        Ok(())
+    }
+
+    /// Return true if any field printed so far differed between the files.
+    pub fn diff(&self) -> bool {
+        self.diff
+    }
 }
 
 pub(crate) trait Print {
@@ -871,6 +1592,25 @@ where
     }
 }
 
+impl<T> DiffList for &T
+where
+    T: DiffList,
+{
+    fn step_cost(&self, state: &DiffState, arg: &Self::Arg) -> usize {
+        T::step_cost(*self, state, arg)
+    }
+
+    fn diff_cost(
+        state: &DiffState,
+        arg_a: &Self::Arg,
+        a: &Self,
+        arg_b: &Self::Arg,
+        b: &Self,
+    ) -> usize {
+        T::diff_cost(state, arg_a, *a, arg_b, *b)
+    }
+}
+
 pub(crate) trait PrintHeader {
     fn print_header(&self, state: &mut PrintState) -> Result<()>;
     fn print_body(&self, state: &mut PrintState, unit: &parser::Unit) -> Result<()>;
@@ -926,6 +1666,78 @@ pub(crate) trait SortList: Print {
         b: &Self,
         options: &Options,
     ) -> cmp::Ordering;
+
+    /// The namespace used to group items when `options.group_by_namespace` is set.
+    fn namespace(&self) -> Option<&Namespace<'_>> {
+        None
+    }
+
+    /// The size used to check the grow thresholds in a diff.
+    fn size(&self, _hash: &FileHash) -> Option<u64> {
+        None
+    }
+
+    /// The counts that items of this kind are included in for the diff summary.
+    fn counts(_counts: &DiffCounts) -> Option<&ChangeCounts> {
+        None
+    }
+
+    /// Count an item that is only in one of the files for the diff summary.
+    fn count(&self, _hash: &FileHash, _options: &Options, counts: &DiffCounts, change: Change) {
+        if let Some(counts) = Self::counts(counts) {
+            counts.add(change, 1);
+        }
+    }
+}
+
+/// A kind of change that is counted for the diff summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Change {
+    Changed,
+    Added,
+    Deleted,
+}
+
+/// Counts of the entries of one kind that differ between two files.
+#[derive(Debug, Default)]
+pub(crate) struct ChangeCounts {
+    changed: AtomicU64,
+    added: AtomicU64,
+    deleted: AtomicU64,
+}
+
+impl ChangeCounts {
+    pub fn add(&self, change: Change, count: u64) {
+        let counter = match change {
+            Change::Changed => &self.changed,
+            Change::Added => &self.added,
+            Change::Deleted => &self.deleted,
+        };
+        counter.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn print(&self, w: &mut dyn ValuePrinter, label: &str) -> Result<()> {
+        write!(
+            w,
+            "{} {} changed (+{}/-{}), ",
+            self.changed.load(Ordering::Relaxed),
+            label,
+            self.added.load(Ordering::Relaxed),
+            self.deleted.load(Ordering::Relaxed)
+        )?;
+        Ok(())
+    }
+}
+
+/// Counts of the entries that differ between two files, for the diff summary.
+///
+/// The counts are updated while diffing, and are shared with the threads
+/// used by `DiffState::sort_list_parallel`.
+#[derive(Debug, Default)]
+pub(crate) struct DiffCounts {
+    pub types: ChangeCounts,
+    pub functions: ChangeCounts,
+    pub variables: ChangeCounts,
 }
 
 pub enum MergeResult<T, U> {
@@ -1076,12 +1888,18 @@ impl PartialOrd for State {
 //     0 for items that are completely equal
 //     (2 * step_cost) for items that are completely different
 //     values in between for items that are partially equal
+//
+// Items are only paired if their similarity is greater than similarity_threshold,
+// which is a percentage. The similarity of two items is the fraction of the cost of
+// deleting one and adding the other that is saved by pairing them. Equal items
+// are always paired.
 fn shortest_path<Item, Step1, Step2, Diff>(
     item1: &[Item],
     item2: &[Item],
     step_cost1: Step1,
     step_cost2: Step2,
     diff_cost: Diff,
+    similarity_threshold: u32,
 ) -> Vec<Direction>
 where
     Step1: Fn(&Item) -> usize,
@@ -1203,7 +2021,9 @@ where
             if !node[next].done {
                 let step_cost = step_cost1(&item1[next1]) + step_cost2(&item2[next2]);
                 let diff_cost = diff_cost(&item1[next1], &item2[next2]);
-                if diff_cost < step_cost {
+                // Equivalent to `100 * (step_cost - diff_cost) / step_cost > threshold`.
+                let threshold = 100 - cmp::min(similarity_threshold, 100) as usize;
+                if diff_cost == 0 || diff_cost * 100 < step_cost * threshold {
                     let cost = node[index].cost + diff_cost;
                     push(
                         &mut node[next],
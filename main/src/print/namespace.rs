@@ -14,3 +14,21 @@ pub(crate) fn print(namespace: &Namespace, w: &mut dyn ValuePrinter) -> Result<(
     write!(w, "::")?;
     Ok(())
 }
+
+/// The names of a namespace and its parents, starting with the root namespace.
+pub(crate) fn path<'a>(namespace: Option<&'a Namespace>) -> Vec<Option<&'a str>> {
+    let mut path = Vec::new();
+    let mut namespace = namespace;
+    while let Some(ns) = namespace {
+        path.push(ns.name());
+        namespace = ns.parent();
+    }
+    path.reverse();
+    path
+}
+
+pub(crate) fn print_header(name: Option<&str>, w: &mut dyn ValuePrinter) -> Result<()> {
+    write!(w, "namespace ")?;
+    w.name(name.unwrap_or("<anon>"))?;
+    Ok(())
+}
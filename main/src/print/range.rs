@@ -4,25 +4,20 @@ use crate::print::{DiffState, Print, PrintState, ValuePrinter};
 use crate::Result;
 
 pub(crate) fn print_address(range: &Range, w: &mut dyn ValuePrinter) -> Result<()> {
+    w.address(range.begin)?;
     if range.end > range.begin {
-        write!(w, "0x{:x}-0x{:x}", range.begin, range.end - 1)?;
-    } else {
-        write!(w, "0x{:x}", range.begin)?;
+        write!(w, "-")?;
+        w.address(range.end - 1)?;
     }
     Ok(())
 }
 
 pub(crate) fn print_address_and_size(range: &Range, w: &mut dyn ValuePrinter) -> Result<()> {
+    print_address(range, w)?;
     if range.end > range.begin {
-        write!(
-            w,
-            "0x{:x}-0x{:x} ({})",
-            range.begin,
-            range.end - 1,
-            range.end - range.begin
-        )?;
-    } else {
-        write!(w, "0x{:x}", range.begin)?;
+        write!(w, " (")?;
+        w.size(range.end - range.begin)?;
+        write!(w, ")")?;
     }
     Ok(())
 }
@@ -31,7 +31,8 @@ impl<'input> Print for Section<'input> {
             |state| state.line(|w, _state| print_name(self, w)),
             |state| {
                 state.field("address", |w, _state| print_address(self, w))?;
-                state.field_u64("size", self.size())
+                state.field_size("size", self.size())?;
+                state.field_u64("align", self.align())
             },
         )
     }
@@ -41,13 +42,14 @@ impl<'input> Print for Section<'input> {
             |state| state.line(a, b, |w, _state, x| print_name(x, w)),
             |state| {
                 state.field("address", a, b, |w, _state, x| print_address(x, w))?;
-                state.field_u64("size", a.size(), b.size())
+                state.field_size("size", a.size(), b.size())?;
+                state.field_u64("align", a.align(), b.align())
             },
         )
     }
 }
 
-impl<'input> DiffList for Section<'input> {
+impl<'a, 'input> DiffList for &'a Section<'input> {
     fn step_cost(&self, _state: &DiffState, _arg: &()) -> usize {
         1
     }
@@ -59,6 +61,9 @@ impl<'input> DiffList for Section<'input> {
         {
             cost += 2;
         }
+        if a.align() != b.align() {
+            cost += 1;
+        }
         cost
     }
 }
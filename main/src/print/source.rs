@@ -1,10 +1,15 @@
 use parser::{Source, Unit};
 
 use crate::print::ValuePrinter;
-use crate::Result;
+use crate::{Options, Result};
 
-pub(crate) fn print(source: &Source, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    if let Some(path) = source.path(unit) {
+pub(crate) fn print(
+    source: &Source,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    if let Some(path) = options.source_path(source, unit) {
         write!(w, "{}", path)?;
         if source.line() != 0 {
             write!(w, ":{}", source.line())?;
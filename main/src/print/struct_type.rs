@@ -1,38 +1,56 @@
-use parser::{FileHash, StructType, Unit};
+use std::cmp;
 
-use crate::print::{self, DiffState, PrintHeader, PrintState, ValuePrinter};
-use crate::Result;
+use parser::{FileHash, Function, LayoutItem, Member, StructType, Unit, Virtuality};
 
-fn print_name(ty: &StructType, w: &mut dyn ValuePrinter) -> Result<()> {
+use crate::print::{self, DiffList, DiffState, Print, PrintHeader, PrintState, ValuePrinter};
+use crate::{Options, Result};
+
+fn print_name(ty: &StructType, w: &mut dyn ValuePrinter, anon_name: Option<&str>) -> Result<()> {
     write!(w, "struct ")?;
     if let Some(namespace) = ty.namespace() {
         print::namespace::print(namespace, w)?;
     }
-    w.name(ty.name().unwrap_or("<anon>"))?;
+    match ty.name() {
+        Some(name) => w.name(name)?,
+        None => w.name(anon_name.unwrap_or("<anon>"))?,
+    }
     Ok(())
 }
 
-pub(crate) fn print_ref(ty: &StructType, w: &mut dyn ValuePrinter, id: usize) -> Result<()> {
-    w.link(id, &mut |w| print_name(ty, w))
+pub(crate) fn print_ref(
+    ty: &StructType,
+    w: &mut dyn ValuePrinter,
+    id: usize,
+    anon_name: Option<&str>,
+) -> Result<()> {
+    w.link(id, &mut |w| print_name(ty, w, anon_name))
 }
 
 impl<'input> PrintHeader for StructType<'input> {
     fn print_header(&self, state: &mut PrintState) -> Result<()> {
-        state.line(|w, _state| print_name(self, w))
+        state.line(|w, _state| print_name(self, w, None))
     }
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
         }
         state.field("declaration", |w, state| print_declaration(self, w, state))?;
         state.field("size", |w, state| print_byte_size(self, w, state))?;
+        state.field("alignment", |w, state| print_alignment(self, w, state))?;
         print::inherit::print_list(state, self.inherits())?;
+        let methods = virtual_methods(self, state.hash());
+        state.field_expanded("virtual methods", |state| state.list(&(), &methods))?;
         state.field_expanded("members", |state| print_members(self, state, unit))
     }
 
     fn diff_header(state: &mut DiffState, a: &Self, b: &Self) -> Result<()> {
-        state.line(a, b, |w, _state, x| print_name(x, w))
+        // The names can only differ by discriminators, which are not a change.
+        let flag = state.options().canonical_names;
+        state.ignore_diff(flag, |state| {
+            state.line(a, b, |w, _state, x| print_name(x, w, None))
+        })
     }
 
     fn diff_body(
@@ -42,36 +60,60 @@ impl<'input> PrintHeader for StructType<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
         state.field("declaration", a, b, |w, state, x| {
             print_declaration(x, w, state)
         })?;
         state.field("size", a, b, |w, state, x| print_byte_size(x, w, state))?;
+        state.field("alignment", a, b, |w, state, x| {
+            print_alignment(x, w, state)
+        })?;
         print::inherit::diff_list(state, a.inherits(), b.inherits())?;
+        let methods_a = virtual_methods(a, state.hash_a());
+        let methods_b = virtual_methods(b, state.hash_b());
+        state.field_expanded("virtual methods", |state| {
+            state.list(&(), &methods_a, &(), &methods_b)
+        })?;
         state.field_expanded("members", |state| diff_members(state, unit_a, a, unit_b, b))
     }
 }
 
-fn print_source(ty: &StructType, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(ty.source(), w, unit)
+fn print_source(
+    ty: &StructType,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(ty.source(), w, unit, options)
 }
 
 fn print_byte_size(ty: &StructType, w: &mut dyn ValuePrinter, _hash: &FileHash) -> Result<()> {
     if let Some(size) = ty.byte_size() {
-        write!(w, "{}", size)?;
+        w.size(size)?;
     } else if !ty.is_declaration() {
         debug!("struct with no size");
     }
     Ok(())
 }
 
+// Print the explicit alignment if it differs from the natural alignment.
+fn print_alignment(ty: &StructType, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(alignment) = ty.alignment() {
+        if Some(alignment) != ty.natural_alignment(hash) {
+            write!(w, "{}", alignment)?;
+        }
+    }
+    Ok(())
+}
+
 fn print_declaration(ty: &StructType, w: &mut dyn ValuePrinter, _hash: &FileHash) -> Result<()> {
     if ty.is_declaration() {
         write!(w, "yes")?;
@@ -79,9 +121,146 @@ fn print_declaration(ty: &StructType, w: &mut dyn ValuePrinter, _hash: &FileHash
     Ok(())
 }
 
-pub(crate) fn print_members(ty: &StructType, state: &mut PrintState, unit: &Unit) -> Result<()> {
+// The characters used in a layout diagram for padding, and for bits
+// that are not known to be occupied.
+const LAYOUT_PADDING: char = '.';
+const LAYOUT_UNKNOWN: char = '?';
+const LAYOUT_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Print a diagram showing which layout item occupies each bit of the struct.
+///
+/// Each row of the diagram is 8 bytes, and each byte is 8 characters,
+/// in order of increasing bit offset. The legend gives the item for each character.
+pub(crate) fn print_layout(ty: &StructType, state: &mut PrintState) -> Result<()> {
     let layout = ty.layout(state.hash());
-    state.list(unit, &layout)
+    let bit_size = ty.bit_size().unwrap_or_else(|| {
+        layout
+            .iter()
+            .map(|item| item.bit_offset + item.bit_size.get().unwrap_or(0))
+            .max()
+            .unwrap_or(0)
+    });
+
+    let mut bits = vec![LAYOUT_UNKNOWN; bit_size as usize];
+    let mut legend = Vec::new();
+    let mut index = 0;
+    for item in &layout {
+        let c = match item.item {
+            LayoutItem::Padding => LAYOUT_PADDING,
+            _ => {
+                index += 1;
+                LAYOUT_CHARS.get(index - 1).map_or('#', |&c| c as char)
+            }
+        };
+        if let Some(size) = item.bit_size.get() {
+            let begin = cmp::min(item.bit_offset, bit_size) as usize;
+            let end = cmp::min(item.bit_offset.saturating_add(size), bit_size) as usize;
+            for bit in &mut bits[begin..end] {
+                *bit = c;
+            }
+        }
+        legend.push((c, item));
+    }
+
+    let width = format!("{}", bit_size.saturating_sub(1) / 64 * 8).len();
+    state.collapsed(
+        |state| state.line(|w, _hash| print_name(ty, w, None)),
+        |state| {
+            state.field("size", |w, hash| print_byte_size(ty, w, hash))?;
+            state.field_expanded("layout", |state| {
+                for (row, row_bits) in bits.chunks(64).enumerate() {
+                    state.line(|w, _hash| {
+                        write!(w, "{:>width$} ", row * 8, width = width)?;
+                        for byte_bits in row_bits.chunks(8) {
+                            write!(w, " {}", byte_bits.iter().collect::<String>())?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })?;
+            state.field_expanded("legend", |state| {
+                for (c, item) in &legend {
+                    state.line(|w, hash| {
+                        write!(w, "{}\t", c)?;
+                        print::member::print_layout_item(item, w, hash)
+                    })?;
+                }
+                Ok(())
+            })
+        },
+    )
+}
+
+// A virtual method and its index in the vtable.
+struct VirtualMethod<'a, 'input> {
+    function: &'a Function<'input>,
+}
+
+fn virtual_methods<'a, 'input>(
+    ty: &StructType<'input>,
+    hash: &'a FileHash<'input>,
+) -> Vec<VirtualMethod<'a, 'input>> {
+    ty.virtual_methods(hash)
+        .into_iter()
+        .map(|function| VirtualMethod { function })
+        .collect()
+}
+
+fn print_virtual_method(method: &VirtualMethod, w: &mut dyn ValuePrinter) -> Result<()> {
+    let function = method.function;
+    if let Some(index) = function.vtable_index() {
+        write!(w, "[{}]\t", index)?;
+    }
+    w.name(function.name().unwrap_or("<anon>"))?;
+    if function.virtuality() == Some(Virtuality::PureVirtual) {
+        write!(w, " (pure)")?;
+    }
+    Ok(())
+}
+
+impl<'a, 'input> Print for VirtualMethod<'a, 'input> {
+    type Arg = ();
+
+    fn print(&self, state: &mut PrintState, _arg: &()) -> Result<()> {
+        state.line(|w, _hash| print_virtual_method(self, w))
+    }
+
+    fn diff(state: &mut DiffState, _arg_a: &(), a: &Self, _arg_b: &(), b: &Self) -> Result<()> {
+        state.line(a, b, |w, _hash, x| print_virtual_method(x, w))
+    }
+}
+
+impl<'a, 'input> DiffList for VirtualMethod<'a, 'input> {
+    fn step_cost(&self, _state: &DiffState, _arg: &()) -> usize {
+        1
+    }
+
+    fn diff_cost(_state: &DiffState, _arg_a: &(), a: &Self, _arg_b: &(), b: &Self) -> usize {
+        let mut cost = 0;
+        if a.function.name() != b.function.name() {
+            cost += 2;
+        }
+        if a.function.vtable_index() != b.function.vtable_index() {
+            cost += 1;
+        }
+        cost
+    }
+}
+
+// Static members are not part of the layout, so they are listed after it.
+fn static_members<'a, 'input>(ty: &'a StructType<'input>) -> Vec<&'a Member<'input>> {
+    ty.members().iter().filter(|m| m.is_static()).collect()
+}
+
+pub(crate) fn print_members(ty: &StructType, state: &mut PrintState, unit: &Unit) -> Result<()> {
+    let layout = if state.options().member_declaration_order {
+        ty.declaration_layout(state.hash())
+    } else {
+        ty.layout(state.hash())
+    };
+    state.list(unit, &layout)?;
+    state.list(unit, &static_members(ty))
 }
 
 pub(crate) fn diff_members(
@@ -91,7 +270,14 @@ pub(crate) fn diff_members(
     unit_b: &Unit,
     b: &StructType,
 ) -> Result<()> {
-    let layout_a = a.layout(state.hash_a());
-    let layout_b = b.layout(state.hash_b());
-    state.list(unit_a, &layout_a, unit_b, &layout_b)
+    let (layout_a, layout_b) = if state.options().member_declaration_order {
+        (
+            a.declaration_layout(state.hash_a()),
+            b.declaration_layout(state.hash_b()),
+        )
+    } else {
+        (a.layout(state.hash_a()), b.layout(state.hash_b()))
+    };
+    state.list(unit_a, &layout_a, unit_b, &layout_b)?;
+    state.list(unit_a, &static_members(a), unit_b, &static_members(b))
 }
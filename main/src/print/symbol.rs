@@ -29,7 +29,7 @@ impl<'input> Print for Symbol<'input> {
             |state| state.line(|w, _state| print_name(self, w)),
             |state| {
                 state.field("address", |w, _state| print_address(self, w))?;
-                state.field_u64("size", self.size())
+                state.field_size("size", self.size())
             },
         )
     }
@@ -39,7 +39,7 @@ impl<'input> Print for Symbol<'input> {
             |state| state.line(a, b, |w, _state, x| print_name(x, w)),
             |state| {
                 state.field("address", a, b, |w, _state, x| print_address(x, w))?;
-                state.field_u64("size", a.size(), b.size())
+                state.field_size("size", a.size(), b.size())
             },
         )
     }
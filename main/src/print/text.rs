@@ -1,7 +1,7 @@
 use std;
 use std::io::Write;
 
-use super::{DiffPrefix, Printer, ValuePrinter};
+use super::{format_size, BufferFn, DiffPrefix, Printer, ValuePrinter};
 use crate::{Options, Result};
 
 pub struct TextPrinter<'w> {
@@ -9,15 +9,28 @@ pub struct TextPrinter<'w> {
     indent: usize,
     prefix: DiffPrefix,
     inline_depth: usize,
+    nested_depth: usize,
+    decimal_addresses: bool,
+    load_bias: u64,
+    human_sizes: bool,
 }
 
 impl<'w> TextPrinter<'w> {
+    /// Create a printer that writes plain text to `w`.
+    ///
+    /// The output is written in many small pieces, so `w` should normally be
+    /// buffered, such as with `std::io::BufWriter`. The printer does no buffering
+    /// of its own, so callers that already buffer don't pay for it twice.
     pub fn new(w: &'w mut dyn Write, options: &Options) -> Self {
         TextPrinter {
             w,
             indent: 0,
             prefix: DiffPrefix::None,
             inline_depth: options.inline_depth,
+            nested_depth: super::nested_depth(options),
+            decimal_addresses: options.decimal_addresses,
+            load_bias: options.load_bias,
+            human_sizes: options.human_sizes,
         }
     }
 
@@ -45,7 +58,12 @@ impl<'w> Printer for TextPrinter<'w> {
         buf: &mut Vec<u8>,
         f: &mut dyn FnMut(&mut dyn ValuePrinter) -> Result<()>,
     ) -> Result<()> {
-        let mut p = TextValuePrinter { w: buf };
+        let mut p = TextValuePrinter {
+            w: buf,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
+        };
         f(&mut p)
     }
 
@@ -60,10 +78,37 @@ impl<'w> Printer for TextPrinter<'w> {
             indent: self.indent,
             prefix: self.prefix,
             inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
         };
         f(&mut p)
     }
 
+    fn buffer_fn(&self) -> Option<BufferFn> {
+        let indent = self.indent;
+        let prefix = self.prefix;
+        let inline_depth = self.inline_depth;
+        let nested_depth = self.nested_depth;
+        let decimal_addresses = self.decimal_addresses;
+        let load_bias = self.load_bias;
+        let human_sizes = self.human_sizes;
+        Some(Box::new(move |buf, f| {
+            let mut p = TextPrinter {
+                w: buf,
+                indent,
+                prefix,
+                inline_depth,
+                nested_depth,
+                decimal_addresses,
+                load_bias,
+                human_sizes,
+            };
+            f(&mut p)
+        }))
+    }
+
     fn write_buf(&mut self, buf: &[u8]) -> Result<()> {
         self.w.write_all(buf)?;
         Ok(())
@@ -103,6 +148,10 @@ impl<'w> Printer for TextPrinter<'w> {
             indent: self.indent + 1,
             prefix: self.prefix,
             inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
         };
         body(&mut printer)
     }
@@ -130,6 +179,10 @@ impl<'w> Printer for TextPrinter<'w> {
             indent: self.indent + 1,
             prefix: self.prefix,
             inline_depth: self.inline_depth,
+            nested_depth: self.nested_depth,
+            decimal_addresses: self.decimal_addresses,
+            load_bias: self.load_bias,
+            human_sizes: self.human_sizes,
         };
         body(&mut printer)
     }
@@ -159,6 +212,19 @@ impl<'w> Printer for TextPrinter<'w> {
         self.inline_depth += 1;
     }
 
+    fn nested_begin(&mut self) -> bool {
+        if self.nested_depth == 0 {
+            false
+        } else {
+            self.nested_depth -= 1;
+            true
+        }
+    }
+
+    fn nested_end(&mut self) {
+        self.nested_depth += 1;
+    }
+
     fn instruction(&mut self, address: Option<u64>, mnemonic: &str, buf: &[u8]) -> Result<()> {
         self.write_indent()?;
         if let Some(address) = address {
@@ -181,10 +247,30 @@ impl<'w> Printer for TextPrinter<'w> {
         writeln!(self.w)?;
         Ok(())
     }
+
+    fn flush(&mut self) -> Result<()> {
+        self.w.flush()?;
+        Ok(())
+    }
 }
 
-struct TextValuePrinter<'w> {
-    w: &'w mut Vec<u8>,
+pub(super) struct TextValuePrinter<'w> {
+    pub(super) w: &'w mut Vec<u8>,
+    pub(super) decimal_addresses: bool,
+    pub(super) load_bias: u64,
+    pub(super) human_sizes: bool,
+}
+
+impl<'w> TextValuePrinter<'w> {
+    // A printer that ignores the address and size formatting options.
+    pub(super) fn new(w: &'w mut Vec<u8>) -> Self {
+        TextValuePrinter {
+            w,
+            decimal_addresses: false,
+            load_bias: 0,
+            human_sizes: false,
+        }
+    }
 }
 
 impl<'w> Write for TextValuePrinter<'w> {
@@ -210,4 +296,23 @@ impl<'w> ValuePrinter for TextValuePrinter<'w> {
         self.w.write_all(name.as_bytes())?;
         Ok(())
     }
+
+    fn address(&mut self, address: u64) -> Result<()> {
+        let address = address.wrapping_add(self.load_bias);
+        if self.decimal_addresses {
+            write!(self.w, "{}", address)?;
+        } else {
+            write!(self.w, "0x{:x}", address)?;
+        }
+        Ok(())
+    }
+
+    fn size(&mut self, size: u64) -> Result<()> {
+        if self.human_sizes {
+            write!(self.w, "{}", format_size(size))?;
+        } else {
+            write!(self.w, "{}", size)?;
+        }
+        Ok(())
+    }
 }
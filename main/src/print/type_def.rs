@@ -1,10 +1,11 @@
 use std::borrow::Cow;
 use std::ops::Deref;
 
-use parser::{FileHash, TypeDef, Unit};
+use parser::{FileHash, TypeDef, TypeKind, Unit};
 
+use crate::print::types::AnonName;
 use crate::print::{self, DiffState, PrintHeader, PrintState, ValuePrinter};
-use crate::Result;
+use crate::{Options, Result};
 
 fn print_name(ty: &TypeDef, w: &mut dyn ValuePrinter) -> Result<()> {
     if let Some(namespace) = ty.namespace() {
@@ -22,17 +23,44 @@ fn print_def(ty: &TypeDef, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<
     write!(w, "type ")?;
     print_name(ty, w)?;
     write!(w, " = ")?;
-    print::types::print_ref(ty.ty(hash), w, hash)?;
+    let anon = ty.name().map(AnonName::Except).unwrap_or(AnonName::Derived);
+    print::types::print_ref_anon(ty.ty(hash), w, hash, anon)?;
     Ok(())
 }
 
-fn print_source(ty: &TypeDef, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(ty.source(), w, unit)
+// Return true if the type being aliased is another type definition.
+fn is_chain(ty: &TypeDef, hash: &FileHash) -> bool {
+    ty.ty(hash)
+        .map(|ty| matches!(*ty.kind(), TypeKind::Def(..)))
+        .unwrap_or(false)
+}
+
+fn print_resolved(ty: &TypeDef, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    print::types::print_ref(ty.resolved_ty(hash), w, hash)
+}
+
+fn print_source(
+    ty: &TypeDef,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(ty.source(), w, unit, options)
 }
 
 fn print_byte_size(ty: &TypeDef, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
     if let Some(byte_size) = ty.byte_size(hash) {
-        write!(w, "{}", byte_size)?;
+        w.size(byte_size)?;
+    }
+    Ok(())
+}
+
+// Print the explicit alignment if it differs from the natural alignment.
+fn print_alignment(ty: &TypeDef, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(alignment) = ty.alignment() {
+        if Some(alignment) != ty.natural_alignment(hash) {
+            write!(w, "{}", alignment)?;
+        }
     }
     Ok(())
 }
@@ -44,10 +72,15 @@ impl<'input> PrintHeader for TypeDef<'input> {
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
         let ty = self.ty(state.hash());
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
+        }
+        if is_chain(self, state.hash()) {
+            state.field("resolves to", |w, state| print_resolved(self, w, state))?;
         }
         state.field("size", |w, state| print_byte_size(self, w, state))?;
+        state.field("alignment", |w, state| print_alignment(self, w, state))?;
         if let Some(ref ty) = ty {
             if ty.is_anon() {
                 state.field_expanded("members", |state| {
@@ -69,15 +102,24 @@ impl<'input> PrintHeader for TypeDef<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
+        if is_chain(a, state.hash_a()) || is_chain(b, state.hash_b()) {
+            state.field("resolves to", a, b, |w, state, x| {
+                print_resolved(x, w, state)
+            })?;
+        }
         state.field("size", a, b, |w, state, x| print_byte_size(x, w, state))?;
+        state.field("alignment", a, b, |w, state, x| {
+            print_alignment(x, w, state)
+        })?;
         let ty_a = filter_option(a.ty(state.hash_a()), |ty| ty.is_anon());
         let ty_a = ty_a.as_ref().map(Cow::deref);
         let ty_b = filter_option(b.ty(state.hash_b()), |ty| ty.is_anon());
@@ -2,11 +2,15 @@ use std::borrow::Cow;
 use std::cmp;
 
 use parser::{
-    ArrayType, FileHash, FunctionType, PointerToMemberType, SubrangeType, Type, TypeKind,
-    TypeModifier, TypeModifierKind, Unit, UnspecifiedType,
+    ArrayType, FileHash, FunctionType, Namespace, PointerToMemberType, Source, SubrangeType, Type,
+    TypeKind, TypeModifier, TypeModifierKind, TypeOffset, Unit, UnspecifiedType,
 };
 
-use crate::print::{self, DiffState, Print, PrintHeader, PrintState, SortList, ValuePrinter};
+use crate::filter;
+use crate::print::{
+    self, ChangeCounts, DiffCounts, DiffState, Print, PrintHeader, PrintState, SortList,
+    ValuePrinter,
+};
 use crate::{Options, Result, Sort};
 
 pub(crate) fn kind<'a>(ty: &'a Type) -> Result<&'a dyn PrintHeader> {
@@ -31,16 +35,43 @@ pub(crate) fn print(ty: &Type, state: &mut PrintState, unit: &Unit) -> Result<()
     state.id(
         ty.id(),
         |state| kind.print_header(state),
-        |state| kind.print_body(state, unit),
+        |state| {
+            if state.options().print_offset {
+                state.field("offset", |w, _state| {
+                    print::print_offset(ty.offset().get(), w)
+                })?;
+            }
+            kind.print_body(state, unit)
+        },
     )?;
     state.line_break()?;
     Ok(())
 }
 
+/// How to name anonymous types when printing a type reference.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum AnonName<'a> {
+    /// Use the name derived from a use of the type, or the source location.
+    Derived,
+    /// As for `Derived`, but omit the name if it is derived from this use.
+    Except(&'a str),
+    /// Omit the name. Used when the type is printed inline.
+    Omit,
+}
+
 pub(crate) fn print_ref(
     ty: Option<Cow<Type>>,
     w: &mut dyn ValuePrinter,
     hash: &FileHash,
+) -> Result<()> {
+    print_ref_anon(ty, w, hash, AnonName::Derived)
+}
+
+pub(crate) fn print_ref_anon(
+    ty: Option<Cow<Type>>,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+    anon: AnonName,
 ) -> Result<()> {
     match ty {
         None => {
@@ -53,9 +84,18 @@ pub(crate) fn print_ref(
                 TypeKind::Void => print_ref_void(w),
                 TypeKind::Base(ref val) => print::base_type::print_ref(val, w, id),
                 TypeKind::Def(ref val) => print::type_def::print_ref(val, w, id),
-                TypeKind::Struct(ref val) => print::struct_type::print_ref(val, w, id),
-                TypeKind::Union(ref val) => print::union_type::print_ref(val, w, id),
-                TypeKind::Enumeration(ref val) => print::enumeration::print_ref(val, w, id),
+                TypeKind::Struct(ref val) => {
+                    let anon_name = anon_label(ty.offset(), val.source(), hash, anon);
+                    print::struct_type::print_ref(val, w, id, anon_name.as_deref())
+                }
+                TypeKind::Union(ref val) => {
+                    let anon_name = anon_label(ty.offset(), val.source(), hash, anon);
+                    print::union_type::print_ref(val, w, id, anon_name.as_deref())
+                }
+                TypeKind::Enumeration(ref val) => {
+                    let anon_name = anon_label(ty.offset(), val.source(), hash, anon);
+                    print::enumeration::print_ref(val, w, id, anon_name.as_deref())
+                }
                 TypeKind::Array(ref val) => print_ref_array(val, w, hash),
                 TypeKind::Function(ref val) => print_ref_function(val, w, hash),
                 TypeKind::Unspecified(ref val) => print_ref_unspecified(val, w),
@@ -86,18 +126,60 @@ fn ref_id(ty: Option<Cow<Type>>, hash: &FileHash) -> Option<usize> {
     }
 }
 
+/// Return the name to print for an anonymous type.
+///
+/// This is the name derived from a use of the type if there is one,
+/// otherwise the source location of the type.
+pub(crate) fn anon_label(
+    offset: TypeOffset,
+    source: &Source,
+    hash: &FileHash,
+    anon: AnonName,
+) -> Option<String> {
+    let name = hash.anon_type_name(offset);
+    match (anon, name) {
+        (AnonName::Omit, _) => return None,
+        (AnonName::Except(user), Some(name)) if user == name => return None,
+        _ => {}
+    }
+    match (name, source.file()) {
+        (Some(name), _) => Some(format!("<anon {}>", name)),
+        (None, Some(file)) if source.line() != 0 => {
+            Some(format!("<anon@{}:{}>", file, source.line()))
+        }
+        (None, _) => None,
+    }
+}
+
 fn print_ref_void(w: &mut dyn ValuePrinter) -> Result<()> {
     write!(w, "void")?;
     Ok(())
 }
 
 fn print_ref_array(ty: &ArrayType, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
-    write!(w, "[")?;
+    let counts: Vec<_> = ty.counts().collect();
+    if counts.len() <= 1 {
+        write!(w, "[")?;
+        print_ref(ty.element_type(hash), w, hash)?;
+        if let Some(count) = ty.count(hash) {
+            write!(w, "; {}", count)?;
+        }
+        write!(w, "]")?;
+        return Ok(());
+    }
+
+    // Print multi-dimensional arrays as nested arrays, with the
+    // outermost dimension first.
+    for _ in &counts {
+        write!(w, "[")?;
+    }
     print_ref(ty.element_type(hash), w, hash)?;
-    if let Some(count) = ty.count(hash) {
-        write!(w, "; {}", count)?;
+    for count in counts.iter().rev() {
+        if let Some(count) = count {
+            write!(w, "; {}", count)?;
+        }
+        write!(w, "]")?;
     }
-    write!(w, "]")?;
     Ok(())
 }
 
@@ -115,6 +197,12 @@ fn print_ref_function(ty: &FunctionType, w: &mut dyn ValuePrinter, hash: &FileHa
         }
         print_ref(parameter.ty(hash), w, hash)?;
     }
+    if ty.is_variadic() {
+        if !first {
+            write!(w, ", ")?;
+        }
+        write!(w, "...")?;
+    }
     write!(w, ")")?;
 
     if let Some(return_type) = ty.return_type(hash) {
@@ -155,14 +243,15 @@ fn print_ref_modifier(ty: &TypeModifier, w: &mut dyn ValuePrinter, hash: &FileHa
     } else {
         match ty.kind() {
             TypeModifierKind::Pointer => write!(w, "* ")?,
-            TypeModifierKind::Reference | TypeModifierKind::RvalueReference => write!(w, "& ")?,
+            TypeModifierKind::Reference => write!(w, "& ")?,
+            TypeModifierKind::RvalueReference => write!(w, "&& ")?,
             TypeModifierKind::Const => write!(w, "const ")?,
             TypeModifierKind::Volatile => write!(w, "volatile ")?,
             TypeModifierKind::Restrict => write!(w, "restrict ")?,
-            TypeModifierKind::Packed
-            | TypeModifierKind::Shared
-            | TypeModifierKind::Atomic
-            | TypeModifierKind::Other => {}
+            TypeModifierKind::Atomic => write!(w, "atomic ")?,
+            TypeModifierKind::Packed => write!(w, "packed ")?,
+            TypeModifierKind::Shared => write!(w, "shared ")?,
+            TypeModifierKind::Other => {}
         }
         print_ref(ty.ty(hash), w, hash)?;
     }
@@ -209,6 +298,14 @@ pub(crate) fn diff_body(
     type_b: &Type,
 ) -> Result<()> {
     use self::TypeKind::*;
+    if state.options().print_offset {
+        // Offsets are expected to differ, so they are not a change.
+        state.ignore_diff(true, |state| {
+            state.field("offset", type_a, type_b, |w, _state, x| {
+                print::print_offset(x.offset().get(), w)
+            })
+        })?;
+    }
     match (type_a.kind(), type_b.kind()) {
         (&Base(ref a), &Base(ref b)) => PrintHeader::diff_body(state, unit_a, a, unit_b, b),
         (&Def(ref a), &Def(ref b)) => PrintHeader::diff_body(state, unit_a, a, unit_b, b),
@@ -298,8 +395,21 @@ impl<'input> SortList for Type<'input> {
         type_a: &Type,
         hash_b: &FileHash,
         type_b: &Type,
-        _options: &Options,
+        options: &Options,
     ) -> cmp::Ordering {
+        if options.canonical_names {
+            let names = match (type_a.kind(), type_b.kind()) {
+                (TypeKind::Def(a), TypeKind::Def(b)) => Some((a.name(), b.name())),
+                (TypeKind::Struct(a), TypeKind::Struct(b)) => Some((a.name(), b.name())),
+                (TypeKind::Union(a), TypeKind::Union(b)) => Some((a.name(), b.name())),
+                (TypeKind::Enumeration(a), TypeKind::Enumeration(b)) => Some((a.name(), b.name())),
+                _ => None,
+            };
+            if let Some((name_a, name_b)) = names {
+                let (ns_a, ns_b) = (type_a.namespace(), type_b.namespace());
+                return filter::cmp_ns_and_name(ns_a, name_a, ns_b, name_b, options);
+            }
+        }
         Type::cmp_id(hash_a, type_a, hash_b, type_b)
     }
 
@@ -312,8 +422,20 @@ impl<'input> SortList for Type<'input> {
     ) -> cmp::Ordering {
         match options.sort {
             Sort::None => a.offset().cmp(&b.offset()),
-            Sort::Name => Type::cmp_id(hash_a, a, hash_b, b),
+            Sort::Name => SortList::cmp_id(hash_a, a, hash_b, b, options),
             Sort::Size => a.byte_size(hash_a).cmp(&b.byte_size(hash_b)),
         }
     }
+
+    fn namespace(&self) -> Option<&Namespace<'_>> {
+        self.namespace()
+    }
+
+    fn size(&self, hash: &FileHash) -> Option<u64> {
+        self.byte_size(hash)
+    }
+
+    fn counts(counts: &DiffCounts) -> Option<&ChangeCounts> {
+        Some(&counts.types)
+    }
 }
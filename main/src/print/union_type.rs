@@ -1,37 +1,51 @@
 use parser::{FileHash, UnionType, Unit};
 
 use crate::print::{self, DiffState, PrintHeader, PrintState, ValuePrinter};
-use crate::Result;
+use crate::{Options, Result};
 
-fn print_name(ty: &UnionType, w: &mut dyn ValuePrinter) -> Result<()> {
+fn print_name(ty: &UnionType, w: &mut dyn ValuePrinter, anon_name: Option<&str>) -> Result<()> {
     write!(w, "union ")?;
     if let Some(namespace) = ty.namespace() {
         print::namespace::print(namespace, w)?;
     }
-    w.name(ty.name().unwrap_or("<anon>"))?;
+    match ty.name() {
+        Some(name) => w.name(name)?,
+        None => w.name(anon_name.unwrap_or("<anon>"))?,
+    }
     Ok(())
 }
 
-pub(crate) fn print_ref(ty: &UnionType, w: &mut dyn ValuePrinter, id: usize) -> Result<()> {
-    w.link(id, &mut |w| print_name(ty, w))
+pub(crate) fn print_ref(
+    ty: &UnionType,
+    w: &mut dyn ValuePrinter,
+    id: usize,
+    anon_name: Option<&str>,
+) -> Result<()> {
+    w.link(id, &mut |w| print_name(ty, w, anon_name))
 }
 
 impl<'input> PrintHeader for UnionType<'input> {
     fn print_header(&self, state: &mut PrintState) -> Result<()> {
-        state.line(|w, _state| print_name(self, w))
+        state.line(|w, _state| print_name(self, w, None))
     }
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
         }
         state.field("declaration", |w, state| print_declaration(self, w, state))?;
         state.field("size", |w, state| print_byte_size(self, w, state))?;
+        state.field("alignment", |w, state| print_alignment(self, w, state))?;
         state.field_expanded("members", |state| print_members(self, state, unit))
     }
 
     fn diff_header(state: &mut DiffState, a: &Self, b: &Self) -> Result<()> {
-        state.line(a, b, |w, _state, x| print_name(x, w))
+        // The names can only differ by discriminators, which are not a change.
+        let flag = state.options().canonical_names;
+        state.ignore_diff(flag, |state| {
+            state.line(a, b, |w, _state, x| print_name(x, w, None))
+        })
     }
 
     fn diff_body(
@@ -41,35 +55,54 @@ impl<'input> PrintHeader for UnionType<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
         state.field("declaration", a, b, |w, state, x| {
             print_declaration(x, w, state)
         })?;
         state.field("size", a, b, |w, state, x| print_byte_size(x, w, state))?;
+        state.field("alignment", a, b, |w, state, x| {
+            print_alignment(x, w, state)
+        })?;
         state.field_expanded("members", |state| diff_members(state, unit_a, a, unit_b, b))
     }
 }
 
-fn print_source(ty: &UnionType, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(ty.source(), w, unit)
+fn print_source(
+    ty: &UnionType,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(ty.source(), w, unit, options)
 }
 
 fn print_byte_size(ty: &UnionType, w: &mut dyn ValuePrinter, _hash: &FileHash) -> Result<()> {
     if let Some(size) = ty.byte_size() {
-        write!(w, "{}", size)?;
+        w.size(size)?;
     } else if !ty.is_declaration() {
         debug!("struct with no size");
     }
     Ok(())
 }
 
+// Print the explicit alignment if it differs from the natural alignment.
+fn print_alignment(ty: &UnionType, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(alignment) = ty.alignment() {
+        if Some(alignment) != ty.natural_alignment(hash) {
+            write!(w, "{}", alignment)?;
+        }
+    }
+    Ok(())
+}
+
 fn print_declaration(ty: &UnionType, w: &mut dyn ValuePrinter, _hash: &FileHash) -> Result<()> {
     if ty.is_declaration() {
         write!(w, "yes")?;
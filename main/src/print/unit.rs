@@ -1,10 +1,11 @@
 use std::cmp;
 
-use parser::{FileHash, Function, Range, Type, Unit, Variable};
+use parser::{File, FileHash, Function, Range, Type, Unit, Variable};
 
 use crate::filter;
 use crate::print::{
-    self, DiffState, MergeIterator, MergeResult, Print, PrintState, SortList, ValuePrinter,
+    self, Change, DiffCounts, DiffState, MergeIterator, MergeResult, Print, PrintState, SortList,
+    ValuePrinter,
 };
 use crate::{Options, Result, Sort};
 
@@ -20,7 +21,7 @@ pub(crate) fn merged_types<'a, 'input>(
     let mut types_b = filter::filter_types(unit_b, hash_b, options, true);
     types_b.sort_by(|x, y| Type::cmp_id_for_sort(hash_b, x, hash_b, y, options));
     MergeIterator::new(types_a.into_iter(), types_b.into_iter(), |a, b| {
-        Type::cmp_id(hash_a, a, hash_b, b)
+        <Type as SortList>::cmp_id(hash_a, a, hash_b, b, options)
     })
     .collect()
 }
@@ -65,9 +66,9 @@ pub(crate) fn merged_variables<'a, 'input>(
     unit_b: &'a Unit<'input>,
     options: &Options,
 ) -> Vec<MergeResult<&'a Variable<'input>, &'a Variable<'input>>> {
-    let mut variables_a = filter::filter_variables(unit_a, options);
+    let mut variables_a = filter::filter_variables(unit_a, hash_a, options);
     variables_a.sort_by(|x, y| Variable::cmp_id_for_sort(hash_a, x, hash_a, y, options));
-    let mut variables_b = filter::filter_variables(unit_b, options);
+    let mut variables_b = filter::filter_variables(unit_b, hash_b, options);
     variables_b.sort_by(|x, y| Variable::cmp_id_for_sort(hash_b, x, hash_b, y, options));
     MergeIterator::new(variables_a.into_iter(), variables_b.into_iter(), |a, b| {
         <Variable as SortList>::cmp_id(hash_a, a, hash_b, b, options)
@@ -75,15 +76,16 @@ pub(crate) fn merged_variables<'a, 'input>(
     .collect()
 }
 
-fn print_ref(unit: &Unit, w: &mut dyn ValuePrinter) -> Result<()> {
-    write!(w, "{}", unit.name().unwrap_or("<anon>"))?;
+fn print_ref(unit: &Unit, w: &mut dyn ValuePrinter, options: &Options) -> Result<()> {
+    write!(w, "{}", options.unit_name(unit).unwrap_or("<anon>"))?;
     Ok(())
 }
 
 pub(crate) fn print_header(unit: &Unit, state: &mut PrintState) -> Result<()> {
+    let options = state.options();
     state.line(|w, _state| {
         write!(w, "unit ")?;
-        print_ref(unit, w)
+        print_ref(unit, w, options)
     })
 }
 
@@ -105,21 +107,31 @@ pub(crate) fn print_body(unit: &Unit, state: &mut PrintState) -> Result<()> {
             state.field_collapsed("unknown addresses", |state| {
                 state.list(&(), unknown_ranges.list())
             })?;
+            state.field("address span", |w, _state| print_span(unit, w))?;
+            state.field("density", |w, _state| print_density(unit, w))?;
+        }
+
+        state.field("language", |w, _state| print_language(unit, w))?;
+        state.field("producer", |w, _state| print_producer(unit, w))?;
+
+        let size = unit.size(state.hash());
+        if size != 0 {
+            state.field_size("size", size)?;
         }
 
         let fn_size = unit.function_size();
         if fn_size != 0 {
-            state.field_u64("fn size", fn_size)?;
+            state.field_size("fn size", fn_size)?;
         }
 
         let var_size = unit.variable_size(state.hash());
         if var_size != 0 {
-            state.field_u64("var size", var_size)?;
+            state.field_size("var size", var_size)?;
         }
 
         let unknown_size = unknown_ranges.size();
         if unknown_size != 0 {
-            state.field_u64("unknown size", unknown_size)?;
+            state.field_size("unknown size", unknown_size)?;
         }
 
         state.line_break()?;
@@ -142,7 +154,7 @@ pub(crate) fn print_body(unit: &Unit, state: &mut PrintState) -> Result<()> {
     };
     let print_variables = |state: &mut PrintState| -> Result<()> {
         if options.category_variable {
-            let mut variables = filter::filter_variables(unit, options);
+            let mut variables = filter::filter_variables(unit, state.hash(), options);
             state.sort_list(unit, &mut variables)?;
         }
         Ok(())
@@ -189,9 +201,10 @@ pub(crate) fn print(unit: &Unit, state: &mut PrintState) -> Result<()> {
 }
 
 fn diff_header(state: &mut DiffState, unit_a: &Unit, unit_b: &Unit) -> Result<()> {
+    let options = state.options();
     state.line(unit_a, unit_b, |w, _state, unit| {
         write!(w, "unit ")?;
-        print_ref(unit, w)
+        print_ref(unit, w, options)
     })
 }
 
@@ -223,24 +236,43 @@ pub(crate) fn diff_body(state: &mut DiffState, unit_a: &Unit, unit_b: &Unit) ->
             state.field_collapsed("unknown addresses", |state| {
                 state.ord_list(&(), unknown_ranges_a.list(), &(), unknown_ranges_b.list())
             })?;
+            state.field("address span", unit_a, unit_b, |w, _state, unit| {
+                print_span(unit, w)
+            })?;
+            state.field("density", unit_a, unit_b, |w, _state, unit| {
+                print_density(unit, w)
+            })?;
+        }
+
+        state.field("language", unit_a, unit_b, |w, _state, unit| {
+            print_language(unit, w)
+        })?;
+        state.field("producer", unit_a, unit_b, |w, _state, unit| {
+            print_producer(unit, w)
+        })?;
+
+        let size_a = unit_a.size(state.hash_a());
+        let size_b = unit_b.size(state.hash_b());
+        if size_a != 0 || size_b != 0 {
+            state.field_size("size", size_a, size_b)?;
         }
 
         let fn_size_a = unit_a.function_size();
         let fn_size_b = unit_b.function_size();
         if fn_size_a != 0 || fn_size_b != 0 {
-            state.field_u64("fn size", fn_size_a, fn_size_b)?;
+            state.field_size("fn size", fn_size_a, fn_size_b)?;
         }
 
         let var_size_a = unit_a.variable_size(state.hash_a());
         let var_size_b = unit_b.variable_size(state.hash_b());
         if var_size_a != 0 || var_size_b != 0 {
-            state.field_u64("var size", var_size_a, var_size_b)?;
+            state.field_size("var size", var_size_a, var_size_b)?;
         }
 
         let unknown_size_a = unknown_ranges_a.size();
         let unknown_size_b = unknown_ranges_b.size();
         if unknown_size_a != 0 || unknown_size_b != 0 {
-            state.field_u64("unknown size", unknown_size_a, unknown_size_b)?;
+            state.field_size("unknown size", unknown_size_a, unknown_size_b)?;
         }
 
         state.line_break()?;
@@ -255,7 +287,16 @@ pub(crate) fn diff_body(state: &mut DiffState, unit_a: &Unit, unit_b: &Unit) ->
         Ok(())
     };
     let merged_functions = |state: &mut DiffState| {
-        merged_functions(state.hash_a(), unit_a, state.hash_b(), unit_b, options)
+        let (mut functions, inlined_functions) =
+            merged_functions(state.hash_a(), unit_a, state.hash_b(), unit_b, options);
+        let renamed = state.match_renamed_functions(&mut functions);
+        (functions, inlined_functions, renamed)
+    };
+    let diff_renamed = |state: &mut DiffState, renamed: &[(&Function, &Function)]| {
+        for (a, b) in renamed {
+            print::function::diff_renamed(state, a, b)?;
+        }
+        Ok(())
     };
     let diff_variables = |state: &mut DiffState| -> Result<()> {
         if options.category_variable {
@@ -272,9 +313,10 @@ pub(crate) fn diff_body(state: &mut DiffState, unit_a: &Unit, unit_b: &Unit) ->
         }
         state.field_collapsed("types", &diff_types)?;
         if options.category_function {
-            let (mut functions, mut inlined_functions) = merged_functions(state);
+            let (mut functions, mut inlined_functions, renamed) = merged_functions(state);
             state.field_collapsed("functions", |state| {
-                state.sort_list(unit_a, unit_b, &mut functions)
+                state.sort_list(unit_a, unit_b, &mut functions)?;
+                diff_renamed(state, &renamed)
             })?;
             state.field_collapsed("inlined functions", |state| {
                 state.sort_list(unit_a, unit_b, &mut inlined_functions)
@@ -287,8 +329,9 @@ pub(crate) fn diff_body(state: &mut DiffState, unit_a: &Unit, unit_b: &Unit) ->
         }
         diff_types(state)?;
         if options.category_function {
-            let (mut functions, mut inlined_functions) = merged_functions(state);
+            let (mut functions, mut inlined_functions, renamed) = merged_functions(state);
             state.sort_list(unit_a, unit_b, &mut functions)?;
+            diff_renamed(state, &renamed)?;
             state.sort_list(unit_a, unit_b, &mut inlined_functions)?;
         }
         diff_variables(state)?;
@@ -313,7 +356,98 @@ fn print_address(unit: &Unit, w: &mut dyn ValuePrinter, range: Option<Range>) ->
     if let Some(ref range) = range {
         print::range::print_address(range, w)?;
     } else if let Some(low_pc) = unit.address() {
-        write!(w, "0x{:x}", low_pc)?;
+        w.address(low_pc)?;
+    }
+    Ok(())
+}
+
+fn print_span(unit: &Unit, w: &mut dyn ValuePrinter) -> Result<()> {
+    if let Some(ref span) = unit.address_span() {
+        print::range::print_address_and_size(span, w)?;
+    }
+    Ok(())
+}
+
+fn print_density(unit: &Unit, w: &mut dyn ValuePrinter) -> Result<()> {
+    if let Some(density) = unit.address_density() {
+        write!(w, "{:.1}%", density * 100.0)?;
+    }
+    Ok(())
+}
+
+/// Print the `top` units with the lowest address density, in order of
+/// increasing density.
+pub(crate) fn print_top_fragmented(
+    state: &mut PrintState,
+    units: &[&Unit],
+    top: usize,
+) -> Result<()> {
+    let mut units: Vec<_> = units
+        .iter()
+        .filter_map(|unit| unit.address_density().map(|density| (density, *unit)))
+        .collect();
+    units.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(cmp::Ordering::Equal));
+    for (_, unit) in units.into_iter().take(top) {
+        state.expanded(
+            |state| print_header(unit, state),
+            |state| {
+                state.field("address span", |w, _state| print_span(unit, w))?;
+                state.field("density", |w, _state| print_density(unit, w))?;
+                Ok(())
+            },
+        )?;
+        state.line_break()?;
+    }
+    Ok(())
+}
+
+fn print_producer(unit: &Unit, w: &mut dyn ValuePrinter) -> Result<()> {
+    if let Some(producer) = unit.producer() {
+        write!(w, "{}", producer)?;
+    }
+    Ok(())
+}
+
+/// The producer of one or more units.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Producer<'a>(&'a str);
+
+impl<'a> Print for Producer<'a> {
+    type Arg = ();
+
+    fn print(&self, state: &mut PrintState, _arg: &()) -> Result<()> {
+        state.line(|w, _state| {
+            write!(w, "{}", self.0)?;
+            Ok(())
+        })
+    }
+
+    fn diff(state: &mut DiffState, _arg_a: &(), a: &Self, _arg_b: &(), b: &Self) -> Result<()> {
+        state.line(a, b, |w, _state, x| {
+            write!(w, "{}", x.0)?;
+            Ok(())
+        })
+    }
+}
+
+/// The distinct producers of the units in a file, in sorted order.
+pub(crate) fn producers<'a>(file: &'a File) -> Vec<Producer<'a>> {
+    let mut producers: Vec<_> = file
+        .units()
+        .iter()
+        .filter_map(|unit| unit.producer().map(Producer))
+        .collect();
+    producers.sort();
+    producers.dedup();
+    producers
+}
+
+fn print_language(unit: &Unit, w: &mut dyn ValuePrinter) -> Result<()> {
+    if let Some(language) = unit.language() {
+        match language.static_string() {
+            Some(name) => write!(w, "{}", name.trim_start_matches("DW_LANG_"))?,
+            None => write!(w, "0x{:x}", language.0)?,
+        }
     }
     Ok(())
 }
@@ -338,8 +472,8 @@ impl<'input> SortList for Unit<'input> {
         b: &Self,
         options: &Options,
     ) -> cmp::Ordering {
-        let (prefix_a, suffix_a) = options.prefix_map(a.name().unwrap_or(""));
-        let (prefix_b, suffix_b) = options.prefix_map(b.name().unwrap_or(""));
+        let (prefix_a, suffix_a) = options.prefix_map(options.unit_name(a).unwrap_or(""));
+        let (prefix_b, suffix_b) = options.prefix_map(options.unit_name(b).unwrap_or(""));
         let iter_a = prefix_a.bytes().chain(suffix_a.bytes());
         let iter_b = prefix_b.bytes().chain(suffix_b.bytes());
         iter_a.cmp(iter_b)
@@ -359,4 +493,23 @@ impl<'input> SortList for Unit<'input> {
             Sort::Size => a.size(hash_a).cmp(&b.size(hash_b)),
         }
     }
+
+    // An added or deleted unit isn't diffed, so count its entries instead.
+    fn count(&self, hash: &FileHash, options: &Options, counts: &DiffCounts, change: Change) {
+        let empty = Unit::default();
+        if options.category_type {
+            let types = merged_types(hash, self, hash, &empty, options);
+            counts.types.add(change, types.len() as u64);
+        }
+        if options.category_function {
+            let (functions, inlined_functions) =
+                merged_functions(hash, self, hash, &empty, options);
+            let len = functions.len() + inlined_functions.len();
+            counts.functions.add(change, len as u64);
+        }
+        if options.category_variable {
+            let variables = merged_variables(hash, self, hash, &empty, options);
+            counts.variables.add(change, variables.len() as u64);
+        }
+    }
 }
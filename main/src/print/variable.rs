@@ -1,10 +1,21 @@
 use std::cmp;
 
-use parser::{FileHash, Unit, Variable};
+use parser::{FileHash, Namespace, Range, SectionKind, Unit, Variable};
 
-use crate::print::{self, DiffState, Print, PrintHeader, PrintState, SortList, ValuePrinter};
+use crate::filter;
+use crate::print::types::AnonName;
+use crate::print::{
+    self, ChangeCounts, DiffCounts, DiffState, Print, PrintHeader, PrintState, SortList,
+    ValuePrinter,
+};
 use crate::{Options, Result, Sort};
 
+// Variables larger than this don't have their bytes printed.
+const MAX_BYTES: u64 = 64;
+
+// The number of bytes printed on each line.
+const BYTES_PER_LINE: usize = 16;
+
 pub(crate) fn print_ref(v: &Variable, w: &mut dyn ValuePrinter) -> Result<()> {
     w.link(v.id(), &mut |w| {
         if let Some(namespace) = v.namespace() {
@@ -15,19 +26,71 @@ pub(crate) fn print_ref(v: &Variable, w: &mut dyn ValuePrinter) -> Result<()> {
     })
 }
 
+// Diff a variable from the debuginfo of one file against a variable from the
+// symbol table of another file.
+//
+// Only the fields that are known for symbols are compared.
+pub(crate) fn diff_symbol(state: &mut DiffState, a: &Variable, b: &Variable) -> Result<()> {
+    state.collapsed(
+        |state| {
+            // Symbols have no type, and their name may be mangled.
+            state.ignore_diff(true, |state| PrintHeader::diff_header(state, a, b))
+        },
+        |state| {
+            let flag = state.options().ignore_variable_address;
+            state.ignore_diff(flag, |state| {
+                state.field("address", a, b, |w, _state, x| {
+                    print::print_address_presence(x.address(), w)
+                })
+            })?;
+            state.field("size", a, b, |w, state, x| print_size(x, w, state))
+        },
+    )?;
+    state.line_break()
+}
+
 impl<'input> PrintHeader for Variable<'input> {
     fn print_header(&self, state: &mut PrintState) -> Result<()> {
         state.line(|w, state| print_name(self, w, state))
     }
 
     fn print_body(&self, state: &mut PrintState, unit: &Unit) -> Result<()> {
+        if state.options().print_offset {
+            state.field("offset", |w, _state| {
+                print::print_offset(self.offset().get(), w)
+            })?;
+        }
         state.field("linkage name", |w, _state| print_linkage_name(self, w))?;
         state.field("symbol name", |w, _state| print_symbol_name(self, w))?;
-        if state.options().print_source {
-            state.field("source", |w, _state| print_source(self, w, unit))?;
+        let options = state.options();
+        if options.print_source {
+            state.field("source", |w, _state| print_source(self, w, unit, options))?;
         }
         state.field("address", |w, _state| print_address(self, w))?;
-        state.field("size", |w, state| print_size(self, w, state))?;
+        if state.options().print_variable_section {
+            state.field("section", |w, state| print_section(self, w, state))?;
+        }
+        let percent = size_percent(self, state.hash(), state.options());
+        state.field("size", |w, hash| {
+            print_size(self, w, hash)?;
+            print::print_percent(percent, None, w)
+        })?;
+        if state.options().print_variable_bytes {
+            let bytes = variable_bytes(self, state.hash()).unwrap_or(&[]);
+            let width = cmp::min(bytes.len(), BYTES_PER_LINE);
+            state.field_collapsed("bytes", |state| {
+                for line in bytes.chunks(BYTES_PER_LINE) {
+                    state.line(|w, _hash| print_bytes(line, width, w))?;
+                }
+                Ok(())
+            })?;
+        }
+        state.field_expanded("members", |state| {
+            for member in self.members() {
+                state.line(|w, hash| print_member(self, member, w, hash))?;
+            }
+            Ok(())
+        })?;
         state.field("declaration", |w, _state| print_declaration(self, w))
         // TODO: print anon type inline
     }
@@ -43,6 +106,14 @@ impl<'input> PrintHeader for Variable<'input> {
         unit_b: &parser::Unit,
         b: &Self,
     ) -> Result<()> {
+        if state.options().print_offset {
+            // Offsets are expected to differ, so they are not a change.
+            state.ignore_diff(true, |state| {
+                state.field("offset", a, b, |w, _state, x| {
+                    print::print_offset(x.offset().get(), w)
+                })
+            })?;
+        }
         let flag = state.options().ignore_variable_linkage_name;
         state.ignore_diff(flag, |state| {
             state.field("linkage name", a, b, |w, _state, x| {
@@ -53,19 +124,63 @@ impl<'input> PrintHeader for Variable<'input> {
         state.ignore_diff(flag, |state| {
             state.field("symbol name", a, b, |w, _state, x| print_symbol_name(x, w))
         })?;
-        if state.options().print_source {
+        let options = state.options();
+        if options.print_source {
             state.field(
                 "source",
                 (unit_a, a),
                 (unit_b, b),
-                |w, _state, (unit, x)| print_source(x, w, unit),
+                |w, _state, (unit, x)| print_source(x, w, unit, options),
             )?;
         }
         let flag = state.options().ignore_variable_address;
         state.ignore_diff(flag, |state| {
             state.field("address", a, b, |w, _state, x| print_address(x, w))
         })?;
-        state.field("size", a, b, |w, state, x| print_size(x, w, state))?;
+        if state.options().print_variable_section {
+            state.field("section", a, b, |w, state, x| print_section(x, w, state))?;
+        }
+        let options = state.options();
+        // A change in percentage alone doesn't mean that the variable changed.
+        let flag = options.percent && a.byte_size(state.hash_a()) == b.byte_size(state.hash_b());
+        let base = size_percent(a, state.hash_a(), options);
+        state.ignore_diff(flag, |state| {
+            state.field("size", a, b, |w, hash, x| {
+                print_size(x, w, hash)?;
+                print::print_percent(size_percent(x, hash, options), base, w)
+            })
+        })?;
+        if state.options().print_variable_bytes {
+            let bytes_a = variable_bytes(a, state.hash_a()).unwrap_or(&[]);
+            let bytes_b = variable_bytes(b, state.hash_b()).unwrap_or(&[]);
+            let lines = cmp::max(
+                bytes_a.chunks(BYTES_PER_LINE).len(),
+                bytes_b.chunks(BYTES_PER_LINE).len(),
+            );
+            let width = cmp::min(cmp::max(bytes_a.len(), bytes_b.len()), BYTES_PER_LINE);
+            state.field_collapsed("bytes", |state| {
+                for i in 0..lines {
+                    let line_a = bytes_a.chunks(BYTES_PER_LINE).nth(i).unwrap_or(&[]);
+                    let line_b = bytes_b.chunks(BYTES_PER_LINE).nth(i).unwrap_or(&[]);
+                    state.line(line_a, line_b, |w, _hash, x| print_bytes(x, width, w))?;
+                }
+                Ok(())
+            })?;
+        }
+        state.field_expanded("members", |state| {
+            let len = cmp::max(a.members().len(), b.members().len());
+            for i in 0..len {
+                state.line(
+                    (a, a.members().get(i)),
+                    (b, b.members().get(i)),
+                    |w, hash, (block, member)| match member {
+                        Some(member) => print_member(block, member, w, hash),
+                        None => Ok(()),
+                    },
+                )?;
+            }
+            Ok(())
+        })?;
         state.field("declaration", a, b, |w, _state, x| print_declaration(x, w))
     }
 }
@@ -96,13 +211,40 @@ pub(crate) fn diff(
 }
 
 fn print_name(v: &Variable, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    // Common blocks have no type.
+    if !v.members().is_empty() {
+        write!(w, "common ")?;
+        w.name(v.name().unwrap_or("<anon>"))?;
+        return Ok(());
+    }
     write!(w, "var ")?;
     if let Some(namespace) = v.namespace() {
         print::namespace::print(namespace, w)?;
     }
     w.name(v.name().unwrap_or("<anon>"))?;
     write!(w, ": ")?;
-    print::types::print_ref(v.ty(hash), w, hash)?;
+    let anon = v.name().map(AnonName::Except).unwrap_or(AnonName::Derived);
+    print::types::print_ref_anon(v.ty(hash), w, hash, anon)?;
+    Ok(())
+}
+
+// Print a variable in a common block, with its offset and size within the block.
+fn print_member(
+    block: &Variable,
+    member: &Variable,
+    w: &mut dyn ValuePrinter,
+    hash: &FileHash,
+) -> Result<()> {
+    match (block.address(), member.address()) {
+        (Some(begin), Some(address)) => write!(w, "{}", address.wrapping_sub(begin))?,
+        _ => write!(w, "??")?,
+    }
+    match member.byte_size(hash) {
+        Some(size) => write!(w, "[{}]", size)?,
+        None => write!(w, "[??]")?,
+    }
+    write!(w, "\t{}: ", member.name().unwrap_or("<anon>"))?;
+    print::types::print_ref(member.ty(hash), w, hash)?;
     Ok(())
 }
 
@@ -120,26 +262,94 @@ fn print_symbol_name(v: &Variable, w: &mut dyn ValuePrinter) -> Result<()> {
     Ok(())
 }
 
-fn print_source(v: &Variable, w: &mut dyn ValuePrinter, unit: &Unit) -> Result<()> {
-    print::source::print(v.source(), w, unit)
+fn print_source(
+    v: &Variable,
+    w: &mut dyn ValuePrinter,
+    unit: &Unit,
+    options: &Options,
+) -> Result<()> {
+    print::source::print(v.source(), w, unit, options)
 }
 
 fn print_address(v: &Variable, w: &mut dyn ValuePrinter) -> Result<()> {
     if let Some(address) = v.address() {
-        write!(w, "0x{:x}", address)?;
+        w.address(address)?;
+    }
+    Ok(())
+}
+
+fn print_section(v: &Variable, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
+    if let Some(section) = v
+        .address()
+        .and_then(|address| hash.file.section_at(address))
+    {
+        write!(w, "{}", section.name().unwrap_or("<anon>"))?;
+        if section.is_uninitialized() {
+            write!(w, " (uninitialized)")?;
+        }
     }
     Ok(())
 }
 
 fn print_size(v: &Variable, w: &mut dyn ValuePrinter, hash: &FileHash) -> Result<()> {
     if let Some(byte_size) = v.byte_size(hash) {
-        write!(w, "{}", byte_size)?;
+        w.size(byte_size)?;
     } else if !v.is_declaration() {
         debug!("variable with no size");
     }
     Ok(())
 }
 
+// The size of the variable as a percentage of the size of all variables,
+// if `options.percent` is set.
+fn size_percent(v: &Variable, hash: &FileHash, options: &Options) -> Option<f64> {
+    if !options.percent {
+        return None;
+    }
+    print::percent(v.byte_size(hash)?, hash.variable_size())
+}
+
+// The initial value of a variable in a data section.
+//
+// Returns `None` if the variable is too large, or its value isn't stored in the file.
+fn variable_bytes<'input>(v: &Variable, hash: &FileHash<'input>) -> Option<&'input [u8]> {
+    let address = v.address()?;
+    let size = v.byte_size(hash)?;
+    if size == 0 || size > MAX_BYTES {
+        return None;
+    }
+    match hash.file.classify_address(address)? {
+        SectionKind::Data | SectionKind::ReadOnlyData => {}
+        _ => return None,
+    }
+    hash.file.segment_bytes(Range {
+        begin: address,
+        end: address + size,
+    })
+}
+
+// Print bytes as hex followed by ASCII, with the hex padded to `width` bytes.
+fn print_bytes(bytes: &[u8], width: usize, w: &mut dyn ValuePrinter) -> Result<()> {
+    if bytes.is_empty() {
+        return Ok(());
+    }
+    for byte in bytes {
+        write!(w, "{:02x} ", byte)?;
+    }
+    for _ in bytes.len()..width {
+        write!(w, "   ")?;
+    }
+    write!(w, " ")?;
+    for &byte in bytes {
+        if byte.is_ascii_graphic() || byte == b' ' {
+            write!(w, "{}", byte as char)?;
+        } else {
+            write!(w, ".")?;
+        }
+    }
+    Ok(())
+}
+
 fn print_declaration(v: &Variable, w: &mut dyn ValuePrinter) -> Result<()> {
     if v.is_declaration() {
         write!(w, "yes")?;
@@ -161,13 +371,13 @@ impl<'input> Print for Variable<'input> {
 
 impl<'input> SortList for Variable<'input> {
     fn cmp_id(
-        hash_a: &FileHash,
+        _hash_a: &FileHash,
         a: &Self,
-        hash_b: &FileHash,
+        _hash_b: &FileHash,
         b: &Self,
-        _options: &Options,
+        options: &Options,
     ) -> cmp::Ordering {
-        Variable::cmp_id(hash_a, a, hash_b, b)
+        filter::cmp_ns_and_name(a.namespace(), a.name(), b.namespace(), b.name(), options)
     }
 
     fn cmp_by(
@@ -184,4 +394,16 @@ impl<'input> SortList for Variable<'input> {
             Sort::Size => a.byte_size(hash_a).cmp(&b.byte_size(hash_b)),
         }
     }
+
+    fn namespace(&self) -> Option<&Namespace<'_>> {
+        self.namespace()
+    }
+
+    fn size(&self, hash: &FileHash) -> Option<u64> {
+        self.byte_size(hash)
+    }
+
+    fn counts(counts: &DiffCounts) -> Option<&ChangeCounts> {
+        Some(&counts.variables)
+    }
 }
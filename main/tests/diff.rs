@@ -1,12 +1,7 @@
 fn diff(name: &str, expect: &str) {
     let mut options = options();
     options.unit("src/diff.c").name(name);
-    let mut diff = Vec::new();
-    let output_1 = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
-    let output_2 = ddbug::File::parse("tests/bin/diff2".into()).unwrap();
-    let mut printer = ddbug::TextPrinter::new(&mut diff, &options);
-    ddbug::diff(&mut printer, output_1.file(), output_2.file(), &options).unwrap();
-    let diff = String::from_utf8(diff).unwrap();
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
     if !equal(&diff, expect) {
         println!("\nDiff:");
         println!("{}", diff);
@@ -16,9 +11,27 @@ fn diff(name: &str, expect: &str) {
     }
 }
 
+fn diff_files(path_a: &str, path_b: &str, options: &ddbug::Options) -> String {
+    let mut diff = Vec::new();
+    let output_1 = ddbug::File::parse(path_a.into()).unwrap();
+    let output_2 = ddbug::File::parse(path_b.into()).unwrap();
+    let mut printer = ddbug::TextPrinter::new(&mut diff, options);
+    ddbug::diff(&mut printer, output_1.file(), output_2.file(), options).unwrap();
+    String::from_utf8(diff).unwrap()
+}
+
+fn print_file(path: &str, options: &ddbug::Options) -> String {
+    let mut output = Vec::new();
+    let file = ddbug::File::parse(path.into()).unwrap();
+    let mut printer = ddbug::TextPrinter::new(&mut output, options);
+    ddbug::print(file.file(), &mut printer, options).unwrap();
+    String::from_utf8(output).unwrap()
+}
+
 fn options<'a>() -> ddbug::Options {
     ddbug::Options {
         print_function_variables: true,
+        print_variable_section: true,
         inline_depth: 1,
 
         category_unit: false,
@@ -61,6 +74,1691 @@ fn equal(mut diff: &str, expect: &str) -> bool {
     diff.is_empty()
 }
 
+#[test]
+fn shuffled_units() {
+    // Linking the units in a different order must not cause differences.
+    let options = options();
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff1-shuffled", &options);
+    assert_eq!(diff, "");
+}
+
+#[test]
+fn decimal_addresses() {
+    let mut options = options();
+    options.unit("src/diff.c").name("function_moved");
+    options.ignore_function_address = false;
+    options.decimal_addresses = true;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert!(diff.contains("\taddress: "), "{}", diff);
+    assert!(!diff.contains("0x"), "{}", diff);
+}
+
+#[test]
+fn human_sizes() {
+    let mut options = options();
+    options.unit("src/diff.c").name("typedef_diff_base");
+    options.human_sizes = true;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert_eq!(
+        diff,
+        concat!(
+            "- type typedef_diff_base = char\n",
+            "+ type typedef_diff_base = int\n",
+            "- \tsize: 1 B\n",
+            "+ \tsize: 4 B\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn markdown() {
+    let mut options = options();
+    options.unit("src/diff.c").name("typedef_diff_base");
+    let file_a = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let file_b = ddbug::File::parse("tests/bin/diff2".into()).unwrap();
+    let mut diff = Vec::new();
+    let mut printer = ddbug::MarkdownPrinter::new(&mut diff, &options);
+    ddbug::diff(&mut printer, file_a.file(), file_b.file(), &options).unwrap();
+    assert_eq!(
+        String::from_utf8(diff).unwrap(),
+        concat!(
+            "#### ~ type typedef\\_diff\\_base = char → type typedef\\_diff\\_base = int\n",
+            "\n",
+            "| | Field | Value |\n",
+            "|---|---|---|\n",
+            "| ~ | size | 1 → 4 |\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn compare() {
+    let mut options = options();
+    options.unit("src/diff.c").name("function_moved_pad");
+    let file_a = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let file_b = ddbug::File::parse("tests/bin/diff2".into()).unwrap();
+    let file_c = ddbug::File::parse("tests/bin/diff1-shuffled".into()).unwrap();
+    let files = [file_a.file(), file_b.file(), file_c.file()];
+    let mut output = Vec::new();
+    let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+    let changed = ddbug::compare(&mut printer, &files, &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let expect = concat!(
+        "functions\n",
+        "\tfunction_moved_pad: [..] | [..] | [..] (differs)\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+    assert!(changed);
+
+    let files = [file_a.file(), file_c.file(), file_a.file()];
+    let mut output = Vec::new();
+    let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+    assert!(!ddbug::compare(&mut printer, &files, &options).unwrap());
+}
+
+#[test]
+fn unit_size() {
+    let mut options = options();
+    options.unit("src/diff.c");
+    options.category_unit = true;
+    options.category_type = false;
+    options.category_function = false;
+    options.category_variable = false;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  unit src/diff.c\n",
+        "  \tlanguage: C11\n",
+        "  \tproducer: GNU C[..]\n",
+        "- \tsize: [..]\n",
+        "+ \tsize: [..] (+[..])\n",
+        "- \tfn size: [..]\n",
+        "+ \tfn size: [..] (+[..])\n",
+        "- \tvar size: [..]\n",
+        "+ \tvar size: [..] (-[..])\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn top() {
+    let mut options = options();
+    options.unit("src/diff.c");
+    options.category_type = false;
+    options.category_variable = false;
+    options.top = Some(2);
+    let output = print_file("tests/bin/diff2", &options);
+    let sizes: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix("\tsize: "))
+        .map(|size| size.parse().unwrap())
+        .collect();
+    assert_eq!(output.lines().filter(|l| l.starts_with("fn ")).count(), 2);
+    assert_eq!(sizes.len(), 2, "{}", output);
+    assert!(sizes[0] >= sizes[1], "{}", output);
+}
+
+#[test]
+fn top_fragmented_units() {
+    let mut options = options();
+    options.unit("src/diff.c");
+    options.category_unit = true;
+    options.category_type = false;
+    options.category_function = false;
+    options.category_variable = false;
+    options.top = Some(1);
+    let output = print_file("tests/bin/diff2", &options);
+    let expect = concat!(
+        "unit src/diff.c\n",
+        "\taddress span: [..] ([..])\n",
+        "\tdensity: 100.0%\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn by_file() {
+    let mut options = options();
+    options.by_file = true;
+    let output = print_file("tests/bin/diff2", &options);
+    let mut sizes = Vec::new();
+    for entry in output.split("\n\n").filter(|entry| !entry.is_empty()) {
+        let mut lines = entry.lines();
+        let path = lines.next().unwrap();
+        let size: u64 = lines
+            .find_map(|line| line.strip_prefix("\tsize: "))
+            .unwrap()
+            .parse()
+            .unwrap();
+        sizes.push((path, size));
+    }
+    let paths: Vec<_> = sizes.iter().map(|(path, _)| *path).collect();
+    assert_eq!(
+        paths,
+        ["/", "/src/", "/src/diff.c", "/src/support.c", "<symtab>"],
+        "{}",
+        output
+    );
+    // Directory sizes are the total of their contents.
+    assert_eq!(sizes[1].1, sizes[2].1 + sizes[3].1, "{}", output);
+}
+
+#[test]
+fn group_generics() {
+    let mut options = options();
+    options.category_type = false;
+    options.category_variable = false;
+    options.group_generics = true;
+    let output = print_file("tests/bin/generic", &options);
+    let expect = concat!(
+        "core::mem::size_of_val<_>\n",
+        "\tsize: [..]\n",
+        "\tinstantiations: 3\n",
+        "\n",
+        "generic::generic_size<_>\n",
+        "\tsize: [..]\n",
+        "\tinstantiations: 3\n",
+        "\n",
+        "generic::Wrapper<_>::get<_>\n",
+        "\tsize: [..]\n",
+        "\tinstantiations: 2\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn coverage() {
+    let mut options = options();
+    options.category_file = false;
+    options.coverage = true;
+    let output = print_file("tests/bin/high_pc", &options);
+    // Only high_pc_address and high_pc_offset have debuginfo.
+    let expect = concat!(
+        "[..]",
+        "section .text\n",
+        "\tsize: [..]\n",
+        "\tcovered: 12\n",
+        "\tcoverage: [..]%\n",
+        "\n",
+        "[..]",
+        "total\n",
+        "\tsize: [..]\n",
+        "\tcovered: 12\n",
+        "\tcoverage: [..]%\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn inline_report() {
+    let mut options = options();
+    options.category_file = false;
+    options.inline_report = true;
+    let output = print_file("tests/bin/inline", &options);
+    let expect = concat!(
+        "inline_large\n",
+        "\tsize: [..]\n",
+        "\tinstances: 1\n",
+        "\tcallers: 1\n",
+        "\n",
+        "inline_small\n",
+        "\tsize: [..]\n",
+        "\tinstances: 3\n",
+        "\tcallers: 2\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn dwarf5() {
+    // Names, addresses, range lists and location lists use the indexed forms,
+    // and the declarations use file index 0 of the DWARF 5 line table.
+    let mut options = options();
+    options.filter_glob = Some("dwarf5*".into());
+    options.print_source = true;
+    options.print_variable_locations = true;
+    let output = print_file("tests/bin/dwarf5", &options);
+    let expect = concat!(
+        "fn dwarf5_func\n",
+        "\tsource: /src/dwarf5.c:3\n",
+        "\taddress: [..]\n",
+        "\tsize: 3\n",
+        "\tunwind: absent\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "\t\t[4]\tint\n",
+        "\tparameters:\n",
+        "\t\t[4]\tx: int\n",
+        "\t\t\tregisters:\n",
+        "\t\t\t\trdi\n",
+        "\n",
+        "fn dwarf5_split\n",
+        "\tsource: /src/dwarf5.c:8\n",
+        "\taddress: [..]\n",
+        "\tsize: 20\n",
+        "\tunwind: absent\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "\t\t[4]\tint\n",
+        "\tparameters:\n",
+        "\t\t[4]\tx: int\n",
+        "\t\t\tregisters:\n",
+        "\t\t\t\trbx\n",
+        "\t\t\t\trdi\n",
+        "\n",
+        "var dwarf5_var: int\n",
+        "\tsource: /src/dwarf5.c:1\n",
+        "\taddress: [..]\n",
+        "\tsection: .data\n",
+        "\tsize: 4\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn dwarf5_ranges() {
+    // The unit ranges include the .cold part of dwarf5_split, and the unit
+    // without a line program has the range from its range list.
+    let mut options = options();
+    options.only(ddbug::Category::Unit);
+    options.print_unit_address = true;
+    let output = print_file("tests/bin/dwarf5", &options);
+    let units: Vec<_> = output.split("\n\n").collect();
+    let unit = units
+        .iter()
+        .find(|unit| unit.starts_with("unit dwarf5.c\n"))
+        .unwrap();
+    let expect = concat!(
+        "unit dwarf5.c\n",
+        "\taddresses:\n",
+        "\t\t[..] (7)\n",
+        "\t\t[..] (23)\n",
+        "\t\t[..] (4)\n",
+        "\taddress span: [..]\n",
+        "\tdensity: [..]\n",
+        "\tlanguage: C11\n",
+        "\tsize: 34\n",
+        "\tfn size: 30\n",
+        "\tvar size: 4",
+    );
+    assert!(equal(unit, expect), "{}", unit);
+    let unit = units
+        .iter()
+        .find(|unit| unit.starts_with("unit dwarf5_main.c\n"))
+        .unwrap();
+    let expect = concat!(
+        "unit dwarf5_main.c\n",
+        "\taddress: [..]\n",
+        "\tunknown addresses:\n",
+        "\t\t[..] (3)\n",
+        "\taddress span: [..]\n",
+        "\tdensity: 100.0%\n",
+        "\tlanguage: C11\n",
+        "\tunknown size: 3",
+    );
+    assert!(equal(unit, expect), "{}", unit);
+}
+
+#[test]
+fn icf_report() {
+    let mut options = options();
+    options.category_file = false;
+    options.icf_report = true;
+    let output = print_file("tests/bin/icf", &options);
+    let expect = concat!(
+        "identical functions\n",
+        "\tsize: 5\n",
+        "\tcopies: 2\n",
+        "\tsavings: 5\n",
+        "\tfunctions:\n",
+        "\t\tfn icf_c\n",
+        "\t\tfn icf_d\n",
+        "\n",
+        "folded functions\n",
+        "\taddress: [..]\n",
+        "\tsize: 5\n",
+        "\tfunctions:\n",
+        "\t\tfn icf_a\n",
+        "\t\tfn icf_b\n",
+        "\n",
+        "total\n",
+        "\tsavings: 5\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn dump() {
+    let mut options = options();
+    options
+        .only(ddbug::Category::Function)
+        .name("high_pc_offset");
+    options.dump = true;
+    let output = print_file("tests/bin/high_pc", &options);
+    let expect = concat!(
+        "<0x7c> DW_TAG_subprogram\n",
+        "\tDW_AT_name DW_FORM_string: \"high_pc_offset\"\n",
+        "\tDW_AT_external DW_FORM_flag_present: true\n",
+        "\tDW_AT_low_pc DW_FORM_addr: [..]\n",
+        "\tDW_AT_high_pc DW_FORM_data4: 8\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn diff_symbol_only() {
+    // Functions in a file without debuginfo are paired with functions in a
+    // file with debuginfo by name, and only their address and size are compared.
+    let mut options = options();
+    options.only(ddbug::Category::Function);
+    let diff = diff_files("tests/bin/diff1-nodebug", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  fn function_diff_inlined\n",
+        "- \taddress: yes\n",
+        "+ \taddress: none\n",
+        "- \tsize: 18\n",
+        "\n",
+        "  fn function_diff_inlined_caller\n",
+        "  \taddress: yes\n",
+        "- \tsize: 26\n",
+        "+ \tsize: 27\n",
+        "\n",
+        "  fn function_diff_variadic\n",
+        "  \taddress: yes\n",
+        "- \tsize: 15\n",
+        "+ \tsize: 96\n",
+        "\n",
+        "  fn function_moved_pad\n",
+        "  \taddress: yes\n",
+        "- \tsize: 7\n",
+        "+ \tsize: 23\n",
+        "\n",
+        "- fn function_renamed_a\n",
+        "[..]",
+        "+ fn function_renamed_b\n",
+        "[..]",
+        "- fn symbol_in_function_label\n",
+        "- \tlinkage name: symbol_in_function_label\n",
+        "- \taddress: [..]\n",
+        "- \tsize: 1\n",
+        "- \tunwind: present\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+
+    // Types can't be compared, so say so instead of omitting them.
+    options.only(ddbug::Category::Type);
+    let diff = diff_files("tests/bin/diff1-nodebug", "tests/bin/diff2", &options);
+    assert_eq!(
+        diff,
+        "  types: not compared because only one file has debuginfo\n\n"
+    );
+}
+
+#[test]
+fn layout() {
+    let mut options = options();
+    options.only(ddbug::Category::Type).name("layout");
+    options.layout = true;
+    let output = print_file("tests/bin/layout.o", &options);
+    let expect = concat!(
+        "struct layout\n",
+        "\tsize: 16\n",
+        "\tlayout:\n",
+        "\t\t0  aaaaaaaa ........ ........ ........ bbbbbbbb bbbbbbbb bbbbbbbb bbbbbbbb\n",
+        "\t\t8  cccddddd d....... eeeeeeee eeeeeeee ffffffff ........ ........ ........\n",
+        "\tlegend:\n",
+        "\t\ta\t0[1]\ta: char\n",
+        "\t\t.\t1[3]\t<padding>\n",
+        "\t\tb\t4[4]\tb: int\n",
+        "\t\tc\t8[0.3]\tc: unsigned int\n",
+        "\t\td\t8.3[0.6]\td: unsigned int\n",
+        "\t\t.\t9.1[0.7]\t<padding>\n",
+        "\t\te\t10[2]\te: short int\n",
+        "\t\tf\t12[1]\tf: char\n",
+        "\t\t.\t13[3]\t<padding>\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn similarity_threshold() {
+    // The variants differ only in their discriminant value, so they are 50% similar.
+    let mut options = options();
+    options.name("Signed");
+    options.similarity_threshold = 40;
+    let diff = diff_files("tests/bin/variant1.o", "tests/bin/variant2.o", &options);
+    let expect = concat!(
+        "[..]",
+        "- \t\t\tA: <-1>\n",
+        "+ \t\t\tA: <-2>\n",
+        "  \t\t\t\t0[1]\t<padding>\n",
+        "  \t\t\t\t1[1]\tpublic: __0: u8\n",
+        "  \t\t\tB: <1>\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+
+    options.similarity_threshold = 60;
+    let diff = diff_files("tests/bin/variant1.o", "tests/bin/variant2.o", &options);
+    let expect = concat!(
+        "[..]",
+        "- \t\t\tA: <-1>\n",
+        "- \t\t\t\t0[1]\t<padding>\n",
+        "- \t\t\t\t1[1]\tpublic: __0: u8\n",
+        "+ \t\t\tA: <-2>\n",
+        "+ \t\t\t\t0[1]\t<padding>\n",
+        "+ \t\t\t\t1[1]\tpublic: __0: u8\n",
+        "  \t\t\tB: <1>\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn flamegraph() {
+    let mut options = options();
+    options.flamegraph = true;
+    options.filter_namespace = vec!["generic".into()];
+    let output = print_file("tests/bin/generic", &options);
+    let expect = concat!(
+        "generic;generic_size<u64> 16\n",
+        "generic;generic_size<u32> 18\n",
+        "generic;generic_size<u8> 21\n",
+        "generic;generic_use 273\n",
+        "generic;Wrapper<u32>;get<u32> 8\n",
+        "generic;Wrapper<u8>;get<u8> 8\n",
+    );
+    assert_eq!(output, expect);
+}
+
+#[test]
+fn instruction_count() {
+    let mut options = options();
+    options.only(ddbug::Category::Function).unit("thunk.c");
+    options.print_function_instruction_count = true;
+    let output = print_file("tests/bin/thunk", &options);
+    let expect = concat!(
+        "fn code_thunk\n",
+        "[..]",
+        "\tsize: 4\n",
+        "\tinstructions: 3\n",
+        "[..]",
+        "fn data_thunk\n",
+        "[..]",
+        "\tsize: 16\n",
+        "\tinstructions: n/a\n",
+        "[..]",
+        "\tlinkage: external\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn addresses() {
+    let ctx = ddbug::File::parse("tests/bin/diff2".into()).unwrap();
+    let file = ctx.file();
+    let hash = ddbug::parser::FileHash::new(file);
+    let caller = file
+        .units()
+        .iter()
+        .flat_map(|unit| unit.functions())
+        .find(|function| function.name() == Some("function_diff_inlined_caller"))
+        .unwrap();
+    let details = caller.details(&hash);
+    let address = details.inlined_functions()[0].ranges()[0].begin;
+
+    let frames = file.symbolize(&hash, address);
+    let frames: Vec<_> = frames
+        .iter()
+        .map(|frame| (frame.function().name().unwrap(), frame.source().line()))
+        .collect();
+    assert_eq!(
+        frames,
+        [
+            ("function_diff_inlined", 505),
+            ("function_diff_inlined_caller", 508),
+        ]
+    );
+    assert!(file.symbolize(&hash, 0).is_empty());
+
+    let mut options = options();
+    options.addresses = vec![address, 0];
+    let output = print_file("tests/bin/diff2", &options);
+    let expect = format!(
+        concat!(
+            "0x{:x}\n",
+            "\tfunction_diff_inlined at /src/diff.c:505:74\n",
+            "\tfunction_diff_inlined_caller at /src/diff.c:508:41\n",
+            "0x0\n",
+            "\t??\n",
+        ),
+        address
+    );
+    assert_eq!(output, expect);
+}
+
+#[test]
+fn cfi() {
+    let mut options = options();
+    options
+        .only(ddbug::Category::Function)
+        .name("function_diff_inlined_caller");
+    options.print_function_cfi = true;
+    let output = print_file("tests/bin/diff2", &options);
+    let expect = concat!(
+        "fn function_diff_inlined_caller\n",
+        "[..]",
+        "\tunwind: present\n",
+        "\tcfi:\n",
+        "\t\tcfa: rsp+0x8\n",
+        "\t\treturn address: [cfa-0x8]\n",
+        "\t\tsaved registers: rbp=[cfa-0x10]\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "[..]",
+        "\t\t[..]\tfunction_diff_inlined\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn grow_threshold() {
+    let mut options = options();
+    options.only(ddbug::Category::Function);
+    options.filter_glob = Some("function_*".into());
+    options.grow_threshold_bytes = Some(20);
+
+    // Small size changes are hidden, but other changes are not.
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert!(
+        !diff.contains("fn function_diff_inlined_caller\n"),
+        "{}",
+        diff
+    );
+    assert!(diff.contains("fn function_diff_return_type\n"), "{}", diff);
+
+    // Large size decreases are shown, but not marked as growth.
+    let diff = diff_files("tests/bin/diff2", "tests/bin/diff1", &options);
+    let expect = concat!(
+        "  fn function_diff_variadic\n",
+        "- \taddress: 0x1184-0x11e3\n",
+        "+ \taddress: 0x1195-0x11a3\n",
+        "- \tsize: 96\n",
+        "+ \tsize: 15\n",
+    );
+    assert!(diff.contains(expect), "{}", diff);
+    assert!(!diff.contains("grew: +81"), "{}", diff);
+
+    options.grow_only = true;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  grew: +22\n",
+        "+ fn function_renamed_b\n",
+        "[..]",
+        "  grew: +81 (+540%)\n",
+        "  fn function_diff_variadic\n",
+        "[..]",
+        "- \tsize: 15\n",
+        "+ \tsize: 96\n",
+        "[..]",
+        "+ \t\t...\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn abi_check() {
+    let abi_check = |path_a: &str, path_b: &str, name: &str| {
+        let mut options = options();
+        options.filter_name = Some(name.into());
+        let mut output = Vec::new();
+        let file_a = ddbug::File::parse(path_a.into()).unwrap();
+        let file_b = ddbug::File::parse(path_b.into()).unwrap();
+        let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+        let breaking =
+            ddbug::abi_check(&mut printer, file_a.file(), file_b.file(), &options).unwrap();
+        (breaking, String::from_utf8(output).unwrap())
+    };
+
+    let (breaking, output) = abi_check("tests/bin/diff1", "tests/bin/diff2", "struct_diff_member");
+    let expect = concat!(
+        "struct struct_diff_member\n",
+        "\tsize changed from 1 to 4\n",
+        "\tmember a: type changed from char to int\n",
+        "\tmember a: size changed from 1 to 4\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert!(equal(&output, expect), "{}", output);
+
+    let (breaking, output) = abi_check("tests/bin/diff1", "tests/bin/diff2", "enum_diff");
+    let expect = concat!(
+        "enum enum_diff\n",
+        "\tenumerator B2: value changed from 2 to 3\n",
+        "\tenumerator C2: value changed from 3 to 2\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert!(equal(&output, expect), "{}", output);
+
+    let (breaking, output) = abi_check(
+        "tests/bin/diff1",
+        "tests/bin/diff2",
+        "function_diff_variadic",
+    );
+    let expect = concat!(
+        "fn function_diff_variadic\n",
+        "\tsignature changed from fn(char) -> char to fn(char, ...) -> char\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert!(equal(&output, expect), "{}", output);
+
+    let (breaking, output) = abi_check("tests/bin/vtable1", "tests/bin/vtable2", "vtable_class");
+    let expect = concat!(
+        "struct vtable_class\n",
+        "\tvirtual first: vtable index changed from 2 to 4\n",
+        "\tvirtual second: vtable index changed from 3 to 2\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert!(equal(&output, expect), "{}", output);
+
+    // Changes to the size and code of a function are benign.
+    let (breaking, output) = abi_check(
+        "tests/bin/diff1",
+        "tests/bin/diff2",
+        "function_diff_inlined_caller",
+    );
+    assert!(!breaking);
+    assert_eq!(output, "");
+
+    // Anonymous members are paired by their position.
+    let (breaking, output) = abi_check("tests/bin/abi1", "tests/bin/abi2", "abi_anon_equal");
+    assert!(!breaking);
+    assert_eq!(output, "");
+
+    let (breaking, output) = abi_check("tests/bin/abi1", "tests/bin/abi2", "abi_anon_changed");
+    let expect = concat!(
+        "struct abi_anon_changed\n",
+        "\tsize changed from 8 to 16\n",
+        "\tmember <anon>: offset changed from 4 to 8\n",
+        "\tmember <anon>: size changed from 4 to 8\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert_eq!(output, expect);
+
+    // Changing the type of a member breaks the ABI even if the size is unchanged.
+    let (breaking, output) = abi_check("tests/bin/abi1", "tests/bin/abi2", "abi_member_type");
+    let expect = concat!(
+        "struct abi_member_type\n",
+        "\tmember a: type changed from int to float\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert_eq!(output, expect);
+
+    // Every definition with the same name is checked, not only the first.
+    let (breaking, output) = abi_check("tests/bin/abi1", "tests/bin/abi2", "abi_duplicate");
+    let expect = concat!(
+        "struct abi_duplicate\n",
+        "\tsize changed from 8 to 16\n",
+        "\n",
+    );
+    assert!(breaking);
+    assert_eq!(output, expect);
+}
+
+#[test]
+fn percent() {
+    let mut options = options();
+    options.percent = true;
+
+    options.filter_name = Some("function_diff_variadic".into());
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  fn function_diff_variadic\n",
+        "[..]",
+        "- \tsize: 15 (7.7%)\n",
+        "+ \tsize: 96 (35.0%, +27.3pp)\n",
+        "[..]",
+        "+ \t\t...\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+
+    // A change in the total size alone is not a difference.
+    options.filter_name = Some("function_equal".into());
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert!(!diff.contains("fn function_equal"), "{}", diff);
+
+    options.filter_name = Some("variable_diff_size".into());
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "[..]",
+        "- \tsize: 1 (0.2%)\n",
+        "+ \tsize: 2 (0.4%, +0.2pp)\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn threads() {
+    let mut options = options();
+    options.category_unit = true;
+    options.diff_threads = 1;
+    let serial = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert!(serial.matches("  unit ").count() > 1, "{}", serial);
+    for threads in 2..5 {
+        options.diff_threads = threads;
+        let parallel = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+        assert_eq!(parallel, serial);
+    }
+}
+
+#[test]
+fn strip_prefix() {
+    let mut options = options();
+    options.category_unit = true;
+    options.print_source = true;
+    let diff = diff_files("tests/bin/paths1", "tests/bin/paths2", &options);
+    assert!(
+        diff.contains("- unit /home/ci/build1/src/diff.c\n")
+            && diff.contains("+ unit /home/ci/build2/src/diff.c\n"),
+        "{}",
+        diff
+    );
+
+    options.strip_prefix = vec!["/home/ci/build1".into(), "/home/ci/build2/".into()];
+    let diff = diff_files("tests/bin/paths1", "tests/bin/paths2", &options);
+    assert_eq!(diff, "");
+
+    // A prefix only matches whole path components.
+    options.strip_prefix = vec!["/home/ci/build".into()];
+    let diff = diff_files("tests/bin/paths1", "tests/bin/paths2", &options);
+    assert!(
+        diff.contains("- unit /home/ci/build1/src/diff.c\n"),
+        "{}",
+        diff
+    );
+
+    options.strip_prefix = Vec::new();
+    options.relative = true;
+    let diff = diff_files("tests/bin/paths1", "tests/bin/paths2", &options);
+    assert_eq!(diff, "");
+
+    // The other file was built with its paths remapped to the empty string.
+    let diff = diff_files("tests/bin/diff1", "tests/bin/paths1", &options);
+    assert_eq!(diff, "");
+}
+
+#[test]
+fn relocatable() {
+    // Each function is in its own section, so both are at offset 0 before linking.
+    let mut options = options();
+    options.only(ddbug::Category::Function);
+    options.filter_glob = Some("reloc_*".into());
+    let output = print_file("tests/bin/reloc.o", &options);
+    let expect = concat!(
+        "fn reloc_a\n",
+        "\taddress: 0x1000-0x1003\n",
+        "\tsize: 4\n",
+        "[..]",
+        "\tunwind: present\n",
+        "[..]",
+        "fn reloc_b\n",
+        "\taddress: 0x1004-0x1008\n",
+        "\tsize: 5\n",
+        "[..]",
+        "\tunwind: present\n",
+        "[..]",
+        "\t\t[4]\tx: int\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn variable_bytes() {
+    let mut options = options();
+    options.only(ddbug::Category::Variable);
+    options.name("variable_diff_bytes");
+    options.print_variable_bytes = true;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  var variable_diff_bytes: const [const char; 6]\n",
+        "[..]",
+        "  \tsize: 6\n",
+        "  \tbytes:\n",
+        "- \t\t68 65 6c 6c 6f 00  hello.\n",
+        "+ \t\t77 6f 72 6c 64 00  world.\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn toc() {
+    let mut options = options();
+    options.category_file = true;
+    options.category_unit = true;
+    options.toc = true;
+    let output = print_file("tests/bin/diff1", &options);
+
+    // Each entry gives the line number of the unit that it names.
+    let lines: Vec<_> = output.lines().collect();
+    let contents = lines.iter().position(|line| *line == "contents:").unwrap();
+    let entries: Vec<_> = lines[contents + 1..]
+        .iter()
+        .take_while(|line| !line.is_empty())
+        .collect();
+    assert!(entries
+        .iter()
+        .any(|entry| entry.ends_with("\tunit src/diff.c")));
+    for entry in entries {
+        let mut parts = entry.trim_start().splitn(2, '\t');
+        let line: usize = parts.next().unwrap().parse().unwrap();
+        let name = parts.next().unwrap();
+        assert_eq!(lines[line - 1], name, "{}", output);
+    }
+}
+
+#[test]
+fn common_block() {
+    let mut options = options();
+    options.only(ddbug::Category::Variable);
+    // The block's symbol is associated with the block, instead of being
+    // added to the `<symtab>` unit.
+    let output = print_file("tests/bin/common", &options);
+    assert!(!output.contains("common_blk_:"), "{}", output);
+
+    // The block is described by each function that uses it, but is only
+    // printed once.
+    options.filter_name = Some("common_blk".into());
+    let output = print_file("tests/bin/common", &options);
+    let expect = concat!(
+        "common common_blk\n",
+        "\tsymbol name: common_blk_\n",
+        "[..]",
+        "\tsize: 16\n",
+        "\tmembers:\n",
+        "\t\t0[4]\ti: integer(kind=4)\n",
+        "\t\t8[8]\tx: real(kind=8)\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn show_declarations() {
+    // The declaration in diff.c is hidden because support.c has the definition.
+    let headers = |show_declarations| {
+        let mut options = options();
+        options.name("struct_diff_decl_support");
+        options.show_declarations = show_declarations;
+        let output = print_file("tests/bin/diff1", &options);
+        output
+            .lines()
+            .filter(|line| line.starts_with("struct ") || line.starts_with("\tdeclaration:"))
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(headers(false), ["struct struct_diff_decl_support"]);
+    assert_eq!(
+        headers(true),
+        [
+            "struct struct_diff_decl_support",
+            "\tdeclaration: yes",
+            "struct struct_diff_decl_support",
+        ]
+    );
+}
+
+#[test]
+fn diff_summary() {
+    // The summary is printed after the changes, so that they can be streamed.
+    let mut options = options();
+    options.diff_summary = true;
+    options.unit("src/diff.c").name("function_diff_return_type");
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    assert!(
+        diff.starts_with("  fn function_diff_return_type\n"),
+        "{}",
+        diff
+    );
+    let mut lines = diff.lines().rev();
+    assert_eq!(lines.next(), Some(""));
+    let expect = concat!(
+        "  summary: 1 functions changed (+0/-0), 0 types changed (+0/-0), ",
+        "0 variables changed (+0/-0), total size ",
+    );
+    assert!(lines.next().unwrap().starts_with(expect), "{}", diff);
+    assert_eq!(lines.next(), Some(""));
+
+    options.name("variable_diff_decl");
+    options.category_type = false;
+    options.category_function = false;
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "- var variable_diff_decl: int\n",
+        "- \taddress: [..]\n",
+        "- \tsize: 4\n",
+        "\n",
+        "  summary: 0 variables changed (+0/-1), total size [..]\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn group_by_namespace() {
+    // Namespaces without any differences are omitted.
+    let mut options = options();
+    options.group_by_namespace = true;
+    options.ignore_function_address = true;
+    options.only(ddbug::Category::Function);
+    options.filter_namespace = vec!["vtable_class".into()];
+    let diff = diff_files("tests/bin/vtable1", "tests/bin/vtable2", &options);
+    let expect = concat!(
+        "  namespace vtable_class\n",
+        "+ \tfn vtable_class::inserted\n",
+        "+ \t\tlinkage name: _ZN12vtable_class8insertedEv\n",
+        "+ \t\taddress: [..]\n",
+        "+ \t\tsize: 15\n",
+        "+ \t\tunwind: present\n",
+        "+ \t\tvirtual: yes\n",
+        "+ \t\tlinkage: external\n",
+        "+ \t\treturn type:\n",
+        "+ \t\t\t[4]\tint\n",
+        "+ \t\tparameters:\n",
+        "+ \t\t\t[8]\tthis: const * struct vtable_class\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn function_signature() {
+    let mut options = options();
+    options.print_function_signature = true;
+    options.unit("src/diff.c").name("function_diff_return_type");
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "- fn function_diff_return_type() -> char\n",
+        "+ fn function_diff_return_type() -> int\n",
+        "- \taddress: [..]\n",
+        "+ \taddress: [..]\n",
+        "  \tsize: 7\n",
+        "  \tunwind: present\n",
+        "  \tlinkage: external\n",
+        "  \treturn type:\n",
+        "- \t\t[1]\tchar\n",
+        "+ \t\t[4]\tint\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn max_depth() {
+    let mut options = options();
+    options.max_depth = 1;
+    options.unit("src/diff.c").name("struct_diff_anon_member");
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let expect = concat!(
+        "  struct struct_diff_anon_member\n",
+        "- \tsize: 2\n",
+        "+ \tsize: 4\n",
+        "  \tmembers:\n",
+        "  \t\t0[1]\ta: char\n",
+        "+ \t\t1[1]\t<padding>\n",
+        "- \t\t1[1]\t<anon>: union <anon>\n",
+        "+ \t\t2[2]\t<anon>: union <anon>\n",
+        "  \t\t\t{ ... }\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn canonical_names() {
+    let mut options = options();
+    options.category_type = false;
+    options.category_variable = false;
+    let headers = |options: &ddbug::Options| {
+        let diff = diff_files("tests/bin/canonical1", "tests/bin/canonical2", options);
+        diff.lines()
+            .filter(|line| line.contains(" fn "))
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        headers(&options),
+        [
+            "- fn outer::{closure#0}",
+            "+ fn outer::{closure#2}",
+            "+ fn (anonymous namespace)::anon_fn",
+            "- fn _GLOBAL__N_1::anon_fn",
+        ]
+    );
+
+    options.canonical_names = true;
+    let diff = diff_files("tests/bin/canonical1", "tests/bin/canonical2", &options);
+    assert_eq!(diff, "");
+}
+
+#[test]
+fn unwind() {
+    let mut options = options();
+    options.unit("src/diff.c").name("function_diff_linkage");
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff1-nounwind", &options);
+    let expect = concat!(
+        "  fn function_diff_linkage\n",
+        "[..]",
+        "- \tunwind: present\n",
+        "+ \tunwind: absent\n",
+        "  \tlinkage: static\n",
+        "  \treturn type:\n",
+        "  \t\t[1]\tchar\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn match_renamed_functions() {
+    let mut options = options();
+    options.unit("src/diff.c");
+    options.category_type = false;
+    options.category_variable = false;
+    let renamed = |options: &ddbug::Options| {
+        let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", options);
+        diff.lines()
+            .filter(|line| line.contains("function_renamed"))
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        renamed(&options),
+        ["+ fn function_renamed_b", "- fn function_renamed_a"]
+    );
+    options.match_renamed_functions = true;
+    assert_eq!(
+        renamed(&options),
+        ["  renamed function_renamed_a -> function_renamed_b (unchanged)"]
+    );
+}
+
+#[test]
+fn match_renamed_symbols() {
+    // All functions in the <symtab> unit have the same offset, and matching one
+    // of them must not hide the others.
+    let mut options = options();
+    options.category_type = false;
+    options.category_variable = false;
+    options.match_renamed_functions = true;
+    let diff = diff_files("tests/bin/renamed1", "tests/bin/renamed2", &options);
+    let lines: Vec<_> = diff
+        .lines()
+        .filter(|line| line.contains(" fn ") || line.contains("renamed "))
+        .collect();
+    assert_eq!(
+        lines,
+        [
+            "+ fn added_1",
+            "- fn deleted_1",
+            "+ fn added_2",
+            "- fn deleted_2",
+            "  renamed renamed_a -> renamed_b (unchanged)",
+        ]
+    );
+}
+
+#[test]
+fn accessibility() {
+    let mut options = options();
+    options.unit("src/access.cpp").name("access_class");
+    options.category_function = false;
+    options.category_variable = false;
+    let diff = diff_files("tests/bin/access1", "tests/bin/access2", &options);
+    assert_eq!(
+        diff,
+        concat!(
+            "  struct access_class\n",
+            "  \tsize: 16\n",
+            "  \tvirtual methods:\n",
+            "  \t\t[0]\tget\n",
+            "  \tmembers:\n",
+            "  \t\t0[8]\tpublic artificial: _vptr.access_class: * __vtbl_ptr_type\n",
+            "  \t\t8[4]\tpublic: a: int\n",
+            "- \t\t12[4]\tprivate: b: int\n",
+            "+ \t\t12[4]\tprotected: b: int\n",
+            "- \t\t[4]\tprivate static: s: int\n",
+            "+ \t\t[4]\tprotected static: s: int\n",
+            "\n",
+        )
+    );
+
+    options.filter_unit = None;
+    options.filter_namespace = vec!["access_class".into()];
+    options.name("get");
+    options.category_function = true;
+    let output = print_file("tests/bin/access2", &options);
+    assert!(output.contains("\n\tvirtual: yes\n"), "{}", output);
+}
+
+#[test]
+fn vtable() {
+    let mut options = options();
+    options.unit("src/vtable.cpp").name("vtable_class");
+    options.category_function = false;
+    options.category_variable = false;
+    let diff = diff_files("tests/bin/vtable1", "tests/bin/vtable2", &options);
+    assert_eq!(
+        diff,
+        concat!(
+            "  struct vtable_class\n",
+            "  \tsize: 24\n",
+            "  \tinherits:\n",
+            "  \t\tpublic struct vtable_base\n",
+            "  \t\tprivate virtual struct vtable_virtual_base\n",
+            "  \tvirtual methods:\n",
+            "- \t\t[2]\tfirst\n",
+            "- \t\t[3]\tsecond\n",
+            "+ \t\t[2]\tsecond\n",
+            "+ \t\t[3]\tinserted\n",
+            "+ \t\t[4]\tfirst\n",
+            "  \tmembers:\n",
+            "  \t\t0[16]\t<inherit>: struct vtable_base\n",
+            "  \t\t12[4]\tpublic: member: int\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn variant_part() {
+    let mut options = options();
+    options.name("Option<&u32>");
+    let output = print_file("tests/bin/variant1.o", &options);
+    let expect = concat!(
+        "struct core::option::Option<&u32>\n",
+        "\tsize: 8\n",
+        "\tmembers:\n",
+        "\t\t0[8]\tartificial: <anon>: u64\n",
+        "\t\t0[8]\t<variant part>: niche discriminant 0[8]\n",
+        "\t\t\tNone: <0>\n",
+        "\t\t\tSome: <default>\n",
+        "\t\t\t\t0[8]\tpublic: __0: &u32\n",
+        "\n",
+    );
+    assert_eq!(output, expect);
+
+    options.name("Signed");
+    let diff = diff_files("tests/bin/variant1.o", "tests/bin/variant2.o", &options);
+    let expect = concat!(
+        "  struct variant::Signed\n",
+        "  \tsize: 2\n",
+        "  \tmembers:\n",
+        "  \t\t0[1]\tartificial: <anon>: i8\n",
+        "  \t\t0[2]\t<variant part>: discriminant 0[1]\n",
+        "- \t\t\tA: <-1>\n",
+        "+ \t\t\tA: <-2>\n",
+        "  \t\t\t\t0[1]\t<padding>\n",
+        "  \t\t\t\t1[1]\tpublic: __0: u8\n",
+        "  \t\t\tB: <1>\n",
+        "\n",
+    );
+    assert_eq!(diff, expect);
+
+    options.name("Tagged");
+    let diff = diff_files("tests/bin/variant1.o", "tests/bin/variant2.o", &options);
+    let expect = concat!(
+        "  struct variant::Tagged\n",
+        "- \tsize: 8\n",
+        "+ \tsize: 16\n",
+        "  \tmembers:\n",
+        "  \t\t0[1]\tartificial: <anon>: u8\n",
+        "- \t\t0[8]\t<variant part>: discriminant 0[1]\n",
+        "+ \t\t0[16]\t<variant part>: discriminant 0[1]\n",
+        "[..]",
+        "  \t\t\tB: <1>\n",
+        "  \t\t\t\t0[2]\t<padding>\n",
+        "  \t\t\t\t2[2]\tpublic: __1: u16\n",
+        "+ \t\t\t\t4[4]\t<padding>\n",
+        "- \t\t\t\t4[4]\tpublic: __0: u32\n",
+        "+ \t\t\t\t8[8]\tpublic: __0: u64\n",
+        "  \t\t\tC: <2>\n",
+        "\n",
+    );
+    assert!(equal(&diff, expect), "{}", diff);
+}
+
+#[test]
+fn diff_changed() {
+    let options = options();
+    let changed = |path_a: &str, path_b: &str| {
+        let file_a = ddbug::File::parse(path_a.into()).unwrap();
+        let file_b = ddbug::File::parse(path_b.into()).unwrap();
+        let mut output = Vec::new();
+        let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+        ddbug::diff(&mut printer, file_a.file(), file_b.file(), &options).unwrap()
+    };
+    assert!(!changed("tests/bin/diff1", "tests/bin/diff1-shuffled"));
+    assert!(changed("tests/bin/diff1", "tests/bin/diff2"));
+}
+
+#[test]
+fn cache() {
+    let options = ddbug::Options {
+        print_source: true,
+        print_function_variables: true,
+        ..options()
+    };
+    let print = |file: &ddbug::File| {
+        let mut output = Vec::new();
+        let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+        ddbug::print(file, &mut printer, &options).unwrap();
+        String::from_utf8(output).unwrap()
+    };
+    let cache_dir = std::env::temp_dir().join(format!("ddbug-test-cache-{}", std::process::id()));
+    let parse = |progress: &mut dyn FnMut(usize, usize)| {
+        ddbug::File::parse_with_cache("tests/bin/diff1".into(), Some(&cache_dir), progress).unwrap()
+    };
+
+    let expect = print(ddbug::File::parse("tests/bin/diff1".into()).unwrap().file());
+    // The first parse writes the cache, and the second parse reads it.
+    let mut parsed = false;
+    assert_eq!(print(parse(&mut |_, _| parsed = true).file()), expect);
+    assert!(parsed);
+    assert_eq!(std::fs::read_dir(&cache_dir).unwrap().count(), 1);
+    let mut parsed = false;
+    assert_eq!(print(parse(&mut |_, _| parsed = true).file()), expect);
+    assert!(!parsed);
+    std::fs::remove_dir_all(&cache_dir).unwrap();
+}
+
+#[test]
+fn symtab_overlap() {
+    // Symbols within a function that has debuginfo must not be added to the
+    // `<symtab>` unit.
+    let file = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let names: Vec<_> = file
+        .file()
+        .units()
+        .iter()
+        .flat_map(|unit| unit.functions())
+        .filter_map(|function| function.name())
+        .collect();
+    assert!(names.contains(&"symbol_in_function"));
+    assert!(!names.contains(&"symbol_in_function_label"));
+}
+
+#[test]
+fn symtab_options() {
+    let parse = |symtab, symtab_ranges| {
+        let options = ddbug::parser::ParseOptions {
+            symtab,
+            symtab_ranges,
+            ..Default::default()
+        };
+        ddbug::File::parse_with_options("tests/bin/diff1".into(), &options, &mut |_, _| {}).unwrap()
+    };
+    let unit_size = |file: &ddbug::File, name| {
+        let hash = ddbug::parser::FileHash::new(file);
+        file.units()
+            .iter()
+            .find(|unit| unit.name() == Some(name))
+            .map(|unit| unit.ranges(&hash).size() + unit.unknown_ranges(&hash).size())
+    };
+    let file_size = |file: &ddbug::File| file.ranges(&ddbug::parser::FileHash::new(file)).size();
+
+    let unit = parse(true, true);
+    let symtab_size = unit_size(unit.file(), "<symtab>").unwrap();
+    assert_ne!(symtab_size, 0);
+
+    // Without the unit, the symbol ranges still count towards the file.
+    let ranges = parse(false, true);
+    assert_eq!(unit_size(ranges.file(), "<symtab>"), None);
+    assert_eq!(file_size(ranges.file()), file_size(unit.file()));
+    assert_eq!(
+        unit_size(ranges.file(), "<unknown>"),
+        unit_size(unit.file(), "<unknown>")
+    );
+
+    // Otherwise, they are unknown.
+    let none = parse(false, false);
+    assert_eq!(unit_size(none.file(), "<symtab>"), None);
+    assert!(
+        unit_size(none.file(), "<unknown>").unwrap() > unit_size(unit.file(), "<unknown>").unwrap()
+    );
+}
+
+#[test]
+fn ref_addr() {
+    // Types may be referenced from other units using `DW_FORM_ref_addr`.
+    let file = ddbug::File::parse("tests/bin/ref_addr".into()).unwrap();
+    let hash = ddbug::parser::FileHash::new(file.file());
+    assert_eq!(file.file().unresolved_types(&hash), Vec::new());
+
+    let options = ddbug::Options {
+        filter_unit: Some("ref_addr2.c".into()),
+        ..options()
+    };
+    // The address depends on the linker.
+    let output: String = print_file("tests/bin/ref_addr", &options)
+        .lines()
+        .filter(|line| !line.starts_with("\taddress:"))
+        .flat_map(|line| vec![line, "\n"])
+        .collect();
+    assert_eq!(
+        output,
+        concat!(
+            "struct ref_addr_struct\n",
+            "\tsize: 16\n",
+            "\tmembers:\n",
+            "\t\t0[4]\ta: int\n",
+            "\t\t4[4]\t<padding>\n",
+            "\t\t8[8]\tb: long\n",
+            "\n",
+            "type ref_addr_long = long\n",
+            "\tsize: 8\n",
+            "\n",
+            "var ref_addr_var: struct ref_addr_struct\n",
+            "\tsection: .data\n",
+            "\tsize: 16\n",
+            "\n",
+        )
+    );
+}
+
+#[test]
+fn dwarf64() {
+    // Section offsets are 8 bytes in 64-bit units and 4 bytes in 32-bit units,
+    // including in references between units of different formats.
+    let file = ddbug::File::parse("tests/bin/dwarf64".into()).unwrap();
+    let hash = ddbug::parser::FileHash::new(file.file());
+    assert_eq!(file.file().unresolved_types(&hash), Vec::new());
+
+    let mut options = options();
+    options.filter_glob = Some("dwarf*".into());
+    options.print_source = true;
+    let output = print_file("tests/bin/dwarf64", &options);
+    let expect = concat!(
+        "type dwarf32_typedef = struct dwarf64_struct\n",
+        "\tsize: 16\n",
+        "\n",
+        "struct dwarf64_struct\n",
+        "\tsize: 16\n",
+        "\tmembers:\n",
+        "\t\t0[4]\ta: int\n",
+        "\t\t4[4]\t<padding>\n",
+        "\t\t8[8]\tb: long\n",
+        "\n",
+        "var dwarf64_var: struct dwarf64_struct\n",
+        "\tsource: dwarf64.c:3\n",
+        "\taddress: [..]\n",
+        "\tsection: .data\n",
+        "\tsize: 16\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn print_offset() {
+    // The offsets are fixed because the debuginfo is hand-written.
+    let options = ddbug::Options {
+        print_offset: true,
+        filter_unit: Some("ref_addr2.c".into()),
+        ..options()
+    };
+    let output = print_file("tests/bin/ref_addr", &options);
+    let headers_and_offsets: Vec<_> = output
+        .lines()
+        .filter(|line| !line.starts_with('\t') || line.starts_with("\toffset:"))
+        .filter(|line| !line.is_empty())
+        .collect();
+    assert_eq!(
+        headers_and_offsets,
+        [
+            "struct ref_addr_struct",
+            "\toffset: 0x42",
+            "type ref_addr_long = long",
+            "\toffset: 0x81",
+            "var ref_addr_var: struct ref_addr_struct",
+            "\toffset: 0x65",
+        ]
+    );
+}
+
+#[test]
+fn filter_glob() {
+    let file = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let hash = ddbug::parser::FileHash::new(file.file());
+    let names = |glob: &str| {
+        let options = ddbug::Options {
+            filter_glob: Some(glob.into()),
+            ..options()
+        };
+        let mut names = Vec::new();
+        ddbug::for_each_variable(file.file(), &hash, &options, |_, v| {
+            names.push(v.name().unwrap().to_string())
+        });
+        names.sort();
+        names
+    };
+    assert_eq!(
+        names("variable_diff_s*"),
+        ["variable_diff_section", "variable_diff_size"]
+    );
+    assert_eq!(names("variable*_si?e"), ["variable_diff_size"]);
+    assert_eq!(
+        names("use_*_defn_decl"),
+        ["use_struct_diff_defn_decl", "use_union_diff_defn_decl"]
+    );
+    assert_eq!(names("variable_diff_"), Vec::<String>::new());
+}
+
+#[test]
+fn filter_qualified_name() {
+    let file = ddbug::File::parse("tests/bin/generic".into()).unwrap();
+    let count = |name: &str| {
+        let options = ddbug::Options {
+            filter_name: Some(name.into()),
+            ..options()
+        };
+        let mut count = 0;
+        ddbug::for_each_function(file.file(), &options, |_, _| count += 1);
+        count
+    };
+    assert_eq!(count("generic_size<u8>"), 1);
+    assert_eq!(count("generic::generic_size<u8>"), 1);
+    assert_eq!(count("generic::Wrapper<u8>::get<u8>"), 1);
+    assert_eq!(count("Wrapper<u8>::get<u8>"), 0);
+    assert_eq!(count("core::generic_size<u8>"), 0);
+}
+
+#[test]
+fn filter_include_exclude() {
+    let file = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let hash = ddbug::parser::FileHash::new(file.file());
+    let names = |include: &[&str], exclude: &[&str]| {
+        let options = ddbug::Options {
+            filter_include: include.iter().map(|s| s.to_string()).collect(),
+            filter_exclude: exclude.iter().map(|s| s.to_string()).collect(),
+            ..options()
+        };
+        let mut names = Vec::new();
+        ddbug::for_each_variable(file.file(), &hash, &options, |_, v| {
+            names.push(v.name().unwrap().to_string())
+        });
+        names.sort();
+        names
+    };
+    assert_eq!(
+        names(&["variable_diff_s*"], &[]),
+        ["variable_diff_section", "variable_diff_size"]
+    );
+    assert_eq!(
+        names(&["variable_diff_size", "use_*_defn_decl"], &[]),
+        [
+            "use_struct_diff_defn_decl",
+            "use_union_diff_defn_decl",
+            "variable_diff_size"
+        ]
+    );
+    assert_eq!(
+        names(&["variable_diff_s*"], &["*_section"]),
+        ["variable_diff_size"]
+    );
+    let all = names(&[], &[]);
+    let excluded = names(&[], &["variable_*"]);
+    assert!(excluded.len() < all.len());
+    assert!(excluded.iter().all(|name| !name.starts_with("variable_")));
+}
+
+#[test]
+fn sections() {
+    let parse = |sections: &[&str]| {
+        let options = ddbug::parser::ParseOptions {
+            sections: sections.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        ddbug::File::parse_with_options("tests/bin/thunk".into(), &options, &mut |_, _| {}).unwrap()
+    };
+    let functions = |file: &ddbug::File| {
+        let mut names = Vec::new();
+        for unit in file.units() {
+            for function in unit.functions() {
+                if let Some(name) = function.name() {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names.retain(|name| name.ends_with("_thunk"));
+        names
+    };
+    let section_size = |file: &ddbug::File, names: &[&str]| {
+        file.sections()
+            .iter()
+            .filter(|section| names.contains(&section.name().unwrap_or("")))
+            .map(|section| section.size())
+            .sum::<u64>()
+    };
+    let file_size = |file: &ddbug::File| file.ranges(&ddbug::parser::FileHash::new(file)).size();
+
+    let all = parse(&[]);
+    assert_eq!(functions(all.file()), ["code_thunk", "data_thunk"]);
+
+    let text = parse(&[".text"]);
+    assert_eq!(functions(text.file()), ["code_thunk"]);
+    assert_eq!(
+        file_size(text.file()),
+        section_size(text.file(), &[".text"])
+    );
+
+    let data = parse(&[".data", ".bss"]);
+    assert_eq!(functions(data.file()), ["data_thunk"]);
+    assert_eq!(
+        file_size(data.file()),
+        section_size(data.file(), &[".data", ".bss"])
+    );
+}
+
+#[test]
+fn archive() {
+    let options = ddbug::parser::ParseOptions::default();
+    let units = |path: &str| {
+        let members = ddbug::File::parse_archive(path.into(), &options)
+            .unwrap()
+            .unwrap();
+        members
+            .iter()
+            .map(|member| {
+                let file = member.file();
+                let names = file
+                    .units()
+                    .iter()
+                    .filter_map(|unit| unit.name())
+                    .filter(|name| name.starts_with("high_pc"))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("{}: {}", file.path(), names)
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        units("tests/bin/archive.a"),
+        ["tests/bin/archive.a(high_pc_long_member_name.o): high_pc_address.c,high_pc_offset.c"]
+    );
+    assert_eq!(
+        units("tests/bin/archive-thin.a"),
+        ["tests/bin/archive-thin.a(high_pc_long_member_name.o): high_pc_address.c,high_pc_offset.c"]
+    );
+    // Missing members are skipped.
+    assert_eq!(
+        units("tests/bin/archive-missing.a"),
+        ["tests/bin/archive-missing.a(high_pc_long_member_name.o): high_pc_address.c,high_pc_offset.c"]
+    );
+    assert!(
+        ddbug::File::parse_archive("tests/bin/high_pc".into(), &options)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn merge_sections() {
+    let mut options = options();
+    options.category_file = true;
+    options.merge_sections = true;
+    let members =
+        ddbug::File::parse_archive("tests/bin/archive-merge.a".into(), &Default::default())
+            .unwrap()
+            .unwrap();
+    let files: Vec<_> = members.iter().map(|member| member.file()).collect();
+    let mut output = Vec::new();
+    let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+    ddbug::print_archive("archive-merge.a", &files, &mut printer, &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    // `high_pc.s` has 15 bytes of code, and `thunk.s` has 7.
+    let expect = concat!(
+        "archive archive-merge.a\n",
+        "\tmembers: 2\n",
+        "\tsections:\n",
+        "\t\t.text\n",
+        "\t\t\taddress: [..]\n",
+        "\t\t\tsize: 22\n",
+        "[..]",
+        "\t\t.data\n",
+        "\t\t\taddress: [..]\n",
+        "\t\t\tsize: 16\n",
+        "\t\t\talign: 1\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
 macro_rules! test {
     ($name:ident, $($val:expr),*) => {
         #[test]
@@ -71,4 +1769,267 @@ macro_rules! test {
     }
 }
 
+#[test]
+fn specification() {
+    // Definitions inherit their name, type and parameters from a
+    // declaration or abstract instance, which may be in a different unit.
+    let mut options = options();
+    options.category_type = false;
+    options.filter_include = vec!["spec_*".into()];
+    let output = print_file("tests/bin/specification", &options);
+    let expect = concat!(
+        "fn spec_inline\n",
+        "\tinline: yes\n",
+        "[..]",
+        "fn spec_forward\n",
+        "[..]",
+        "\tparameters:\n",
+        "\t\t[4]\tc: int\n",
+        "\n",
+        "fn spec_local\n",
+        "[..]",
+        "\tparameters:\n",
+        "\t\t[4]\tb: int\n",
+        "\n",
+        "fn spec_backward\n",
+        "[..]",
+        "\tparameters:\n",
+        "\t\t[4]\ta: int\n",
+        "\n",
+        "fn spec_inline\n",
+        "\taddress: [..]",
+        "\tparameters:\n",
+        "\t\t[4]\tx: int\n",
+        "\n",
+        "var spec_var: int\n",
+        "\taddress: [..]\n",
+        "\tsection: .data\n",
+        "\tsize: 4\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn only_category() {
+    let mut options = options();
+    options.filter_include = vec!["spec_*".into()];
+    options.only(ddbug::Category::Variable);
+    let output = print_file("tests/bin/specification", &options);
+    let expect = concat!(
+        "var spec_var: int\n",
+        "\taddress: [..]\n",
+        "\tsection: .data\n",
+        "\tsize: 4\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+
+    // The section is only printed if requested.
+    options.print_variable_section = false;
+    let output = print_file("tests/bin/specification", &options);
+    assert!(!output.contains("\tsection:"), "{}", output);
+}
+
+#[test]
+fn diff_symbols() {
+    // `main` is written in assembly, so it only has a symbol.
+    let mut options = options();
+    options.filter_include = vec!["main".into(), "spec_*".into()];
+    options.only(ddbug::Category::Function);
+    let parse = |options: &ddbug::parser::ParseOptions| {
+        ddbug::File::parse_with_options("tests/bin/specification".into(), options, &mut |_, _| {})
+            .unwrap()
+    };
+    let file_a = parse(&ddbug::parser::ParseOptions {
+        symtab: false,
+        symtab_ranges: false,
+        ..Default::default()
+    });
+    let file_b = parse(&ddbug::parser::ParseOptions {
+        debuginfo: false,
+        ..Default::default()
+    });
+    let mut output = Vec::new();
+    let mut printer = ddbug::TextPrinter::new(&mut output, &options);
+    let changed =
+        ddbug::diff_symbols(&mut printer, file_a.file(), file_b.file(), &options).unwrap();
+    assert!(changed);
+    let output = String::from_utf8(output).unwrap();
+    let expect = concat!(
+        "+ fn main\n",
+        "+ \tlinkage name: main\n",
+        "+ \taddress: [..]\n",
+        "+ \tsize: 3\n",
+        "+ \tunwind: absent\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn load_bias() {
+    let mut options = options();
+    options.load_bias = 0x10000;
+    options.only(ddbug::Category::Function).name("main");
+    let output = print_file("tests/bin/diff1", &options);
+    let expect = concat!(
+        "fn main\n",
+        "\taddress: 0x11129-0x11133\n",
+        "\tsize: 11\n",
+        "\tunwind: present\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "\t\t[4]\tint\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+}
+
+#[test]
+fn load_bias_instructions() {
+    // Offsets within the function are not biased, but call targets are.
+    let mut options = options();
+    options.load_bias = 0x10000;
+    options.print_function_instructions = true;
+    options
+        .only(ddbug::Category::Function)
+        .name("function_diff_inlined_caller");
+    let output = print_file("tests/bin/diff1", &options);
+    assert!(
+        output.contains(
+            "\t\t 10:  call    0x11134\n\t\t              0x11134 = function_diff_inlined\n"
+        ),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn wasm() {
+    // Function addresses are offsets within the code section.
+    let mut options = options();
+    options.only(ddbug::Category::Function);
+    let output = print_file("tests/bin/wasm.o", &options);
+    let expect = concat!(
+        "fn wasm_add\n",
+        "\taddress: 0x2-0x8\n",
+        "\tsize: 7\n",
+        "\tunwind: absent\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "\t\t[4]\tint\n",
+        "\tparameters:\n",
+        "\t\t[4]\ta: int\n",
+        "\t\t[4]\tb: int\n",
+        "\n",
+        "fn wasm_twice\n",
+        "\taddress: 0xa-0x15\n",
+        "\tsize: 12\n",
+        "\tunwind: absent\n",
+        "\tlinkage: external\n",
+        "\treturn type:\n",
+        "\t\t[4]\tint\n",
+        "\n",
+    );
+    assert!(equal(&output, expect), "{}", output);
+    let file = ddbug::File::parse("tests/bin/wasm.o".into()).unwrap();
+    let symbols: Vec<_> = file.file().symbols().iter().map(|s| s.name()).collect();
+    assert_eq!(symbols, [Some("wasm_add"), Some("wasm_twice")]);
+}
+
+#[test]
+fn member_declaration_order() {
+    // Rust reorders the fields of `Tagged::B`.
+    let mut options = options();
+    options.member_declaration_order = true;
+    options.only(ddbug::Category::Type).name("Tagged");
+    let output = print_file("tests/bin/variant1.o", &options);
+    let expect = concat!(
+        "struct variant::Tagged\n",
+        "\tsize: 8\n",
+        "\tmembers:\n",
+        "\t\t0[1]\tartificial: <anon>: u8\n",
+        "\t\t0[8]\t<variant part>: discriminant 0[1]\n",
+        "\t\t\tA: <0>\n",
+        "\t\t\t\t1[1]\tpublic: __0: u8\n",
+        "\t\t\tB: <1>\n",
+        "\t\t\t\t4[4]\tpublic: __0: u32\n",
+        "\t\t\t\t2[2]\tpublic: __1: u16\n",
+        "\t\t\tC: <2>\n",
+        "\n",
+    );
+    assert_eq!(output, expect);
+}
+
+#[test]
+fn debug_sizes() {
+    let mut options = options();
+    options.debug_sizes = true;
+    options.only(ddbug::Category::File);
+    let diff = diff_files("tests/bin/diff1", "tests/bin/diff2", &options);
+    let lines: Vec<_> = diff
+        .lines()
+        .filter(|line| line.contains("debug size"))
+        .collect();
+    assert_eq!(
+        lines,
+        ["- \tdebug size: 7066", "+ \tdebug size: 7246 (+180)"],
+        "{}",
+        diff
+    );
+
+    let file = ddbug::File::parse("tests/bin/diff1".into()).unwrap();
+    let debug_size: u64 = file
+        .file()
+        .sections()
+        .iter()
+        .filter(|section| matches!(section.name(), Some(name) if name.starts_with(".debug_")))
+        .map(|section| section.size())
+        .sum();
+    assert_eq!(file.file().debug_size(), debug_size);
+}
+
+#[test]
+fn high_pc() {
+    // `DW_AT_high_pc` is an address for `DW_FORM_addr`, and an offset from
+    // `DW_AT_low_pc` for constant forms.
+    let file = ddbug::File::parse("tests/bin/high_pc".into()).unwrap();
+    let hash = ddbug::parser::FileHash::new(file.file());
+    let mut sizes = Vec::new();
+    for unit in file.file().units() {
+        if !matches!(unit.name(), Some(name) if name.starts_with("high_pc")) {
+            continue;
+        }
+        for function in unit.functions() {
+            sizes.push((function.name().unwrap(), function.size()));
+        }
+        // The unit ranges match the function ranges.
+        assert_eq!(unit.unknown_ranges(&hash).list(), &[]);
+    }
+    sizes.sort();
+    assert_eq!(
+        sizes,
+        [("high_pc_address", Some(4)), ("high_pc_offset", Some(8)),]
+    );
+}
+
+#[test]
+fn debug_file() {
+    // These files have no debuginfo, but there is a separate debuginfo file
+    // at `<path>.debug` or `<dir>/.debug/<name>`.
+    for path in &["tests/bin/sidecar", "tests/bin/sidecar-dir"] {
+        let file = ddbug::File::parse((*path).into()).unwrap();
+        let mut names: Vec<_> = file
+            .file()
+            .units()
+            .iter()
+            .filter_map(|unit| unit.name())
+            .filter(|name| name.starts_with("high_pc"))
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, ["high_pc_address.c", "high_pc_offset.c"], "{}", path);
+    }
+}
+
 include!("src/diff.rs");
@@ -0,0 +1,9 @@
+// Only this definition of the type changes.
+struct abi_duplicate {
+    long b;
+#ifdef TEST2
+    int c;
+#endif
+};
+
+struct abi_duplicate abi_duplicate_other;
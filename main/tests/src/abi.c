@@ -0,0 +1,52 @@
+// Types with anonymous members, which have no name to pair them by.
+
+// The ABI of this type is the same for TEST1 and TEST2.
+struct abi_anon_equal {
+    union {
+        char a;
+        int b;
+    };
+    union {
+        char c;
+        int d;
+    };
+};
+
+// Only the second anonymous member changes size.
+struct abi_anon_changed {
+    union {
+        char a;
+        int b;
+    };
+    union {
+        char c;
+#ifdef TEST1
+        int d;
+#else
+        long d;
+#endif
+    };
+};
+
+// A member changes type without changing size.
+struct abi_member_type {
+#ifdef TEST1
+    int a;
+#else
+    float a;
+#endif
+};
+
+// The same name is used for a different type in abi-other.c.
+struct abi_duplicate {
+    int a;
+};
+
+struct abi_anon_equal abi_anon_equal;
+struct abi_anon_changed abi_anon_changed;
+struct abi_member_type abi_member_type;
+struct abi_duplicate abi_duplicate;
+
+int main(void) {
+    return 0;
+}
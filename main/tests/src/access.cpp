@@ -0,0 +1,21 @@
+// Member accessibility, static members and virtual functions.
+
+class access_class {
+public:
+    virtual int get();
+    int a;
+#ifdef TEST1
+private:
+#else
+protected:
+#endif
+    int b;
+    static int s;
+};
+
+int access_class::s;
+int access_class::get() { return a + b + s; }
+
+access_class access_var;
+
+int main() {}
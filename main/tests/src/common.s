@@ -0,0 +1,171 @@
+# A Fortran common block, as described by gfortran.
+#
+# The block is described within each subprogram that uses it, and the
+# location of each variable in the block is relative to the block's symbol.
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+	.globl	common_a
+	.type	common_a, @function
+common_a:
+	movl	$1, common_blk_(%rip)
+	ret
+	.size	common_a, .-common_a
+	.globl	common_b
+	.type	common_b, @function
+common_b:
+	movl	$2, common_blk_(%rip)
+	ret
+	.size	common_b, .-common_b
+	.comm	common_blk_,16,16
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 2		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 3		# subprogram
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x7		# DW_FORM_data8
+	.byte	0
+	.byte	0
+	.uleb128 4		# common block
+	.uleb128 0x1a		# DW_TAG_common_block
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.uleb128 5		# variable
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.byte	0
+	.section	.debug_info,"",@progbits
+	.long	.Lunit_end - .Lunit_start
+.Lunit_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.long	.Lstr_common_f90
+	.byte	0xe		# DW_LANG_Fortran95
+.Linteger:
+	.uleb128 2		# DW_TAG_base_type
+	.long	.Lstr_integer
+	.byte	4
+	.byte	5		# DW_ATE_signed
+.Lreal:
+	.uleb128 2		# DW_TAG_base_type
+	.long	.Lstr_real
+	.byte	8
+	.byte	4		# DW_ATE_float
+	.uleb128 3		# DW_TAG_subprogram
+	.long	.Lstr_common_a
+	.quad	common_a
+	.quad	common_b - common_a
+	.uleb128 4		# DW_TAG_common_block
+	.long	.Lstr_common_blk
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.uleb128 5		# DW_TAG_variable
+	.long	.Lstr_i
+	.long	.Linteger - .Lunit_start + 4
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.uleb128 5		# DW_TAG_variable
+	.long	.Lstr_x
+	.long	.Lreal - .Lunit_start + 4
+	.uleb128 11		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.byte	0x23		# DW_OP_plus_uconst
+	.uleb128 8
+	.byte	0
+	.byte	0
+	.uleb128 3		# DW_TAG_subprogram
+	.long	.Lstr_common_b
+	.quad	common_b
+	.quad	.Ltext_end - common_b
+	.uleb128 4		# DW_TAG_common_block
+	.long	.Lstr_common_blk
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.uleb128 5		# DW_TAG_variable
+	.long	.Lstr_i
+	.long	.Linteger - .Lunit_start + 4
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.uleb128 5		# DW_TAG_variable
+	.long	.Lstr_x
+	.long	.Lreal - .Lunit_start + 4
+	.uleb128 11		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	common_blk_
+	.byte	0x23		# DW_OP_plus_uconst
+	.uleb128 8
+	.byte	0
+	.byte	0
+	.byte	0
+.Lunit_end:
+	.section	.debug_str,"MS",@progbits,1
+.Lstr_common_f90:
+	.string	"common.f90"
+.Lstr_integer:
+	.string	"integer(kind=4)"
+.Lstr_real:
+	.string	"real(kind=8)"
+.Lstr_common_a:
+	.string	"common_a"
+.Lstr_common_b:
+	.string	"common_b"
+.Lstr_common_blk:
+	.string	"common_blk"
+.Lstr_i:
+	.string	"i"
+.Lstr_x:
+	.string	"x"
+	.text
+.Ltext_end:
+	.section	.note.GNU-stack,"",@progbits
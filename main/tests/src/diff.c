@@ -211,6 +211,27 @@ EXPECT(
     "+ \tsize: 4\n",
     "\n")
 
+#undef T
+#define T typedef_diff_chain
+#ifdef TEST1
+    typedef char NAME(T, base);
+    typedef NAME(T, base) T;
+    USE_TYPE(T);
+#endif
+#ifdef TEST2
+    typedef int NAME(T, base);
+    typedef NAME(T, base) T;
+    USE_TYPE(T);
+#endif
+EXPECT(
+    T,
+    "  type ", S(T), " = ", S(NAME(T, base)), "\n",
+    "- \tresolves to: char\n",
+    "+ \tresolves to: int\n",
+    "- \tsize: 1\n",
+    "+ \tsize: 4\n",
+    "\n")
+
 // TODO: typedef_diff_union, typedef_diff_enum, typedef_diff_pointer_anon
 
 #undef T
@@ -322,6 +343,230 @@ EXPECT(
     "+ \t\t0[4]\ta: int\n",
     "\n")
 
+#undef T
+#define T struct_diff_alignment
+#ifdef TEST1
+    struct T {
+        char a;
+        int b;
+    };
+    USE_STRUCT(T);
+#endif
+#ifdef TEST2
+    struct T {
+        char a;
+        int b __attribute__((aligned(8)));
+    } __attribute__((aligned(16)));
+    USE_STRUCT(T);
+#endif
+EXPECT(
+    T,
+    "  struct ", S(T), "\n",
+    "- \tsize: 8\n",
+    "+ \tsize: 16\n",
+    "+ \talignment: 16\n",
+    "  \tmembers:\n",
+    "  \t\t0[1]\ta: char\n",
+    "- \t\t1[3]\t<padding>\n",
+    "+ \t\t1[7]\t<padding>\n",
+    "- \t\t4[4]\tb: int\n",
+    "+ \t\t8[4]\taligned(8): b: int\n",
+    "+ \t\t12[4]\t<padding>\n",
+    "\n")
+
+// Members of anonymous aggregates are accessible from the outer struct,
+// so changes to them must be shown.
+#undef T
+#define T struct_diff_anon_member
+#ifdef TEST1
+    struct T {
+        char a;
+        union {
+            char b;
+            char c;
+        };
+    };
+    USE_STRUCT(T);
+#endif
+#ifdef TEST2
+    struct T {
+        char a;
+        union {
+            char b;
+            short c;
+        };
+    };
+    USE_STRUCT(T);
+#endif
+EXPECT(
+    T,
+    "  struct ", S(T), "\n",
+    "- \tsize: 2\n",
+    "+ \tsize: 4\n",
+    "  \tmembers:\n",
+    "  \t\t0[1]\ta: char\n",
+    "+ \t\t1[1]\t<padding>\n",
+    "- \t\t1[1]\t<anon>: union <anon>\n",
+    "+ \t\t2[2]\t<anon>: union <anon>\n",
+    "  \t\t\t0[1]\tb: char\n",
+    "- \t\t\t0[1]\tc: char\n",
+    "+ \t\t\t0[2]\tc: short int\n",
+    "\n")
+
+// Array dimensions must be compared, even if the total size is unchanged.
+#undef T
+#define T struct_diff_array_dimensions
+#ifdef TEST1
+    struct T {
+        char a[2][3];
+        char b[];
+    };
+    USE_STRUCT(T);
+#endif
+#ifdef TEST2
+    struct T {
+        char a[3][2];
+        char b[];
+    };
+    USE_STRUCT(T);
+#endif
+EXPECT(
+    T,
+    "  struct ", S(T), "\n",
+    "  \tsize: 6\n",
+    "  \tmembers:\n",
+    "- \t\t0[6]\ta: [[char; 3]; 2]\n",
+    "+ \t\t0[6]\ta: [[char; 2]; 3]\n",
+    "  \t\t6[??]\tb: [char]\n",
+    "\n")
+
+// Function pointers must show variadic parameters.
+#undef T
+#define T struct_diff_member_variadic
+#ifdef TEST1
+    struct T {
+        void (*a)(int);
+    };
+    USE_STRUCT(T);
+#endif
+#ifdef TEST2
+    struct T {
+        void (*a)(int, ...);
+    };
+    USE_STRUCT(T);
+#endif
+EXPECT(
+    T,
+    "  struct ", S(T), "\n",
+    "  \tsize: 8\n",
+    "  \tmembers:\n",
+    "- \t\t0[8]\ta: * (int)\n",
+    "+ \t\t0[8]\ta: * (int, ...)\n",
+    "\n")
+
+// Type qualifiers are part of the member type.
+#undef T
+#define T struct_diff_member_qualifier
+#ifdef TEST1
+    struct T {
+        char *a;
+        int b;
+        int c;
+    };
+    USE_STRUCT(T);
+#endif
+#ifdef TEST2
+    struct T {
+        const char *a;
+        volatile int b;
+        _Atomic int c;
+    };
+    USE_STRUCT(T);
+#endif
+EXPECT(
+    T,
+    "  struct ", S(T), "\n",
+    "  \tsize: 16\n",
+    "  \tmembers:\n",
+    "- \t\t0[8]\ta: * char\n",
+    "+ \t\t0[8]\ta: * const char\n",
+    "- \t\t8[4]\tb: int\n",
+    "+ \t\t8[4]\tb: volatile int\n",
+    "- \t\t12[4]\tc: int\n",
+    "+ \t\t12[4]\tc: atomic int\n",
+    "\n")
+
+#undef T
+#define T function_diff_inlined
+#ifdef TEST1
+    __attribute__((noinline)) static int T(int x) { return x * 3; }
+#endif
+#ifdef TEST2
+    __attribute__((always_inline)) static inline int T(int x) { return x * 3; }
+#endif
+#ifdef TEST
+    int NAME(T, caller)(int x) { return T(x) + 1; }
+#endif
+EXPECT(
+    T,
+    "  fn ", S(T), "\n",
+    "- \taddress: [..]\n",
+    "+ \taddress: <inlined>\n",
+    "- \tsize: 18\n",
+    "+ \tsize: 9 (inlined 1 time)\n",
+    "+ \tinline: yes\n",
+    "[..]\n",
+    "\n")
+
+#undef T
+#define T variable_diff_section
+#ifdef TEST1
+    int T;
+#endif
+#ifdef TEST2
+    int T = 1;
+#endif
+EXPECT(
+    T,
+    "  var ", S(T), ": int\n",
+    "[..]",
+    "- \tsection: .bss (uninitialized)\n",
+    "+ \tsection: .data\n",
+    "  \tsize: 4\n",
+    "\n")
+
+#undef T
+#define T variable_diff_anon_use
+#ifdef TEST
+    struct {
+        char c;
+    } NAME(T, a);
+    struct {
+        short s;
+    } NAME(T, b);
+#endif
+#ifdef TEST1
+    typeof(NAME(T, a)) *T = &NAME(T, a);
+#endif
+#ifdef TEST2
+    typeof(NAME(T, b)) *T = &NAME(T, b);
+#endif
+EXPECT(
+    T,
+    "- var ", S(T), ": * struct <anon ", S(NAME(T, a)), ">\n",
+    "+ var ", S(T), ": * struct <anon ", S(NAME(T, b)), ">\n",
+    "[..]",
+    "  \tsize: 8\n",
+    "\n")
+#undef T
+#define T function_renamed
+#ifdef TEST1
+    int NAME(T, a)(int x) { return x * 5 + 1; }
+#endif
+#ifdef TEST2
+    int NAME(T, b)(int x) { return x * 5 + 1; }
+#endif
+
 #undef T
 #define T struct_diff_member_reorder
 #ifdef TEST1
@@ -904,6 +1149,7 @@ EXPECT(
     T,
     "- type ", S(T), " = char\n",
     "+ type ", S(T), " = C\n",
+    "  \tresolves to: char\n",
     "  \tsize: 1\n",
     "\n")
 
@@ -949,6 +1195,46 @@ EXPECT(
     "+ \t\t[4]\tint\n",
     "\n")
 
+#undef T
+#define T function_diff_linkage
+#ifdef TEST1
+    static char T() {}
+#endif
+#ifdef TEST2
+    char T() {}
+#endif
+#ifdef TEST
+    char (*NAME(T, use))() = T;
+#endif
+EXPECT(
+    T,
+    "  fn ", S(T), "\n",
+    "[..]\n",
+    "- \tlinkage: static\n",
+    "+ \tlinkage: external\n",
+    "  \treturn type:\n",
+    "  \t\t[1]\tchar\n",
+    "\n")
+
+#undef T
+#define T function_diff_variadic
+#ifdef TEST1
+    char T(char a) { return a; }
+#endif
+#ifdef TEST2
+    char T(char a, ...) { return a; }
+#endif
+EXPECT(
+    T,
+    "  fn ", S(T), "\n",
+    "[..]\n",
+    "  \treturn type:\n",
+    "  \t\t[1]\tchar\n",
+    "  \tparameters:\n",
+    "  \t\t[1]\ta: char\n",
+    "+ \t\t...\n",
+    "\n")
+
 /* TODO: requires fuzzy parameter cmp */
 #if 0
 #undef T
@@ -1002,6 +1288,21 @@ EXPECT(
     "  \t\t[1]\tg: char\n",
     "\n")
 
+// The address of this function differs because the preceding function changed size,
+// but it should still be matched by name.
+#undef T
+#define T function_moved
+#ifdef TEST1
+    void NAME(T, pad)(void) {}
+#endif
+#ifdef TEST2
+    void NAME(T, pad)(void) { volatile int x = 0; x++; }
+#endif
+#ifdef TEST
+    char T(char a) { return a; }
+#endif
+EXPECT(T, "")
+
 #undef T
 #define T variable_equal
 #ifdef TEST
@@ -1026,6 +1327,16 @@ EXPECT(
     "+ \tsize: 2\n",
     "\n")
 
+#undef T
+#define T variable_diff_bytes
+#ifdef TEST1
+    const char T[6] = "hello";
+#endif
+#ifdef TEST2
+    const char T[6] = "world";
+#endif
+EXPECT(T, "")
+
 #undef T
 #define T variable_diff_decl
 #ifdef SUPPORT
@@ -1040,8 +1351,20 @@ EXPECT(
 #endif
 EXPECT(
     T,
-    "  var ", S(T), ": int\n",
+    "- var ", S(T), ": int\n",
     "[..]",
-    "  \tsize: 4\n",
-    "+ \tdeclaration: yes\n",
+    "- \tsize: 4\n",
     "\n")
+
+#undef T
+#define T struct_diff_decl_support
+#ifdef SUPPORT
+    struct T {
+        char c;
+    };
+#endif
+#ifdef TEST
+    struct T;
+    USE_STRUCT(T);
+#endif
+EXPECT(T, "")
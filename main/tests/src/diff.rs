@@ -7,12 +7,21 @@ test!(typedef_diff_anon_struct_union, "- type ", "typedef_diff_anon_struct_union
 test!(typedef_diff_base_anon, "- type ", "typedef_diff_base_anon", " = struct <anon>\n", "+ type ", "typedef_diff_base_anon", " = char\n", "  \tsize: 1\n", "  \tmembers:\n", "- \t\t0[1]\tc: char\n", "\n");
 test!(typedef_diff_struct_name, "- type ", "typedef_diff_struct_name", " = struct ", "typedef_diff_struct_name_s1", "\n", "+ type ", "typedef_diff_struct_name", " = struct ", "typedef_diff_struct_name_s2", "\n", "  \tsize: 1\n", "\n");
 test!(typedef_diff_struct_size, "  type ", "typedef_diff_struct_size", " = struct s\n", "- \tsize: 1\n", "+ \tsize: 4\n", "\n");
+test!(typedef_diff_chain, "  type ", "typedef_diff_chain", " = ", "typedef_diff_chain_base", "\n", "- \tresolves to: char\n", "+ \tresolves to: int\n", "- \tsize: 1\n", "+ \tsize: 4\n", "\n");
 test!(struct_diff_defn_equal, "");
 test!(struct_diff_decl_equal, "");
 test!(struct_diff_defn_decl, "  struct ", "struct_diff_defn_decl", "\n", "+ \tdeclaration: yes\n", "- \tsize: 1\n", "  \tmembers:\n", "- \t\t0[1]\tc: char\n", "\n");
 test!(struct_diff_decl_defn, "  struct ", "struct_diff_decl_defn", "\n", "- \tdeclaration: yes\n", "+ \tsize: 1\n", "  \tmembers:\n", "+ \t\t0[1]\tc: char\n", "\n");
 test!(struct_diff_size_equal, "  struct ", "struct_diff_size_equal", "\n", "  \tsize: 2\n", "  \tmembers:\n", "- \t\t0[2]\tc: [char; 2]\n", "+ \t\t0[1]\tc1: char\n", "+ \t\t1[1]\tc2: char\n", "\n");
 test!(struct_diff_member, "  struct ", "struct_diff_member", "\n", "- \tsize: 1\n", "+ \tsize: 4\n", "  \tmembers:\n", "- \t\t0[1]\ta: char\n", "+ \t\t0[4]\ta: int\n", "\n");
+test!(struct_diff_alignment, "  struct ", "struct_diff_alignment", "\n", "- \tsize: 8\n", "+ \tsize: 16\n", "+ \talignment: 16\n", "  \tmembers:\n", "  \t\t0[1]\ta: char\n", "- \t\t1[3]\t<padding>\n", "+ \t\t1[7]\t<padding>\n", "- \t\t4[4]\tb: int\n", "+ \t\t8[4]\taligned(8): b: int\n", "+ \t\t12[4]\t<padding>\n", "\n");
+test!(struct_diff_anon_member, "  struct ", "struct_diff_anon_member", "\n", "- \tsize: 2\n", "+ \tsize: 4\n", "  \tmembers:\n", "  \t\t0[1]\ta: char\n", "+ \t\t1[1]\t<padding>\n", "- \t\t1[1]\t<anon>: union <anon>\n", "+ \t\t2[2]\t<anon>: union <anon>\n", "  \t\t\t0[1]\tb: char\n", "- \t\t\t0[1]\tc: char\n", "+ \t\t\t0[2]\tc: short int\n", "\n");
+test!(struct_diff_array_dimensions, "  struct ", "struct_diff_array_dimensions", "\n", "  \tsize: 6\n", "  \tmembers:\n", "- \t\t0[6]\ta: [[char; 3]; 2]\n", "+ \t\t0[6]\ta: [[char; 2]; 3]\n", "  \t\t6[??]\tb: [char]\n", "\n");
+test!(struct_diff_member_variadic, "  struct ", "struct_diff_member_variadic", "\n", "  \tsize: 8\n", "  \tmembers:\n", "- \t\t0[8]\ta: * (int)\n", "+ \t\t0[8]\ta: * (int, ...)\n", "\n");
+test!(struct_diff_member_qualifier, "  struct ", "struct_diff_member_qualifier", "\n", "  \tsize: 16\n", "  \tmembers:\n", "- \t\t0[8]\ta: * char\n", "+ \t\t0[8]\ta: * const char\n", "- \t\t8[4]\tb: int\n", "+ \t\t8[4]\tb: volatile int\n", "- \t\t12[4]\tc: int\n", "+ \t\t12[4]\tc: atomic int\n", "\n");
+test!(function_diff_inlined, "  fn ", "function_diff_inlined", "\n", "- \taddress: [..]\n", "+ \taddress: <inlined>\n", "- \tsize: 18\n", "+ \tsize: 9 (inlined 1 time)\n", "+ \tinline: yes\n", "[..]\n", "\n");
+test!(variable_diff_section, "  var ", "variable_diff_section", ": int\n", "[..]", "- \tsection: .bss (uninitialized)\n", "+ \tsection: .data\n", "  \tsize: 4\n", "\n");
+test!(variable_diff_anon_use, "- var ", "variable_diff_anon_use", ": * struct <anon ", "variable_diff_anon_use_a", ">\n", "+ var ", "variable_diff_anon_use", ": * struct <anon ", "variable_diff_anon_use_b", ">\n", "[..]", "  \tsize: 8\n", "\n");
 test!(struct_diff_member_reorder, "  struct ", "struct_diff_member_reorder", "\n", "  \tsize: 7\n", "  \tmembers:\n", "+ \t\t0[2]\td: [char; 2]\n", "+ \t\t2[1]\tc: char\n", "- \t\t0[1]\ta: char\n", "+ \t\t3[1]\ta: char\n", "- \t\t1[1]\tb: char\n", "+ \t\t4[1]\tb: char\n", "- \t\t2[1]\tc: char\n", "- \t\t3[2]\td: [char; 2]\n", "  \t\t5[1]\tx: char\n", "- \t\t6[1]\ty: char\n", "+ \t\t6[1]\tz: char\n", "\n");
 test!(struct_diff_recursive_equal, "");
 test!(union_diff_defn_equal, "");
@@ -38,11 +47,16 @@ test!(member_diff_inline_none_struct, "  struct ", "member_diff_inline_none_stru
 test!(enum_diff_equal, "");
 test!(enum_diff, "  enum ", "enum_diff", "\n", "  \tsize: 4\n", "  \tenumerators:\n", "  \t\tA2(1)\n", "- \t\tB2(2)\n", "+ \t\tC2(2)\n", "- \t\tC2(3)\n", "+ \t\tB2(3)\n", "- \t\tD2(4)\n", "  \t\tE2(5)\n", "+ \t\tF2(6)\n", "\n");
 test!(array_diff_equal, "");
-test!(array_diff_type, "- type ", "array_diff_type", " = char\n", "+ type ", "array_diff_type", " = C\n", "  \tsize: 1\n", "\n");
+test!(array_diff_type, "- type ", "array_diff_type", " = char\n", "+ type ", "array_diff_type", " = C\n", "  \tresolves to: char\n", "  \tsize: 1\n", "\n");
 test!(array_diff_size, "- type ", "array_diff_size", " = [char; 1]\n", "+ type ", "array_diff_size", " = [char; 2]\n", "- \tsize: 1\n", "+ \tsize: 2\n", "\n");
 test!(function_equal, "");
 test!(function_diff_return_type, "  fn ", "function_diff_return_type", "\n", "[..]\n", "  \treturn type:\n", "- \t\t[1]\tchar\n", "+ \t\t[4]\tint\n", "\n");
+test!(function_diff_linkage, "  fn ", "function_diff_linkage", "\n", "[..]\n", "- \tlinkage: static\n", "+ \tlinkage: external\n", "  \treturn type:\n", "  \t\t[1]\tchar\n", "\n");
+test!(function_diff_variadic, "  fn ", "function_diff_variadic", "\n", "[..]\n", "  \treturn type:\n", "  \t\t[1]\tchar\n", "  \tparameters:\n", "  \t\t[1]\ta: char\n", "+ \t\t...\n", "\n");
 test!(function_diff_variables, "  fn ", "function_diff_variables", "\n", "[..]\n", "  \tvariables:\n", "- \t\t[1]\ta: char\n", "+ \t\t[1]\tb: char\n", "  \t\t[1]\tc: char\n", "- \t\t[1]\td: char\n", "+ \t\t[4]\td: int\n", "- \t\t[1]\te: char\n", "+ \t\t[1]\tf: char\n", "- \t\t[4]\textra: int\n", "  \t\t[1]\tg: char\n", "\n");
+test!(function_moved, "");
 test!(variable_equal, "");
 test!(variable_diff_size, "- var ", "variable_diff_size", ": [char; 1]\n", "+ var ", "variable_diff_size", ": [char; 2]\n", "[..]", "- \tsize: 1\n", "+ \tsize: 2\n", "\n");
-test!(variable_diff_decl, "  var ", "variable_diff_decl", ": int\n", "[..]", "  \tsize: 4\n", "+ \tdeclaration: yes\n", "\n");
+test!(variable_diff_bytes, "");
+test!(variable_diff_decl, "- var ", "variable_diff_decl", ": int\n", "[..]", "- \tsize: 4\n", "\n");
+test!(struct_diff_decl_support, "");
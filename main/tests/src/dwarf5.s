@@ -0,0 +1,420 @@
+# Debuginfo using the DWARF 5 indexed forms.
+#
+# Names use DW_FORM_strx1 and DW_FORM_strx, addresses use DW_FORM_addrx and
+# DW_OP_addrx, and range lists and location lists use DW_FORM_rnglistx and
+# DW_FORM_loclistx. All of the units share the same tables.
+#
+# The first unit has a DWARF 5 line program, and its declarations refer to
+# file index 0. dwarf5_split is non-contiguous, with a .cold part in another
+# section. The second unit has no line program, so its range comes from
+# DW_AT_ranges.
+
+	.text
+	.globl	dwarf5_func
+	.type	dwarf5_func, @function
+dwarf5_func:
+	movl	%edi, %eax
+	ret
+	.size	dwarf5_func, .-dwarf5_func
+
+	.globl	dwarf5_split
+	.type	dwarf5_split, @function
+dwarf5_split:
+	pushq	%rbx
+	movl	%edi, %ebx
+.Lsplit_saved:
+	call	dwarf5_func
+	testl	%eax, %eax
+	je	dwarf5_split.cold
+	movl	%ebx, %eax
+	popq	%rbx
+	ret
+.Lsplit_end:
+	.size	dwarf5_split, .-dwarf5_split
+
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+.Lmain_end:
+	.size	main, .-main
+
+	.section	.text.unlikely,"ax",@progbits
+	.type	dwarf5_split.cold, @function
+dwarf5_split.cold:
+	movl	$-1, %eax
+	popq	%rbx
+	ret
+.Lsplit_cold_end:
+	.size	dwarf5_split.cold, .-dwarf5_split.cold
+
+	.data
+	.globl	dwarf5_var
+	.type	dwarf5_var, @object
+	.size	dwarf5_var, 4
+dwarf5_var:
+	.zero	4
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit with a line program
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x72		# DW_AT_str_offsets_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x73		# DW_AT_addr_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x74		# DW_AT_rnglists_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x8c		# DW_AT_loclists_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x10		# DW_AT_stmt_list
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.byte	0
+	.byte	0
+	.uleb128 2		# compile unit with a range list
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x1a		# DW_FORM_strx
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x72		# DW_AT_str_offsets_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x73		# DW_AT_addr_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x74		# DW_AT_rnglists_base
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.uleb128 0x55		# DW_AT_ranges
+	.uleb128 0x23		# DW_FORM_rnglistx
+	.byte	0
+	.byte	0
+	.uleb128 3		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 4		# contiguous function
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x3a		# DW_AT_decl_file
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3b		# DW_AT_decl_line
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1b		# DW_FORM_addrx
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x6		# DW_FORM_data4
+	.byte	0
+	.byte	0
+	.uleb128 5		# non-contiguous function
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x3a		# DW_AT_decl_file
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3b		# DW_AT_decl_line
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x55		# DW_AT_ranges
+	.uleb128 0x23		# DW_FORM_rnglistx
+	.byte	0
+	.byte	0
+	.uleb128 6		# parameter with a location expression
+	.uleb128 0x5		# DW_TAG_formal_parameter
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.uleb128 7		# parameter with a location list
+	.uleb128 0x5		# DW_TAG_formal_parameter
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x22		# DW_FORM_loclistx
+	.byte	0
+	.byte	0
+	.uleb128 8		# variable
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x25		# DW_FORM_strx1
+	.uleb128 0x3a		# DW_AT_decl_file
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3b		# DW_AT_decl_line
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit1_end - .Lunit1_start
+.Lunit1_start:
+	.value	5		# version
+	.byte	1		# DW_UT_compile
+	.byte	8		# address size
+	.long	.Labbrev
+	.uleb128 1		# DW_TAG_compile_unit
+	.byte	0		# dwarf5.c
+	.byte	0x1d		# DW_LANG_C11
+	.long	.Lstr_offsets_base
+	.long	.Laddr_base
+	.long	.Lrnglists_base
+	.long	.Lloclists_base
+	.long	.Lline
+.Lint:
+	.uleb128 3		# DW_TAG_base_type
+	.byte	1		# int
+	.byte	4
+	.byte	5		# DW_ATE_signed
+	.uleb128 4		# DW_TAG_subprogram
+	.byte	2		# dwarf5_func
+	.byte	0		# file
+	.byte	3		# line
+	.long	.Lint - .Lunit1_start + 4
+	.uleb128 0		# dwarf5_func
+	.long	dwarf5_split - dwarf5_func
+	.uleb128 6		# DW_TAG_formal_parameter
+	.byte	4		# x
+	.long	.Lint - .Lunit1_start + 4
+	.uleb128 1		# expression length
+	.byte	0x55		# DW_OP_reg5
+	.byte	0
+	.uleb128 5		# DW_TAG_subprogram
+	.byte	3		# dwarf5_split
+	.byte	0		# file
+	.byte	8		# line
+	.long	.Lint - .Lunit1_start + 4
+	.uleb128 0		# range list
+	.uleb128 7		# DW_TAG_formal_parameter
+	.byte	4		# x
+	.long	.Lint - .Lunit1_start + 4
+	.uleb128 0		# location list
+	.byte	0
+	.uleb128 8		# DW_TAG_variable
+	.byte	5		# dwarf5_var
+	.byte	0		# file
+	.byte	1		# line
+	.long	.Lint - .Lunit1_start + 4
+	.uleb128 2		# expression length
+	.byte	0xa1		# DW_OP_addrx
+	.uleb128 3		# dwarf5_var
+	.byte	0
+.Lunit1_end:
+
+	.long	.Lunit2_end - .Lunit2_start
+.Lunit2_start:
+	.value	5		# version
+	.byte	1		# DW_UT_compile
+	.byte	8		# address size
+	.long	.Labbrev
+	.uleb128 2		# DW_TAG_compile_unit
+	.uleb128 6		# dwarf5_main.c
+	.byte	0x1d		# DW_LANG_C11
+	.long	.Lstr_offsets_base
+	.long	.Laddr_base
+	.long	.Lrnglists_base
+	.uleb128 1		# range list
+	.byte	0
+.Lunit2_end:
+
+	.section	.debug_str_offsets,"",@progbits
+	.long	.Lstr_offsets_end - .Lstr_offsets_start
+.Lstr_offsets_start:
+	.value	5		# version
+	.value	0		# padding
+.Lstr_offsets_base:
+	.long	.Lstr_dwarf5_c
+	.long	.Lstr_int
+	.long	.Lstr_dwarf5_func
+	.long	.Lstr_dwarf5_split
+	.long	.Lstr_x
+	.long	.Lstr_dwarf5_var
+	.long	.Lstr_dwarf5_main_c
+.Lstr_offsets_end:
+
+	.section	.debug_addr,"",@progbits
+	.long	.Laddr_end - .Laddr_start
+.Laddr_start:
+	.value	5		# version
+	.byte	8		# address size
+	.byte	0		# segment selector size
+.Laddr_base:
+	.quad	dwarf5_func
+	.quad	dwarf5_split
+	.quad	dwarf5_split.cold
+	.quad	dwarf5_var
+	.quad	main
+.Laddr_end:
+
+	.section	.debug_rnglists,"",@progbits
+	.long	.Lrnglists_end - .Lrnglists_start
+.Lrnglists_start:
+	.value	5		# version
+	.byte	8		# address size
+	.byte	0		# segment selector size
+	.long	2		# offset entry count
+.Lrnglists_base:
+	.long	.Lrnglist0 - .Lrnglists_base
+	.long	.Lrnglist1 - .Lrnglists_base
+.Lrnglist0:
+	.byte	1		# DW_RLE_base_addressx
+	.uleb128 1		# dwarf5_split
+	.byte	4		# DW_RLE_offset_pair
+	.uleb128 0
+	.uleb128 .Lsplit_end - dwarf5_split
+	.byte	3		# DW_RLE_startx_length
+	.uleb128 2		# dwarf5_split.cold
+	.uleb128 .Lsplit_cold_end - dwarf5_split.cold
+	.byte	0		# DW_RLE_end_of_list
+.Lrnglist1:
+	.byte	3		# DW_RLE_startx_length
+	.uleb128 4		# main
+	.uleb128 .Lmain_end - main
+	.byte	0		# DW_RLE_end_of_list
+.Lrnglists_end:
+
+	.section	.debug_loclists,"",@progbits
+	.long	.Lloclists_end - .Lloclists_start
+.Lloclists_start:
+	.value	5		# version
+	.byte	8		# address size
+	.byte	0		# segment selector size
+	.long	1		# offset entry count
+.Lloclists_base:
+	.long	.Lloclist0 - .Lloclists_base
+.Lloclist0:
+	.byte	1		# DW_LLE_base_addressx
+	.uleb128 1		# dwarf5_split
+	.byte	4		# DW_LLE_offset_pair
+	.uleb128 0
+	.uleb128 .Lsplit_saved - dwarf5_split
+	.uleb128 1		# expression length
+	.byte	0x55		# DW_OP_reg5
+	.byte	4		# DW_LLE_offset_pair
+	.uleb128 .Lsplit_saved - dwarf5_split
+	.uleb128 .Lsplit_end - dwarf5_split
+	.uleb128 1		# expression length
+	.byte	0x53		# DW_OP_reg3
+	.byte	3		# DW_LLE_startx_length
+	.uleb128 2		# dwarf5_split.cold
+	.uleb128 .Lsplit_cold_end - dwarf5_split.cold
+	.uleb128 1		# expression length
+	.byte	0x53		# DW_OP_reg3
+	.byte	0		# DW_LLE_end_of_list
+.Lloclists_end:
+
+	.section	.debug_line,"",@progbits
+.Lline:
+	.long	.Lline_end - .Lline_start
+.Lline_start:
+	.value	5		# version
+	.byte	8		# address size
+	.byte	0		# segment selector size
+	.long	.Lline_header_end - .Lline_header_start
+.Lline_header_start:
+	.byte	1		# minimum_instruction_length
+	.byte	1		# maximum_operations_per_instruction
+	.byte	1		# default_is_stmt
+	.byte	-5		# line_base
+	.byte	14		# line_range
+	.byte	13		# opcode_base
+	.byte	0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1
+	.byte	1		# directory entry format count
+	.uleb128 1		# DW_LNCT_path
+	.uleb128 0x1f		# DW_FORM_line_strp
+	.uleb128 1		# directories count
+	.long	.Lline_str_src
+	.byte	2		# file name entry format count
+	.uleb128 1		# DW_LNCT_path
+	.uleb128 0x1f		# DW_FORM_line_strp
+	.uleb128 2		# DW_LNCT_directory_index
+	.uleb128 0xf		# DW_FORM_udata
+	.uleb128 1		# file names count
+	.long	.Lline_str_dwarf5_c
+	.uleb128 0		# directory
+.Lline_header_end:
+	.byte	0		# DW_LNE_set_address
+	.uleb128 9
+	.byte	2
+	.quad	dwarf5_func
+	.byte	3		# DW_LNS_advance_line
+	.sleb128 2
+	.byte	1		# DW_LNS_copy
+	.byte	2		# DW_LNS_advance_pc
+	.uleb128 .Lsplit_end - dwarf5_func
+	.byte	0		# DW_LNE_end_sequence
+	.uleb128 1
+	.byte	1
+	.byte	0		# DW_LNE_set_address
+	.uleb128 9
+	.byte	2
+	.quad	dwarf5_split.cold
+	.byte	3		# DW_LNS_advance_line
+	.sleb128 7
+	.byte	1		# DW_LNS_copy
+	.byte	2		# DW_LNS_advance_pc
+	.uleb128 .Lsplit_cold_end - dwarf5_split.cold
+	.byte	0		# DW_LNE_end_sequence
+	.uleb128 1
+	.byte	1
+.Lline_end:
+
+	.section	.debug_line_str,"MS",@progbits,1
+.Lline_str_src:
+	.string	"/src"
+.Lline_str_dwarf5_c:
+	.string	"dwarf5.c"
+
+	.section	.debug_str,"MS",@progbits,1
+.Lstr_dwarf5_c:
+	.string	"dwarf5.c"
+.Lstr_int:
+	.string	"int"
+.Lstr_dwarf5_func:
+	.string	"dwarf5_func"
+.Lstr_dwarf5_split:
+	.string	"dwarf5_split"
+.Lstr_x:
+	.string	"x"
+.Lstr_dwarf5_var:
+	.string	"dwarf5_var"
+.Lstr_dwarf5_main_c:
+	.string	"dwarf5_main.c"
+
+	.section	.note.GNU-stack,"",@progbits
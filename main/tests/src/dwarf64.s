@@ -0,0 +1,217 @@
+# Debuginfo with both 64-bit and 32-bit DWARF units.
+#
+# The first unit uses the 64-bit format, so its section offsets (for
+# DW_FORM_strp, DW_FORM_ref_addr and DW_FORM_sec_offset) are 8 bytes, and
+# its line program also uses the 64-bit format. The second unit uses the
+# 32-bit format. Each unit refers to a type in the other unit.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.data
+	.globl	dwarf64_var
+	.type	dwarf64_var, @object
+	.size	dwarf64_var, 16
+dwarf64_var:
+	.zero	16
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit with a line program
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x10		# DW_AT_stmt_list
+	.uleb128 0x17		# DW_FORM_sec_offset
+	.byte	0
+	.byte	0
+	.uleb128 2		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 3		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 4		# struct type
+	.uleb128 0x13		# DW_TAG_structure_type
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 5		# member with a type in another unit
+	.uleb128 0xd		# DW_TAG_member
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x38		# DW_AT_data_member_location
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 6		# variable with a type in this unit
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x3a		# DW_AT_decl_file
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3b		# DW_AT_decl_line
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.uleb128 7		# typedef with a type in another unit
+	.uleb128 0x16		# DW_TAG_typedef
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0xe		# DW_FORM_strp
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	0xffffffff	# 64-bit DWARF
+	.quad	.Lunit1_end - .Lunit1_start
+.Lunit1_start:
+	.value	4		# version
+	.quad	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.quad	.Lstr_dwarf64_c
+	.byte	0xc		# DW_LANG_C99
+	.quad	.Lline
+.Lstruct:
+	.uleb128 4		# DW_TAG_structure_type
+	.quad	.Lstr_dwarf64_struct
+	.byte	16
+	.uleb128 5		# DW_TAG_member
+	.quad	.Lstr_a
+	.quad	.Lint
+	.byte	0
+	.uleb128 5		# DW_TAG_member
+	.quad	.Lstr_b
+	.quad	.Llong
+	.byte	8
+	.byte	0
+	.uleb128 6		# DW_TAG_variable
+	.quad	.Lstr_dwarf64_var
+	.byte	1		# file
+	.byte	3		# line
+	.long	.Lstruct - .Lunit1_start + 12
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	dwarf64_var
+	.byte	0
+.Lunit1_end:
+
+	.long	.Lunit2_end - .Lunit2_start
+.Lunit2_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 2		# DW_TAG_compile_unit
+	.long	.Lstr_dwarf32_c
+	.byte	0xc		# DW_LANG_C99
+.Lint:
+	.uleb128 3		# DW_TAG_base_type
+	.long	.Lstr_int
+	.byte	4
+	.byte	5		# DW_ATE_signed
+.Llong:
+	.uleb128 3		# DW_TAG_base_type
+	.long	.Lstr_long
+	.byte	8
+	.byte	5		# DW_ATE_signed
+	.uleb128 7		# DW_TAG_typedef
+	.long	.Lstr_dwarf32_typedef
+	.long	.Lstruct
+	.byte	0
+.Lunit2_end:
+
+	.section	.debug_line,"",@progbits
+.Lline:
+	.long	0xffffffff	# 64-bit DWARF
+	.quad	.Lline_end - .Lline_start
+.Lline_start:
+	.value	4		# version
+	.quad	.Lline_header_end - .Lline_header_start
+.Lline_header_start:
+	.byte	1		# minimum_instruction_length
+	.byte	1		# maximum_operations_per_instruction
+	.byte	1		# default_is_stmt
+	.byte	-5		# line_base
+	.byte	14		# line_range
+	.byte	13		# opcode_base
+	.byte	0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1
+	.byte	0		# no include directories
+	.string	"dwarf64.c"
+	.uleb128 0		# directory
+	.uleb128 0		# modification time
+	.uleb128 0		# length
+	.byte	0
+.Lline_header_end:
+	.byte	0		# DW_LNE_set_address
+	.uleb128 9
+	.byte	2
+	.quad	main
+	.byte	1		# DW_LNS_copy
+	.byte	2		# DW_LNS_advance_pc
+	.uleb128 3
+	.byte	0		# DW_LNE_end_sequence
+	.uleb128 1
+	.byte	1
+.Lline_end:
+
+	.section	.debug_str,"MS",@progbits,1
+.Lstr_dwarf64_c:
+	.string	"dwarf64.c"
+.Lstr_dwarf32_c:
+	.string	"dwarf32.c"
+.Lstr_dwarf64_struct:
+	.string	"dwarf64_struct"
+.Lstr_dwarf64_var:
+	.string	"dwarf64_var"
+.Lstr_dwarf32_typedef:
+	.string	"dwarf32_typedef"
+.Lstr_a:
+	.string	"a"
+.Lstr_b:
+	.string	"b"
+.Lstr_int:
+	.string	"int"
+.Lstr_long:
+	.string	"long"
+
+	.section	.note.GNU-stack,"",@progbits
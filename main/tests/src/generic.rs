@@ -0,0 +1,19 @@
+// Generic functions with multiple instantiations.
+
+pub fn generic_size<T>(x: T) -> usize {
+    core::mem::size_of_val(&x)
+}
+
+pub struct Wrapper<T>(pub T);
+
+impl<T: Copy> Wrapper<T> {
+    pub fn get(&self) -> T {
+        self.0
+    }
+}
+
+#[no_mangle]
+pub fn generic_use(a: u8, b: u32, c: u64) -> usize {
+    generic_size(a) + generic_size(b) + generic_size(c) + Wrapper(a).get() as usize
+        + Wrapper(b).get() as usize
+}
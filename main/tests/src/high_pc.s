@@ -0,0 +1,134 @@
+# Debuginfo with both encodings of DW_AT_high_pc.
+#
+# The first unit uses DW_FORM_addr, so DW_AT_high_pc is an address.
+# The second unit uses DW_FORM_data4, so DW_AT_high_pc is an offset
+# from DW_AT_low_pc.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.globl	high_pc_address
+	.type	high_pc_address, @function
+high_pc_address:
+	nop
+	nop
+	nop
+	ret
+.Lhigh_pc_address_end:
+	.size	high_pc_address, .-high_pc_address
+
+	.globl	high_pc_offset
+	.type	high_pc_offset, @function
+high_pc_offset:
+	nop
+	nop
+	nop
+	nop
+	nop
+	nop
+	nop
+	ret
+.Lhigh_pc_offset_end:
+	.size	high_pc_offset, .-high_pc_offset
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev_address:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.byte	0
+	.byte	0
+	.uleb128 2		# subprogram
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0xc		# DW_FORM_flag
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.byte	0
+	.byte	0
+	.byte	0
+
+.Labbrev_offset:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x6		# DW_FORM_data4
+	.byte	0
+	.byte	0
+	.uleb128 2		# subprogram
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x6		# DW_FORM_data4
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit1_end - .Lunit1_start
+.Lunit1_start:
+	.value	2		# version
+	.long	.Labbrev_address
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"high_pc_address.c"
+	.byte	0x1		# DW_LANG_C89
+	.quad	high_pc_address
+	.quad	.Lhigh_pc_address_end
+	.uleb128 2		# DW_TAG_subprogram
+	.string	"high_pc_address"
+	.byte	1
+	.quad	high_pc_address
+	.quad	.Lhigh_pc_address_end
+	.byte	0
+.Lunit1_end:
+
+	.long	.Lunit2_end - .Lunit2_start
+.Lunit2_start:
+	.value	4		# version
+	.long	.Labbrev_offset
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"high_pc_offset.c"
+	.byte	0xc		# DW_LANG_C99
+	.quad	high_pc_offset
+	.long	.Lhigh_pc_offset_end - high_pc_offset
+	.uleb128 2		# DW_TAG_subprogram
+	.string	"high_pc_offset"
+	.quad	high_pc_offset
+	.long	.Lhigh_pc_offset_end - high_pc_offset
+	.byte	0
+.Lunit2_end:
+
+	.section	.note.GNU-stack,"",@progbits
@@ -0,0 +1,26 @@
+/*
+ * Functions with identical code, for the ICF report.
+ *
+ * This is linked with identical code folding, so icf_a and icf_b share an
+ * address. icf_c and icf_d are kept unique.
+ */
+
+int icf_a(int x) {
+	return x * 3 + 1;
+}
+
+int icf_b(int x) {
+	return x * 3 + 1;
+}
+
+int icf_c(int x) {
+	return x * 5 + 1;
+}
+
+int icf_d(int x) {
+	return x * 5 + 1;
+}
+
+int main(void) {
+	return icf_a(1) + icf_b(2) + icf_c(3) + icf_d(4);
+}
@@ -0,0 +1,34 @@
+/* Functions that are inlined into multiple callers. */
+
+volatile int inline_sink;
+
+static inline __attribute__((always_inline)) void inline_small(int x)
+{
+	inline_sink = x;
+}
+
+static inline __attribute__((always_inline)) void inline_large(int x)
+{
+	inline_sink = x;
+	inline_sink = x * 2;
+	inline_sink = x * 3;
+	inline_sink = x * 4;
+	inline_sink = x * 5;
+}
+
+void inline_caller_a(int x)
+{
+	inline_small(x);
+	inline_large(x);
+}
+
+void inline_caller_b(int x)
+{
+	inline_small(x);
+	inline_small(x + 1);
+}
+
+int main(void)
+{
+	return 0;
+}
@@ -0,0 +1,14 @@
+/*
+ * A struct with padding and bitfields, for the layout diagram.
+ */
+
+struct layout {
+	char a;
+	int b;
+	unsigned int c : 3;
+	unsigned int d : 6;
+	short e;
+	char f;
+};
+
+struct layout layout;
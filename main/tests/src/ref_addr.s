@@ -0,0 +1,142 @@
+# Debuginfo with type references between units.
+#
+# The second unit refers to types in the first unit using DW_FORM_ref_addr,
+# which is used by tools such as dwz and by LTO.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.data
+	.globl	ref_addr_var
+	.type	ref_addr_var, @object
+	.size	ref_addr_var, 16
+ref_addr_var:
+	.zero	16
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 2		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 3		# struct type
+	.uleb128 0x13		# DW_TAG_structure_type
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 4		# member with a type in another unit
+	.uleb128 0xd		# DW_TAG_member
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x38		# DW_AT_data_member_location
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 5		# variable with a type in this unit
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.uleb128 6		# typedef with a type in another unit
+	.uleb128 0x16		# DW_TAG_typedef
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit1_end - .Lunit1_start
+.Lunit1_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"ref_addr1.c"
+	.byte	0xc		# DW_LANG_C99
+.Lint:
+	.uleb128 2		# DW_TAG_base_type
+	.string	"int"
+	.byte	4
+	.byte	5		# DW_ATE_signed
+.Llong:
+	.uleb128 2		# DW_TAG_base_type
+	.string	"long"
+	.byte	8
+	.byte	5		# DW_ATE_signed
+	.byte	0
+.Lunit1_end:
+
+	.long	.Lunit2_end - .Lunit2_start
+.Lunit2_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"ref_addr2.c"
+	.byte	0xc		# DW_LANG_C99
+.Lstruct:
+	.uleb128 3		# DW_TAG_structure_type
+	.string	"ref_addr_struct"
+	.byte	16
+	.uleb128 4		# DW_TAG_member
+	.string	"a"
+	.long	.Lint
+	.byte	0
+	.uleb128 4		# DW_TAG_member
+	.string	"b"
+	.long	.Llong
+	.byte	8
+	.byte	0
+	.uleb128 5		# DW_TAG_variable
+	.string	"ref_addr_var"
+	.long	.Lstruct - .Lunit2_start + 4
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	ref_addr_var
+	.uleb128 6		# DW_TAG_typedef
+	.string	"ref_addr_long"
+	.long	.Llong
+	.byte	0
+.Lunit2_end:
+
+	.section	.note.GNU-stack,"",@progbits
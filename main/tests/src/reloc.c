@@ -0,0 +1,12 @@
+/* Two functions in separate sections of a relocatable object, so that both
+ * are at offset 0 of their section. */
+
+int reloc_a(int x)
+{
+	return x + 1;
+}
+
+int reloc_b(int x)
+{
+	return x * 3 + 2;
+}
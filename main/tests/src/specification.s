@@ -0,0 +1,251 @@
+# Debuginfo with definitions that refer to declarations or abstract instances.
+#
+# Each definition only describes its address, and relies on
+# DW_AT_specification or DW_AT_abstract_origin for its name and type.
+# The references are within the same unit, to an earlier unit, and to a
+# later unit, using DW_FORM_ref_addr.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.globl	spec_backward
+	.type	spec_backward, @function
+spec_backward:
+	ret
+.Lspec_backward_end:
+	.size	spec_backward, .-spec_backward
+
+	.globl	spec_forward
+	.type	spec_forward, @function
+spec_forward:
+	ret
+.Lspec_forward_end:
+	.size	spec_forward, .-spec_forward
+
+	.globl	spec_local
+	.type	spec_local, @function
+spec_local:
+	ret
+.Lspec_local_end:
+	.size	spec_local, .-spec_local
+
+	.globl	spec_inline
+	.type	spec_inline, @function
+spec_inline:
+	ret
+.Lspec_inline_end:
+	.size	spec_inline, .-spec_inline
+
+	.data
+	.globl	spec_var
+	.type	spec_var, @object
+	.size	spec_var, 4
+spec_var:
+	.zero	4
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 2		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 3		# subprogram declaration
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x3c		# DW_AT_declaration
+	.uleb128 0x19		# DW_FORM_flag_present
+	.byte	0
+	.byte	0
+	.uleb128 4		# parameter
+	.uleb128 0x5		# DW_TAG_formal_parameter
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.byte	0
+	.byte	0
+	.uleb128 5		# subprogram definition
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x47		# DW_AT_specification
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x6		# DW_FORM_data4
+	.byte	0
+	.byte	0
+	.uleb128 6		# abstract instance
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x20		# DW_AT_inline
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 7		# concrete instance
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x31		# DW_AT_abstract_origin
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x6		# DW_FORM_data4
+	.byte	0
+	.byte	0
+	.uleb128 8		# concrete parameter
+	.uleb128 0x5		# DW_TAG_formal_parameter
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x31		# DW_AT_abstract_origin
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.byte	0
+	.byte	0
+	.uleb128 9		# variable declaration
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x3c		# DW_AT_declaration
+	.uleb128 0x19		# DW_FORM_flag_present
+	.byte	0
+	.byte	0
+	.uleb128 10		# variable definition
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x47		# DW_AT_specification
+	.uleb128 0x10		# DW_FORM_ref_addr
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit1_end - .Lunit1_start
+.Lunit1_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"specification1.c"
+	.byte	0xc		# DW_LANG_C99
+.Lint:
+	.uleb128 2		# DW_TAG_base_type
+	.string	"int"
+	.byte	4
+	.byte	5		# DW_ATE_signed
+.Lbackward_decl:
+	.uleb128 3		# DW_TAG_subprogram
+	.string	"spec_backward"
+	.long	.Lint
+	.uleb128 4		# DW_TAG_formal_parameter
+	.string	"a"
+	.long	.Lint
+	.byte	0
+	.uleb128 5		# DW_TAG_subprogram
+	.long	.Lforward_decl
+	.quad	spec_forward
+	.long	.Lspec_forward_end - spec_forward
+.Llocal_decl:
+	.uleb128 3		# DW_TAG_subprogram
+	.string	"spec_local"
+	.long	.Lint
+	.uleb128 4		# DW_TAG_formal_parameter
+	.string	"b"
+	.long	.Lint
+	.byte	0
+	.uleb128 5		# DW_TAG_subprogram
+	.long	.Llocal_decl
+	.quad	spec_local
+	.long	.Lspec_local_end - spec_local
+.Linline_abstract:
+	.uleb128 6		# DW_TAG_subprogram
+	.string	"spec_inline"
+	.long	.Lint
+	.byte	1		# DW_INL_inlined
+.Linline_x:
+	.uleb128 4		# DW_TAG_formal_parameter
+	.string	"x"
+	.long	.Lint
+	.byte	0
+.Lvar_decl:
+	.uleb128 9		# DW_TAG_variable
+	.string	"spec_var"
+	.long	.Lint
+	.byte	0
+.Lunit1_end:
+
+	.long	.Lunit2_end - .Lunit2_start
+.Lunit2_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"specification2.c"
+	.byte	0xc		# DW_LANG_C99
+.Lforward_decl:
+	.uleb128 3		# DW_TAG_subprogram
+	.string	"spec_forward"
+	.long	.Lint
+	.uleb128 4		# DW_TAG_formal_parameter
+	.string	"c"
+	.long	.Lint
+	.byte	0
+	.uleb128 5		# DW_TAG_subprogram
+	.long	.Lbackward_decl
+	.quad	spec_backward
+	.long	.Lspec_backward_end - spec_backward
+	.uleb128 7		# DW_TAG_subprogram
+	.long	.Linline_abstract
+	.quad	spec_inline
+	.long	.Lspec_inline_end - spec_inline
+	.uleb128 8		# DW_TAG_formal_parameter
+	.long	.Linline_x
+	.byte	0
+	.uleb128 10		# DW_TAG_variable
+	.long	.Lvar_decl
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	spec_var
+	.byte	0
+.Lunit2_end:
+
+	.section	.note.GNU-stack,"",@progbits
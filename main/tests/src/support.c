@@ -1 +1,15 @@
 int variable_diff_decl;
+
+struct struct_diff_decl_support {
+    char c;
+} *use_struct_diff_decl_support_defn;
+
+// A symbol within a function that has debuginfo.
+void symbol_in_function(void) {
+    __asm__(
+        ".globl symbol_in_function_label\n"
+        ".type symbol_in_function_label, @function\n"
+        "symbol_in_function_label:\n"
+        "\tnop\n"
+        ".size symbol_in_function_label, 1\n");
+}
@@ -0,0 +1,77 @@
+# Debuginfo for functions in both code and data sections.
+#
+# `data_thunk` is placed in a writable data section, such as is done for
+# trampolines that are generated at runtime, so it has no instructions.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.globl	code_thunk
+	.type	code_thunk, @function
+code_thunk:
+	nop
+	nop
+	jmp	main
+.Lcode_thunk_end:
+	.size	code_thunk, .-code_thunk
+
+	.data
+	.globl	data_thunk
+	.type	data_thunk, @object
+data_thunk:
+	.zero	16
+.Ldata_thunk_end:
+	.size	data_thunk, .-data_thunk
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 2		# subprogram
+	.uleb128 0x2e		# DW_TAG_subprogram
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x11		# DW_AT_low_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.uleb128 0x12		# DW_AT_high_pc
+	.uleb128 0x1		# DW_FORM_addr
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit_end - .Lunit_start
+.Lunit_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"thunk.c"
+	.byte	0xc		# DW_LANG_C99
+	.uleb128 2		# DW_TAG_subprogram
+	.string	"code_thunk"
+	.quad	code_thunk
+	.quad	.Lcode_thunk_end
+	.uleb128 2		# DW_TAG_subprogram
+	.string	"data_thunk"
+	.quad	data_thunk
+	.quad	.Ldata_thunk_end
+	.byte	0
+.Lunit_end:
+
+	.section	.note.GNU-stack,"",@progbits
@@ -0,0 +1,93 @@
+# Debuginfo with a type reference that doesn't refer to a type.
+#
+# The typedef refers to the variable, which is a valid entry, but not a type.
+
+	.text
+	.globl	main
+	.type	main, @function
+main:
+	xorl	%eax, %eax
+	ret
+	.size	main, .-main
+
+	.data
+	.globl	unresolved_var
+	.type	unresolved_var, @object
+	.size	unresolved_var, 4
+unresolved_var:
+	.zero	4
+
+	.section	.debug_abbrev,"",@progbits
+.Labbrev:
+	.uleb128 1		# compile unit
+	.uleb128 0x11		# DW_TAG_compile_unit
+	.byte	1		# DW_CHILDREN_yes
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x13		# DW_AT_language
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 2		# base type
+	.uleb128 0x24		# DW_TAG_base_type
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0xb		# DW_AT_byte_size
+	.uleb128 0xb		# DW_FORM_data1
+	.uleb128 0x3e		# DW_AT_encoding
+	.uleb128 0xb		# DW_FORM_data1
+	.byte	0
+	.byte	0
+	.uleb128 3		# variable
+	.uleb128 0x34		# DW_TAG_variable
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.uleb128 0x3f		# DW_AT_external
+	.uleb128 0x19		# DW_FORM_flag_present
+	.uleb128 0x2		# DW_AT_location
+	.uleb128 0x18		# DW_FORM_exprloc
+	.byte	0
+	.byte	0
+	.uleb128 4		# typedef
+	.uleb128 0x16		# DW_TAG_typedef
+	.byte	0		# DW_CHILDREN_no
+	.uleb128 0x3		# DW_AT_name
+	.uleb128 0x8		# DW_FORM_string
+	.uleb128 0x49		# DW_AT_type
+	.uleb128 0x13		# DW_FORM_ref4
+	.byte	0
+	.byte	0
+	.byte	0
+
+	.section	.debug_info,"",@progbits
+	.long	.Lunit_end - .Lunit_start
+.Lunit_start:
+	.value	4		# version
+	.long	.Labbrev
+	.byte	8		# address size
+	.uleb128 1		# DW_TAG_compile_unit
+	.string	"unresolved.c"
+	.byte	0xc		# DW_LANG_C99
+.Lint:
+	.uleb128 2		# DW_TAG_base_type
+	.string	"int"
+	.byte	4
+	.byte	5		# DW_ATE_signed
+.Lvar:
+	.uleb128 3		# DW_TAG_variable
+	.string	"unresolved_var"
+	.long	.Lint - .Lunit_start + 4
+	.uleb128 9		# expression length
+	.byte	3		# DW_OP_addr
+	.quad	unresolved_var
+	.uleb128 4		# DW_TAG_typedef
+	.string	"unresolved_typedef"
+	.long	.Lvar - .Lunit_start + 4
+	.byte	0
+.Lunit_end:
+
+	.section	.note.GNU-stack,"",@progbits
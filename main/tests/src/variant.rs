@@ -0,0 +1,30 @@
+// Rust enums, which are described using variant parts.
+//
+// `Option<&T>` stores its discriminant in a niche of the pointer.
+
+#[repr(i8)]
+pub enum Signed {
+    #[cfg(test1)]
+    A(u8) = -1,
+    #[cfg(test2)]
+    A(u8) = -2,
+    B = 1,
+}
+
+pub enum Tagged {
+    A(u8),
+    #[cfg(test1)]
+    B(u32, u16),
+    #[cfg(test2)]
+    B(u64, u16),
+    C,
+}
+
+#[no_mangle]
+pub static mut VARIANT_SIGNED: Signed = Signed::B;
+
+#[no_mangle]
+pub static mut VARIANT_TAGGED: Tagged = Tagged::C;
+
+#[no_mangle]
+pub static mut VARIANT_NICHE: Option<&u32> = None;
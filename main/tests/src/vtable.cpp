@@ -0,0 +1,36 @@
+// Base classes and virtual function tables.
+
+struct vtable_base {
+    virtual ~vtable_base() {}
+    int base;
+};
+
+struct vtable_virtual_base {
+    int virtual_base;
+};
+
+class vtable_class : public vtable_base, virtual vtable_virtual_base {
+public:
+#ifdef TEST1
+    virtual int first();
+    virtual int second();
+#else
+    virtual int second();
+    virtual int inserted();
+    virtual int first();
+#endif
+    int member;
+};
+
+#ifdef TEST1
+int vtable_class::first() { return 1; }
+int vtable_class::second() { return 2; }
+#else
+int vtable_class::first() { return 1; }
+int vtable_class::second() { return 2; }
+int vtable_class::inserted() { return 3; }
+#endif
+
+vtable_class vtable_var;
+
+int main() {}
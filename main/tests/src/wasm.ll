@@ -0,0 +1,44 @@
+; A WebAssembly object with debuginfo, written by hand so that it only needs llc.
+
+target datalayout = "e-m:e-p:32:32-i64:64-n32:64-S128"
+target triple = "wasm32-unknown-unknown"
+
+@wasm_var = global i32 1, align 4, !dbg !0
+
+define i32 @wasm_add(i32 %a, i32 %b) !dbg !10 {
+  call void @llvm.dbg.value(metadata i32 %a, metadata !14, metadata !DIExpression()), !dbg !16
+  call void @llvm.dbg.value(metadata i32 %b, metadata !15, metadata !DIExpression()), !dbg !16
+  %sum = add nsw i32 %a, %b, !dbg !16
+  ret i32 %sum, !dbg !16
+}
+
+define i32 @wasm_twice(i32 %a) !dbg !17 {
+  %sum = call i32 @wasm_add(i32 %a, i32 %a), !dbg !19
+  ret i32 %sum, !dbg !19
+}
+
+declare void @llvm.dbg.value(metadata, metadata, metadata)
+
+!llvm.dbg.cu = !{!2}
+!llvm.module.flags = !{!7, !8}
+
+!0 = !DIGlobalVariableExpression(var: !1, expr: !DIExpression())
+!1 = distinct !DIGlobalVariable(name: "wasm_var", scope: !2, file: !3, line: 1, type: !6, isLocal: false, isDefinition: true)
+!2 = distinct !DICompileUnit(language: DW_LANG_C99, file: !3, producer: "llc", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug, globals: !5)
+!3 = !DIFile(filename: "wasm.c", directory: "")
+!4 = !{}
+!5 = !{!0}
+!6 = !DIBasicType(name: "int", size: 32, encoding: DW_ATE_signed)
+!7 = !{i32 7, !"Dwarf Version", i32 4}
+!8 = !{i32 2, !"Debug Info Version", i32 3}
+!10 = distinct !DISubprogram(name: "wasm_add", scope: !3, file: !3, line: 3, type: !11, scopeLine: 3, spFlags: DISPFlagDefinition, unit: !2, retainedNodes: !13)
+!11 = !DISubroutineType(types: !12)
+!12 = !{!6, !6, !6}
+!13 = !{!14, !15}
+!14 = !DILocalVariable(name: "a", arg: 1, scope: !10, file: !3, line: 3, type: !6)
+!15 = !DILocalVariable(name: "b", arg: 2, scope: !10, file: !3, line: 3, type: !6)
+!16 = !DILocation(line: 3, scope: !10)
+!17 = distinct !DISubprogram(name: "wasm_twice", scope: !3, file: !3, line: 5, type: !18, scopeLine: 5, spFlags: DISPFlagDefinition, unit: !2, retainedNodes: !4)
+!18 = !DISubroutineType(types: !20)
+!19 = !DILocation(line: 5, scope: !17)
+!20 = !{!6, !6}
@@ -1,3 +1,5 @@
+use std::cmp;
+
 use crate::location::Register;
 use crate::Address;
 
@@ -64,3 +66,85 @@ pub enum CfiDirective {
     /// An unsupported instruction.
     Other,
 }
+
+/// Calculate the size of the stack frame from a list of CFI directives.
+///
+/// This is the maximum offset of the CFA from the register that it is initially
+/// defined relative to (normally the stack pointer), so it includes the return
+/// address if that is stored on the stack.
+///
+/// Returns `None` if the CFA is not defined, or if it is later defined relative to
+/// a different register (such as a frame pointer), since the size can't be
+/// determined in that case.
+pub(crate) fn frame_size(cfi: &[Cfi]) -> Option<u64> {
+    let mut register = None;
+    let mut offset = 0;
+    let mut max_offset = 0;
+    let mut stack = Vec::new();
+    for &(_, directive) in cfi {
+        match directive {
+            CfiDirective::DefCfa(new_register, new_offset) => {
+                if *register.get_or_insert(new_register) != new_register {
+                    return None;
+                }
+                offset = new_offset;
+            }
+            CfiDirective::DefCfaRegister(new_register) => {
+                let old_register = *register.get_or_insert(new_register);
+                if old_register != new_register {
+                    return None;
+                }
+            }
+            CfiDirective::DefCfaOffset(new_offset) => {
+                offset = new_offset;
+            }
+            CfiDirective::RememberState => {
+                stack.push(offset);
+            }
+            CfiDirective::RestoreState => {
+                offset = stack.pop().unwrap_or(offset);
+            }
+            _ => {}
+        }
+        max_offset = cmp::max(max_offset, offset);
+    }
+    register.map(|_| max_offset as u64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn frame_size_sp() {
+        let sp = Register(7);
+        let cfi = [
+            (Address::none(), CfiDirective::StartProc),
+            (Address::none(), CfiDirective::DefCfa(sp, 8)),
+            (Address::new(0x1001), CfiDirective::DefCfaOffset(16)),
+            (Address::new(0x1005), CfiDirective::DefCfaOffset(48)),
+            (Address::new(0x1010), CfiDirective::RememberState),
+            (Address::new(0x1010), CfiDirective::DefCfaOffset(8)),
+            (Address::new(0x1011), CfiDirective::RestoreState),
+            (Address::new(0x1020), CfiDirective::EndProc),
+        ];
+        assert_eq!(frame_size(&cfi), Some(48));
+    }
+
+    #[test]
+    fn frame_size_fp() {
+        let sp = Register(7);
+        let fp = Register(6);
+        let cfi = [
+            (Address::none(), CfiDirective::DefCfa(sp, 8)),
+            (Address::new(0x1001), CfiDirective::DefCfaOffset(16)),
+            (Address::new(0x1004), CfiDirective::DefCfaRegister(fp)),
+        ];
+        assert_eq!(frame_size(&cfi), None);
+    }
+
+    #[test]
+    fn frame_size_none() {
+        assert_eq!(frame_size(&[]), None);
+    }
+}
@@ -0,0 +1,148 @@
+// `object` reads the members of regular archives, but not thin archives, which
+// store the paths of their members instead of the member data.
+use std::path::{Path, PathBuf};
+use std::str;
+
+use crate::Result;
+
+pub(crate) const MAGIC: &[u8] = b"!<arch>\n";
+pub(crate) const THIN_MAGIC: &[u8] = b"!<thin>\n";
+const HEADER_SIZE: usize = 60;
+
+/// A member of a thin archive.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Member {
+    pub(crate) name: String,
+    /// The path of the file containing the data.
+    pub(crate) path: PathBuf,
+}
+
+/// Parse the member headers of a thin archive.
+///
+/// The members are given as paths relative to the directory of `path`.
+///
+/// Members with invalid names are skipped with a warning.
+pub(crate) fn thin_members(data: &[u8], path: &Path) -> Result<Vec<Member>> {
+    if !data.starts_with(THIN_MAGIC) {
+        return Err("not a thin archive".into());
+    }
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    // The GNU extended name table.
+    let mut names: &[u8] = &[];
+    let mut members = Vec::new();
+    let mut offset = THIN_MAGIC.len();
+    while offset < data.len() {
+        let header = match data.get(offset..offset + HEADER_SIZE) {
+            Some(header) if header.ends_with(b"`\n") => header,
+            _ => return Err(format!("invalid archive member header at 0x{:x}", offset).into()),
+        };
+        let size = match parse_decimal(&header[48..58]) {
+            Some(size) => size,
+            None => return Err(format!("invalid archive member size at 0x{:x}", offset).into()),
+        };
+        let header_offset = offset;
+        offset += HEADER_SIZE;
+
+        // Only the symbol table and extended name table are stored in the archive.
+        let field = trim(&header[..16]);
+        let name = if field == b"/" || field == b"/SYM64/" {
+            offset += size + (size & 1);
+            continue;
+        } else if field == b"//" {
+            names = data.get(offset..offset + size).unwrap_or(&[]);
+            offset += size + (size & 1);
+            continue;
+        } else if let Some(index) = field.strip_prefix(b"/") {
+            // GNU long name: an offset into the extended name table.
+            parse_decimal(index)
+                .and_then(|index| names.get(index..))
+                .map(|name| {
+                    let end = name.iter().position(|&c| c == b'\n').unwrap_or(name.len());
+                    let name = &name[..end];
+                    name.strip_suffix(b"/").unwrap_or(name)
+                })
+        } else {
+            Some(field.strip_suffix(b"/").unwrap_or(field))
+        };
+
+        let name = match name {
+            Some(name) => String::from_utf8_lossy(name).into_owned(),
+            None => {
+                warn!("invalid archive member name at 0x{:x}", header_offset);
+                continue;
+            }
+        };
+        members.push(Member {
+            path: dir.join(&name),
+            name,
+        });
+    }
+    Ok(members)
+}
+
+fn trim(field: &[u8]) -> &[u8] {
+    let end = field
+        .iter()
+        .rposition(|&c| c != b' ')
+        .map(|x| x + 1)
+        .unwrap_or(0);
+    &field[..end]
+}
+
+fn parse_decimal(field: &[u8]) -> Option<usize> {
+    str::from_utf8(trim(field)).ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn header(name: &str, size: usize) -> Vec<u8> {
+        let header = format!(
+            "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+            name, 0, 0, 0, 644, size
+        );
+        assert_eq!(header.len(), HEADER_SIZE);
+        header.into_bytes()
+    }
+
+    #[test]
+    fn thin() {
+        let names = b"dir/a_long_member_name.o/\n/abs/b.o/\n";
+        let mut data = THIN_MAGIC.to_vec();
+        data.extend(header("/", 4));
+        data.extend(&[0; 4]);
+        data.extend(header("//", names.len()));
+        data.extend(&names[..]);
+        data.extend(header("/0", 100));
+        data.extend(header("/26", 200));
+        data.extend(header("short.o/", 300));
+        data.extend(header("/99", 0));
+        let members = thin_members(&data, Path::new("lib/lib.a")).unwrap();
+        assert_eq!(
+            members,
+            [
+                Member {
+                    name: "dir/a_long_member_name.o".into(),
+                    path: "lib/dir/a_long_member_name.o".into(),
+                },
+                Member {
+                    name: "/abs/b.o".into(),
+                    path: "/abs/b.o".into(),
+                },
+                Member {
+                    name: "short.o".into(),
+                    path: "lib/short.o".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_header() {
+        let mut data = THIN_MAGIC.to_vec();
+        data.extend(b"short");
+        assert!(thin_members(&data, Path::new("lib.a")).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use fnv::FnvHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::file::{Arena, ParseOptions};
+use crate::range::RangeList;
+use crate::unit::Unit;
+use crate::Result;
+
+// The layout of the cached data.
+//
+// This must be incremented whenever the serialized form of the cached
+// types changes, since that doesn't always change the package version.
+const FORMAT: u32 = 1;
+
+/// Identifies the version of an input file that a cache was created for.
+///
+/// The cache is only used if all of these match.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Key {
+    // The cached model may change between versions.
+    version: String,
+    format: u32,
+    path: PathBuf,
+    len: u64,
+    modified: Option<(u64, u32)>,
+    // The separate file that the debuginfo was read from, if any.
+    debug_file: Option<(PathBuf, u64, Option<(u64, u32)>)>,
+    build_id: Option<Vec<u8>>,
+    debuginfo: bool,
+    symtab: bool,
+    symtab_ranges: bool,
+    sections: Vec<String>,
+}
+
+impl Key {
+    pub(crate) fn new(
+        path: &str,
+        debug_path: Option<&Path>,
+        build_id: Option<&[u8]>,
+        options: &ParseOptions,
+    ) -> Result<Key> {
+        let (path, len, modified) = file_version(Path::new(path))?;
+        let debug_file = match debug_path {
+            Some(debug_path) => Some(file_version(debug_path)?),
+            None => None,
+        };
+        Ok(Key {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            format: FORMAT,
+            path,
+            len,
+            modified,
+            debug_file,
+            build_id: build_id.map(<[u8]>::to_vec),
+            debuginfo: options.debuginfo,
+            symtab: options.symtab,
+            symtab_ranges: options.symtab_ranges,
+            sections: options.sections.clone(),
+        })
+    }
+
+    // The name of the cache file for this key.
+    //
+    // This only depends on the input path, so that a modified input replaces
+    // its previous cache file.
+    fn cache_path(&self, cache_dir: &Path) -> PathBuf {
+        let mut hasher = FnvHasher::default();
+        hasher.write(self.path.to_string_lossy().as_bytes());
+        cache_dir.join(format!("{:016x}.ddbug", hasher.finish()))
+    }
+}
+
+// The canonical path, length and modification time of a file.
+fn file_version(path: &Path) -> Result<(PathBuf, u64, Option<(u64, u32)>)> {
+    let path = fs::canonicalize(path)?;
+    let metadata = fs::metadata(&path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|modified| (modified.as_secs(), modified.subsec_nanos()));
+    Ok((path, metadata.len(), modified))
+}
+
+#[derive(Deserialize)]
+struct Cache<'input> {
+    key: Key,
+    #[serde(borrow)]
+    units: Vec<Unit<'input>>,
+    symbol_ranges: RangeList,
+    warnings: Vec<String>,
+}
+
+// Must have the same layout as `Cache`.
+#[derive(Serialize)]
+struct CacheRef<'a, 'input> {
+    key: &'a Key,
+    units: &'a [Unit<'input>],
+    symbol_ranges: &'a RangeList,
+    warnings: &'a [String],
+}
+
+/// Load the units, symbol ranges and warnings that were cached for `key`.
+///
+/// Returns `None` if there is no valid cache.
+pub(crate) fn load<'input>(
+    cache_dir: &Path,
+    key: &Key,
+    arena: &'input Arena,
+) -> Option<(Vec<Unit<'input>>, RangeList, Vec<String>)> {
+    let path = key.cache_path(cache_dir);
+    let bytes = fs::read(&path).ok()?;
+    // The units borrow their strings from the cache data.
+    let bytes = arena.add_buffer(bytes);
+    match bincode::deserialize::<Cache>(bytes) {
+        Ok(cache) if cache.key == *key => Some((cache.units, cache.symbol_ranges, cache.warnings)),
+        Ok(_) => {
+            debug!("{}: cache is out of date", path.display());
+            None
+        }
+        Err(e) => {
+            debug!("{}: invalid cache: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write the units, symbol ranges and warnings for `key` to the cache.
+pub(crate) fn store(
+    cache_dir: &Path,
+    key: &Key,
+    units: &[Unit],
+    symbol_ranges: &RangeList,
+    warnings: &[String],
+) -> Result<()> {
+    let cache = CacheRef {
+        key,
+        units,
+        symbol_ranges,
+        warnings,
+    };
+    let bytes = bincode::serialize(&cache).map_err(|e| format!("serialize failed: {}", e))?;
+    fs::create_dir_all(cache_dir)?;
+    // Write to a temporary file first so that other processes never see
+    // a partially written cache.
+    let path = key.cache_path(cache_dir);
+    let tmp_path = path.with_extension(format!("tmp{}", std::process::id()));
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
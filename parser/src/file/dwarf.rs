@@ -6,32 +6,35 @@ use std::u32;
 
 use gimli;
 use gimli::Reader as GimliReader;
-use object::{self, ObjectSection, ObjectSymbol};
+use object::{self, ObjectSection};
 
 use crate::cfi::{Cfi, CfiDirective};
-use crate::file::{Architecture, Arena, DebugInfo, FileHash};
+use crate::file::{Architecture, Arena, DebugInfo, FileHash, SectionAddresses};
 use crate::function::{
     Function, FunctionDetails, FunctionOffset, InlinedFunction, Parameter, ParameterOffset,
+    Virtuality,
 };
 use crate::location::{Location, Piece, Register};
 use crate::namespace::{Namespace, NamespaceKind};
 use crate::range::Range;
+use crate::raw::{RawAttribute, RawEntry};
 use crate::source::Source;
 use crate::types::{
-    ArrayType, BaseType, BaseTypeEncoding, Endianity, EnumerationType, Enumerator, FunctionType,
-    Inherit, Member, MemberOffset, ParameterType, PointerToMemberType, StructType, SubrangeType,
-    Type, TypeDef, TypeKind, TypeModifier, TypeModifierKind, TypeOffset, UnionType,
+    Accessibility, ArrayType, BaseType, BaseTypeEncoding, Endianity, EnumerationType, Enumerator,
+    FunctionType, Inherit, Member, MemberOffset, ParameterType, PointerToMemberType, StructType,
+    SubrangeType, Type, TypeDef, TypeKind, TypeModifier, TypeModifierKind, TypeOffset, UnionType,
     UnspecifiedType, Variant, VariantPart,
 };
 use crate::unit::Unit;
 use crate::variable::{LocalVariable, Variable, VariableOffset};
-use crate::{Address, Id, Result, Size};
+use crate::{Address, Error, Id, Result, Size};
 
 pub(crate) type RelocationMap = HashMap<usize, object::Relocation>;
 
 fn add_relocations<'input, 'file, Object>(
     relocations: &mut RelocationMap,
     file: &'file Object,
+    addresses: &SectionAddresses,
     section: &Object::Section,
 ) where
     Object: object::Object<'input, 'file>,
@@ -45,7 +48,7 @@ fn add_relocations<'input, 'file, Object>(
         let target = match relocation.target() {
             object::RelocationTarget::Symbol(index) => {
                 if let Ok(symbol) = file.symbol_by_index(index) {
-                    symbol.address()
+                    addresses.symbol(&symbol)
                 } else {
                     println!(
                         "Relocation with invalid symbol index {} for section {} at offset 0x{:08x}",
@@ -58,7 +61,7 @@ fn add_relocations<'input, 'file, Object>(
             }
             object::RelocationTarget::Section(index) => {
                 if let Ok(section) = file.section_by_index(index) {
-                    section.address()
+                    addresses.section(&section)
                 } else {
                     println!(
                         "Relocation with invalid section index {} for section {} at offset 0x{:08x}",
@@ -88,7 +91,7 @@ fn add_relocations<'input, 'file, Object>(
             object::RelocationKind::Relative => {
                 let addend = target
                     .wrapping_add(relocation.addend() as u64)
-                    .wrapping_sub(section.address())
+                    .wrapping_sub(addresses.section(section))
                     .wrapping_sub(offset as u64);
                 relocation.set_addend(addend as i64);
                 if relocations.insert(offset, relocation).is_some() {
@@ -173,6 +176,19 @@ impl<'a, R: gimli::Reader<Offset = usize>> gimli::Reader for Relocate<'a, R> {
         <usize as gimli::ReaderOffset>::from_u64(self.relocate(offset, value as u64))
     }
 
+    // Used for pointers in `.eh_frame`.
+    fn read_u32(&mut self) -> gimli::Result<u32> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_u32()?;
+        Ok(self.relocate(offset, u64::from(value)) as u32)
+    }
+
+    fn read_i32(&mut self) -> gimli::Result<i32> {
+        let offset = self.reader.offset_from(&self.section);
+        let value = self.reader.read_i32()?;
+        Ok(self.relocate(offset, value as u64) as i32)
+    }
+
     #[inline]
     fn split(&mut self, len: Self::Offset) -> gimli::Result<Self> {
         let mut other = self.clone();
@@ -277,6 +293,53 @@ where
             .ok()
     }
 
+    fn address(
+        &self,
+        dwarf_unit: &DwarfUnit<'input, Endian>,
+        value: gimli::AttributeValue<Reader<'input, Endian>>,
+    ) -> Option<u64> {
+        // Handles both DW_FORM_addr and the DWARF 5 DW_FORM_addrx forms.
+        self.read.attr_address(dwarf_unit, value).ok().flatten()
+    }
+
+    fn high_pc(
+        &self,
+        dwarf_unit: &DwarfUnit<'input, Endian>,
+        value: gimli::AttributeValue<Reader<'input, Endian>>,
+    ) -> Option<HighPc> {
+        // The form class determines the interpretation: an address is the
+        // end of the range, and a constant is an offset from DW_AT_low_pc.
+        // gimli converts all constant forms for this attribute to `Udata`.
+        match value {
+            gimli::AttributeValue::Udata(val) => Some(HighPc::Offset(val)),
+            val => self.address(dwarf_unit, val).map(HighPc::Address),
+        }
+    }
+
+    fn ranges_offset(
+        &self,
+        dwarf_unit: &DwarfUnit<'input, Endian>,
+        value: gimli::AttributeValue<Reader<'input, Endian>>,
+    ) -> Option<gimli::RangeListsOffset> {
+        // Handles offsets into .debug_ranges/.debug_rnglists and DW_FORM_rnglistx.
+        self.read
+            .attr_ranges_offset(dwarf_unit, value)
+            .ok()
+            .flatten()
+    }
+
+    fn locations_offset(
+        &self,
+        dwarf_unit: &DwarfUnit<'input, Endian>,
+        value: gimli::AttributeValue<Reader<'input, Endian>>,
+    ) -> Option<gimli::LocationListsOffset> {
+        // Handles offsets into .debug_loc/.debug_loclists and DW_FORM_loclistx.
+        self.read
+            .attr_locations_offset(dwarf_unit, value)
+            .ok()
+            .flatten()
+    }
+
     fn tree(
         &self,
         offset: gimli::DebugInfoOffset,
@@ -364,10 +427,177 @@ where
         };
         register_name(gimli::Register(register.0))
     }
+
+    pub(crate) fn get_raw_entry(&self, offset: usize) -> Option<RawEntry> {
+        self.tree(gimli::DebugInfoOffset(offset))
+            .and_then(|(unit, mut tree)| {
+                let node = tree.root().ok()?;
+                self.raw_entry(unit, node).ok()
+            })
+    }
+
+    pub(crate) fn get_line_source(&self, address: u64) -> Option<Source<'input>> {
+        self.units
+            .iter()
+            .find_map(|unit| self.line_source(unit, address).ok().flatten())
+    }
+
+    fn line_source(
+        &self,
+        unit: &DwarfUnit<'input, Endian>,
+        address: u64,
+    ) -> Result<Option<Source<'input>>> {
+        let program = match unit.line_program.clone() {
+            Some(program) => program,
+            None => return Ok(None),
+        };
+        let mut rows = program.rows();
+        let mut prev: Option<gimli::LineRow> = None;
+        while let Some((_, row)) = rows.next_row()? {
+            if let Some(prev) = prev {
+                if prev.address() <= address && address < row.address() {
+                    let header = rows.header();
+                    let mut source = Source::default();
+                    if let Some(file) = prev.file(header) {
+                        source.file = self.string(unit, file.path_name());
+                        if let Some(directory) = file.directory(header) {
+                            source.directory = self.string(unit, directory);
+                        }
+                    }
+                    source.line = prev.line().map(|line| line.get() as u32).unwrap_or(0);
+                    source.column = match prev.column() {
+                        gimli::ColumnType::LeftEdge => 0,
+                        gimli::ColumnType::Column(column) => column.get() as u32,
+                    };
+                    return Ok(Some(source));
+                }
+            }
+            prev = if row.end_sequence() { None } else { Some(*row) };
+        }
+        Ok(None)
+    }
+
+    fn raw_entry(
+        &self,
+        unit: &DwarfUnit<'input, Endian>,
+        node: gimli::EntriesTreeNode<Reader<'input, Endian>>,
+    ) -> Result<RawEntry> {
+        let entry = node.entry();
+        let offset = match entry.offset().to_unit_section_offset(unit) {
+            gimli::UnitSectionOffset::DebugInfoOffset(offset) => offset.0,
+            gimli::UnitSectionOffset::DebugTypesOffset(offset) => offset.0,
+        };
+        // The forms are only stored in the abbreviation.
+        let specs = unit
+            .abbreviations
+            .get(entry.code())
+            .map(|abbrev| abbrev.attributes())
+            .unwrap_or(&[]);
+        let mut attributes = Vec::new();
+        let mut attrs = entry.attrs();
+        while let Some(attr) = attrs.next()? {
+            let form = match specs.get(attributes.len()) {
+                Some(spec) => spec.form().to_string(),
+                None => String::new(),
+            };
+            attributes.push(RawAttribute {
+                name: attr.name().to_string(),
+                form,
+                value: self.raw_value(unit, attr.value()),
+            });
+        }
+
+        let tag = entry.tag().to_string();
+        let mut children = Vec::new();
+        let mut iter = node.children();
+        while let Some(child) = iter.next()? {
+            children.push(self.raw_entry(unit, child)?);
+        }
+        Ok(RawEntry {
+            offset,
+            tag,
+            attributes,
+            children,
+        })
+    }
+
+    fn raw_value(
+        &self,
+        unit: &DwarfUnit<'input, Endian>,
+        value: gimli::AttributeValue<Reader<'input, Endian>>,
+    ) -> String {
+        use gimli::AttributeValue as Value;
+        let bytes = |data: &[u8]| {
+            let bytes: Vec<_> = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+            format!("[{}]", bytes.join(" "))
+        };
+        match value {
+            Value::Addr(address) => format!("0x{:x}", address),
+            Value::DebugAddrIndex(_) => match self.address(unit, value) {
+                Some(address) => format!("0x{:x}", address),
+                None => "<invalid address>".into(),
+            },
+            Value::Block(data) => bytes(data.slice()),
+            Value::Exprloc(expression) => bytes(expression.0.slice()),
+            Value::Data1(val) => format!("0x{:02x}", val),
+            Value::Data2(val) => format!("0x{:04x}", val),
+            Value::Data4(val) => format!("0x{:08x}", val),
+            Value::Data8(val) => format!("0x{:016x}", val),
+            Value::Sdata(val) => val.to_string(),
+            Value::Udata(val) | Value::FileIndex(val) => val.to_string(),
+            Value::Flag(val) => val.to_string(),
+            Value::SecOffset(offset) => format!("0x{:x}", offset),
+            Value::UnitRef(offset) => match offset.to_unit_section_offset(unit) {
+                gimli::UnitSectionOffset::DebugInfoOffset(offset) => format!("<0x{:x}>", offset.0),
+                gimli::UnitSectionOffset::DebugTypesOffset(offset) => format!("<0x{:x}>", offset.0),
+            },
+            Value::DebugInfoRef(offset) => format!("<0x{:x}>", offset.0),
+            Value::String(_)
+            | Value::DebugStrRef(_)
+            | Value::DebugStrRefSup(_)
+            | Value::DebugStrOffsetsIndex(_)
+            | Value::DebugLineStrRef(_) => match self.read.attr_string(unit, value) {
+                Ok(val) => format!("{:?}", String::from_utf8_lossy(val.slice())),
+                Err(_) => "<invalid string>".into(),
+            },
+            Value::Encoding(val) => val.to_string(),
+            Value::DecimalSign(val) => val.to_string(),
+            Value::Endianity(val) => val.to_string(),
+            Value::Accessibility(val) => val.to_string(),
+            Value::Visibility(val) => val.to_string(),
+            Value::Virtuality(val) => val.to_string(),
+            Value::Language(val) => val.to_string(),
+            Value::AddressClass(val) => val.to_string(),
+            Value::IdentifierCase(val) => val.to_string(),
+            Value::CallingConvention(val) => val.to_string(),
+            Value::Inline(val) => val.to_string(),
+            Value::Ordering(val) => val.to_string(),
+            val => format!("{:?}", val),
+        }
+    }
 }
 
 type DwarfUnit<'input, Endian> = gimli::Unit<Reader<'input, Endian>>;
 
+/// The value of a `DW_AT_high_pc` attribute.
+#[derive(Debug, Clone, Copy)]
+enum HighPc {
+    /// The address of the first location past the end of the range.
+    Address(u64),
+    /// The size of the range.
+    Offset(u64),
+}
+
+impl HighPc {
+    /// Return the size of the range that starts at `low_pc`.
+    fn size(self, low_pc: u64) -> Option<u64> {
+        match self {
+            HighPc::Address(high_pc) => high_pc.checked_sub(low_pc),
+            HighPc::Offset(size) => Some(size),
+        }
+    }
+}
+
 struct DwarfSubprogram<'input> {
     offset: gimli::UnitOffset,
     specification: FunctionOffset,
@@ -381,20 +611,31 @@ struct DwarfVariable<'input> {
     variable: Variable<'input>,
 }
 
-pub(crate) fn parse<'input: 'file, 'file, Endian, Object>(
+/// Subprograms and variables with a specification in a different unit.
+///
+/// These are resolved after all units have been parsed.
+#[derive(Default)]
+struct DwarfCrossUnit<'input> {
+    subprograms: Vec<DwarfSubprogram<'input>>,
+    variables: Vec<DwarfVariable<'input>>,
+}
+
+// Load the DWARF sections, without parsing any units.
+fn load<'input: 'file, 'file, Endian, Object>(
     endian: Endian,
     object: &'file Object,
     arena: &'input Arena,
-) -> Result<(Vec<Unit<'input>>, DebugInfo<'input, Endian>)>
+) -> Result<DwarfDebugInfo<'input, Endian>>
 where
     Endian: gimli::Endianity,
     Object: object::Object<'input, 'file>,
 {
+    let addresses = SectionAddresses::new(object);
     let get_section = |id: gimli::SectionId| -> Result<_> {
         let mut relocations = RelocationMap::default();
         let data = match object.section_by_name(id.name()) {
             Some(ref section) => {
-                add_relocations(&mut relocations, object, section);
+                add_relocations(&mut relocations, object, &addresses, section);
                 match section.uncompressed_data()? {
                     Cow::Borrowed(bytes) => bytes,
                     Cow::Owned(bytes) => arena.add_buffer(bytes),
@@ -410,43 +651,171 @@ where
             reader,
         })
     };
-    let read = gimli::Dwarf::load(get_section)?;
-
-    let debug_frame = get_section(gimli::SectionId::DebugFrame)?;
-    let eh_frame = get_section(gimli::SectionId::EhFrame)?;
     let mut bases = gimli::BaseAddresses::default();
     if let Some(section) = object.section_by_name(".eh_frame") {
-        bases = bases.set_eh_frame(section.address());
+        bases = bases.set_eh_frame(addresses.section(&section));
     }
     if let Some(section) = object.section_by_name(".text") {
-        bases = bases.set_text(section.address());
+        bases = bases.set_text(addresses.section(&section));
     }
     if let Some(section) = object.section_by_name(".got") {
-        bases = bases.set_got(section.address());
+        bases = bases.set_got(addresses.section(&section));
     }
+    load_sections(endian, arena, get_section, bases)
+}
+
+fn load_sections<'input, Endian, F>(
+    endian: Endian,
+    arena: &'input Arena,
+    get_section: F,
+    bases: gimli::BaseAddresses,
+) -> Result<DwarfDebugInfo<'input, Endian>>
+where
+    Endian: gimli::Endianity,
+    F: Fn(gimli::SectionId) -> Result<Reader<'input, Endian>>,
+{
+    let read = gimli::Dwarf::load(&get_section)?;
+
+    let debug_frame = get_section(gimli::SectionId::DebugFrame)?;
+    let eh_frame = get_section(gimli::SectionId::EhFrame)?;
     let frame = DwarfFrame::new(debug_frame.into(), eh_frame.into(), bases);
 
-    let mut dwarf = DwarfDebugInfo {
+    Ok(DwarfDebugInfo {
         endian,
         read,
         frame,
         arena,
         units: Vec::new(),
+    })
+}
+
+pub(crate) fn parse<'input: 'file, 'file, Endian, Object>(
+    endian: Endian,
+    object: &'file Object,
+    arena: &'input Arena,
+    warnings: &mut Vec<String>,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<(Vec<Unit<'input>>, DebugInfo<'input, Endian>)>
+where
+    Endian: gimli::Endianity,
+    Object: object::Object<'input, 'file>,
+{
+    let dwarf = load(endian, object, arena)?;
+    parse_units(dwarf, warnings, progress)
+}
+
+/// Parse the DWARF in the sections returned by `sections` for a section name.
+///
+/// This is used for file formats that aren't supported by `object`.
+/// Relocations are not applied.
+pub(crate) fn parse_sections<'input, Endian, F>(
+    endian: Endian,
+    arena: &'input Arena,
+    sections: F,
+    warnings: &mut Vec<String>,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<(Vec<Unit<'input>>, DebugInfo<'input, Endian>)>
+where
+    Endian: gimli::Endianity,
+    F: Fn(&str) -> Option<&'input [u8]>,
+{
+    let get_section = |id: gimli::SectionId| -> Result<_> {
+        let data = sections(id.name()).unwrap_or(&[]);
+        let relocations = arena.add_relocations(Box::new(RelocationMap::default()));
+        let reader = gimli::EndianSlice::new(data, endian);
+        Ok(Relocate {
+            relocations,
+            section: reader,
+            reader,
+        })
     };
+    let dwarf = load_sections(endian, arena, get_section, gimli::BaseAddresses::default())?;
+    parse_units(dwarf, warnings, progress)
+}
+
+fn parse_units<'input, Endian>(
+    mut dwarf: DwarfDebugInfo<'input, Endian>,
+    warnings: &mut Vec<String>,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<(Vec<Unit<'input>>, DebugInfo<'input, Endian>)>
+where
+    Endian: gimli::Endianity,
+{
+    // Counting the units only needs to read the unit headers.
+    let mut total = 0;
+    let mut unit_headers = dwarf.read.units();
+    while let Ok(Some(_)) = unit_headers.next() {
+        total += 1;
+    }
+    progress(0, total);
 
     let mut units = Vec::new();
+    let mut cross_units = Vec::new();
     let mut unit_headers = dwarf.read.units();
-    while let Some(unit_header) = unit_headers.next()? {
-        let dwarf_unit = dwarf.read.unit(unit_header)?;
-        units.push(parse_unit(&mut dwarf, dwarf_unit)?);
+    let mut count = 0;
+    loop {
+        // An error in a unit header means the offset of the following units
+        // is unknown, so stop parsing. Errors within a unit only affect that unit.
+        let unit_header = match unit_headers.next() {
+            Ok(Some(unit_header)) => unit_header,
+            Ok(None) => break,
+            Err(e) => {
+                warnings.push(format!("invalid unit header: {}", e));
+                break;
+            }
+        };
+        let offset = match unit_header.offset() {
+            gimli::UnitSectionOffset::DebugInfoOffset(offset) => offset.0,
+            gimli::UnitSectionOffset::DebugTypesOffset(offset) => offset.0,
+        };
+        match dwarf
+            .read
+            .unit(unit_header)
+            .map_err(Error::from)
+            .and_then(|dwarf_unit| parse_unit(&mut dwarf, dwarf_unit))
+        {
+            Ok((unit, cross_unit)) => {
+                cross_units.push((units.len(), cross_unit));
+                units.push(unit);
+            }
+            Err(e) => warnings.push(format!("unit at offset 0x{:x}: {}", offset, e)),
+        }
+        count += 1;
+        progress(count, total);
+    }
+    if let Err(e) = fixup_cross_unit_specifications(&mut units, &dwarf, cross_units) {
+        warnings.push(format!("cross unit specifications: {}", e));
     }
     Ok((units, DebugInfo::Dwarf(dwarf)))
 }
 
+/// Load the DWARF for units that were parsed previously.
+///
+/// This only reads the unit headers, which are needed for loading
+/// details on demand.
+pub(crate) fn load_units<'input: 'file, 'file, Endian, Object>(
+    endian: Endian,
+    object: &'file Object,
+    arena: &'input Arena,
+) -> Result<DebugInfo<'input, Endian>>
+where
+    Endian: gimli::Endianity,
+    Object: object::Object<'input, 'file>,
+{
+    let mut dwarf = load(endian, object, arena)?;
+    let mut unit_headers = dwarf.read.units();
+    while let Ok(Some(unit_header)) = unit_headers.next() {
+        if let Ok(dwarf_unit) = dwarf.read.unit(unit_header) {
+            dwarf.units.push(dwarf_unit);
+        }
+    }
+    Ok(DebugInfo::Dwarf(dwarf))
+}
+
 fn parse_unit<'input, Endian>(
     dwarf: &mut DwarfDebugInfo<'input, Endian>,
     dwarf_unit: DwarfUnit<'input, Endian>,
-) -> Result<Unit<'input>>
+) -> Result<(Unit<'input>, DwarfCrossUnit<'input>)>
 where
     Endian: gimli::Endianity,
 {
@@ -460,13 +829,14 @@ where
     let root = tree.root()?;
 
     let entry = root.entry();
-    if entry.tag() != gimli::DW_TAG_compile_unit {
+    // Skeleton units from split DWARF 5 have no children, but still
+    // describe the unit's name and address ranges.
+    if entry.tag() != gimli::DW_TAG_compile_unit && entry.tag() != gimli::DW_TAG_skeleton_unit {
         return Err(format!("unknown CU tag: {}", entry.tag()).into());
     }
 
     let mut ranges = None;
     let mut high_pc = None;
-    let mut size = None;
     let mut attrs = entry.attrs();
     while let Some(attr) = attrs.next()? {
         match attr.name() {
@@ -476,33 +846,41 @@ where
             gimli::DW_AT_comp_dir => {
                 unit.dir = dwarf.string(&dwarf_unit, attr.value()).map(Cow::Borrowed);
             }
+            gimli::DW_AT_producer => {
+                unit.producer = dwarf.string(&dwarf_unit, attr.value()).map(Cow::Borrowed);
+            }
             gimli::DW_AT_language => {
                 if let gimli::AttributeValue::Language(language) = attr.value() {
                     unit.language = Some(language);
                 }
             }
             gimli::DW_AT_low_pc => {
-                if let gimli::AttributeValue::Addr(addr) = attr.value() {
+                if let Some(addr) = dwarf.address(&dwarf_unit, attr.value()) {
                     unit.low_pc = Some(addr);
                 }
             }
-            gimli::DW_AT_high_pc => match attr.value() {
-                gimli::AttributeValue::Addr(val) => high_pc = Some(val),
-                gimli::AttributeValue::Udata(val) => size = Some(val),
-                val => debug!("unknown CU DW_AT_high_pc: {:?}", val),
-            },
-            gimli::DW_AT_ranges => {
-                if let gimli::AttributeValue::RangeListsRef(val) = attr.value() {
-                    ranges = Some(val);
+            gimli::DW_AT_high_pc => {
+                high_pc = dwarf.high_pc(&dwarf_unit, attr.value());
+                if high_pc.is_none() {
+                    debug!("unknown CU DW_AT_high_pc: {:?}", attr.value());
                 }
             }
+            gimli::DW_AT_ranges => {
+                ranges = dwarf.ranges_offset(&dwarf_unit, attr.value());
+            }
             gimli::DW_AT_stmt_list
-            | gimli::DW_AT_producer
             | gimli::DW_AT_entry_pc
             | gimli::DW_AT_APPLE_optimized
             | gimli::DW_AT_macro_info
             | gimli::DW_AT_GNU_macros
             | gimli::DW_AT_GNU_pubnames
+            | gimli::DW_AT_macros
+            | gimli::DW_AT_str_offsets_base
+            | gimli::DW_AT_addr_base
+            | gimli::DW_AT_rnglists_base
+            | gimli::DW_AT_loclists_base
+            | gimli::DW_AT_dwo_name
+            | gimli::DW_AT_GNU_dwo_id
             | gimli::DW_AT_sibling => {}
             _ => debug!("unknown CU attribute: {} {:?}", attr.name(), attr.value()),
         }
@@ -534,7 +912,6 @@ where
             }
         }
     } else if let Some(offset) = ranges {
-        let offset = dwarf.read.ranges_offset_from_raw(&dwarf_unit, offset);
         let mut ranges = dwarf.read.ranges(&dwarf_unit, offset)?;
         while let Some(range) = ranges.next()? {
             if range.begin < range.end {
@@ -544,17 +921,12 @@ where
                 });
             }
         }
-    } else if let Some(low_pc) = unit.low_pc {
-        if let Some(size) = size {
-            if high_pc.is_none() {
-                high_pc = low_pc.checked_add(size);
-            }
-        }
-        if let Some(high_pc) = high_pc {
-            unit.ranges.push(Range {
-                begin: low_pc,
-                end: high_pc,
-            });
+    } else if let (Some(low_pc), Some(high_pc)) = (unit.low_pc, high_pc) {
+        if let Some(end) = high_pc
+            .size(low_pc)
+            .and_then(|size| low_pc.checked_add(size))
+        {
+            unit.ranges.push(Range { begin: low_pc, end });
         }
     }
     unit.ranges.sort();
@@ -572,17 +944,25 @@ where
         root.children(),
     )?;
 
+    let mut cross_unit = DwarfCrossUnit::default();
     fixup_subprogram_specifications(
         &mut unit,
         dwarf,
         &dwarf_unit,
         &mut subprograms,
         &mut variables,
+        &mut cross_unit.subprograms,
+    )?;
+    fixup_variable_specifications(
+        &mut unit,
+        dwarf,
+        &dwarf_unit,
+        &mut variables,
+        &mut cross_unit.variables,
     )?;
-    fixup_variable_specifications(&mut unit, dwarf, &dwarf_unit, &mut variables)?;
 
     dwarf.units.push(dwarf_unit);
-    Ok(unit)
+    Ok((unit, cross_unit))
 }
 
 #[inline(never)]
@@ -592,6 +972,7 @@ fn fixup_subprogram_specifications<'input, Endian>(
     dwarf_unit: &DwarfUnit<'input, Endian>,
     subprograms: &mut Vec<DwarfSubprogram<'input>>,
     variables: &mut Vec<DwarfVariable<'input>>,
+    cross_unit: &mut Vec<DwarfSubprogram<'input>>,
 ) -> Result<()>
 where
     Endian: gimli::Endianity,
@@ -612,20 +993,16 @@ where
         mem::swap(&mut defer, subprograms);
         for mut subprogram in defer.drain(..) {
             if inherit_subprogram(
-                &functions,
+                functions.get(&subprogram.specification),
                 &mut subprogram.function,
-                subprogram.specification,
-                subprogram.abstract_origin,
             ) {
-                let mut tree = dwarf_unit.entries_tree(Some(subprogram.offset))?;
-                parse_subprogram_children(
+                parse_specification_children(
                     unit,
                     dwarf,
                     dwarf_unit,
                     subprograms,
                     variables,
-                    &mut subprogram.function,
-                    tree.root()?.children(),
+                    &mut subprogram,
                 )?;
                 let offset = subprogram.offset.to_unit_section_offset(dwarf_unit);
                 functions.insert(offset.into(), subprogram.function);
@@ -639,21 +1016,21 @@ where
         }
 
         if !progress {
-            debug!(
-                "invalid specification for {} subprograms",
-                subprograms.len()
-            );
             mem::swap(&mut defer, subprograms);
             for mut subprogram in defer.drain(..) {
-                let mut tree = dwarf_unit.entries_tree(Some(subprogram.offset))?;
-                parse_subprogram_children(
+                if !is_unit_offset(dwarf_unit, subprogram.specification.get()) {
+                    // Resolved after all units have been parsed.
+                    cross_unit.push(subprogram);
+                    continue;
+                }
+                debug!("invalid specification for subprogram");
+                parse_specification_children(
                     unit,
                     dwarf,
                     dwarf_unit,
                     subprograms,
                     variables,
-                    &mut subprogram.function,
-                    tree.root()?.children(),
+                    &mut subprogram,
                 )?;
                 let offset = subprogram.offset.to_unit_section_offset(dwarf_unit);
                 functions.insert(offset.into(), subprogram.function);
@@ -661,7 +1038,7 @@ where
                     functions.insert(function.offset, function);
                 }
             }
-            // And keep going, because parse_subprogram_children() may have added more.
+            // And keep going, because parse_specification_children() may have added more.
         }
     }
 
@@ -675,6 +1052,7 @@ fn fixup_variable_specifications<'input, Endian>(
     _dwarf: &DwarfDebugInfo<'input, Endian>,
     dwarf_unit: &DwarfUnit<'input, Endian>,
     variables: &mut Vec<DwarfVariable<'input>>,
+    cross_unit: &mut Vec<DwarfVariable<'input>>,
 ) -> Result<()>
 where
     Endian: gimli::Endianity,
@@ -691,19 +1069,7 @@ where
 
         for mut variable in variables.drain(..) {
             match variable.specification.and_then(|v| variable_map.get(&v)) {
-                Some(specification) => {
-                    let variable = &mut variable.variable;
-                    variable.namespace = specification.namespace.clone();
-                    if variable.name.is_none() {
-                        variable.name = specification.name;
-                    }
-                    if variable.linkage_name.is_none() {
-                        variable.linkage_name = specification.linkage_name;
-                    }
-                    if variable.ty.is_none() {
-                        variable.ty = specification.ty;
-                    }
-                }
+                Some(specification) => inherit_variable(specification, &mut variable.variable),
                 None => {
                     defer.push(variable);
                     continue;
@@ -718,8 +1084,14 @@ where
             break;
         }
         if !progress {
-            debug!("invalid specification for {} variables", defer.len());
-            for variable in variables.drain(..) {
+            for variable in defer {
+                let specification = variable.specification.and_then(VariableOffset::get);
+                if !is_unit_offset(dwarf_unit, specification) {
+                    // Resolved after all units have been parsed.
+                    cross_unit.push(variable);
+                    continue;
+                }
+                debug!("invalid specification for variable");
                 let offset = variable.offset.to_unit_section_offset(dwarf_unit);
                 variable_map.insert(offset.into(), variable.variable);
             }
@@ -728,8 +1100,223 @@ where
         *variables = defer;
     }
 
-    unit.variables = variable_map.into_iter().map(|(_, x)| x).collect();
-    Ok(())
+    unit.variables = variable_map.into_iter().map(|(_, x)| x).collect();
+    Ok(())
+}
+
+/// Resolve specifications that refer to entries in a different unit.
+///
+/// `DW_FORM_ref_addr` references may be to either earlier or later units,
+/// so this must wait until all units have been parsed.
+#[inline(never)]
+fn fixup_cross_unit_specifications<'input, Endian>(
+    units: &mut [Unit<'input>],
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    cross_units: Vec<(usize, DwarfCrossUnit<'input>)>,
+) -> Result<()>
+where
+    Endian: gimli::Endianity,
+{
+    let mut subprograms = Vec::new();
+    let mut variables = Vec::new();
+    for (index, cross_unit) in cross_units {
+        subprograms.extend(cross_unit.subprograms.into_iter().map(|x| (index, x)));
+        variables.extend(cross_unit.variables.into_iter().map(|x| (index, x)));
+    }
+
+    let mut defer = Vec::new();
+    while !subprograms.is_empty() {
+        let mut progress = false;
+        for (index, mut subprogram) in mem::take(&mut subprograms) {
+            let specification = find_function(units, dwarf, subprogram.specification);
+            if inherit_subprogram(specification, &mut subprogram.function) {
+                add_cross_unit_subprogram(
+                    units,
+                    dwarf,
+                    index,
+                    subprogram,
+                    &mut subprograms,
+                    &mut variables,
+                )?;
+                progress = true;
+            } else {
+                defer.push((index, subprogram));
+            }
+        }
+
+        if progress {
+            subprograms.append(&mut defer);
+        } else {
+            debug!("invalid specification for {} subprograms", defer.len());
+            for (index, subprogram) in defer.drain(..) {
+                add_cross_unit_subprogram(
+                    units,
+                    dwarf,
+                    index,
+                    subprogram,
+                    &mut subprograms,
+                    &mut variables,
+                )?;
+            }
+        }
+    }
+
+    let mut defer = Vec::new();
+    while !variables.is_empty() {
+        let mut progress = false;
+        for (index, mut variable) in mem::take(&mut variables) {
+            let specification = variable
+                .specification
+                .and_then(|offset| find_variable(units, dwarf, offset));
+            match specification {
+                Some(specification) => {
+                    inherit_variable(specification, &mut variable.variable);
+                    add_cross_unit_variable(&mut units[index], variable.variable);
+                    progress = true;
+                }
+                None => defer.push((index, variable)),
+            }
+        }
+
+        if progress {
+            variables.append(&mut defer);
+        } else {
+            debug!("invalid specification for {} variables", defer.len());
+            for (index, variable) in defer.drain(..) {
+                add_cross_unit_variable(&mut units[index], variable.variable);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn add_cross_unit_subprogram<'input, Endian>(
+    units: &mut [Unit<'input>],
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    index: usize,
+    mut subprogram: DwarfSubprogram<'input>,
+    subprograms: &mut Vec<(usize, DwarfSubprogram<'input>)>,
+    variables: &mut Vec<(usize, DwarfVariable<'input>)>,
+) -> Result<()>
+where
+    Endian: gimli::Endianity,
+{
+    let unit = &mut units[index];
+    let dwarf_unit = &dwarf.units[index];
+    let mut unit_subprograms = Vec::new();
+    let mut unit_variables = Vec::new();
+    parse_specification_children(
+        unit,
+        dwarf,
+        dwarf_unit,
+        &mut unit_subprograms,
+        &mut unit_variables,
+        &mut subprogram,
+    )?;
+    unit.functions.push(subprogram.function);
+
+    // Also handles sorting the new functions.
+    let mut cross_unit = DwarfCrossUnit::default();
+    fixup_subprogram_specifications(
+        unit,
+        dwarf,
+        dwarf_unit,
+        &mut unit_subprograms,
+        &mut unit_variables,
+        &mut cross_unit.subprograms,
+    )?;
+    fixup_variable_specifications(
+        unit,
+        dwarf,
+        dwarf_unit,
+        &mut unit_variables,
+        &mut cross_unit.variables,
+    )?;
+    subprograms.extend(cross_unit.subprograms.into_iter().map(|x| (index, x)));
+    variables.extend(cross_unit.variables.into_iter().map(|x| (index, x)));
+    Ok(())
+}
+
+fn add_cross_unit_variable<'input>(unit: &mut Unit<'input>, variable: Variable<'input>) {
+    let index = unit
+        .variables
+        .binary_search_by_key(&variable.offset, |x| x.offset)
+        .unwrap_or_else(|x| x);
+    unit.variables.insert(index, variable);
+}
+
+/// Find the index of the unit containing the given `.debug_info` offset.
+fn find_unit<Endian>(dwarf: &DwarfDebugInfo<Endian>, offset: usize) -> Option<usize>
+where
+    Endian: gimli::Endianity,
+{
+    let offset = gimli::DebugInfoOffset(offset);
+    // The units are in the order that they were read from `.debug_info`,
+    // so they are sorted by offset.
+    let index =
+        dwarf
+            .units
+            .partition_point(|unit| match unit.header.offset().as_debug_info_offset() {
+                Some(unit_offset) => unit_offset <= offset,
+                None => true,
+            });
+    let index = index.checked_sub(1)?;
+    offset.to_unit_offset(&dwarf.units[index].header)?;
+    Some(index)
+}
+
+fn find_function<'a, 'input, Endian>(
+    units: &'a [Unit<'input>],
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    offset: FunctionOffset,
+) -> Option<&'a Function<'input>>
+where
+    Endian: gimli::Endianity,
+{
+    let functions = &units[find_unit(dwarf, offset.get()?)?].functions;
+    let index = functions.binary_search_by_key(&offset, |x| x.offset).ok()?;
+    Some(&functions[index])
+}
+
+fn find_variable<'a, 'input, Endian>(
+    units: &'a [Unit<'input>],
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    offset: VariableOffset,
+) -> Option<&'a Variable<'input>>
+where
+    Endian: gimli::Endianity,
+{
+    let variables = &units[find_unit(dwarf, offset.get()?)?].variables;
+    let index = variables.binary_search_by_key(&offset, |x| x.offset).ok()?;
+    Some(&variables[index])
+}
+
+/// Return true if the `.debug_info` offset is within the unit.
+///
+/// Invalid offsets are considered to be within the unit.
+fn is_unit_offset<Endian>(dwarf_unit: &DwarfUnit<Endian>, offset: Option<usize>) -> bool
+where
+    Endian: gimli::Endianity,
+{
+    match offset {
+        Some(offset) => gimli::DebugInfoOffset(offset)
+            .to_unit_offset(&dwarf_unit.header)
+            .is_some(),
+        None => true,
+    }
+}
+
+fn inherit_variable<'input>(specification: &Variable<'input>, variable: &mut Variable<'input>) {
+    variable.namespace = specification.namespace.clone();
+    if variable.name.is_none() {
+        variable.name = specification.name;
+    }
+    if variable.linkage_name.is_none() {
+        variable.linkage_name = specification.linkage_name;
+    }
+    if variable.ty.is_none() {
+        variable.ty = specification.ty;
+    }
 }
 
 fn parse_namespace_children<'input, 'abbrev, 'unit, 'tree, Endian>(
@@ -777,6 +1364,9 @@ where
                     unit.variables.push(variable.variable);
                 }
             }
+            gimli::DW_TAG_common_block => {
+                parse_common_block(unit, dwarf, dwarf_unit, child)?;
+            }
             gimli::DW_TAG_dwarf_procedure
             | gimli::DW_TAG_imported_declaration
             | gimli::DW_TAG_imported_module => {}
@@ -1162,6 +1752,11 @@ where
             }
             gimli::DW_AT_decl_line => parse_source_line(&attr, &mut typedef.source),
             gimli::DW_AT_decl_column => parse_source_column(&attr, &mut typedef.source),
+            gimli::DW_AT_alignment => {
+                if let Some(alignment) = attr.udata_value() {
+                    typedef.alignment = Size::new(alignment);
+                }
+            }
             _ => debug!(
                 "unknown typedef attribute: {} {:?}",
                 attr.name(),
@@ -1195,6 +1790,7 @@ where
 {
     let mut ty = StructType::default();
     ty.namespace = namespace.clone();
+    let class = node.entry().tag() == gimli::DW_TAG_class_type;
 
     let mut attrs = node.entry().attrs();
     while let Some(attr) = attrs.next()? {
@@ -1215,7 +1811,12 @@ where
             gimli::DW_AT_decl_file => parse_source_file(dwarf, dwarf_unit, &attr, &mut ty.source),
             gimli::DW_AT_decl_line => parse_source_line(&attr, &mut ty.source),
             gimli::DW_AT_decl_column => parse_source_column(&attr, &mut ty.source),
-            gimli::DW_AT_containing_type | gimli::DW_AT_alignment | gimli::DW_AT_sibling => {}
+            gimli::DW_AT_alignment => {
+                if let Some(alignment) = attr.udata_value() {
+                    ty.alignment = Size::new(alignment);
+                }
+            }
+            gimli::DW_AT_containing_type | gimli::DW_AT_sibling => {}
             _ => debug!(
                 "unknown struct attribute: {} {:?}",
                 attr.name(),
@@ -1229,6 +1830,7 @@ where
     while let Some(child) = iter.next()? {
         match child.entry().tag() {
             gimli::DW_TAG_subprogram => {
+                let offset = child.entry().offset().to_unit_section_offset(dwarf_unit);
                 parse_subprogram(
                     unit,
                     dwarf,
@@ -1238,8 +1840,15 @@ where
                     &namespace,
                     child,
                 )?;
+                // Method declarations are never specifications, so they are
+                // always added to the unit.
+                if let Some(function) = unit.functions.last() {
+                    if function.offset == offset.into() && function.vtable_index.is_some() {
+                        ty.virtual_methods.push(function.offset);
+                    }
+                }
             }
-            gimli::DW_TAG_member => {
+            gimli::DW_TAG_member | gimli::DW_TAG_variable => {
                 parse_member(&mut ty.members, unit, dwarf, dwarf_unit, &namespace, child)?;
             }
             gimli::DW_TAG_inheritance => {
@@ -1274,6 +1883,19 @@ where
             }
         }
     }
+    if class {
+        // Members and bases of a class are private unless specified otherwise.
+        for member in &mut ty.members {
+            if member.accessibility.is_none() {
+                member.accessibility = Some(Accessibility::Private);
+            }
+        }
+        for inherit in &mut ty.inherits {
+            if inherit.accessibility.is_none() {
+                inherit.accessibility = Some(Accessibility::Private);
+            }
+        }
+    }
     Ok(ty)
 }
 
@@ -1311,7 +1933,12 @@ where
             gimli::DW_AT_decl_file => parse_source_file(dwarf, dwarf_unit, &attr, &mut ty.source),
             gimli::DW_AT_decl_line => parse_source_line(&attr, &mut ty.source),
             gimli::DW_AT_decl_column => parse_source_column(&attr, &mut ty.source),
-            gimli::DW_AT_alignment | gimli::DW_AT_sibling => {}
+            gimli::DW_AT_alignment => {
+                if let Some(alignment) = attr.udata_value() {
+                    ty.alignment = Size::new(alignment);
+                }
+            }
+            gimli::DW_AT_sibling => {}
             _ => debug!(
                 "unknown union attribute: {} {:?}",
                 attr.name(),
@@ -1335,7 +1962,7 @@ where
                     child,
                 )?;
             }
-            gimli::DW_TAG_member => {
+            gimli::DW_TAG_member | gimli::DW_TAG_variable => {
                 parse_member(&mut ty.members, unit, dwarf, dwarf_unit, &namespace, child)?;
             }
             gimli::DW_TAG_template_type_parameter => {}
@@ -1432,11 +2059,15 @@ where
     let mut attrs = node.entry().attrs();
     while let Some(attr) = attrs.next()? {
         match attr.name() {
-            gimli::DW_AT_discr_value => {
-                if let Some(value) = attr.udata_value() {
-                    variant.discr_value = Some(value);
+            gimli::DW_AT_discr_value => match attr.value() {
+                // Negative values are stored as their two's complement.
+                gimli::AttributeValue::Sdata(value) => variant.discr_value = Some(value as u64),
+                _ => {
+                    if let Some(value) = attr.udata_value() {
+                        variant.discr_value = Some(value);
+                    }
                 }
-            }
+            },
             gimli::DW_AT_sibling => {}
             _ => debug!(
                 "unknown variant attribute: {} {:?}",
@@ -1529,7 +2160,8 @@ where
     member.offset = offset.into();
     let mut bit_offset = None;
     let mut byte_size = None;
-    let mut declaration = false;
+    // DWARF 5 uses DW_TAG_variable for static data members.
+    let mut declaration = node.entry().tag() == gimli::DW_TAG_variable;
 
     let mut attrs = node.entry().attrs();
     while let Some(attr) = attrs.next()? {
@@ -1566,14 +2198,26 @@ where
             gimli::DW_AT_declaration => {
                 declaration = true;
             }
+            gimli::DW_AT_accessibility => {
+                member.accessibility = parse_accessibility(&attr);
+            }
+            gimli::DW_AT_artificial => {
+                if let gimli::AttributeValue::Flag(flag) = attr.value() {
+                    member.artificial = flag;
+                }
+            }
+            gimli::DW_AT_alignment => {
+                if let Some(alignment) = attr.udata_value() {
+                    member.alignment = Size::new(alignment);
+                }
+            }
             gimli::DW_AT_decl_file
             | gimli::DW_AT_decl_line
             | gimli::DW_AT_decl_column
             | gimli::DW_AT_external
-            | gimli::DW_AT_accessibility
-            | gimli::DW_AT_artificial
+            | gimli::DW_AT_linkage_name
+            | gimli::DW_AT_MIPS_linkage_name
             | gimli::DW_AT_const_value
-            | gimli::DW_AT_alignment
             | gimli::DW_AT_sibling => {}
             _ => {
                 debug!(
@@ -1586,9 +2230,11 @@ where
     }
 
     if declaration {
-        // This is a C++ static data member. Parse it as a variable instead.
+        // This is a C++ static data member. Parse it as a variable too.
         // Note: the DWARF 5 standard says static members should use DW_TAG_variable,
         // but at least clang 3.7.1 uses DW_TAG_member.
+        member.is_static = true;
+        members.push(member);
         let variable = parse_variable(unit, dwarf, dwarf_unit, namespace.clone(), node)?;
         if variable.specification.is_some() {
             debug!(
@@ -1644,6 +2290,24 @@ where
     Ok(())
 }
 
+fn parse_accessibility<R: gimli::Reader>(attr: &gimli::Attribute<R>) -> Option<Accessibility> {
+    match attr.value() {
+        gimli::AttributeValue::Accessibility(gimli::DW_ACCESS_public) => {
+            Some(Accessibility::Public)
+        }
+        gimli::AttributeValue::Accessibility(gimli::DW_ACCESS_protected) => {
+            Some(Accessibility::Protected)
+        }
+        gimli::AttributeValue::Accessibility(gimli::DW_ACCESS_private) => {
+            Some(Accessibility::Private)
+        }
+        value => {
+            debug!("unknown accessibility: {:?}", value);
+            None
+        }
+    }
+}
+
 fn parse_inheritance<'input, 'abbrev, 'unit, 'tree, Endian>(
     inherits: &mut Vec<Inherit>,
     dwarf_unit: &DwarfUnit<'input, Endian>,
@@ -1667,7 +2331,15 @@ where
                     inherit.bit_offset = offset;
                 }
             }
-            gimli::DW_AT_accessibility | gimli::DW_AT_virtuality | gimli::DW_AT_sibling => {}
+            gimli::DW_AT_accessibility => {
+                inherit.accessibility = parse_accessibility(&attr);
+            }
+            gimli::DW_AT_virtuality => {
+                if let gimli::AttributeValue::Virtuality(virtuality) = attr.value() {
+                    inherit.is_virtual = virtuality != gimli::DW_VIRTUALITY_none;
+                }
+            }
+            gimli::DW_AT_sibling => {}
             _ => {
                 debug!(
                     "unknown inheritance attribute: {} {:?}",
@@ -1707,7 +2379,7 @@ where
             }
         }
         gimli::AttributeValue::Exprloc(expr) => {
-            if let Some(offset) = evaluate_member_location(&dwarf_unit.header, expr) {
+            if let Some(offset) = evaluate_member_location(dwarf_unit, expr) {
                 return Some(offset);
             }
         }
@@ -1902,29 +2574,22 @@ where
     while let Some(child) = iter.next()? {
         match child.entry().tag() {
             gimli::DW_TAG_subrange_type => {
+                let mut count = None;
+                let mut lower = None;
+                let mut upper = None;
                 let mut attrs = child.entry().attrs();
                 while let Some(attr) = attrs.next()? {
                     match attr.name() {
                         gimli::DW_AT_count => {
-                            if let Some(count) = attr.udata_value() {
-                                array.count = Size::new(count);
-                            }
+                            count = attr.udata_value();
+                        }
+                        gimli::DW_AT_lower_bound => {
+                            lower = attr.udata_value();
                         }
                         gimli::DW_AT_upper_bound => {
-                            // byte_size takes precedence over upper_bound when
-                            // determining the count.
-                            if array.byte_size.is_none() {
-                                if let Some(upper_bound) = attr.udata_value() {
-                                    // TODO: use AT_lower_bound too (and default lower bound)
-                                    if let Some(count) = u64::checked_add(upper_bound, 1) {
-                                        array.count = Size::new(count);
-                                    } else {
-                                        debug!("overflow for array upper bound: {}", upper_bound);
-                                    }
-                                }
-                            }
+                            upper = attr.udata_value();
                         }
-                        gimli::DW_AT_type | gimli::DW_AT_lower_bound => {}
+                        gimli::DW_AT_type => {}
                         _ => debug!(
                             "unknown array subrange attribute: {} {:?}",
                             attr.name(),
@@ -1932,6 +2597,20 @@ where
                         ),
                     }
                 }
+                if count.is_none() {
+                    if let Some(upper) = upper {
+                        // TODO: lower bound default should depend on language
+                        let lower = lower.unwrap_or(0);
+                        count = upper.checked_sub(lower).and_then(|v| v.checked_add(1));
+                        if count.is_none() {
+                            debug!("invalid array bounds: {}..={}", lower, upper);
+                        }
+                    }
+                }
+                array.counts.push(match count {
+                    Some(count) => Size::new(count),
+                    None => Size::none(),
+                });
             }
             tag => {
                 debug!("unknown array child tag: {}", tag);
@@ -2046,6 +2725,9 @@ where
             gimli::DW_TAG_formal_parameter => {
                 parse_parameter_type(&mut function.parameters, dwarf, dwarf_unit, child)?;
             }
+            gimli::DW_TAG_unspecified_parameters => {
+                function.variadic = true;
+            }
             tag => {
                 debug!("unknown subroutine child tag: {}", tag);
             }
@@ -2164,16 +2846,21 @@ where
         source: Source::default(),
         address: Address::none(),
         size: Size::none(),
+        ranges: Vec::new(),
         inline: false,
         declaration: false,
+        external: false,
+        virtuality: None,
+        vtable_index: None,
         parameters: Vec::new(),
+        variadic: false,
         return_type: TypeOffset::none(),
     };
 
     let mut specification = None;
     let mut abstract_origin = false;
     let mut high_pc = None;
-    let mut size = None;
+    let mut ranges = None;
 
     let entry = node.entry();
     let mut attrs = entry.attrs();
@@ -2201,21 +2888,16 @@ where
                 }
             }
             gimli::DW_AT_low_pc => {
-                if let gimli::AttributeValue::Addr(addr) = attr.value() {
+                if let Some(addr) = dwarf.address(dwarf_unit, attr.value()) {
                     if addr != 0 || unit.low_pc == Some(0) {
                         function.address = Address::new(addr);
                     }
                 }
             }
-            gimli::DW_AT_high_pc => match attr.value() {
-                gimli::AttributeValue::Addr(addr) => high_pc = Some(addr),
-                gimli::AttributeValue::Udata(val) => {
-                    if val != 0 {
-                        size = Some(val);
-                    }
-                }
-                _ => {}
-            },
+            gimli::DW_AT_high_pc => high_pc = dwarf.high_pc(dwarf_unit, attr.value()),
+            gimli::DW_AT_ranges => {
+                ranges = dwarf.ranges_offset(dwarf_unit, attr.value());
+            }
             gimli::DW_AT_type => {
                 if let Some(offset) = parse_type_offset(dwarf_unit, &attr) {
                     function.return_type = offset;
@@ -2232,19 +2914,37 @@ where
                     function.declaration = flag;
                 }
             }
+            gimli::DW_AT_external => {
+                if let gimli::AttributeValue::Flag(flag) = attr.value() {
+                    function.external = flag;
+                }
+            }
             gimli::DW_AT_frame_base => {
                 // FIXME
             }
-            gimli::DW_AT_external
-            | gimli::DW_AT_GNU_all_call_sites
+            gimli::DW_AT_virtuality => {
+                function.virtuality = match attr.value() {
+                    gimli::AttributeValue::Virtuality(gimli::DW_VIRTUALITY_virtual) => {
+                        Some(Virtuality::Virtual)
+                    }
+                    gimli::AttributeValue::Virtuality(gimli::DW_VIRTUALITY_pure_virtual) => {
+                        Some(Virtuality::PureVirtual)
+                    }
+                    _ => None,
+                };
+            }
+            gimli::DW_AT_vtable_elem_location => {
+                if let gimli::AttributeValue::Exprloc(expr) = attr.value() {
+                    function.vtable_index = evaluate_vtable_elem_location(dwarf_unit, expr);
+                }
+            }
+            gimli::DW_AT_GNU_all_call_sites
             | gimli::DW_AT_GNU_all_tail_call_sites
             | gimli::DW_AT_prototyped
             | gimli::DW_AT_accessibility
             | gimli::DW_AT_explicit
             | gimli::DW_AT_artificial
             | gimli::DW_AT_object_pointer
-            | gimli::DW_AT_virtuality
-            | gimli::DW_AT_vtable_elem_location
             | gimli::DW_AT_containing_type
             | gimli::DW_AT_main_subprogram
             | gimli::DW_AT_noreturn
@@ -2259,13 +2959,27 @@ where
         }
     }
 
-    if let Some(address) = function.address() {
-        if let Some(high_pc) = high_pc {
-            if high_pc > address {
-                function.size = Size::new(high_pc - address);
+    if let Some(offset) = ranges {
+        let mut ranges = dwarf.read.ranges(dwarf_unit, offset)?;
+        while let Some(range) = ranges.next()? {
+            if range.begin < range.end {
+                function.ranges.push(range.into());
             }
-        } else if let Some(size) = size {
-            function.size = Size::new(size);
+        }
+        // The first range contains the entry point, so use it for the
+        // function address and size.
+        if function.address.is_none() {
+            if let Some(range) = function.ranges.first() {
+                function.address = Address::new(range.begin);
+                function.size = Size::new(range.size());
+            }
+        }
+    }
+
+    if let (Some(address), Some(high_pc)) = (function.address(), high_pc) {
+        match high_pc.size(address) {
+            Some(size) if size != 0 => function.size = Size::new(size),
+            _ => debug!("invalid subprogram DW_AT_high_pc: {:?}", high_pc),
         }
     }
 
@@ -2293,12 +3007,10 @@ where
 }
 
 fn inherit_subprogram<'input>(
-    functions: &BTreeMap<FunctionOffset, Function<'input>>,
+    specification: Option<&Function<'input>>,
     function: &mut Function<'input>,
-    specification: FunctionOffset,
-    abstract_origin: bool,
 ) -> bool {
-    let specification = match functions.get(&specification) {
+    let specification = match specification {
         Some(val) => val,
         None => return false,
     };
@@ -2316,16 +3028,59 @@ fn inherit_subprogram<'input>(
     if function.return_type.is_none() {
         function.return_type = specification.return_type;
     }
-    if abstract_origin {
-        // We inherit all children, and then extend them when parsing our children.
-        function.parameters = specification.parameters.clone();
-    } else {
-        // TODO: inherit children from specifications?
+    if specification.external {
+        function.external = true;
+    }
+    if function.virtuality.is_none() {
+        function.virtuality = specification.virtuality;
+    }
+    if function.vtable_index.is_none() {
+        function.vtable_index = specification.vtable_index;
+    }
+    // For abstract origins, we inherit all children, and then extend them when
+    // parsing our children. For specifications, the parameters are only used if
+    // we have none of our own (see `parse_specification_children`).
+    function.parameters = specification.parameters.clone();
+    if specification.variadic {
+        function.variadic = true;
     }
 
     true
 }
 
+/// Parse the children of a subprogram that has a specification or abstract origin.
+fn parse_specification_children<'input, Endian>(
+    unit: &mut Unit<'input>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
+    subprograms: &mut Vec<DwarfSubprogram<'input>>,
+    variables: &mut Vec<DwarfVariable<'input>>,
+    subprogram: &mut DwarfSubprogram<'input>,
+) -> Result<()>
+where
+    Endian: gimli::Endianity,
+{
+    let parameters = if subprogram.abstract_origin {
+        Vec::new()
+    } else {
+        mem::take(&mut subprogram.function.parameters)
+    };
+    let mut tree = dwarf_unit.entries_tree(Some(subprogram.offset))?;
+    parse_subprogram_children(
+        unit,
+        dwarf,
+        dwarf_unit,
+        subprograms,
+        variables,
+        &mut subprogram.function,
+        tree.root()?.children(),
+    )?;
+    if subprogram.function.parameters.is_empty() {
+        subprogram.function.parameters = parameters;
+    }
+    Ok(())
+}
+
 fn parse_subprogram_children<'input, 'abbrev, 'unit, 'tree, Endian>(
     unit: &mut Unit<'input>,
     dwarf: &DwarfDebugInfo<'input, Endian>,
@@ -2348,9 +3103,15 @@ where
             gimli::DW_TAG_formal_parameter => {
                 parse_parameter_type(&mut function.parameters, dwarf, dwarf_unit, child)?;
             }
+            gimli::DW_TAG_unspecified_parameters => {
+                function.variadic = true;
+            }
             gimli::DW_TAG_variable => {
                 // Handled in details.
             }
+            gimli::DW_TAG_common_block => {
+                parse_common_block(unit, dwarf, dwarf_unit, child)?;
+            }
             gimli::DW_TAG_inlined_subroutine => {
                 parse_inlined_subroutine(child)?;
             }
@@ -2376,8 +3137,7 @@ where
                     child,
                 )?;
             }
-            gimli::DW_TAG_unspecified_parameters
-            | gimli::DW_TAG_template_type_parameter
+            gimli::DW_TAG_template_type_parameter
             | gimli::DW_TAG_template_value_parameter
             | gimli::DW_TAG_GNU_template_parameter_pack
             | gimli::DW_TAG_label
@@ -2526,27 +3286,31 @@ where
                 match attr.value() {
                     gimli::AttributeValue::Exprloc(expr) => {
                         evaluate_parameter_location(
-                            &dwarf_unit.header,
+                            dwarf,
+                            dwarf_unit,
                             Range::all(),
                             expr,
                             &mut parameter,
                         );
                     }
-                    gimli::AttributeValue::LocationListsRef(offset) => {
-                        let mut locations = dwarf.read.locations(dwarf_unit, offset)?;
-                        while let Some(location) = locations.next()? {
-                            // TODO: use location.range too
-                            evaluate_parameter_location(
-                                &dwarf_unit.header,
-                                location.range.into(),
-                                location.data,
-                                &mut parameter,
-                            );
+                    val => match dwarf.locations_offset(dwarf_unit, val) {
+                        Some(offset) => {
+                            let mut locations = dwarf.read.locations(dwarf_unit, offset)?;
+                            while let Some(location) = locations.next()? {
+                                // TODO: use location.range too
+                                evaluate_parameter_location(
+                                    dwarf,
+                                    dwarf_unit,
+                                    location.range.into(),
+                                    location.data,
+                                    &mut parameter,
+                                );
+                            }
                         }
-                    }
-                    _ => {
-                        debug!("unknown parameter DW_AT_location: {:?}", attr.value());
-                    }
+                        None => {
+                            debug!("unknown parameter DW_AT_location: {:?}", val);
+                        }
+                    },
                 }
             }
             gimli::DW_AT_decl_file
@@ -2643,6 +3407,9 @@ where
             gimli::DW_TAG_variable => {
                 // Handled in details.
             }
+            gimli::DW_TAG_common_block => {
+                parse_common_block(unit, dwarf, dwarf_unit, child)?;
+            }
             gimli::DW_TAG_inlined_subroutine => {
                 parse_inlined_subroutine(child)?;
             }
@@ -2763,6 +3530,7 @@ where
     Endian: gimli::Endianity,
 {
     let mut abstract_origin = None;
+    let mut specification = None;
 
     let entry = node.entry();
     let mut attrs = entry.attrs();
@@ -2773,6 +3541,11 @@ where
                     abstract_origin = Some(offset);
                 }
             }
+            gimli::DW_AT_specification => {
+                if let Some(offset) = parse_function_offset(dwarf_unit, &attr) {
+                    specification = Some(offset);
+                }
+            }
             _ => {}
         }
     }
@@ -2787,6 +3560,15 @@ where
         });
 
     parse_subprogram_children_details(hash, dwarf, dwarf_unit, &mut details, node.children())?;
+
+    // Definitions often omit the parameters given in their declaration.
+    if details.parameters.is_empty() {
+        if let Some(specification) =
+            specification.and_then(|offset| dwarf.get_function_details(offset, hash))
+        {
+            details.parameters = specification.parameters;
+        }
+    }
     Ok(details)
 }
 
@@ -2885,7 +3667,6 @@ where
     let mut function = InlinedFunction::default();
     let mut low_pc = None;
     let mut high_pc = None;
-    let mut size = None;
     let mut ranges = None;
     let mut attrs = node.entry().attrs();
     while let Some(attr) = attrs.next()? {
@@ -2896,19 +3677,11 @@ where
                 }
             }
             gimli::DW_AT_low_pc => {
-                if let gimli::AttributeValue::Addr(addr) = attr.value() {
-                    low_pc = Some(addr);
-                }
+                low_pc = dwarf.address(dwarf_unit, attr.value());
             }
-            gimli::DW_AT_high_pc => match attr.value() {
-                gimli::AttributeValue::Addr(addr) => high_pc = Some(addr),
-                gimli::AttributeValue::Udata(val) => size = Some(val),
-                _ => {}
-            },
+            gimli::DW_AT_high_pc => high_pc = dwarf.high_pc(dwarf_unit, attr.value()),
             gimli::DW_AT_ranges => {
-                if let gimli::AttributeValue::RangeListsRef(val) = attr.value() {
-                    ranges = Some(val);
-                }
+                ranges = dwarf.ranges_offset(dwarf_unit, attr.value());
             }
             gimli::DW_AT_call_file => {
                 parse_source_file(dwarf, dwarf_unit, &attr, &mut function.call_source)
@@ -2940,16 +3713,24 @@ where
 
     if let Some(offset) = ranges {
         let mut size = 0;
-        let offset = dwarf.read.ranges_offset_from_raw(dwarf_unit, offset);
         let mut ranges = dwarf.read.ranges(dwarf_unit, offset)?;
         while let Some(range) = ranges.next()? {
             size += range.end.wrapping_sub(range.begin);
+            if range.begin < range.end {
+                function.ranges.push(Range {
+                    begin: range.begin,
+                    end: range.end,
+                });
+            }
         }
         function.size = Size::new(size);
-    } else if let Some(size) = size {
+    } else if let Some((low_pc, size)) =
+        low_pc.and_then(|low_pc| Some((low_pc, high_pc?.size(low_pc)?)))
+    {
+        if let Some(end) = low_pc.checked_add(size) {
+            function.ranges.push(Range { begin: low_pc, end });
+        }
         function.size = Size::new(size);
-    } else if let (Some(low_pc), Some(high_pc)) = (low_pc, high_pc) {
-        function.size = Size::new(high_pc.wrapping_sub(low_pc));
     } else {
         debug!("unknown inlined_subroutine size");
     }
@@ -3039,7 +3820,7 @@ where
             gimli::DW_AT_location => match attr.value() {
                 gimli::AttributeValue::Exprloc(expr) => {
                     if let Some((address, size)) =
-                        evaluate_variable_location(&dwarf_unit.header, expr)
+                        evaluate_variable_location(dwarf, dwarf_unit, expr)
                     {
                         variable.address = address;
                         if size.is_some() {
@@ -3047,11 +3828,12 @@ where
                         }
                     }
                 }
-                gimli::AttributeValue::LocationListsRef(..) => {
-                    debug!("loclist for variable: {:?}", attr.value());
-                }
-                _ => {
-                    debug!("unknown variable DW_AT_location: {:?}", attr.value());
+                val => {
+                    if dwarf.locations_offset(dwarf_unit, val).is_some() {
+                        debug!("loclist for variable: {:?}", val);
+                    } else {
+                        debug!("unknown variable DW_AT_location: {:?}", val);
+                    }
                 }
             },
             gimli::DW_AT_abstract_origin
@@ -3084,6 +3866,84 @@ where
     })
 }
 
+// Parse a Fortran common block as a variable containing the variables in the block.
+//
+// Common blocks are global, but are described within each subprogram that uses
+// them, so only the first description of each block in a unit is kept.
+fn parse_common_block<'input, 'abbrev, 'unit, 'tree, Endian>(
+    unit: &mut Unit<'input>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
+    node: gimli::EntriesTreeNode<'abbrev, 'unit, 'tree, Reader<'input, Endian>>,
+) -> Result<()>
+where
+    Endian: gimli::Endianity,
+{
+    let offset = node.entry().offset();
+    let mut variable = Variable {
+        offset: offset.to_unit_section_offset(dwarf_unit).into(),
+        ..Default::default()
+    };
+
+    let mut attrs = node.entry().attrs();
+    while let Some(attr) = attrs.next()? {
+        match attr.name() {
+            gimli::DW_AT_name => {
+                variable.name = dwarf.string(dwarf_unit, attr.value());
+            }
+            gimli::DW_AT_linkage_name | gimli::DW_AT_MIPS_linkage_name => {
+                variable.linkage_name = dwarf.string(dwarf_unit, attr.value());
+            }
+            gimli::DW_AT_decl_file => {
+                parse_source_file(dwarf, dwarf_unit, &attr, &mut variable.source)
+            }
+            gimli::DW_AT_decl_line => parse_source_line(&attr, &mut variable.source),
+            gimli::DW_AT_decl_column => parse_source_column(&attr, &mut variable.source),
+            gimli::DW_AT_location => match attr.value() {
+                gimli::AttributeValue::Exprloc(expr) => {
+                    if let Some((address, _size)) =
+                        evaluate_variable_location(dwarf, dwarf_unit, expr)
+                    {
+                        variable.address = address;
+                    }
+                }
+                val => debug!("unknown common block DW_AT_location: {:?}", val),
+            },
+            _ => debug!(
+                "unknown common block attribute: {} {:?}",
+                attr.name(),
+                attr.value()
+            ),
+        }
+    }
+
+    let namespace = Some(Namespace::new(
+        &None,
+        variable.name,
+        NamespaceKind::CommonBlock,
+    ));
+    let mut iter = node.children();
+    while let Some(child) = iter.next()? {
+        match child.entry().tag() {
+            gimli::DW_TAG_variable => {
+                let member = parse_variable(unit, dwarf, dwarf_unit, namespace.clone(), child)?;
+                variable.members.push(member.variable);
+            }
+            tag => {
+                debug!("unknown common block child tag: {}", tag);
+            }
+        }
+    }
+    variable.members.sort_by_key(|member| member.address());
+
+    if !unit.variables.iter().any(|x| {
+        !x.members.is_empty() && x.name == variable.name && x.address() == variable.address()
+    }) {
+        unit.variables.push(variable);
+    }
+    Ok(())
+}
+
 fn parse_local_variable<'input, 'abbrev, 'unit, 'tree, Endian>(
     variables: &mut Vec<LocalVariable<'input>>,
     dwarf: &DwarfDebugInfo<'input, Endian>,
@@ -3124,27 +3984,31 @@ where
                 match attr.value() {
                     gimli::AttributeValue::Exprloc(expr) => {
                         evaluate_local_variable_location(
-                            &dwarf_unit.header,
+                            dwarf,
+                            dwarf_unit,
                             Range::all(),
                             expr,
                             &mut variable,
                         );
                     }
-                    gimli::AttributeValue::LocationListsRef(offset) => {
-                        let mut locations = dwarf.read.locations(dwarf_unit, offset)?;
-                        while let Some(location) = locations.next()? {
-                            // TODO: use location.range too
-                            evaluate_local_variable_location(
-                                &dwarf_unit.header,
-                                location.range.into(),
-                                location.data,
-                                &mut variable,
-                            );
+                    val => match dwarf.locations_offset(dwarf_unit, val) {
+                        Some(offset) => {
+                            let mut locations = dwarf.read.locations(dwarf_unit, offset)?;
+                            while let Some(location) = locations.next()? {
+                                // TODO: use location.range too
+                                evaluate_local_variable_location(
+                                    dwarf,
+                                    dwarf_unit,
+                                    location.range.into(),
+                                    location.data,
+                                    &mut variable,
+                                );
+                            }
                         }
-                    }
-                    _ => {
-                        debug!("unknown local variable DW_AT_location: {:?}", attr.value());
-                    }
+                        None => {
+                            debug!("unknown local variable DW_AT_location: {:?}", val);
+                        }
+                    },
                 }
             }
             gimli::DW_AT_alignment
@@ -3215,13 +4079,13 @@ where
 }
 
 fn evaluate_member_location<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     expression: gimli::Expression<Reader<'input, Endian>>,
 ) -> Option<u64>
 where
     Endian: gimli::Endianity,
 {
-    let pieces = evaluate(unit, expression, true);
+    let pieces = evaluate(None, dwarf_unit, expression, true);
     if pieces.len() != 1 {
         debug!("unsupported number of evaluation pieces: {:?}", pieces);
         return None;
@@ -3236,14 +4100,36 @@ where
     }
 }
 
+fn evaluate_vtable_elem_location<'input, Endian>(
+    dwarf_unit: &DwarfUnit<'input, Endian>,
+    expression: gimli::Expression<Reader<'input, Endian>>,
+) -> Option<u64>
+where
+    Endian: gimli::Endianity,
+{
+    let pieces = evaluate(None, dwarf_unit, expression, false);
+    if pieces.len() != 1 {
+        debug!("unsupported number of evaluation pieces: {:?}", pieces);
+        return None;
+    }
+    match pieces[0].location {
+        gimli::Location::Address { address } => Some(address),
+        _ => {
+            debug!("unknown DW_AT_vtable_elem_location result: {:?}", pieces);
+            None
+        }
+    }
+}
+
 fn evaluate_variable_location<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     expression: gimli::Expression<Reader<'input, Endian>>,
 ) -> Option<(Address, Size)>
 where
     Endian: gimli::Endianity,
 {
-    let pieces = evaluate(unit, expression, false);
+    let pieces = evaluate(Some(dwarf), dwarf_unit, expression, false);
     let mut result = None;
     for piece in &*pieces {
         match piece.location {
@@ -3273,14 +4159,15 @@ where
 }
 
 fn evaluate_local_variable_location<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     range: Range,
     expression: gimli::Expression<Reader<'input, Endian>>,
     variable: &mut LocalVariable<'input>,
 ) where
     Endian: gimli::Endianity,
 {
-    let pieces = match evaluate_simple(unit, expression, false) {
+    let pieces = match evaluate_simple(dwarf, dwarf_unit, expression, false) {
         Ok(locations) => locations,
         Err(_e) => {
             // This happens a lot, not sure if bugs or bad DWARF.
@@ -3318,14 +4205,15 @@ fn evaluate_local_variable_location<'input, Endian>(
 }
 
 fn evaluate_parameter_location<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     range: Range,
     expression: gimli::Expression<Reader<'input, Endian>>,
     parameter: &mut Parameter<'input>,
 ) where
     Endian: gimli::Endianity,
 {
-    let pieces = match evaluate_simple(unit, expression, false) {
+    let pieces = match evaluate_simple(dwarf, dwarf_unit, expression, false) {
         Ok(locations) => locations,
         Err(_e) => {
             // This happens a lot, not sure if bugs or bad DWARF.
@@ -3340,14 +4228,15 @@ fn evaluate_parameter_location<'input, Endian>(
 }
 
 fn evaluate_simple<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf: &DwarfDebugInfo<'input, Endian>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     expression: gimli::Expression<Reader<'input, Endian>>,
     _object_address: bool,
 ) -> Result<Vec<Piece>>
 where
     Endian: gimli::Endianity + 'input,
 {
-    let encoding = unit.encoding();
+    let encoding = dwarf_unit.encoding();
     let addr_mask = if encoding.address_size == 8 {
         !0u64
     } else {
@@ -3437,9 +4326,15 @@ where
                     address: Address::new(address),
                 });
             }
-            gimli::Operation::AddressIndex { .. } | gimli::Operation::ConstantIndex { .. } => {
-                // Unimplemented.
-                stack.push(Location::Other);
+            gimli::Operation::AddressIndex { index } => {
+                let address = dwarf.read.address(dwarf_unit, index)?;
+                stack.push(Location::Address {
+                    address: Address::new(address),
+                });
+            }
+            gimli::Operation::ConstantIndex { index } => {
+                let value = dwarf.read.address(dwarf_unit, index)?;
+                stack.push(Location::Literal { value });
             }
             gimli::Operation::TLS => {
                 let location = match pop(&mut stack)? {
@@ -3640,14 +4535,15 @@ where
 }
 
 fn evaluate<'input, Endian>(
-    unit: &gimli::UnitHeader<Reader<'input, Endian>>,
+    dwarf: Option<&DwarfDebugInfo<'input, Endian>>,
+    dwarf_unit: &DwarfUnit<'input, Endian>,
     expression: gimli::Expression<Reader<'input, Endian>>,
     object_address: bool,
 ) -> Vec<gimli::Piece<Reader<'input, Endian>>>
 where
     Endian: gimli::Endianity + 'input,
 {
-    let mut evaluation = expression.evaluation(unit.encoding());
+    let mut evaluation = expression.evaluation(dwarf_unit.encoding());
     if object_address {
         evaluation.set_object_address(0);
         evaluation.set_initial_value(0);
@@ -3661,6 +4557,17 @@ where
             Ok(gimli::EvaluationResult::RequiresRelocatedAddress(address)) => {
                 result = evaluation.resume_with_relocated_address(address);
             }
+            Ok(gimli::EvaluationResult::RequiresIndexedAddress { index, .. }) => {
+                // Relocations for .debug_addr have already been applied by the reader.
+                let address = match dwarf.map(|dwarf| dwarf.read.address(dwarf_unit, index)) {
+                    Some(Ok(address)) => address,
+                    _ => {
+                        debug!("unresolved indexed address: {:?}", index);
+                        return Vec::new();
+                    }
+                };
+                result = evaluation.resume_with_indexed_address(address);
+            }
             Ok(_x) => {
                 debug!("incomplete evaluation: {:?}", _x);
                 return Vec::new();
@@ -3824,7 +4731,8 @@ fn parse_source_file<'input, Endian>(
 {
     match attr.value() {
         gimli::AttributeValue::FileIndex(val) => {
-            if val != 0 {
+            // File index 0 is only valid from DWARF 5 onwards.
+            if val != 0 || dwarf_unit.header.version() >= 5 {
                 if let Some(ref line) = dwarf_unit.line_program {
                     if let Some(entry) = line.header().file(val) {
                         source.file = dwarf.string(dwarf_unit, entry.path_name());
@@ -4065,6 +4973,10 @@ where
     }
 
     let cie = fde.cie();
+    cfi.push((
+        Address::none(),
+        CfiDirective::ReturnColumn(cie.return_address_register().into()),
+    ));
     let mut address = 0;
     let mut instructions = cie.instructions(section, bases);
     while let Ok(Some(instruction)) = instructions.next() {
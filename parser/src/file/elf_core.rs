@@ -0,0 +1,145 @@
+use std::borrow::Cow;
+use std::str;
+
+use object::elf;
+use object::read::elf::{FileHeader, ProgramHeader};
+use object::Endianness;
+
+use crate::range::{Range, RangeList};
+use crate::unit::Unit;
+use crate::Result;
+
+/// Create a unit for each file that is mapped in an ELF core file.
+///
+/// The mapped files are read from the `NT_FILE` note.
+pub(crate) fn parse<'input>(data: &'input [u8], is_64: bool) -> Result<Vec<Unit<'input>>> {
+    if is_64 {
+        parse_elf::<elf::FileHeader64<Endianness>>(data)
+    } else {
+        parse_elf::<elf::FileHeader32<Endianness>>(data)
+    }
+}
+
+fn parse_elf<'input, Elf>(data: &'input [u8]) -> Result<Vec<Unit<'input>>>
+where
+    Elf: FileHeader<Endian = Endianness>,
+{
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    let mut units: Vec<Unit> = Vec::new();
+    for segment in header.program_headers(endian, data)? {
+        let mut notes = match segment.notes(endian, data)? {
+            Some(notes) => notes,
+            None => continue,
+        };
+        while let Some(note) = notes.next()? {
+            if note.n_type(endian) != elf::NT_FILE || note.name() != b"CORE" {
+                continue;
+            }
+            let files = match parse_files(note.desc(), endian, header.is_type_64()) {
+                Some(files) => files,
+                None => {
+                    debug!("invalid NT_FILE note");
+                    continue;
+                }
+            };
+            for (path, range) in files {
+                // A file usually has several mappings, so combine them into one unit.
+                match units
+                    .iter_mut()
+                    .find(|unit| unit.name.as_deref() == Some(path))
+                {
+                    Some(unit) => unit.ranges.push(range),
+                    None => {
+                        let mut ranges = RangeList::default();
+                        ranges.push(range);
+                        units.push(Unit {
+                            name: Some(Cow::Borrowed(path)),
+                            ranges,
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+    }
+    for unit in &mut units {
+        unit.ranges.sort();
+    }
+    Ok(units)
+}
+
+// The note contains the number of mappings, the page size, then the start, end and
+// file offset of each mapping, followed by the path of each mapping.
+fn parse_files(mut desc: &[u8], endian: Endianness, is_64: bool) -> Option<Vec<(&str, Range)>> {
+    let mut word = || -> Option<u64> {
+        let size = if is_64 { 8 } else { 4 };
+        if desc.len() < size {
+            return None;
+        }
+        let (bytes, rest) = desc.split_at(size);
+        desc = rest;
+        let mut value = [0; 8];
+        if endian == Endianness::Little {
+            value[..size].copy_from_slice(bytes);
+            Some(u64::from_le_bytes(value))
+        } else {
+            value[8 - size..].copy_from_slice(bytes);
+            Some(u64::from_be_bytes(value))
+        }
+    };
+
+    let count = word()?;
+    let _page_size = word()?;
+    let mut ranges = Vec::new();
+    for _ in 0..count {
+        let begin = word()?;
+        let end = word()?;
+        let _offset = word()?;
+        ranges.push(Range { begin, end });
+    }
+
+    let mut files = Vec::with_capacity(ranges.len());
+    let mut paths = desc.split(|c| *c == 0);
+    for range in ranges {
+        let path = str::from_utf8(paths.next()?).ok()?;
+        files.push((path, range));
+    }
+    Some(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn files() {
+        let mut desc = Vec::new();
+        for word in &[2u64, 0x1000, 0x1000, 0x2000, 0, 0x5000, 0x6000, 0x1000] {
+            desc.extend_from_slice(&word.to_le_bytes());
+        }
+        desc.extend_from_slice(b"/bin/a\0/lib/b\0");
+        let files = parse_files(&desc, Endianness::Little, true).unwrap();
+        assert_eq!(
+            files,
+            [
+                (
+                    "/bin/a",
+                    Range {
+                        begin: 0x1000,
+                        end: 0x2000
+                    }
+                ),
+                (
+                    "/lib/b",
+                    Range {
+                        begin: 0x5000,
+                        end: 0x6000
+                    }
+                ),
+            ]
+        );
+
+        assert!(parse_files(&desc[..40], Endianness::Little, true).is_none());
+    }
+}
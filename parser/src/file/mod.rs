@@ -1,25 +1,36 @@
 use std::borrow::Cow;
+use std::cmp;
 use std::default::Default;
 use std::fs;
 use std::mem;
-use std::ops::Deref;
-use std::sync::Mutex;
+use std::ops::{self, Deref};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+mod archive;
+mod cache;
 mod dwarf;
+mod elf_core;
+mod wasm;
 
 use fnv::FnvHashMap as HashMap;
+use fnv::FnvHashSet as HashSet;
 use gimli;
 use memmap;
+use object::read::archive::ArchiveFile;
 use object::{self, Object, ObjectSection, ObjectSegment, ObjectSymbol, ObjectSymbolTable};
 
 use crate::cfi::Cfi;
 use crate::function::{Function, FunctionDetails, FunctionOffset};
 use crate::location::Register;
+use crate::namespace::Namespace;
 use crate::range::{Range, RangeList};
-use crate::types::{Enumerator, Type, TypeOffset};
+use crate::raw::RawEntry;
+use crate::source::Source;
+use crate::types::{Enumerator, Type, TypeKind, TypeOffset};
 use crate::unit::Unit;
 use crate::variable::Variable;
-use crate::{Address, Result, Size};
+use crate::{Address, Error, Result, Size};
 
 pub(crate) enum DebugInfo<'input, Endian>
 where
@@ -65,6 +76,18 @@ where
             DebugInfo::Dwarf(dwarf) => dwarf.get_register_name(machine, register),
         }
     }
+
+    fn get_raw_entry(&self, offset: usize) -> Option<RawEntry> {
+        match self {
+            DebugInfo::Dwarf(dwarf) => dwarf.get_raw_entry(offset),
+        }
+    }
+
+    fn get_line_source(&self, address: u64) -> Option<Source<'input>> {
+        match self {
+            DebugInfo::Dwarf(dwarf) => dwarf.get_line_source(address),
+        }
+    }
 }
 
 pub(crate) struct Arena {
@@ -126,22 +149,25 @@ pub use object::Architecture;
 pub struct FileContext {
     // Self-referential, not actually `static.
     file: File<'static>,
-    _map: memmap::Mmap,
+    // Shared by the members of an archive.
+    _map: Arc<memmap::Mmap>,
+    _debug_map: Option<memmap::Mmap>,
     _arena: Box<Arena>,
 }
 
 impl FileContext {
-    fn new<F>(map: memmap::Mmap, f: F) -> Result<FileContext>
+    fn new<F>(map: Arc<memmap::Mmap>, debug_map: Option<memmap::Mmap>, f: F) -> Result<FileContext>
     where
-        F: for<'a> FnOnce(&'a [u8], &'a Arena) -> Result<File<'a>>,
+        F: for<'a> FnOnce(&'a [u8], Option<&'a [u8]>, &'a Arena) -> Result<File<'a>>,
     {
         let arena = Box::new(Arena::new());
-        let file = f(&map, &arena)?;
+        let file = f(&map, debug_map.as_deref(), &arena)?;
         Ok(FileContext {
-            // `file` only borrows from `map` and `arena`, which we are preserving
-            // without moving.
+            // `file` only borrows from `map`, `debug_map` and `arena`, which we are
+            // preserving without moving.
             file: unsafe { mem::transmute::<File<'_>, File<'static>>(file) },
             _map: map,
+            _debug_map: debug_map,
             _arena: arena,
         })
     }
@@ -152,15 +178,183 @@ impl FileContext {
     }
 }
 
+fn map_file(path: &Path) -> Result<memmap::Mmap> {
+    let handle = fs::File::open(path).map_err(Error::Open)?;
+    unsafe { memmap::Mmap::map(&handle) }.map_err(Error::Mmap)
+}
+
+/// Parse an object file, distinguishing unrecognized formats from invalid files.
+fn parse_object(data: &[u8]) -> Result<object::File<'_>> {
+    object::FileKind::parse(data).map_err(Error::UnknownFormat)?;
+    object::File::parse(data).map_err(Error::Object)
+}
+
+/// Find a separate debuginfo file for the file at `path`.
+///
+/// This checks the conventional locations `<path>.debug` and `<dir>/.debug/<name>`.
+fn find_debug_file(path: &Path) -> Option<PathBuf> {
+    let mut sibling = path.as_os_str().to_owned();
+    sibling.push(".debug");
+    let mut candidates = vec![PathBuf::from(sibling)];
+    if let Some(name) = path.file_name() {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        candidates.push(dir.join(".debug").join(name));
+    }
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+// The address of the first section in a relocatable object.
+//
+// This is not 0 so that the first function or variable isn't treated as
+// having no address.
+const RELOCATABLE_BASE: u64 = 0x1000;
+
+/// The addresses to use for the sections of an object file.
+///
+/// All sections in an ELF relocatable object have an address of 0, so the
+/// allocated sections are assigned consecutive addresses instead, in the
+/// order that they are stored. Symbols and relocation targets are adjusted
+/// to match.
+#[derive(Debug, Default)]
+pub(crate) struct SectionAddresses {
+    // Indexed by section index. Empty if the object isn't an ELF relocatable object.
+    addresses: Vec<Option<u64>>,
+}
+
+impl SectionAddresses {
+    pub(crate) fn new<'data: 'file, 'file, O>(object: &'file O) -> Self
+    where
+        O: Object<'data, 'file>,
+    {
+        let mut addresses = Vec::new();
+        if object.kind() != object::ObjectKind::Relocatable {
+            return SectionAddresses { addresses };
+        }
+        let mut address = RELOCATABLE_BASE;
+        for section in object.sections() {
+            let index = section.index().0;
+            if addresses.len() <= index {
+                addresses.resize(index + 1, None);
+            }
+            let alloc = match section.flags() {
+                object::SectionFlags::Elf { sh_flags } => {
+                    sh_flags & u64::from(object::elf::SHF_ALLOC) != 0
+                }
+                _ => false,
+            };
+            if !alloc || section.size() == 0 {
+                continue;
+            }
+            let name = section.name().unwrap_or("<unknown>");
+            let align = cmp::max(section.align(), 1);
+            if !align.is_power_of_two() {
+                warn!("section {}: invalid alignment 0x{:x}", name, align);
+                continue;
+            }
+            let start = match address.checked_add(align - 1) {
+                Some(start) => start & !(align - 1),
+                None => {
+                    warn!("section {}: address overflow", name);
+                    continue;
+                }
+            };
+            let end = match start.checked_add(section.size()) {
+                Some(end) => end,
+                None => {
+                    warn!("section {}: address overflow", name);
+                    continue;
+                }
+            };
+            addresses[index] = Some(start);
+            address = end;
+        }
+        // Other formats don't have allocation flags.
+        if addresses.iter().all(Option::is_none) {
+            addresses.clear();
+        }
+        SectionAddresses { addresses }
+    }
+
+    fn is_relocatable(&self) -> bool {
+        !self.addresses.is_empty()
+    }
+
+    fn base(&self, index: object::SectionIndex) -> Option<u64> {
+        self.addresses.get(index.0).copied().flatten()
+    }
+
+    /// The address of a section.
+    pub(crate) fn section<'data, S: ObjectSection<'data>>(&self, section: &S) -> u64 {
+        self.base(section.index())
+            .unwrap_or_else(|| section.address())
+    }
+
+    /// The address of a symbol.
+    pub(crate) fn symbol<'data, S: ObjectSymbol<'data>>(&self, symbol: &S) -> u64 {
+        let base = symbol
+            .section_index()
+            .and_then(|index| self.base(index))
+            .unwrap_or(0);
+        symbol.address().wrapping_add(base)
+    }
+}
+
+/// Options that control how a file is parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// The directory to cache parsed units in.
+    ///
+    /// See `File::parse_with_cache`.
+    pub cache_dir: Option<PathBuf>,
+    /// Parse the DWARF debuginfo.
+    ///
+    /// If false, then all symbols are described by the `<symtab>` unit.
+    /// Defaults to true.
+    pub debuginfo: bool,
+    /// Create a `<symtab>` unit for symbols that aren't covered by debuginfo.
+    ///
+    /// Defaults to true.
+    pub symtab: bool,
+    /// Include the ranges of symbols that aren't covered by debuginfo in
+    /// `File::ranges` if `symtab` is false.
+    ///
+    /// Otherwise, these ranges are part of the `<unknown>` unit.
+    /// Defaults to true.
+    pub symtab_ranges: bool,
+    /// The names of the sections to include.
+    ///
+    /// If not empty, then functions, variables, symbols and ranges that are
+    /// not within one of these sections are discarded.
+    /// Defaults to empty.
+    pub sections: Vec<String>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            cache_dir: None,
+            debuginfo: true,
+            symtab: true,
+            symtab_ranges: true,
+            sections: Vec::new(),
+        }
+    }
+}
+
 /// The parsed debuginfo for a single file.
 pub struct File<'input> {
     pub(crate) path: String,
     pub(crate) machine: Architecture,
     pub(crate) segments: Vec<Segment<'input>>,
     pub(crate) sections: Vec<Section<'input>>,
+    // Indices of the loadable sections, sorted by address.
+    pub(crate) section_index: Vec<usize>,
     pub(crate) symbols: Vec<Symbol<'input>>,
     pub(crate) relocations: Vec<Relocation<'input>>,
     pub(crate) units: Vec<Unit<'input>>,
+    // Ranges of symbols without debuginfo, if there is no `<symtab>` unit.
+    pub(crate) symbol_ranges: RangeList,
+    pub(crate) warnings: Vec<String>,
     debug_info: DebugInfo<'input, gimli::RunTimeEndian>,
 }
 
@@ -191,37 +385,218 @@ impl<'input> File<'input> {
         self.debug_info.get_register_name(self.machine, register)
     }
 
+    /// The raw debugging information entry at the given offset, including its children.
+    ///
+    /// The offset is the value returned by methods such as `Function::offset`.
+    pub fn raw_entry(&self, offset: usize) -> Option<RawEntry> {
+        self.debug_info.get_raw_entry(offset)
+    }
+
+    /// Find the function and source location for an address, similar to `addr2line`.
+    ///
+    /// Returns a frame for each function that is inlined at the address, followed
+    /// by a frame for the function containing the address. The source location of
+    /// the innermost frame is found using the line table. For the other frames it is
+    /// the location of the call to the inlined function.
+    ///
+    /// Returns an empty list if the address is not within a function.
+    pub fn symbolize<'a>(
+        &'a self,
+        hash: &'a FileHash<'input>,
+        address: u64,
+    ) -> Vec<SymbolizedFrame<'a, 'input>> {
+        let (unit, function) = match hash.function_at(address) {
+            Some(x) => x,
+            None => return Vec::new(),
+        };
+
+        // Find the inlined functions containing the address, outermost first.
+        let details = function.details(hash);
+        let mut inlined = Vec::new();
+        let mut inlined_functions = details.inlined_functions();
+        while let Some(inlined_function) = inlined_functions
+            .iter()
+            .find(|x| x.ranges().iter().any(|range| range.contains(address)))
+        {
+            match inlined_function.abstract_origin(hash) {
+                Some(origin) => inlined.push((origin, inlined_function.call_source().clone())),
+                None => break,
+            }
+            inlined_functions = inlined_function.inlined_functions();
+        }
+
+        let mut source = self.debug_info.get_line_source(address).unwrap_or_default();
+        let mut frames = Vec::new();
+        for (origin, call_source) in inlined.into_iter().rev() {
+            frames.push(SymbolizedFrame {
+                unit,
+                function: origin,
+                source,
+            });
+            source = call_source;
+        }
+        frames.push(SymbolizedFrame {
+            unit,
+            function,
+            source,
+        });
+        frames
+    }
+
     /// Parse the file with the given path.
     pub fn parse(path: String) -> Result<FileContext> {
-        let handle = match fs::File::open(&path) {
-            Ok(handle) => handle,
-            Err(e) => {
-                return Err(format!("open failed: {}", e).into());
-            }
+        File::parse_with_progress(path, &mut |_, _| {})
+    }
+
+    /// Parse the file with the given path, and report progress.
+    ///
+    /// `progress` is called with the number of compilation units parsed so far
+    /// and the total number of compilation units.
+    pub fn parse_with_progress(
+        path: String,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<FileContext> {
+        File::parse_with_cache(path, None, progress)
+    }
+
+    /// Parse the file with the given path, and report progress.
+    ///
+    /// If `cache_dir` is given, then the parsed units are loaded from a cache
+    /// in that directory if the file hasn't changed since the cache was written.
+    /// Otherwise, the file is parsed and the cache is updated. `progress` is
+    /// not called if the cache is used.
+    pub fn parse_with_cache(
+        path: String,
+        cache_dir: Option<&Path>,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<FileContext> {
+        let options = ParseOptions {
+            cache_dir: cache_dir.map(Path::to_path_buf),
+            ..Default::default()
         };
+        File::parse_with_options(path, &options, progress)
+    }
 
-        let map = match unsafe { memmap::Mmap::map(&handle) } {
-            Ok(map) => map,
-            Err(e) => {
-                return Err(format!("memmap failed: {}", e).into());
+    /// Parse the file with the given path and options, and report progress.
+    ///
+    /// See `File::parse_with_cache` for the use of `progress`.
+    pub fn parse_with_options(
+        path: String,
+        options: &ParseOptions,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<FileContext> {
+        let map = Arc::new(map_file(Path::new(&path))?);
+        if wasm::is_wasm(&map) {
+            return FileContext::new(map, None, |data, _debug_data, arena| {
+                File::parse_wasm(data, path, arena, options, progress)
+            });
+        }
+
+        // Use a separate debuginfo file if the file has none of its own.
+        // Ignore parse errors here; they are reported below.
+        let has_debug_info = object::File::parse(&**map)
+            .map(|object| object.section_by_name(".debug_info").is_some())
+            .unwrap_or(true);
+        let debug_path = if has_debug_info {
+            None
+        } else {
+            find_debug_file(Path::new(&path))
+        };
+        let debug_map = match debug_path {
+            Some(ref debug_path) => {
+                warn!("{}: using debuginfo from {}", path, debug_path.display());
+                Some(map_file(debug_path)?)
             }
+            None => None,
         };
 
         // TODO: split DWARF
         // TODO: PDB
-        FileContext::new(map, |data, strings| {
-            let object = object::File::parse(data)?;
-            File::parse_object(&object, &object, path, strings)
+        FileContext::new(map, debug_map, |data, debug_data, strings| {
+            let object = parse_object(data)?;
+            match (debug_data, debug_path.as_deref()) {
+                (Some(debug_data), Some(debug_path)) => {
+                    let debug_object = parse_object(debug_data)?;
+                    let debug = Some((&debug_object, debug_path));
+                    File::parse_object(&object, debug, data, path, strings, options, progress)
+                }
+                _ => File::parse_object(&object, None, data, path, strings, options, progress),
+            }
         })
     }
 
+    /// Parse the members of the static archive with the given path.
+    ///
+    /// Returns `None` if the file is not an archive. The members of thin
+    /// archives are read from the paths that they reference. Members that
+    /// can't be read or parsed are skipped with a warning.
+    ///
+    /// The path of each member is given as `archive(member)`.
+    pub fn parse_archive(path: String, options: &ParseOptions) -> Result<Option<Vec<FileContext>>> {
+        let map = Arc::new(map_file(Path::new(&path))?);
+        // The cache is keyed on the file path, which members don't have.
+        let options = ParseOptions {
+            cache_dir: None,
+            ..options.clone()
+        };
+        let parse_member = |map, range: ops::Range<usize>, member_path: &str| {
+            FileContext::new(map, None, |data, _debug_data, strings| {
+                let data = data.get(range).ok_or("archive member data is truncated")?;
+                let object = parse_object(data)?;
+                File::parse_object(
+                    &object,
+                    None,
+                    data,
+                    member_path.to_string(),
+                    strings,
+                    &options,
+                    &mut |_, _| {},
+                )
+            })
+        };
+
+        let mut files = Vec::new();
+        if map.starts_with(archive::THIN_MAGIC) {
+            for member in archive::thin_members(&map, Path::new(&path))? {
+                let member_path = format!("{}({})", path, member.name);
+                let file = map_file(&member.path).and_then(|map| {
+                    let size = map.len();
+                    parse_member(Arc::new(map), 0..size, &member_path)
+                });
+                match file {
+                    Ok(file) => files.push(file),
+                    Err(e) => warn!("{}: {}", member_path, e),
+                }
+            }
+        } else if map.starts_with(archive::MAGIC) {
+            let archive = ArchiveFile::parse(&**map).map_err(Error::Object)?;
+            for member in archive.members() {
+                let member = member.map_err(Error::Object)?;
+                let member_path = format!("{}({})", path, String::from_utf8_lossy(member.name()));
+                let (offset, size) = member.file_range();
+                let range = offset as usize..(offset + size) as usize;
+                match parse_member(map.clone(), range, &member_path) {
+                    Ok(file) => files.push(file),
+                    Err(e) => warn!("{}: {}", member_path, e),
+                }
+            }
+        } else {
+            return Ok(None);
+        }
+        Ok(Some(files))
+    }
+
     fn parse_object(
         object: &object::File<'input>,
-        debug_object: &object::File<'input>,
+        debug: Option<(&object::File<'input>, &Path)>,
+        data: &'input [u8],
         path: String,
         arena: &'input Arena,
+        options: &ParseOptions,
+        progress: &mut dyn FnMut(usize, usize),
     ) -> Result<File<'input>> {
         let machine = object.architecture();
+        let section_addresses = SectionAddresses::new(object);
         let mut segments = Vec::new();
         for segment in object.segments() {
             if let Ok(bytes) = segment.data() {
@@ -231,23 +606,51 @@ impl<'input> File<'input> {
                 });
             }
         }
+        // Relocatable objects have no segments, so use the section data instead.
+        if section_addresses.is_relocatable() {
+            for section in object.sections() {
+                if let (Some(address), Ok(bytes)) =
+                    (section_addresses.base(section.index()), section.data())
+                {
+                    if !bytes.is_empty() {
+                        segments.push(Segment { address, bytes });
+                    }
+                }
+            }
+        }
 
         let mut sections = Vec::new();
         for section in object.sections() {
             let name = Some(section.name()?).map(|x| Cow::Owned(x.to_string()));
             let segment = section.segment_name()?.map(|x| Cow::Owned(x.to_string()));
-            let address = if section.address() != 0 {
-                Some(section.address())
-            } else {
-                None
+            let address = match section_addresses.section(&section) {
+                0 => None,
+                address => Some(address),
             };
             let size = section.size();
+            let align = section.align();
+            let kind = match section.kind() {
+                object::SectionKind::Text => SectionKind::Code,
+                object::SectionKind::Data => SectionKind::Data,
+                object::SectionKind::ReadOnlyData | object::SectionKind::ReadOnlyString => {
+                    SectionKind::ReadOnlyData
+                }
+                object::SectionKind::UninitializedData | object::SectionKind::Common => {
+                    SectionKind::UninitializedData
+                }
+                object::SectionKind::Tls => SectionKind::Tls,
+                object::SectionKind::UninitializedTls => SectionKind::UninitializedTls,
+                object::SectionKind::Debug => SectionKind::Debug,
+                _ => SectionKind::Other,
+            };
             if size != 0 {
                 sections.push(Section {
                     name,
                     segment,
                     address,
                     size,
+                    align,
+                    kind,
                 });
             }
         }
@@ -255,8 +658,7 @@ impl<'input> File<'input> {
         // TODO: symbols from debug_object too?
         let mut symbols = Vec::new();
         for symbol in object.symbols() {
-            // TODO: handle relocatable objects
-            let address = symbol.address();
+            let address = section_addresses.symbol(&symbol);
             if address == 0 {
                 continue;
             }
@@ -280,6 +682,8 @@ impl<'input> File<'input> {
                 kind,
                 address,
                 size,
+                global: symbol.is_global(),
+                weak: symbol.is_weak(),
             });
         }
 
@@ -304,29 +708,148 @@ impl<'input> File<'input> {
             }
         }
 
+        let (debug_object, debug_path) = match debug {
+            Some((debug_object, debug_path)) => (debug_object, Some(debug_path)),
+            None => (object, None),
+        };
         let endian = if debug_object.is_little_endian() {
             gimli::RunTimeEndian::Little
         } else {
             gimli::RunTimeEndian::Big
         };
 
-        let (units, debug_info) = dwarf::parse(endian, debug_object, arena)?;
+        let cache_dir = options.cache_dir.as_deref();
+        let cache_key = match cache_dir {
+            Some(_) => {
+                match cache::Key::new(&path, debug_path, object.build_id().ok().flatten(), options)
+                {
+                    Ok(key) => Some(key),
+                    Err(e) => {
+                        warn!("{}: not using cache: {}", path, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+        if let (Some(cache_dir), Some(key)) = (cache_dir, &cache_key) {
+            if let Some((units, symbol_ranges, warnings)) = cache::load(cache_dir, key, arena) {
+                let debug_info = dwarf::load_units(endian, debug_object, arena)?;
+                let mut file = File {
+                    path,
+                    machine,
+                    segments,
+                    sections,
+                    section_index: Vec::new(),
+                    symbols,
+                    relocations,
+                    units,
+                    symbol_ranges,
+                    warnings: Vec::new(),
+                    debug_info,
+                };
+                // The sections and symbols aren't cached, so they still need
+                // normalizing. The cached warnings already include any that
+                // this generates.
+                file.normalize_sections(options);
+                file.warnings = warnings;
+                return Ok(file);
+            }
+        }
+
+        let mut warnings = Vec::new();
+        let (mut units, debug_info) = if options.debuginfo {
+            dwarf::parse(endian, debug_object, arena, &mut warnings, progress)?
+        } else {
+            (Vec::new(), dwarf::load_units(endian, debug_object, arena)?)
+        };
+        if object.format() == object::BinaryFormat::Elf && object.kind() == object::ObjectKind::Core
+        {
+            units.extend(elf_core::parse(data, object.is_64())?);
+        }
         let mut file = File {
             path,
             machine,
             segments,
             sections,
+            section_index: Vec::new(),
             symbols,
             relocations,
             units,
+            symbol_ranges: RangeList::default(),
+            warnings,
             debug_info,
         };
-        file.normalize();
+        file.finish(options);
+        if let (Some(cache_dir), Some(key)) = (cache_dir, &cache_key) {
+            if let Err(e) = cache::store(
+                cache_dir,
+                key,
+                &file.units,
+                &file.symbol_ranges,
+                &file.warnings,
+            ) {
+                warn!("{}: failed to write cache: {}", file.path, e);
+            }
+        }
         Ok(file)
     }
 
-    fn normalize(&mut self) {
-        self.symbols.sort_by(|a, b| a.address.cmp(&b.address));
+    // Parse a WebAssembly module.
+    //
+    // The DWARF is read from custom sections. The cache is not used.
+    fn parse_wasm(
+        data: &'input [u8],
+        path: String,
+        arena: &'input Arena,
+        options: &ParseOptions,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<File<'input>> {
+        let module = wasm::Module::parse(data)?;
+        let mut warnings = Vec::new();
+        let (units, debug_info) = dwarf::parse_sections(
+            gimli::RunTimeEndian::Little,
+            arena,
+            |name| {
+                if options.debuginfo {
+                    module.custom_section(name)
+                } else {
+                    None
+                }
+            },
+            &mut warnings,
+            progress,
+        )?;
+        let mut file = File {
+            path,
+            machine: Architecture::Wasm32,
+            segments: Vec::new(),
+            sections: module.sections,
+            section_index: Vec::new(),
+            symbols: module.symbols,
+            relocations: Vec::new(),
+            units,
+            symbol_ranges: RangeList::default(),
+            warnings,
+            debug_info,
+        };
+        file.finish(options);
+        Ok(file)
+    }
+
+    // Processing that is common to all file formats after the units are parsed.
+    fn finish(&mut self, options: &ParseOptions) {
+        self.normalize(options);
+        let hash = FileHash::new(self);
+        let unresolved_types = self.unresolved_types(&hash);
+        for offset in unresolved_types.iter().filter_map(|offset| offset.get()) {
+            self.warnings
+                .push(format!("unresolved type at offset 0x{:x}", offset));
+        }
+    }
+
+    fn normalize(&mut self, options: &ParseOptions) {
+        let selected = self.normalize_sections(options);
         let mut used_symbols = vec![false; self.symbols.len()];
 
         // Set symbol names on functions/variables.
@@ -341,6 +864,13 @@ impl<'input> File<'input> {
                     ) {
                         function.symbol_name = symbol.name;
                     }
+                    // Symbols for the other parts of non-contiguous functions
+                    // (such as `.cold` parts) are covered by this function too.
+                    for range in &function.ranges {
+                        if range.begin != address {
+                            Self::get_symbol(&self.symbols, &mut used_symbols, range.begin, None);
+                        }
+                    }
                 }
             }
 
@@ -358,6 +888,33 @@ impl<'input> File<'input> {
             }
         }
 
+        // Symbols within the ranges of existing units are already covered by
+        // debuginfo, even if they didn't match a function or variable.
+        // Note that `RangeList` can't be used for this because it merges
+        // ranges across padding.
+        let hash = FileHash::new(self);
+        let mut covered = Vec::new();
+        for unit in &self.units {
+            covered.extend_from_slice(unit.ranges.list());
+            for function in &unit.functions {
+                covered.extend(function.ranges());
+            }
+            for variable in &unit.variables {
+                covered.extend(variable.range(&hash));
+            }
+        }
+        covered.sort();
+        let mut unit_ranges: Vec<Range> = Vec::with_capacity(covered.len());
+        for range in covered {
+            if let Some(prev) = unit_ranges.last_mut() {
+                if range.begin <= prev.end {
+                    prev.end = cmp::max(prev.end, range.end);
+                    continue;
+                }
+            }
+            unit_ranges.push(range);
+        }
+
         // Create a unit for symbols that don't have debuginfo.
         let mut unit = Unit::default();
         unit.name = Some(Cow::Borrowed("<symtab>"));
@@ -365,10 +922,11 @@ impl<'input> File<'input> {
             if *used {
                 continue;
             }
-            unit.ranges.push(Range {
-                begin: symbol.address,
-                end: symbol.address + symbol.size,
-            });
+            let index = unit_ranges.partition_point(|range| range.begin <= symbol.address);
+            if index > 0 && unit_ranges[index - 1].contains(symbol.address) {
+                continue;
+            }
+            unit.ranges.push(symbol.address());
             match symbol.kind() {
                 SymbolKind::Variable => {
                     unit.variables.push(Variable {
@@ -391,18 +949,137 @@ impl<'input> File<'input> {
             }
         }
         unit.ranges.sort();
-        self.units.push(unit);
+        if options.symtab {
+            self.units.push(unit);
+        } else if options.symtab_ranges {
+            self.symbol_ranges = unit.ranges;
+        }
 
         // Create a unit for all remaining address ranges.
         let mut unit = Unit::default();
         unit.name = Some(Cow::Borrowed("<unknown>"));
         unit.ranges = self.unknown_ranges();
+        if let Some(selected) = &selected {
+            unit.ranges = unit.ranges.intersect(selected);
+        }
         self.units.push(unit);
     }
 
+    // Fix up the sections and symbols, and apply the section selection.
+    //
+    // Returns the address ranges of the selected sections, if any.
+    fn normalize_sections(&mut self, options: &ParseOptions) -> Option<RangeList> {
+        Self::clamp_sizes(&mut self.sections, &mut self.symbols, &mut self.warnings);
+        self.section_index = Self::section_index(&self.sections);
+        self.symbols.sort_by(|a, b| a.address.cmp(&b.address));
+        if options.sections.is_empty() {
+            None
+        } else {
+            Some(self.select_sections(&options.sections))
+        }
+    }
+
+    // Discard everything that is outside the sections with the given names,
+    // and return the address ranges of those sections.
+    fn select_sections(&mut self, names: &[String]) -> RangeList {
+        let mut selected = RangeList::default();
+        for section in &self.sections {
+            if let (Some(name), Some(range)) = (section.name(), section.address()) {
+                if names.iter().any(|n| n == name) {
+                    selected.push(range);
+                }
+            }
+        }
+        for name in names {
+            if !self
+                .sections
+                .iter()
+                .any(|section| section.name() == Some(name))
+            {
+                warn!("{}: no section named {}", self.path, name);
+            }
+        }
+        selected.sort();
+        let contains = |address| selected.list().iter().any(|range| range.contains(address));
+
+        self.symbols.retain(|symbol| contains(symbol.address));
+        for unit in &mut self.units {
+            unit.functions.retain(|function| match function.address() {
+                Some(address) => contains(address),
+                None => true,
+            });
+            for function in &mut unit.functions {
+                function.ranges.retain(|range| contains(range.begin));
+            }
+            unit.variables.retain(|variable| match variable.address() {
+                Some(address) => contains(address),
+                None => true,
+            });
+            unit.ranges = unit.ranges.intersect(&selected);
+        }
+        selected
+    }
+
+    // Return the indices of the loadable sections, sorted by address.
+    //
+    // Uninitialized TLS sections are excluded because they do not occupy
+    // any address space, and so may overlap the following section.
+    fn section_index(sections: &[Section]) -> Vec<usize> {
+        let mut index: Vec<usize> = (0..sections.len())
+            .filter(|&i| {
+                sections[i].address.is_some() && sections[i].kind != SectionKind::UninitializedTls
+            })
+            .collect();
+        index.sort_by_key(|&i| sections[i].address);
+        index
+    }
+
+    // Binary search the sorted section index for the section containing the address.
+    fn find_section(sections: &[Section], index: &[usize], address: u64) -> Option<usize> {
+        let i = index
+            .partition_point(|&i| matches!(sections[i].address, Some(begin) if begin <= address));
+        let i = *index.get(i.checked_sub(1)?)?;
+        match sections[i].address() {
+            Some(range) if range.contains(address) => Some(i),
+            _ => None,
+        }
+    }
+
+    // Clamp the sizes of sections and symbols whose ranges would extend past
+    // the end of the address space, and add a warning for each.
+    fn clamp_sizes(sections: &mut [Section], symbols: &mut [Symbol], warnings: &mut Vec<String>) {
+        for section in sections {
+            if let Some(address) = section.address {
+                if address.checked_add(section.size).is_none() {
+                    warnings.push(format!(
+                        "section {} at 0x{:x} has invalid size 0x{:x}",
+                        section.name().unwrap_or("<anon>"),
+                        address,
+                        section.size
+                    ));
+                    section.size = u64::MAX - address;
+                }
+            }
+        }
+        for symbol in symbols {
+            if symbol.address.checked_add(symbol.size).is_none() {
+                warnings.push(format!(
+                    "symbol {} at 0x{:x} has invalid size 0x{:x}",
+                    symbol.name.unwrap_or("<anon>"),
+                    symbol.address,
+                    symbol.size
+                ));
+                symbol.size = u64::MAX - symbol.address;
+            }
+        }
+    }
+
     // Determine if the symbol at the given address has the given name.
-    // There may be multiple symbols for the same address.
-    // If none match the given name, then return the first one.
+    // There may be multiple symbols for the same address, and all of them
+    // are marked as used. If any of them match the given name, then return
+    // `None`. Otherwise return the preferred symbol: global symbols before
+    // local symbols, then strong symbols before weak symbols, and then the
+    // first symbol in address order.
     fn get_symbol<'sym>(
         symbols: &'sym [Symbol<'input>],
         used_symbols: &mut [bool],
@@ -414,6 +1091,7 @@ impl<'input> File<'input> {
                 index -= 1;
             }
             let mut found = false;
+            let mut best: Option<&Symbol> = None;
             for (symbol, used_symbol) in (&symbols[index..])
                 .iter()
                 .zip((&mut used_symbols[index..]).iter_mut())
@@ -425,11 +1103,17 @@ impl<'input> File<'input> {
                 if symbol.name() == name {
                     found = true;
                 }
+                // Prefer global symbols, then strong symbols, then the first symbol.
+                let rank = |symbol: &Symbol| (symbol.global, !symbol.weak);
+                match best {
+                    Some(best) if rank(best) >= rank(symbol) => {}
+                    _ => best = Some(symbol),
+                }
             }
             if found {
                 None
             } else {
-                Some(&symbols[index])
+                best
             }
         } else {
             None
@@ -452,7 +1136,7 @@ impl<'input> File<'input> {
     pub fn segment_bytes(&self, range: Range) -> Option<&'input [u8]> {
         for segment in &self.segments {
             if range.begin >= segment.address
-                && range.end <= segment.address + segment.bytes.len() as u64
+                && range.end <= segment.address.saturating_add(segment.bytes.len() as u64)
             {
                 let begin = (range.begin - segment.address) as usize;
                 let len = (range.end - range.begin) as usize;
@@ -480,6 +1164,18 @@ impl<'input> File<'input> {
         &self.symbols
     }
 
+    /// Find the loadable section containing the given address.
+    pub fn section_at(&self, address: u64) -> Option<&Section<'input>> {
+        Self::find_section(&self.sections, &self.section_index, address).map(|i| &self.sections[i])
+    }
+
+    /// Classify the given address by the kind of the loadable section containing it.
+    ///
+    /// Returns `None` if the address is not within a loadable section.
+    pub fn classify_address(&self, address: u64) -> Option<SectionKind> {
+        self.section_at(address).map(Section::kind)
+    }
+
     /// A list of relocations in the file.
     #[inline]
     pub fn relocations(&self) -> &[Relocation<'input>] {
@@ -492,11 +1188,59 @@ impl<'input> File<'input> {
         &self.units
     }
 
+    /// A list of errors for parts of the file that could not be parsed.
+    ///
+    /// Compilation units that failed to parse are omitted from `units`.
+    #[inline]
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// Call `f` for each function in each compilation unit.
+    pub fn for_each_function<F>(&self, mut f: F)
+    where
+        F: FnMut(&Unit<'input>, &Function<'input>),
+    {
+        for unit in &self.units {
+            for function in unit.functions() {
+                f(unit, function);
+            }
+        }
+    }
+
+    /// Call `f` for each type in each compilation unit.
+    pub fn for_each_type<F>(&self, mut f: F)
+    where
+        F: FnMut(&Unit<'input>, &Type<'input>),
+    {
+        for unit in &self.units {
+            for ty in unit.types() {
+                f(unit, ty);
+            }
+        }
+    }
+
+    /// Call `f` for each variable in each compilation unit.
+    pub fn for_each_variable<F>(&self, mut f: F)
+    where
+        F: FnMut(&Unit<'input>, &Variable<'input>),
+    {
+        for unit in &self.units {
+            for variable in unit.variables() {
+                f(unit, variable);
+            }
+        }
+    }
+
     /// A list of address ranges covered by the compilation units.
     ///
-    /// This includes both `Unit::ranges` and `Unit::unknown_ranges`.
+    /// This includes both `Unit::ranges` and `Unit::unknown_ranges`, and
+    /// the ranges of symbols if `ParseOptions::symtab_ranges` was used.
     pub fn ranges(&self, hash: &FileHash) -> RangeList {
         let mut ranges = RangeList::default();
+        for range in self.symbol_ranges.list() {
+            ranges.push(*range);
+        }
         for unit in &self.units {
             for range in unit.ranges(hash).list() {
                 ranges.push(*range);
@@ -526,22 +1270,74 @@ impl<'input> File<'input> {
         ranges.subtract(&unit_ranges)
     }
 
+    /// A list of type offsets that are referenced by the compilation units,
+    /// but which can't be resolved to a type.
+    ///
+    /// These indicate incomplete or invalid debuginfo. References to types
+    /// in other units are resolved before they are considered unresolved.
+    /// References from function details are not checked.
+    pub fn unresolved_types(&self, hash: &FileHash) -> Vec<TypeOffset> {
+        let mut offsets = Vec::new();
+        for unit in &self.units {
+            for ty in &unit.types {
+                ty.referenced_types(&mut offsets);
+            }
+            for function in &unit.functions {
+                offsets.push(function.return_type);
+            }
+            for variable in &unit.variables {
+                offsets.push(variable.ty);
+            }
+        }
+        offsets.sort();
+        offsets.dedup();
+        offsets.retain(|offset| {
+            offset.is_some() && !hash.types.contains_key(offset) && self.get_type(*offset).is_none()
+        });
+        offsets
+    }
+
     /// The total size of functions in all compilation units.
+    ///
+    /// Functions that are present in more than one unit are only counted once.
     pub fn function_size(&self) -> u64 {
-        let mut size = 0;
+        let mut ranges = RangeList::default();
         for unit in &self.units {
-            size += unit.function_size();
+            for function in &unit.functions {
+                for range in function.ranges() {
+                    ranges.push(range);
+                }
+            }
         }
-        size
+        ranges.sort();
+        ranges.size()
     }
 
     /// The total size of variables in all compilation units.
+    ///
+    /// Variables that are present in more than one unit are only counted once.
     pub fn variable_size(&self, hash: &FileHash) -> u64 {
-        let mut size = 0;
+        let mut ranges = RangeList::default();
         for unit in &self.units {
-            size += unit.variable_size(hash);
+            for variable in &unit.variables {
+                if let Some(range) = variable.range(hash) {
+                    ranges.push(range);
+                }
+            }
         }
-        size
+        ranges.sort();
+        ranges.size()
+    }
+
+    /// The total size of the sections that contain debuginfo.
+    ///
+    /// See `Section::is_debug`.
+    pub fn debug_size(&self) -> u64 {
+        self.sections
+            .iter()
+            .filter(|section| section.is_debug())
+            .map(Section::size)
+            .sum()
     }
 }
 
@@ -559,19 +1355,220 @@ pub struct FileHash<'input> {
     pub types: HashMap<TypeOffset, &'input Type<'input>>,
     // The type corresponding to `TypeOffset::none()`.
     pub(crate) void: Type<'input>,
+    // Cache for `Type::byte_size`.
+    pub(crate) byte_sizes: Mutex<HashMap<TypeOffset, Option<u64>>>,
+    // Names for anonymous types, derived from their uses.
+    pub(crate) anon_type_names: HashMap<TypeOffset, String>,
+    // The entities that have a definition somewhere in the file.
+    definitions: HashSet<DefinitionKey<'input>>,
+    // Cache for `FileHash::function_size`.
+    function_size: Mutex<Option<u64>>,
+    // Cache for `FileHash::variable_size`.
+    variable_size: Mutex<Option<u64>>,
+    // Cache for `FileHash::function_at`.
+    function_ranges: Mutex<Option<Vec<FunctionRange>>>,
+}
+
+// A range of a function, in a list that is sorted by address.
+struct FunctionRange {
+    range: Range,
+    // The greatest end address of this range and all previous ranges in the list.
+    max_end: u64,
+    // Ordering by these prefers earlier functions when ranges overlap.
+    unit_index: usize,
+    function_index: usize,
+}
+
+/// The kind of an entity that may be declared in one place and defined in another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DefinitionKind {
+    Struct,
+    Union,
+    Enumeration,
+    Variable,
+}
+
+/// The kind, namespace path and name of an entity.
+type DefinitionKey<'input> = (DefinitionKind, Vec<Option<&'input str>>, &'input str);
+
+fn definition_key<'input>(
+    kind: DefinitionKind,
+    namespace: &Option<Arc<Namespace<'input>>>,
+    name: Option<&'input str>,
+) -> Option<DefinitionKey<'input>> {
+    name.map(|name| (kind, Namespace::path(namespace), name))
 }
 
 impl<'input> FileHash<'input> {
     /// Create a new `FileHash` for the given `File`.
     pub fn new(file: &'input File<'input>) -> Self {
+        let types = FileHash::types(file);
+        let anon_type_names = FileHash::anon_type_names(file, &types);
+        let definitions = FileHash::definitions(file);
         FileHash {
             file,
             functions_by_address: FileHash::functions_by_address(file),
             functions_by_offset: FileHash::functions_by_offset(file),
             variables_by_address: FileHash::variables_by_address(file),
-            types: FileHash::types(file),
+            types,
             void: Type::void(),
+            byte_sizes: Mutex::new(HashMap::default()),
+            anon_type_names,
+            definitions,
+            function_size: Mutex::new(None),
+            variable_size: Mutex::new(None),
+            function_ranges: Mutex::new(None),
+        }
+    }
+
+    /// The total size of functions in all compilation units.
+    ///
+    /// This is the same as `File::function_size`, but is only calculated once.
+    pub fn function_size(&self) -> u64 {
+        if let Some(size) = *self.function_size.lock().unwrap() {
+            return size;
+        }
+        let size = self.file.function_size();
+        *self.function_size.lock().unwrap() = Some(size);
+        size
+    }
+
+    /// The total size of variables in all compilation units.
+    ///
+    /// This is the same as `File::variable_size`, but is only calculated once.
+    pub fn variable_size(&self) -> u64 {
+        if let Some(size) = *self.variable_size.lock().unwrap() {
+            return size;
+        }
+        let size = self.file.variable_size(self);
+        *self.variable_size.lock().unwrap() = Some(size);
+        size
+    }
+
+    /// The first function in the file with a range containing the given address,
+    /// and the unit containing it.
+    ///
+    /// The index of function ranges is built on the first call.
+    pub(crate) fn function_at(
+        &self,
+        address: u64,
+    ) -> Option<(&'input Unit<'input>, &'input Function<'input>)> {
+        let mut function_ranges = self.function_ranges.lock().unwrap();
+        let ranges = function_ranges.get_or_insert_with(|| FileHash::function_ranges(self.file));
+        let end = ranges.partition_point(|x| x.range.begin <= address);
+        ranges[..end]
+            .iter()
+            .rev()
+            .take_while(|x| x.max_end > address)
+            .filter(|x| x.range.contains(address))
+            .min_by_key(|x| (x.unit_index, x.function_index))
+            .map(|x| {
+                let unit = &self.file.units[x.unit_index];
+                (unit, &unit.functions[x.function_index])
+            })
+    }
+
+    fn function_ranges(file: &File) -> Vec<FunctionRange> {
+        let mut ranges = Vec::new();
+        for (unit_index, unit) in file.units.iter().enumerate() {
+            for (function_index, function) in unit.functions.iter().enumerate() {
+                for range in function.ranges() {
+                    ranges.push(FunctionRange {
+                        range,
+                        max_end: 0,
+                        unit_index,
+                        function_index,
+                    });
+                }
+            }
+        }
+        ranges.sort_by_key(|x| x.range.begin);
+        let mut max_end = 0;
+        for x in &mut ranges {
+            max_end = cmp::max(max_end, x.range.end);
+            x.max_end = max_end;
+        }
+        ranges
+    }
+
+    /// A name for the anonymous struct, union or enumeration at the given offset.
+    ///
+    /// The name is derived from a use of the type. This is the name of a type
+    /// definition for the type if there is one, otherwise the name of a variable
+    /// with the type, otherwise the qualified name of a member with the type.
+    /// If there are multiple candidates, the first in sorted order is used, so
+    /// the name does not depend on the order of units in the file.
+    pub fn anon_type_name(&self, offset: TypeOffset) -> Option<&str> {
+        self.anon_type_names.get(&offset).map(String::as_str)
+    }
+
+    /// Return true if the type is a declaration of a struct, union or enumeration,
+    /// and a definition of the same type exists in the file.
+    ///
+    /// Types are matched by their kind, namespace and name.
+    pub fn has_type_definition(&self, ty: &Type) -> bool {
+        let key = match ty.kind() {
+            TypeKind::Struct(ty) if ty.declaration => {
+                definition_key(DefinitionKind::Struct, &ty.namespace, ty.name)
+            }
+            TypeKind::Union(ty) if ty.declaration => {
+                definition_key(DefinitionKind::Union, &ty.namespace, ty.name)
+            }
+            TypeKind::Enumeration(ty) if ty.declaration => {
+                definition_key(DefinitionKind::Enumeration, &ty.namespace, ty.name)
+            }
+            _ => None,
+        };
+        match key {
+            Some(key) => self.definitions.contains(&key),
+            None => false,
+        }
+    }
+
+    /// Return true if the variable is a declaration, and a definition of the same
+    /// variable exists in the file.
+    ///
+    /// Variables are matched by their namespace and name.
+    pub fn has_variable_definition(&self, variable: &Variable) -> bool {
+        if !variable.declaration {
+            return false;
+        }
+        match definition_key(DefinitionKind::Variable, &variable.namespace, variable.name) {
+            Some(key) => self.definitions.contains(&key),
+            None => false,
+        }
+    }
+
+    /// Returns the set of entities that are defined in the file.
+    fn definitions(file: &File<'input>) -> HashSet<DefinitionKey<'input>> {
+        let mut definitions = HashSet::default();
+        for unit in &file.units {
+            for ty in &unit.types {
+                let key = match ty.kind() {
+                    TypeKind::Struct(ty) if !ty.declaration => {
+                        definition_key(DefinitionKind::Struct, &ty.namespace, ty.name)
+                    }
+                    TypeKind::Union(ty) if !ty.declaration => {
+                        definition_key(DefinitionKind::Union, &ty.namespace, ty.name)
+                    }
+                    TypeKind::Enumeration(ty) if !ty.declaration => {
+                        definition_key(DefinitionKind::Enumeration, &ty.namespace, ty.name)
+                    }
+                    _ => None,
+                };
+                definitions.extend(key);
+            }
+            for variable in &unit.variables {
+                if !variable.declaration {
+                    definitions.extend(definition_key(
+                        DefinitionKind::Variable,
+                        &variable.namespace,
+                        variable.name,
+                    ));
+                }
+            }
         }
+        definitions
     }
 
     /// Returns a map from address to function for all functions in the file.
@@ -615,6 +1612,68 @@ impl<'input> FileHash<'input> {
         variables
     }
 
+    /// Returns a map from offset to name for anonymous types in the file.
+    fn anon_type_names(
+        file: &File<'input>,
+        types: &HashMap<TypeOffset, &Type<'input>>,
+    ) -> HashMap<TypeOffset, String> {
+        // Lower ranks are preferred.
+        const TYPE_DEF: u8 = 0;
+        const VARIABLE: u8 = 1;
+        const MEMBER: u8 = 2;
+
+        let mut names: HashMap<TypeOffset, (u8, String)> = HashMap::default();
+        let mut add = |offset: TypeOffset, rank: u8, name: String| {
+            let is_anon = match types.get(&offset).map(|ty| ty.kind()) {
+                Some(TypeKind::Struct(ty)) => ty.name().is_none(),
+                Some(TypeKind::Union(ty)) => ty.name().is_none(),
+                Some(TypeKind::Enumeration(ty)) => ty.name().is_none(),
+                _ => false,
+            };
+            if !is_anon {
+                return;
+            }
+            match names.get(&offset) {
+                Some((current_rank, current_name))
+                    if (*current_rank, current_name) <= (rank, &name) => {}
+                _ => {
+                    names.insert(offset, (rank, name));
+                }
+            }
+        };
+        for unit in &file.units {
+            for ty in &unit.types {
+                let (parent, members) = match ty.kind() {
+                    TypeKind::Def(def) => {
+                        if let Some(name) = def.name() {
+                            add(def.ty, TYPE_DEF, name.to_string());
+                        }
+                        continue;
+                    }
+                    TypeKind::Struct(ty) => (ty.name(), ty.members()),
+                    TypeKind::Union(ty) => (ty.name(), ty.members()),
+                    _ => continue,
+                };
+                if let Some(parent) = parent {
+                    for member in members {
+                        if let Some(name) = member.name() {
+                            add(member.ty, MEMBER, format!("{}::{}", parent, name));
+                        }
+                    }
+                }
+            }
+            for variable in &unit.variables {
+                if let Some(name) = variable.name() {
+                    add(variable.ty, VARIABLE, name.to_string());
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|(offset, (_, name))| (offset, name))
+            .collect()
+    }
+
     /// Returns a map from offset to type for all types in the file.
     fn types<'a>(file: &'a File<'input>) -> HashMap<TypeOffset, &'a Type<'input>> {
         let mut types = HashMap::default();
@@ -627,6 +1686,40 @@ impl<'input> FileHash<'input> {
     }
 }
 
+/// A function and source location found by `File::symbolize`.
+#[derive(Debug)]
+pub struct SymbolizedFrame<'a, 'input> {
+    pub(crate) unit: &'a Unit<'input>,
+    pub(crate) function: &'a Function<'input>,
+    pub(crate) source: Source<'input>,
+}
+
+impl<'a, 'input> SymbolizedFrame<'a, 'input> {
+    /// The unit containing the outermost function.
+    ///
+    /// This is needed to find the complete path of the source location.
+    #[inline]
+    pub fn unit(&self) -> &'a Unit<'input> {
+        self.unit
+    }
+
+    /// The function for this frame.
+    ///
+    /// For inlined frames, this is the abstract origin of the inlined function.
+    #[inline]
+    pub fn function(&self) -> &'a Function<'input> {
+        self.function
+    }
+
+    /// The source location within the function.
+    ///
+    /// This has no file name if the location is unknown.
+    #[inline]
+    pub fn source(&self) -> &Source<'input> {
+        &self.source
+    }
+}
+
 /// A loadable range of bytes.
 #[derive(Debug)]
 pub struct Segment<'input> {
@@ -636,6 +1729,27 @@ pub struct Segment<'input> {
     pub bytes: &'input [u8],
 }
 
+/// The kind of data that a section contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionKind {
+    /// Executable code.
+    Code,
+    /// Initialized data that is writable.
+    Data,
+    /// Initialized data that is read-only, including constant strings.
+    ReadOnlyData,
+    /// Zero initialized data that has no data in the file, such as `.bss`.
+    UninitializedData,
+    /// Initialized thread-local data.
+    Tls,
+    /// Zero initialized thread-local data, such as `.tbss`.
+    UninitializedTls,
+    /// Debuginfo.
+    Debug,
+    /// Any other kind of section, such as metadata or symbol tables.
+    Other,
+}
+
 /// A named section.
 #[derive(Debug)]
 pub struct Section<'input> {
@@ -643,6 +1757,8 @@ pub struct Section<'input> {
     pub(crate) segment: Option<Cow<'input, str>>,
     pub(crate) address: Option<u64>,
     pub(crate) size: u64,
+    pub(crate) align: u64,
+    pub(crate) kind: SectionKind,
 }
 
 impl<'input> Section<'input> {
@@ -660,7 +1776,7 @@ impl<'input> Section<'input> {
     pub fn address(&self) -> Option<Range> {
         self.address.map(|address| Range {
             begin: address,
-            end: address + self.size,
+            end: address.saturating_add(self.size),
         })
     }
 
@@ -669,6 +1785,102 @@ impl<'input> Section<'input> {
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    /// The required alignment of the section.
+    ///
+    /// This is `sh_addralign` for ELF and the `align` field for Mach-O.
+    #[inline]
+    pub fn align(&self) -> u64 {
+        self.align
+    }
+
+    /// Return true if the section contains debuginfo.
+    ///
+    /// This is true for DWARF sections, including compressed sections and the
+    /// sections in the Mach-O `__DWARF` segment.
+    pub fn is_debug(&self) -> bool {
+        self.segment() == Some("__DWARF")
+            || matches!(self.name(), Some(name) if name.starts_with(".debug_")
+                || name.starts_with(".zdebug_")
+                || name.starts_with("__debug_"))
+    }
+
+    /// Return true if the section is zero initialized and has no data in the file.
+    ///
+    /// This is true for `SHT_NOBITS` sections such as `.bss` for ELF.
+    #[inline]
+    pub fn is_uninitialized(&self) -> bool {
+        matches!(
+            self.kind,
+            SectionKind::UninitializedData | SectionKind::UninitializedTls
+        )
+    }
+
+    /// Return true if the section contains executable code.
+    #[inline]
+    pub fn is_executable(&self) -> bool {
+        self.kind == SectionKind::Code
+    }
+
+    /// The kind of data that the section contains.
+    #[inline]
+    pub fn kind(&self) -> SectionKind {
+        self.kind
+    }
+
+    /// Compare the identifying information of two sections.
+    ///
+    /// Sections are ordered by segment, name, and then address.
+    /// This can be used to sort sections so that corresponding sections in two files
+    /// are paired even if the link order differs.
+    pub fn cmp_id(a: &Section, b: &Section) -> cmp::Ordering {
+        a.segment()
+            .cmp(&b.segment())
+            .then_with(|| a.name().cmp(&b.name()))
+            .then_with(|| a.address.cmp(&b.address))
+    }
+
+    /// Merge sections that have the same segment and name.
+    ///
+    /// The size of a merged section is the total size of the sections, its alignment
+    /// is the largest alignment, and its address is the lowest address.
+    /// This is intended for combining the sections of multiple object files.
+    ///
+    /// Merged sections are returned in the order that they first occur.
+    pub fn merge<'a, I>(sections: I) -> Vec<Section<'input>>
+    where
+        I: IntoIterator<Item = &'a Section<'input>>,
+        'input: 'a,
+    {
+        let mut merged: Vec<Section<'input>> = Vec::new();
+        let mut indices: HashMap<_, usize> = HashMap::default();
+        for section in sections {
+            let key = (section.segment(), section.name());
+            match indices.get(&key) {
+                Some(&index) => {
+                    let entry = &mut merged[index];
+                    entry.size = entry.size.saturating_add(section.size);
+                    entry.align = cmp::max(entry.align, section.align);
+                    entry.address = match (entry.address, section.address) {
+                        (Some(a), Some(b)) => Some(cmp::min(a, b)),
+                        (a, b) => a.or(b),
+                    };
+                }
+                None => {
+                    indices.insert(key, merged.len());
+                    merged.push(Section {
+                        name: section.name.clone(),
+                        segment: section.segment.clone(),
+                        address: section.address,
+                        size: section.size,
+                        align: section.align,
+                        kind: section.kind,
+                    });
+                }
+            }
+        }
+        merged
+    }
 }
 
 /// A symbol kind.
@@ -687,6 +1899,8 @@ pub struct Symbol<'input> {
     pub(crate) kind: SymbolKind,
     pub(crate) address: u64,
     pub(crate) size: u64,
+    pub(crate) global: bool,
+    pub(crate) weak: bool,
 }
 
 impl<'input> Symbol<'input> {
@@ -707,7 +1921,7 @@ impl<'input> Symbol<'input> {
     pub fn address(&self) -> Range {
         Range {
             begin: self.address,
-            end: self.address + self.size,
+            end: self.address.saturating_add(self.size),
         }
     }
 
@@ -716,6 +1930,18 @@ impl<'input> Symbol<'input> {
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    /// Return true if the symbol is visible outside of its compilation unit.
+    #[inline]
+    pub fn is_global(&self) -> bool {
+        self.global
+    }
+
+    /// Return true if the symbol is weak.
+    #[inline]
+    pub fn is_weak(&self) -> bool {
+        self.weak
+    }
 }
 
 /// A relocation.
@@ -745,3 +1971,163 @@ impl<'input> Relocation<'input> {
         self.symbol
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn section(name: &'static str, address: u64) -> Section<'static> {
+        Section {
+            name: Some(Cow::Borrowed(name)),
+            segment: None,
+            address: Some(address),
+            size: 0x10,
+            align: 1,
+            kind: SectionKind::Data,
+        }
+    }
+
+    fn symbol(name: &'static str, global: bool, weak: bool) -> Symbol<'static> {
+        Symbol {
+            name: Some(name),
+            kind: SymbolKind::Function,
+            address: 0x1000,
+            size: 0x10,
+            global,
+            weak,
+        }
+    }
+
+    #[test]
+    fn get_symbol_alias() {
+        let symbols = [
+            symbol("local", false, false),
+            symbol("alias", true, true),
+            symbol("definition", true, false),
+            symbol("other", true, false),
+        ];
+
+        let mut used_symbols = [false; 4];
+        let symbol = File::get_symbol(&symbols, &mut used_symbols, 0x1000, Some("function"));
+        assert_eq!(symbol.and_then(Symbol::name), Some("definition"));
+        assert_eq!(used_symbols, [true; 4]);
+
+        let mut used_symbols = [false; 4];
+        let symbol = File::get_symbol(&symbols, &mut used_symbols, 0x1000, Some("alias"));
+        assert!(symbol.is_none());
+        assert_eq!(used_symbols, [true; 4]);
+
+        let mut used_symbols = [false; 4];
+        let symbol = File::get_symbol(&symbols, &mut used_symbols, 0x2000, Some("function"));
+        assert!(symbol.is_none());
+        assert_eq!(used_symbols, [false; 4]);
+    }
+
+    #[test]
+    fn clamp_sizes() {
+        let mut sections = [section(".text", u64::MAX - 0x8)];
+        let mut symbols = [
+            Symbol {
+                address: u64::MAX - 0x8,
+                ..symbol("overflow", true, false)
+            },
+            Symbol {
+                address: u64::MAX - 0x10,
+                ..symbol("end", true, false)
+            },
+        ];
+        let mut warnings = Vec::new();
+        File::clamp_sizes(&mut sections, &mut symbols, &mut warnings);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(sections[0].address().map(|range| range.end), Some(u64::MAX));
+        assert_eq!(symbols[0].address().end, u64::MAX);
+        assert_eq!(symbols[0].size(), 0x8);
+        assert_eq!(symbols[1].address().end, u64::MAX);
+        assert_eq!(symbols[1].size(), 0x10);
+
+        let mut ranges = RangeList::default();
+        for symbol in &symbols {
+            ranges.push(symbol.address());
+        }
+        ranges.sort();
+        assert_eq!(ranges.size(), 0x10);
+
+        // Accessors saturate even without clamping.
+        let symbol = Symbol {
+            address: u64::MAX,
+            ..symbol("unclamped", true, false)
+        };
+        assert_eq!(symbol.address().end, u64::MAX);
+    }
+
+    #[test]
+    fn find_section() {
+        let mut sections = [
+            section(".data", 0x3000),
+            section(".text", 0x1000),
+            section(".debug_info", 0),
+            section(".tbss", 0x3000),
+            section(".bss", 0x3010),
+        ];
+        sections[1].kind = SectionKind::Code;
+        sections[2].address = None;
+        sections[3].kind = SectionKind::UninitializedTls;
+        sections[4].kind = SectionKind::UninitializedData;
+        let index = File::section_index(&sections);
+        assert_eq!(index, [1, 0, 4]);
+
+        let kind =
+            |address| File::find_section(&sections, &index, address).map(|i| sections[i].kind());
+        assert_eq!(kind(0xfff), None);
+        assert_eq!(kind(0x1000), Some(SectionKind::Code));
+        assert_eq!(kind(0x100f), Some(SectionKind::Code));
+        assert_eq!(kind(0x1010), None);
+        assert_eq!(kind(0x3008), Some(SectionKind::Data));
+        assert_eq!(kind(0x3010), Some(SectionKind::UninitializedData));
+        assert_eq!(kind(0x3020), None);
+    }
+
+    #[test]
+    fn merge_sections() {
+        let mut sections = [
+            section(".text", 0x2000),
+            section(".data", 0x3000),
+            section(".text", 0x1000),
+            section(".text", 0),
+        ];
+        sections[2].align = 16;
+        sections[3].address = None;
+        let merged = Section::merge(&sections);
+        let merged: Vec<_> = merged
+            .iter()
+            .map(|x| (x.name(), x.address.unwrap(), x.size(), x.align()))
+            .collect();
+        assert_eq!(
+            merged,
+            [
+                (Some(".text"), 0x1000, 0x30, 16),
+                (Some(".data"), 0x3000, 0x10, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn cmp_section() {
+        let mut sections_a = [
+            section(".text", 0x1000),
+            section(".data", 0x2000),
+            section(".bss", 0x3000),
+        ];
+        let mut sections_b = [
+            section(".data", 0x1000),
+            section(".bss", 0x2000),
+            section(".text", 0x3000),
+        ];
+        sections_a.sort_by(Section::cmp_id);
+        sections_b.sort_by(Section::cmp_id);
+        let names_a: Vec<_> = sections_a.iter().map(Section::name).collect();
+        let names_b: Vec<_> = sections_b.iter().map(Section::name).collect();
+        assert_eq!(names_a, names_b);
+        assert_eq!(names_a, [Some(".bss"), Some(".data"), Some(".text")]);
+    }
+}
@@ -0,0 +1,467 @@
+use std::borrow::Cow;
+use std::str;
+
+use fnv::FnvHashMap as HashMap;
+
+use crate::file::{Section, SectionKind, Symbol, SymbolKind};
+use crate::Result;
+
+const MAGIC: &[u8] = b"\0asm";
+const VERSION: &[u8] = &[1, 0, 0, 0];
+
+const SECTION_CUSTOM: u8 = 0;
+const SECTION_IMPORT: u8 = 2;
+const SECTION_EXPORT: u8 = 7;
+const SECTION_CODE: u8 = 10;
+
+const EXTERNAL_FUNCTION: u8 = 0;
+const EXTERNAL_TABLE: u8 = 1;
+const EXTERNAL_MEMORY: u8 = 2;
+const EXTERNAL_GLOBAL: u8 = 3;
+const EXTERNAL_TAG: u8 = 4;
+
+const NAME_FUNCTION: u8 = 1;
+
+const LINKING_SYMBOL_TABLE: u8 = 8;
+const SYMBOL_FUNCTION: u8 = 0;
+const SYMBOL_DATA: u8 = 1;
+const SYMBOL_GLOBAL: u8 = 2;
+const SYMBOL_SECTION: u8 = 3;
+const SYMBOL_TAG: u8 = 4;
+const SYMBOL_TABLE: u8 = 5;
+const SYMBOL_BINDING_WEAK: u64 = 0x1;
+const SYMBOL_BINDING_LOCAL: u64 = 0x2;
+const SYMBOL_UNDEFINED: u64 = 0x10;
+const SYMBOL_EXPLICIT_NAME: u64 = 0x40;
+
+/// Return true if the data is a WebAssembly module.
+pub(crate) fn is_wasm(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// The parts of a WebAssembly module that are needed for debuginfo.
+pub(crate) struct Module<'input> {
+    pub(crate) sections: Vec<Section<'input>>,
+    /// A symbol for each named function in the code section.
+    pub(crate) symbols: Vec<Symbol<'input>>,
+    custom_sections: Vec<(&'input str, &'input [u8])>,
+}
+
+impl<'input> Module<'input> {
+    /// Parse the sections of a WebAssembly module.
+    ///
+    /// The address of a function is the offset of its body within the code
+    /// section, which is the address used by DWARF for WebAssembly.
+    /// Function names are read from the `name` section for linked modules,
+    /// and from the symbol table in the `linking` section for object files.
+    pub(crate) fn parse(data: &'input [u8]) -> Result<Module<'input>> {
+        if !data.starts_with(MAGIC) {
+            return Err("not a wasm module".into());
+        }
+        if data.get(MAGIC.len()..MAGIC.len() + VERSION.len()) != Some(VERSION) {
+            return Err("unsupported wasm version".into());
+        }
+
+        let mut sections = Vec::new();
+        let mut custom_sections = Vec::new();
+        let mut imported_functions = 0;
+        let mut exports = Vec::new();
+        // The address and size of each function body.
+        let mut bodies = Vec::new();
+        let mut reader = Reader::new(data, MAGIC.len() + VERSION.len());
+        while !reader.is_empty() {
+            let id = reader.u8()?;
+            let size = reader.uleb()? as usize;
+            let offset = reader.offset;
+            let payload = reader.bytes(size)?;
+            let mut size = size;
+            let name = match id {
+                SECTION_CUSTOM => {
+                    let mut section = Reader::new(payload, 0);
+                    let name = section.name()?;
+                    let data = &payload[section.offset..];
+                    custom_sections.push((name, data));
+                    size = data.len();
+                    name
+                }
+                1 => "type",
+                SECTION_IMPORT => {
+                    imported_functions = parse_imports(payload, offset)?;
+                    "import"
+                }
+                3 => "function",
+                4 => "table",
+                5 => "memory",
+                6 => "global",
+                SECTION_EXPORT => {
+                    exports = parse_exports(payload)?;
+                    "export"
+                }
+                8 => "start",
+                9 => "element",
+                SECTION_CODE => {
+                    bodies = parse_code(payload)?;
+                    "code"
+                }
+                11 => "data",
+                12 => "datacount",
+                13 => "tag",
+                // Later versions of the format may add sections, and these don't
+                // affect the sections that we need.
+                _ => {
+                    warn!("ignoring unknown wasm section id {} at 0x{:x}", id, offset);
+                    continue;
+                }
+            };
+            sections.push(Section {
+                name: Some(Cow::Borrowed(name)),
+                segment: None,
+                address: None,
+                size: size as u64,
+                align: 1,
+                kind: if id == SECTION_CODE {
+                    SectionKind::Code
+                } else {
+                    SectionKind::Other
+                },
+            });
+        }
+
+        // Map from function index to name, global and weak.
+        let mut names = HashMap::default();
+        for &(section_name, payload) in &custom_sections {
+            // The symbols are optional, so an invalid custom section isn't fatal.
+            match section_name {
+                "name" => match parse_function_names(payload) {
+                    Ok(function_names) => {
+                        for (index, name) in function_names {
+                            names.insert(index, (name, exports.contains(&index), false));
+                        }
+                    }
+                    Err(e) => warn!("ignoring wasm name section: {}", e),
+                },
+                "linking" => match parse_function_symbols(payload) {
+                    Ok(function_symbols) => {
+                        for (index, name, flags) in function_symbols {
+                            let global = flags & SYMBOL_BINDING_LOCAL == 0;
+                            let weak = flags & SYMBOL_BINDING_WEAK != 0;
+                            names.insert(index, (name, global, weak));
+                        }
+                    }
+                    Err(e) => warn!("ignoring wasm linking section: {}", e),
+                },
+                _ => {}
+            }
+        }
+
+        let mut symbols = Vec::new();
+        for (i, &(address, size)) in bodies.iter().enumerate() {
+            if let Some(&(name, global, weak)) = names.get(&(imported_functions + i as u64)) {
+                symbols.push(Symbol {
+                    name: Some(name),
+                    kind: SymbolKind::Function,
+                    address,
+                    size,
+                    global,
+                    weak,
+                });
+            }
+        }
+
+        Ok(Module {
+            sections,
+            symbols,
+            custom_sections,
+        })
+    }
+
+    /// The data of the custom section with the given name.
+    pub(crate) fn custom_section(&self, name: &str) -> Option<&'input [u8]> {
+        self.custom_sections
+            .iter()
+            .find(|section| section.0 == name)
+            .map(|section| section.1)
+    }
+}
+
+// Return the number of imported functions.
+fn parse_imports(payload: &[u8], offset: usize) -> Result<u64> {
+    let mut reader = Reader::new(payload, 0);
+    let mut functions = 0;
+    for _ in 0..reader.uleb()? {
+        reader.name()?;
+        reader.name()?;
+        match reader.u8()? {
+            EXTERNAL_FUNCTION => {
+                reader.uleb()?;
+                functions += 1;
+            }
+            EXTERNAL_TABLE => {
+                reader.u8()?;
+                reader.limits()?;
+            }
+            EXTERNAL_MEMORY => reader.limits()?,
+            EXTERNAL_GLOBAL => {
+                reader.u8()?;
+                reader.u8()?;
+            }
+            EXTERNAL_TAG => {
+                reader.u8()?;
+                reader.uleb()?;
+            }
+            kind => {
+                return Err(format!(
+                    "unknown wasm import kind {} at 0x{:x}",
+                    kind,
+                    offset + reader.offset - 1
+                )
+                .into())
+            }
+        }
+    }
+    Ok(functions)
+}
+
+// Return the indices of exported functions.
+fn parse_exports(payload: &[u8]) -> Result<Vec<u64>> {
+    let mut reader = Reader::new(payload, 0);
+    let mut functions = Vec::new();
+    for _ in 0..reader.uleb()? {
+        reader.name()?;
+        let kind = reader.u8()?;
+        let index = reader.uleb()?;
+        if kind == EXTERNAL_FUNCTION {
+            functions.push(index);
+        }
+    }
+    Ok(functions)
+}
+
+// Return the offset and size of each function body, relative to the start of
+// the code section payload.
+fn parse_code(payload: &[u8]) -> Result<Vec<(u64, u64)>> {
+    let mut reader = Reader::new(payload, 0);
+    let mut bodies = Vec::new();
+    for _ in 0..reader.uleb()? {
+        let size = reader.uleb()?;
+        let address = reader.offset as u64;
+        reader.bytes(size as usize)?;
+        bodies.push((address, size));
+    }
+    Ok(bodies)
+}
+
+// Return the index and name of each function in the `name` section.
+fn parse_function_names(payload: &[u8]) -> Result<Vec<(u64, &str)>> {
+    let mut reader = Reader::new(payload, 0);
+    let mut names = Vec::new();
+    while !reader.is_empty() {
+        let id = reader.u8()?;
+        let size = reader.uleb()? as usize;
+        let subsection = reader.bytes(size)?;
+        if id != NAME_FUNCTION {
+            continue;
+        }
+        let mut subsection = Reader::new(subsection, 0);
+        for _ in 0..subsection.uleb()? {
+            let index = subsection.uleb()?;
+            let name = subsection.name()?;
+            names.push((index, name));
+        }
+    }
+    Ok(names)
+}
+
+// Return the index, name and flags of each defined function in the symbol
+// table of the `linking` section.
+fn parse_function_symbols(payload: &[u8]) -> Result<Vec<(u64, &str, u64)>> {
+    let mut reader = Reader::new(payload, 0);
+    let mut symbols = Vec::new();
+    // Version.
+    reader.uleb()?;
+    while !reader.is_empty() {
+        let kind = reader.u8()?;
+        let size = reader.uleb()? as usize;
+        let subsection = reader.bytes(size)?;
+        if kind != LINKING_SYMBOL_TABLE {
+            continue;
+        }
+        let mut subsection = Reader::new(subsection, 0);
+        for _ in 0..subsection.uleb()? {
+            let kind = subsection.u8()?;
+            let flags = subsection.uleb()?;
+            let undefined = flags & SYMBOL_UNDEFINED != 0;
+            match kind {
+                SYMBOL_FUNCTION | SYMBOL_GLOBAL | SYMBOL_TAG | SYMBOL_TABLE => {
+                    let index = subsection.uleb()?;
+                    if undefined && flags & SYMBOL_EXPLICIT_NAME == 0 {
+                        continue;
+                    }
+                    let name = subsection.name()?;
+                    if kind == SYMBOL_FUNCTION && !undefined {
+                        symbols.push((index, name, flags));
+                    }
+                }
+                SYMBOL_DATA => {
+                    subsection.name()?;
+                    if !undefined {
+                        subsection.uleb()?;
+                        subsection.uleb()?;
+                        subsection.uleb()?;
+                    }
+                }
+                SYMBOL_SECTION => {
+                    subsection.uleb()?;
+                }
+                _ => return Err(format!("unknown wasm symbol kind {}", kind).into()),
+            }
+        }
+    }
+    Ok(symbols)
+}
+
+struct Reader<'input> {
+    data: &'input [u8],
+    offset: usize,
+}
+
+impl<'input> Reader<'input> {
+    fn new(data: &'input [u8], offset: usize) -> Self {
+        Reader { data, offset }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'input [u8]> {
+        let end = self.offset.checked_add(len);
+        match end.and_then(|end| self.data.get(self.offset..end)) {
+            Some(bytes) => {
+                self.offset += len;
+                Ok(bytes)
+            }
+            None => Err(format!("wasm data is truncated at 0x{:x}", self.offset).into()),
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn uleb(&mut self) -> Result<u64> {
+        let mut result = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift >= 64 {
+                return Err(format!("invalid wasm LEB128 at 0x{:x}", self.offset).into());
+            }
+            result |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(result);
+            }
+            shift += 7;
+        }
+    }
+
+    fn name(&mut self) -> Result<&'input str> {
+        let len = self.uleb()? as usize;
+        let offset = self.offset;
+        str::from_utf8(self.bytes(len)?)
+            .map_err(|_| format!("invalid wasm name at 0x{:x}", offset).into())
+    }
+
+    fn limits(&mut self) -> Result<()> {
+        let flags = self.u8()?;
+        self.uleb()?;
+        if flags & 1 != 0 {
+            self.uleb()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![id, payload.len() as u8];
+        data.extend(payload);
+        data
+    }
+
+    #[test]
+    fn function_names() {
+        let mut data = MAGIC.to_vec();
+        data.extend(VERSION);
+        // One imported function, so the defined functions start at index 1.
+        data.extend(section(
+            SECTION_IMPORT,
+            &[1, 1, b'm', 1, b'f', EXTERNAL_FUNCTION, 0],
+        ));
+        data.extend(section(SECTION_EXPORT, &[1, 1, b'a', EXTERNAL_FUNCTION, 1]));
+        data.extend(section(SECTION_CODE, &[2, 2, 0, 0x0b, 3, 0, 0x01, 0x0b]));
+        data.extend(section(
+            SECTION_CUSTOM,
+            &[
+                4,
+                b'n',
+                b'a',
+                b'm',
+                b'e',
+                NAME_FUNCTION,
+                7,
+                2,
+                1,
+                1,
+                b'a',
+                2,
+                1,
+                b'b',
+            ],
+        ));
+        let module = Module::parse(&data).unwrap();
+        let names: Vec<_> = module.sections.iter().map(Section::name).collect();
+        assert_eq!(
+            names,
+            [Some("import"), Some("export"), Some("code"), Some("name")]
+        );
+        let symbols: Vec<_> = module
+            .symbols
+            .iter()
+            .map(|symbol| {
+                (
+                    symbol.name(),
+                    symbol.address().begin,
+                    symbol.size(),
+                    symbol.is_global(),
+                )
+            })
+            .collect();
+        assert_eq!(symbols, [(Some("a"), 2, 2, true), (Some("b"), 5, 3, false)]);
+    }
+
+    #[test]
+    fn unknown_sections() {
+        let mut data = MAGIC.to_vec();
+        data.extend(VERSION);
+        data.extend(section(0x7f, &[1, 2, 3]));
+        data.extend(section(SECTION_CODE, &[1, 2, 0, 0x0b]));
+        // An unknown subsection id in the name section.
+        data.extend(section(SECTION_CUSTOM, &[4, b'n', b'a', b'm', b'e', 0x7f]));
+        let module = Module::parse(&data).unwrap();
+        let names: Vec<_> = module.sections.iter().map(Section::name).collect();
+        assert_eq!(names, [Some("code"), Some("name")]);
+        assert!(module.symbols.is_empty());
+    }
+
+    #[test]
+    fn truncated() {
+        let mut data = MAGIC.to_vec();
+        data.extend(VERSION);
+        data.extend(&[SECTION_CODE, 10, 1]);
+        assert!(Module::parse(&data).is_err());
+    }
+}
@@ -3,7 +3,9 @@ use std::cmp;
 use std::sync::Arc;
 use std::usize;
 
-use crate::cfi::Cfi;
+use serde::{Deserialize, Serialize};
+
+use crate::cfi::{self, Cfi};
 use crate::file::FileHash;
 use crate::location::{self, FrameLocation, Piece, Register};
 use crate::namespace::Namespace;
@@ -16,7 +18,7 @@ use crate::{Address, Id, Size};
 /// The debuginfo offset of a function.
 ///
 /// This is unique for all functions in a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct FunctionOffset(usize);
 
 impl FunctionOffset {
@@ -41,8 +43,10 @@ impl FunctionOffset {
         self != Self::none()
     }
 
+    /// The offset within the debuginfo section, or `None` if the function
+    /// was not created from debuginfo.
     #[inline]
-    pub(crate) fn get(self) -> Option<usize> {
+    pub fn get(self) -> Option<usize> {
         if self.is_none() {
             None
         } else {
@@ -58,21 +62,42 @@ impl Default for FunctionOffset {
     }
 }
 
+/// The virtuality of a member function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Virtuality {
+    /// A virtual function.
+    Virtual,
+    /// A pure virtual function.
+    PureVirtual,
+}
+
 /// A function.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Function<'input> {
+    #[serde(skip)]
     pub(crate) id: Id,
     pub(crate) offset: FunctionOffset,
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) linkage_name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) symbol_name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
     pub(crate) address: Address,
     pub(crate) size: Size,
+    pub(crate) ranges: Vec<Range>,
     pub(crate) inline: bool,
     pub(crate) declaration: bool,
+    pub(crate) external: bool,
+    pub(crate) virtuality: Option<Virtuality>,
+    pub(crate) vtable_index: Option<u64>,
+    #[serde(borrow)]
     pub(crate) parameters: Vec<ParameterType<'input>>,
+    pub(crate) variadic: bool,
     pub(crate) return_type: TypeOffset,
 }
 
@@ -109,6 +134,12 @@ impl<'input> Function<'input> {
         self.id.set(id)
     }
 
+    /// The debuginfo offset of this function.
+    #[inline]
+    pub fn offset(&self) -> FunctionOffset {
+        self.offset
+    }
+
     /// The namespace of the function.
     pub fn namespace(&self) -> Option<&Namespace> {
         self.namespace.as_ref().map(|x| &**x)
@@ -159,13 +190,25 @@ impl<'input> Function<'input> {
         if let (Some(address), Some(size)) = (self.address(), self.size()) {
             Some(Range {
                 begin: address,
-                end: address + size,
+                end: address.saturating_add(size),
             })
         } else {
             None
         }
     }
 
+    /// The address ranges of the function.
+    ///
+    /// Non-contiguous functions may have multiple ranges. Otherwise this
+    /// is the same as `range`.
+    pub fn ranges(&self) -> Vec<Range> {
+        if self.ranges.is_empty() {
+            self.range().into_iter().collect()
+        } else {
+            self.ranges.clone()
+        }
+    }
+
     /// Return true if this is an inlined function.
     #[inline]
     pub fn is_inline(&self) -> bool {
@@ -178,12 +221,37 @@ impl<'input> Function<'input> {
         self.declaration
     }
 
+    /// Return true if this function is visible outside of its compilation unit.
+    #[inline]
+    pub fn is_external(&self) -> bool {
+        self.external
+    }
+
+    /// The virtuality of this function, if it is a virtual member function.
+    #[inline]
+    pub fn virtuality(&self) -> Option<Virtuality> {
+        self.virtuality
+    }
+
+    /// The index of this function in the virtual function table of its class.
+    #[inline]
+    pub fn vtable_index(&self) -> Option<u64> {
+        self.vtable_index
+    }
+
     /// The function parameter types.
     #[inline]
     pub fn parameters(&self) -> &[ParameterType<'input>] {
         &self.parameters
     }
 
+    /// Return true if the function accepts a variable number of arguments
+    /// after the parameters.
+    #[inline]
+    pub fn is_variadic(&self) -> bool {
+        self.variadic
+    }
+
     /// The return type.
     ///
     /// Returns `None` if the return type is invalid.
@@ -202,6 +270,20 @@ impl<'input> Function<'input> {
         hash.file.get_cfi(self.address, self.size)
     }
 
+    /// Return true if there is call frame information for the function.
+    ///
+    /// This is found in either `.eh_frame` or `.debug_frame`.
+    pub fn has_cfi(&self, hash: &FileHash<'input>) -> bool {
+        !self.cfi(hash).is_empty()
+    }
+
+    /// The size of the stack frame, as determined from the call frame information.
+    ///
+    /// Returns `None` if the size can't be determined.
+    pub fn frame_size(&self, hash: &FileHash<'input>) -> Option<u64> {
+        cfi::frame_size(&self.cfi(hash))
+    }
+
     /// Compare the identifying information of two functions.
     ///
     /// Functions are equal if they have the same namespace and name.
@@ -238,7 +320,7 @@ impl<'input> FunctionDetails<'input> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub(crate) struct ParameterOffset(usize);
 
 impl ParameterOffset {
@@ -348,6 +430,7 @@ impl<'input> Parameter<'input> {
 pub struct InlinedFunction<'input> {
     pub(crate) abstract_origin: FunctionOffset,
     pub(crate) size: Size,
+    pub(crate) ranges: Vec<Range>,
     pub(crate) parameters: Vec<Parameter<'input>>,
     pub(crate) variables: Vec<LocalVariable<'input>>,
     pub(crate) inlined_functions: Vec<InlinedFunction<'input>>,
@@ -367,6 +450,12 @@ impl<'input> InlinedFunction<'input> {
         self.size.get()
     }
 
+    /// The address ranges of the inlined function.
+    #[inline]
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
+
     /// The source information for call location.
     #[inline]
     pub fn call_source(&self) -> &Source<'input> {
@@ -36,6 +36,7 @@ mod function;
 mod location;
 mod namespace;
 mod range;
+mod raw;
 mod source;
 mod types;
 mod unit;
@@ -47,12 +48,13 @@ pub use crate::function::*;
 pub use crate::location::*;
 pub use crate::namespace::*;
 pub use crate::range::*;
+pub use crate::raw::*;
 pub use crate::source::*;
 pub use crate::types::*;
 pub use crate::unit::*;
 pub use crate::variable::*;
 
-use std::borrow::{Borrow, Cow};
+use std::borrow::Cow;
 use std::error;
 use std::fmt;
 use std::io;
@@ -61,54 +63,81 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// A parsing error.
 #[derive(Debug)]
-pub struct Error(pub Cow<'static, str>);
+pub enum Error {
+    /// The file could not be opened.
+    Open(io::Error),
+    /// The file could not be memory mapped.
+    Mmap(io::Error),
+    /// Any other IO error.
+    Io(io::Error),
+    /// The file is not in a recognized format.
+    UnknownFormat(object::Error),
+    /// The object file is invalid.
+    Object(object::Error),
+    /// The DWARF debuginfo is invalid.
+    Dwarf(gimli::Error),
+    /// Any other error, such as invalid archive or wasm data.
+    Other(Cow<'static, str>),
+}
 
 impl error::Error for Error {
-    fn description(&self) -> &str {
-        self.0.borrow()
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Open(e) | Error::Mmap(e) | Error::Io(e) => Some(e),
+            Error::UnknownFormat(e) | Error::Object(e) => Some(e),
+            Error::Dwarf(e) => Some(e),
+            Error::Other(_) => None,
+        }
     }
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.0)
+        match self {
+            Error::Open(e) => write!(f, "open failed: {}", e),
+            Error::Mmap(e) => write!(f, "memmap failed: {}", e),
+            Error::Io(e) => write!(f, "IO error: {}", e),
+            Error::UnknownFormat(e) | Error::Object(e) => write!(f, "object error: {}", e),
+            Error::Dwarf(e) => write!(f, "DWARF error: {}", e),
+            Error::Other(s) => write!(f, "{}", s),
+        }
     }
 }
 
 impl From<&'static str> for Error {
     fn from(s: &'static str) -> Error {
-        Error(Cow::Borrowed(s))
+        Error::Other(Cow::Borrowed(s))
     }
 }
 
 impl From<String> for Error {
     fn from(s: String) -> Error {
-        Error(Cow::Owned(s))
+        Error::Other(Cow::Owned(s))
     }
 }
 
 impl From<io::Error> for Error {
     fn from(e: io::Error) -> Error {
-        Error(Cow::Owned(format!("IO error: {}", e)))
+        Error::Io(e)
     }
 }
 
 impl From<gimli::Error> for Error {
     fn from(e: gimli::Error) -> Error {
-        Error(Cow::Owned(format!("DWARF error: {}", e)))
+        Error::Dwarf(e)
     }
 }
 
 impl From<object::Error> for Error {
     fn from(e: object::Error) -> Error {
-        Error(Cow::Owned(format!("object error: {}", e)))
+        Error::Object(e)
     }
 }
 
 /*
 impl From<crate_pdb::Error> for Error {
     fn from(e: crate_pdb::Error) -> Error {
-        Error(Cow::Owned(format!("PDB error: {}", e)))
+        Error::Other(Cow::Owned(format!("PDB error: {}", e)))
     }
 }
 */
@@ -119,10 +148,12 @@ pub type Result<T> = result::Result<T, Error>;
 mod address {
     use std::u64;
 
+    use serde::{Deserialize, Serialize};
+
     /// An optional address.
     ///
     /// This is similar to `Option<u64>`, but uses `!0` to encode the `None` case.
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub struct Address(u64);
 
     impl Address {
@@ -175,10 +206,12 @@ pub use crate::address::Address;
 mod size {
     use std::u64;
 
+    use serde::{Deserialize, Serialize};
+
     /// An optional size.
     ///
     /// This is similar to `Option<u64>`, but uses `u64::MAX` to encode the `None` case.
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
     pub struct Size(u64);
 
     impl Size {
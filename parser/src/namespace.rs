@@ -1,8 +1,10 @@
 use std::cmp;
 use std::sync::Arc;
 
+use serde::{Deserialize, Serialize};
+
 /// A namespace kind.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum NamespaceKind {
     /// An explicit namespace.
     Namespace,
@@ -10,12 +12,16 @@ pub enum NamespaceKind {
     Function,
     /// A namespace for items defined within a type.
     Type,
+    /// A namespace for the variables in a Fortran common block.
+    CommonBlock,
 }
 
 /// A nestable namspace.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Namespace<'input> {
+    #[serde(borrow)]
     pub(crate) parent: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) kind: NamespaceKind,
 }
@@ -68,6 +74,18 @@ impl<'input> Namespace<'input> {
         }
     }
 
+    /// The names of the given namespace and its parents, starting with the root namespace.
+    pub(crate) fn path(namespace: &Option<Arc<Namespace<'input>>>) -> Vec<Option<&'input str>> {
+        let mut path = Vec::new();
+        let mut namespace = namespace.as_deref();
+        while let Some(ns) = namespace {
+            path.push(ns.name);
+            namespace = ns.parent.as_deref();
+        }
+        path.reverse();
+        path
+    }
+
     pub(crate) fn is_anon_type(namespace: &Option<Arc<Namespace>>) -> bool {
         match *namespace {
             Some(ref namespace) => {
@@ -88,7 +106,7 @@ impl<'input> Namespace<'input> {
             if offset < namespace.len() {
                 match self.name() {
                     Some(name) => (name == namespace[offset].as_ref(), offset + 1),
-                    None => (false, offset + 1),
+                    None => (namespace[offset].as_ref() == "<anon>", offset + 1),
                 }
             } else {
                 (true, offset)
@@ -101,6 +119,7 @@ impl<'input> Namespace<'input> {
     /// Return true if this namespace is within the given namespace.
     ///
     /// `namespace` is a slice of names, starting with the root namespace name.
+    /// An anonymous namespace is matched by the name `<anon>`.
     pub fn is_within<T: AsRef<str>>(&self, namespace: &[T]) -> bool {
         self._is_within(namespace) == (true, namespace.len())
     }
@@ -141,7 +160,8 @@ impl<'input> Namespace<'input> {
         }
     }
 
-    pub(crate) fn cmp_ns_and_name(
+    /// Compare two entries by their namespace and name.
+    pub fn cmp_ns_and_name(
         ns1: Option<&Namespace>,
         name1: Option<&str>,
         ns2: Option<&Namespace>,
@@ -169,4 +189,17 @@ mod test {
         let ns2 = Namespace::new(&None, Some("b".into()), NamespaceKind::Namespace);
         assert_eq!(Namespace::cmp(&ns1, &ns2), cmp::Ordering::Less);
     }
+
+    #[test]
+    fn is_within() {
+        let ns1 = Namespace::new(&None, Some("a"), NamespaceKind::Namespace);
+        let ns2 = Namespace::new(&Some(ns1.clone()), None, NamespaceKind::Namespace);
+        let ns3 = Namespace::new(&Some(ns2.clone()), Some("b"), NamespaceKind::Namespace);
+        assert!(ns1.is_within(&["a"]));
+        assert!(!ns1.is_within(&["a", "<anon>"]));
+        assert!(ns3.is_within(&["a"]));
+        assert!(ns3.is_within(&["a", "<anon>"]));
+        assert!(ns3.is_within(&["a", "<anon>", "b"]));
+        assert!(!ns3.is_within(&["a", "b"]));
+    }
 }
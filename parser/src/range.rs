@@ -1,7 +1,10 @@
+use std::cmp;
 use std::mem;
 
+use serde::{Deserialize, Serialize};
+
 /// An address range.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Range {
     /// The beginning of the address range (inclusive).
     pub begin: u64,
@@ -30,7 +33,7 @@ impl Range {
 }
 
 /// A list of address ranges.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct RangeList {
     ranges: Vec<Range>,
 }
@@ -44,9 +47,9 @@ impl RangeList {
 
     /// The total size of the ranges in the list.
     pub fn size(&self) -> u64 {
-        let mut size = 0;
+        let mut size: u64 = 0;
         for range in &self.ranges {
-            size += range.size();
+            size = size.saturating_add(range.size());
         }
         size
     }
@@ -70,7 +73,7 @@ impl RangeList {
             // Merge ranges if new range begins in or after previous range.
             // We don't care about merging in opposite order (that'll happen
             // when sorting).
-            if range.begin >= prev.begin && range.begin <= prev.end + padding {
+            if range.begin >= prev.begin && range.begin <= prev.end.saturating_add(padding) {
                 if prev.end < range.end {
                     prev.end = range.end;
                 }
@@ -138,4 +141,63 @@ impl RangeList {
         ranges.sort();
         ranges
     }
+
+    /// Return the parts of the ranges in the list that are also in another list.
+    ///
+    /// Both lists must be sorted.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut ranges = RangeList::default();
+        let mut i = 0;
+        let mut j = 0;
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let begin = cmp::max(a.begin, b.begin);
+            let end = cmp::min(a.end, b.end);
+            if begin < end {
+                ranges.push(Range { begin, end });
+            }
+            // Advance whichever range ends first, since it can't overlap any more.
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        ranges.sort();
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn list(ranges: &[(u64, u64)]) -> RangeList {
+        let mut list = RangeList::default();
+        for &(begin, end) in ranges {
+            list.push(Range { begin, end });
+        }
+        list.sort();
+        list
+    }
+
+    #[test]
+    fn intersect() {
+        let a = list(&[(0x1000, 0x1100), (0x2000, 0x2100), (0x3000, 0x3100)]);
+        let b = list(&[(0x1080, 0x2080), (0x3100, 0x3200)]);
+        assert_eq!(
+            a.intersect(&b).list(),
+            [
+                Range {
+                    begin: 0x1080,
+                    end: 0x1100
+                },
+                Range {
+                    begin: 0x2000,
+                    end: 0x2080
+                },
+            ]
+        );
+        assert_eq!(a.intersect(&b).list(), b.intersect(&a).list());
+        assert!(a.intersect(&RangeList::default()).list().is_empty());
+    }
 }
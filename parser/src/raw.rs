@@ -0,0 +1,67 @@
+/// A debugging information entry, without any interpretation of its attributes.
+///
+/// This is intended for investigating problems with the parsed debuginfo.
+#[derive(Debug, Default, Clone)]
+pub struct RawEntry {
+    pub(crate) offset: usize,
+    pub(crate) tag: String,
+    pub(crate) attributes: Vec<RawAttribute>,
+    pub(crate) children: Vec<RawEntry>,
+}
+
+impl RawEntry {
+    /// The offset of this entry within the debuginfo section.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The tag of this entry, such as `DW_TAG_subprogram`.
+    #[inline]
+    pub fn tag(&self) -> &str {
+        &self.tag
+    }
+
+    /// The attributes of this entry, in the order they are stored.
+    #[inline]
+    pub fn attributes(&self) -> &[RawAttribute] {
+        &self.attributes
+    }
+
+    /// The children of this entry.
+    #[inline]
+    pub fn children(&self) -> &[RawEntry] {
+        &self.children
+    }
+}
+
+/// An attribute of a `RawEntry`.
+#[derive(Debug, Default, Clone)]
+pub struct RawAttribute {
+    pub(crate) name: String,
+    pub(crate) form: String,
+    pub(crate) value: String,
+}
+
+impl RawAttribute {
+    /// The name of this attribute, such as `DW_AT_name`.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The form used to encode this attribute, such as `DW_FORM_strp`.
+    #[inline]
+    pub fn form(&self) -> &str {
+        &self.form
+    }
+
+    /// The value of this attribute.
+    ///
+    /// Strings are resolved and quoted, references are given as section offsets,
+    /// and constants are given by name if known.
+    #[inline]
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
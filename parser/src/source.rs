@@ -1,9 +1,13 @@
+use serde::{Deserialize, Serialize};
+
 use crate::unit::Unit;
 
 /// A source location.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Source<'input> {
+    #[serde(borrow)]
     pub(crate) directory: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) file: Option<&'input str>,
     pub(crate) line: u32,
     pub(crate) column: u32,
@@ -1,41 +1,56 @@
 use std::borrow::Cow;
 use std::cmp;
+use std::collections::hash_map::Entry;
 use std::marker;
+use std::ptr;
 use std::sync::Arc;
 use std::usize;
 
+use fnv::FnvHashSet as HashSet;
+use serde::{Deserialize, Serialize};
+
 use crate::file::FileHash;
-use crate::function::ParameterOffset;
+use crate::function::{Function, FunctionOffset, ParameterOffset};
 use crate::namespace::Namespace;
 use crate::source::Source;
 use crate::{Id, Size};
 
 /// The kind of a type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TypeKind<'input> {
     /// The void type.
     Void,
     /// A base type.
+    #[serde(borrow)]
     Base(BaseType<'input>),
     /// A type alias definition.
+    #[serde(borrow)]
     Def(TypeDef<'input>),
     /// A struct type.
+    #[serde(borrow)]
     Struct(StructType<'input>),
     /// A union type.
+    #[serde(borrow)]
     Union(UnionType<'input>),
     /// An enumeration type.
+    #[serde(borrow)]
     Enumeration(EnumerationType<'input>),
     /// A type for an array of elements.
+    #[serde(borrow)]
     Array(ArrayType<'input>),
     /// A function type.
+    #[serde(borrow)]
     Function(FunctionType<'input>),
     /// An unspecified type.
+    #[serde(borrow)]
     Unspecified(UnspecifiedType<'input>),
     /// The type of a pointer to a member.
     PointerToMember(PointerToMemberType),
     /// A type that is obtained by adding a modifier to another type.
+    #[serde(borrow)]
     Modifier(TypeModifier<'input>),
     /// A subrange of another type.
+    #[serde(borrow)]
     Subrange(SubrangeType<'input>),
 }
 
@@ -60,8 +75,10 @@ impl<'input> TypeKind<'input> {
 
 /// The debuginfo offset of a type.
 ///
-/// This is unique for all types in a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// This is unique for all types in a file. For DWARF, this is the offset
+/// within `.debug_info`, so references to types in other units are
+/// resolved in the same way as references within a unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TypeOffset(usize);
 
 impl TypeOffset {
@@ -88,8 +105,10 @@ impl TypeOffset {
         self != Self::none()
     }
 
+    /// The offset within the debuginfo section, or `None` if the type is unknown
+    /// or `void`.
     #[inline]
-    pub(crate) fn get(self) -> Option<usize> {
+    pub fn get(self) -> Option<usize> {
         if self.is_none() {
             None
         } else {
@@ -106,10 +125,12 @@ impl Default for TypeOffset {
 }
 
 /// A type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Type<'input> {
+    #[serde(skip)]
     pub(crate) id: Id,
     pub(crate) offset: TypeOffset,
+    #[serde(borrow)]
     pub(crate) kind: TypeKind<'input>,
 }
 
@@ -148,6 +169,39 @@ impl<'input> Type<'input> {
         }
     }
 
+    // Add the offsets of the types that this type refers to.
+    pub(crate) fn referenced_types(&self, offsets: &mut Vec<TypeOffset>) {
+        let members = |members: &[Member], offsets: &mut Vec<TypeOffset>| {
+            offsets.extend(members.iter().map(|member| member.ty));
+        };
+        match self.kind {
+            TypeKind::Void | TypeKind::Base(..) | TypeKind::Unspecified(..) => {}
+            TypeKind::Def(ref val) => offsets.push(val.ty),
+            TypeKind::Struct(ref val) => {
+                members(&val.members, offsets);
+                for variant_part in &val.variant_parts {
+                    for variant in &variant_part.variants {
+                        members(&variant.members, offsets);
+                    }
+                }
+                offsets.extend(val.inherits.iter().map(|inherit| inherit.ty));
+            }
+            TypeKind::Union(ref val) => members(&val.members, offsets),
+            TypeKind::Enumeration(ref val) => offsets.push(val.ty),
+            TypeKind::Array(ref val) => offsets.push(val.ty),
+            TypeKind::Function(ref val) => {
+                offsets.extend(val.parameters.iter().map(|parameter| parameter.ty));
+                offsets.push(val.return_type);
+            }
+            TypeKind::PointerToMember(ref val) => {
+                offsets.push(val.ty);
+                offsets.push(val.containing_ty);
+            }
+            TypeKind::Modifier(ref val) => offsets.push(val.ty),
+            TypeKind::Subrange(ref val) => offsets.push(val.ty),
+        }
+    }
+
     /// Return true if the type is the void type.
     #[inline]
     pub fn is_void(&self) -> bool {
@@ -182,7 +236,32 @@ impl<'input> Type<'input> {
     }
 
     /// The size in bytes of an instance of this type.
+    ///
+    /// This resolves typedefs, modifiers and arrays as needed. The result is cached
+    /// in `hash`, and is `None` for incomplete or self-referential types.
     pub fn byte_size(&self, hash: &FileHash) -> Option<u64> {
+        if self.offset.is_none() {
+            return self.byte_size_uncached(hash);
+        }
+        {
+            let mut byte_sizes = hash.byte_sizes.lock().unwrap();
+            match byte_sizes.entry(self.offset) {
+                Entry::Occupied(entry) => return *entry.get(),
+                // Insert a placeholder so that a cycle back to this type gives `None`.
+                Entry::Vacant(entry) => {
+                    entry.insert(None);
+                }
+            }
+        }
+        let byte_size = self.byte_size_uncached(hash);
+        hash.byte_sizes
+            .lock()
+            .unwrap()
+            .insert(self.offset, byte_size);
+        byte_size
+    }
+
+    fn byte_size_uncached(&self, hash: &FileHash) -> Option<u64> {
         match self.kind {
             TypeKind::Void => Some(0),
             TypeKind::Base(ref val) => val.byte_size(),
@@ -199,6 +278,69 @@ impl<'input> Type<'input> {
         }
     }
 
+    /// The alignment in bytes of an instance of this type.
+    ///
+    /// This is the explicit alignment if one is specified, otherwise it is the
+    /// natural alignment, which is derived from the sizes of base types and pointers,
+    /// and the alignments of members and elements.
+    ///
+    /// Returns `None` if the alignment is unknown.
+    pub fn alignment(&self, hash: &FileHash) -> Option<u64> {
+        self.alignment_impl(hash, &mut HashSet::default())
+    }
+
+    fn alignment_impl(&self, hash: &FileHash, visited: &mut HashSet<TypeOffset>) -> Option<u64> {
+        if self.offset.is_some() && !visited.insert(self.offset) {
+            return None;
+        }
+        let alignment = match self.kind {
+            TypeKind::Void | TypeKind::Function(..) | TypeKind::Unspecified(..) => None,
+            TypeKind::Base(ref val) => val.byte_size().map(size_alignment),
+            TypeKind::Def(ref val) => val
+                .alignment()
+                .or_else(|| val.ty(hash)?.alignment_impl(hash, visited)),
+            TypeKind::Struct(ref val) => val
+                .alignment()
+                .or_else(|| val.natural_alignment_impl(hash, visited)),
+            TypeKind::Union(ref val) => val
+                .alignment()
+                .or_else(|| members_alignment(&val.members, hash, visited)),
+            TypeKind::Enumeration(ref val) => val.byte_size(hash).map(size_alignment),
+            TypeKind::Array(ref val) => val.element_type(hash)?.alignment_impl(hash, visited),
+            TypeKind::PointerToMember(ref val) => val.byte_size(hash).map(size_alignment),
+            TypeKind::Modifier(ref val) => {
+                if val.kind == TypeModifierKind::Packed {
+                    Some(1)
+                } else if val.is_pointer_like() {
+                    val.byte_size(hash).map(size_alignment)
+                } else {
+                    val.ty(hash)?.alignment_impl(hash, visited)
+                }
+            }
+            TypeKind::Subrange(ref val) => val.byte_size(hash).map(size_alignment),
+        };
+        visited.remove(&self.offset);
+        alignment
+    }
+
+    /// The namespace of the type, if the type kind has one.
+    pub fn namespace(&self) -> Option<&Namespace<'_>> {
+        match self.kind {
+            TypeKind::Def(ref val) => val.namespace(),
+            TypeKind::Struct(ref val) => val.namespace(),
+            TypeKind::Union(ref val) => val.namespace(),
+            TypeKind::Enumeration(ref val) => val.namespace(),
+            TypeKind::Unspecified(ref val) => val.namespace(),
+            TypeKind::Void
+            | TypeKind::Base(..)
+            | TypeKind::Array(..)
+            | TypeKind::Function(..)
+            | TypeKind::PointerToMember(..)
+            | TypeKind::Modifier(..)
+            | TypeKind::Subrange(..) => None,
+        }
+    }
+
     /// Return true if this is an anonymous type, or defined within an anonymous type.
     pub fn is_anon(&self) -> bool {
         match self.kind {
@@ -274,6 +416,7 @@ impl<'input> Type<'input> {
     ) -> cmp::Ordering {
         use self::TypeKind::*;
         match (&type_a.kind, &type_b.kind) {
+            (&Void, &Void) => cmp::Ordering::Equal,
             (&Base(ref a), &Base(ref b)) => BaseType::cmp_id(a, b),
             (&Def(ref a), &Def(ref b)) => TypeDef::cmp_id(a, b),
             (&Struct(ref a), &Struct(ref b)) => StructType::cmp_id(a, b),
@@ -297,11 +440,39 @@ impl<'input> Type<'input> {
     }
 }
 
+// The natural alignment of a type with the given size, which is the largest
+// power of two that divides the size.
+fn size_alignment(size: u64) -> u64 {
+    if size == 0 {
+        1
+    } else {
+        size & size.wrapping_neg()
+    }
+}
+
+// The largest alignment of the members, which is the natural alignment of a
+// struct or union.
+fn members_alignment(
+    members: &[Member],
+    hash: &FileHash,
+    visited: &mut HashSet<TypeOffset>,
+) -> Option<u64> {
+    let mut alignment = 1;
+    for member in members.iter().filter(|member| !member.is_static()) {
+        let member_alignment = member
+            .alignment()
+            .or_else(|| member.ty(hash)?.alignment_impl(hash, visited))?;
+        alignment = cmp::max(alignment, member_alignment);
+    }
+    Some(alignment)
+}
+
 /// A type that is obtained by adding a modifier to another type.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeModifier<'input> {
     pub(crate) kind: TypeModifierKind,
     pub(crate) ty: TypeOffset,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) byte_size: Size,
     // TODO: hack
@@ -309,7 +480,7 @@ pub struct TypeModifier<'input> {
 }
 
 /// The kind of a type modifier.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum TypeModifierKind {
     /// The resulting type is a pointer to the type being modified.
     Pointer,
@@ -437,7 +608,7 @@ impl<'input> TypeModifier<'input> {
 }
 
 /// The endianity of an object.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Endianity {
     /// Default endianity encoding.
     Default,
@@ -454,7 +625,7 @@ impl Default for Endianity {
 }
 
 /// The encoding of a base type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BaseTypeEncoding {
     /// Unsupported or unspecified encoding.
     Other,
@@ -481,8 +652,9 @@ impl Default for BaseTypeEncoding {
 }
 
 /// A base type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct BaseType<'input> {
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) byte_size: Size,
     pub(crate) encoding: BaseTypeEncoding,
@@ -526,12 +698,16 @@ impl<'input> BaseType<'input> {
 }
 
 /// A type alias definition.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct TypeDef<'input> {
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) ty: TypeOffset,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
+    pub(crate) alignment: Size,
 }
 
 impl<'input> TypeDef<'input> {
@@ -558,11 +734,43 @@ impl<'input> TypeDef<'input> {
         &self.source
     }
 
+    /// The type that the alias ultimately refers to.
+    ///
+    /// This follows chains of type definitions until a type that is not a type definition
+    /// is found.
+    ///
+    /// Returns `None` if a type offset is invalid or the chain is cyclic.
+    pub fn resolved_ty<'a>(&self, hash: &'a FileHash<'input>) -> Option<Cow<'a, Type<'input>>> {
+        let mut visited = HashSet::default();
+        let mut ty = self.ty(hash)?;
+        loop {
+            let next = match ty.kind {
+                TypeKind::Def(ref def) => def.ty,
+                _ => return Some(ty),
+            };
+            if !visited.insert(ty.offset) {
+                return None;
+            }
+            ty = Type::from_offset(hash, next)?;
+        }
+    }
+
     /// The size in bytes of an instance of this type.
     pub fn byte_size(&self, hash: &FileHash) -> Option<u64> {
         self.ty(hash).and_then(|v| v.byte_size(hash))
     }
 
+    /// The alignment in bytes specified by `DW_AT_alignment`, if any.
+    #[inline]
+    pub fn alignment(&self) -> Option<u64> {
+        self.alignment.get()
+    }
+
+    /// The alignment in bytes of the type being aliased.
+    pub fn natural_alignment(&self, hash: &FileHash) -> Option<u64> {
+        self.ty(hash).and_then(|v| v.alignment(hash))
+    }
+
     /// Compare the identifying information of two types.
     ///
     /// Type definitions are considered equal if their names are equal, even if the type being
@@ -576,16 +784,23 @@ impl<'input> TypeDef<'input> {
 }
 
 /// A struct type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct StructType<'input> {
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
     pub(crate) byte_size: Size,
+    pub(crate) alignment: Size,
     pub(crate) declaration: bool,
+    #[serde(borrow)]
     pub(crate) members: Vec<Member<'input>>,
+    #[serde(borrow)]
     pub(crate) variant_parts: Vec<VariantPart<'input>>,
     pub(crate) inherits: Vec<Inherit>,
+    pub(crate) virtual_methods: Vec<FunctionOffset>,
 }
 
 impl<'input> StructType<'input> {
@@ -618,6 +833,38 @@ impl<'input> StructType<'input> {
         self.byte_size.get()
     }
 
+    /// The alignment in bytes specified by `DW_AT_alignment`, if any.
+    #[inline]
+    pub fn alignment(&self) -> Option<u64> {
+        self.alignment.get()
+    }
+
+    /// The alignment in bytes that this type would have without an explicit alignment.
+    ///
+    /// This is the largest alignment of the members and inherited types.
+    pub fn natural_alignment(&self, hash: &FileHash) -> Option<u64> {
+        self.natural_alignment_impl(hash, &mut HashSet::default())
+    }
+
+    fn natural_alignment_impl(
+        &self,
+        hash: &FileHash,
+        visited: &mut HashSet<TypeOffset>,
+    ) -> Option<u64> {
+        let mut alignment = members_alignment(&self.members, hash, visited)?;
+        for inherit in &self.inherits {
+            let inherit_alignment = inherit.ty(hash)?.alignment_impl(hash, visited)?;
+            alignment = cmp::max(alignment, inherit_alignment);
+        }
+        for variant in self.variant_parts.iter().flat_map(|x| x.variants()) {
+            alignment = cmp::max(
+                alignment,
+                members_alignment(&variant.members, hash, visited)?,
+            );
+        }
+        Some(alignment)
+    }
+
     /// Return true if this is a declaration.
     #[inline]
     pub fn is_declaration(&self) -> bool {
@@ -647,18 +894,51 @@ impl<'input> StructType<'input> {
         &self.inherits
     }
 
+    /// The virtual methods declared by this type, in order of their vtable index.
+    ///
+    /// This does not include virtual methods that are only declared by inherited types.
+    pub fn virtual_methods<'a>(&self, hash: &'a FileHash<'input>) -> Vec<&'a Function<'input>> {
+        let mut methods: Vec<_> = self
+            .virtual_methods
+            .iter()
+            .filter_map(|offset| Function::from_offset(hash, *offset))
+            .collect();
+        methods.sort_by_key(|f| f.vtable_index());
+        methods
+    }
+
     /// The layout of members of this type.
     pub fn layout<'me>(&'me self, hash: &FileHash) -> Vec<Layout<'input, 'me>> {
+        // The location of virtual base classes is not known, so don't
+        // treat the space after the last member as padding.
+        let bit_size = if self.inherits.iter().any(Inherit::is_virtual) {
+            None
+        } else {
+            self.bit_size()
+        };
         layout(
             &*self.members,
             &*self.inherits,
             &*self.variant_parts,
             0,
-            self.bit_size(),
+            bit_size,
             hash,
         )
     }
 
+    /// The members of this type in declaration order.
+    ///
+    /// This is the same as `layout`, except that padding is omitted.
+    /// Inherited types are listed before members, and variant parts after.
+    pub fn declaration_layout<'me>(&'me self, hash: &FileHash) -> Vec<Layout<'input, 'me>> {
+        declaration_order(
+            self.layout(hash),
+            &self.members,
+            &self.inherits,
+            &self.variant_parts,
+        )
+    }
+
     /// Compare the identifying information of two types.
     ///
     /// Structs are considered equal if their names are equal.
@@ -671,13 +951,18 @@ impl<'input> StructType<'input> {
 }
 
 /// A union type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UnionType<'input> {
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
     pub(crate) byte_size: Size,
+    pub(crate) alignment: Size,
     pub(crate) declaration: bool,
+    #[serde(borrow)]
     pub(crate) members: Vec<Member<'input>>,
 }
 
@@ -705,6 +990,19 @@ impl<'input> UnionType<'input> {
         self.byte_size.get()
     }
 
+    /// The alignment in bytes specified by `DW_AT_alignment`, if any.
+    #[inline]
+    pub fn alignment(&self) -> Option<u64> {
+        self.alignment.get()
+    }
+
+    /// The alignment in bytes that this type would have without an explicit alignment.
+    ///
+    /// This is the largest alignment of the members.
+    pub fn natural_alignment(&self, hash: &FileHash) -> Option<u64> {
+        members_alignment(&self.members, hash, &mut HashSet::default())
+    }
+
     /// Return true if this is a declaration.
     #[inline]
     pub fn is_declaration(&self) -> bool {
@@ -737,9 +1035,10 @@ impl<'input> UnionType<'input> {
 ///
 /// A variant part is a discriminant member and list of variants that are
 /// selected based on the value of the discriminant member.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct VariantPart<'input> {
     pub(crate) discr: MemberOffset,
+    #[serde(borrow)]
     pub(crate) variants: Vec<Variant<'input>>,
 }
 
@@ -763,6 +1062,27 @@ impl<'input> VariantPart<'input> {
         &self.variants
     }
 
+    /// Return true if the discriminant overlaps the members of any variant.
+    ///
+    /// This occurs when the discriminant is stored in otherwise invalid values
+    /// of a member, such as the null pointer niche used by Rust for `Option<&T>`.
+    pub fn has_niche(&self, discriminant: &Member, hash: &FileHash) -> bool {
+        let start = discriminant.bit_offset();
+        let end = match discriminant.bit_size(hash) {
+            Some(size) => start + size,
+            None => return false,
+        };
+        self.variants.iter().any(|variant| {
+            variant.members.iter().any(|member| {
+                let o = member.bit_offset();
+                match member.bit_size(hash) {
+                    Some(size) => o < end && start < o + size,
+                    None => false,
+                }
+            })
+        })
+    }
+
     /// The smallest offset in bits for a variant of this variant part.
     pub fn bit_offset(&self) -> u64 {
         let mut bit_offset = u64::max_value();
@@ -802,10 +1122,12 @@ impl<'input> VariantPart<'input> {
 ///
 /// A variant consists of a discriminant value that selects the variant,
 /// and a list of members that are valid when the variant is selected.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Variant<'input> {
     pub(crate) discr_value: Option<u64>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) members: Vec<Member<'input>>,
 }
 
@@ -813,6 +1135,9 @@ impl<'input> Variant<'input> {
     /// The discriminant value which selects this variant.
     ///
     /// The sign of this value depends on the type of the discriminant member.
+    ///
+    /// Returns `None` for the default variant, which is selected if no
+    /// other variant matches.
     #[inline]
     pub fn discriminant_value(&self) -> Option<u64> {
         self.discr_value
@@ -877,6 +1202,23 @@ impl<'input> Variant<'input> {
         layout(&*self.members, &[], &[], bit_offset, bit_size, hash)
     }
 
+    /// The members of this variant in declaration order.
+    ///
+    /// This is the same as `layout`, except that padding is omitted.
+    pub fn declaration_layout<'me>(
+        &'me self,
+        bit_offset: u64,
+        bit_size: Option<u64>,
+        hash: &FileHash<'input>,
+    ) -> Vec<Layout<'input, 'me>> {
+        declaration_order(
+            self.layout(bit_offset, bit_size, hash),
+            &self.members,
+            &[],
+            &[],
+        )
+    }
+
     /// Compare the identifying information of two types.
     ///
     /// Variants are considered equal if the discriminant values are equal.
@@ -894,7 +1236,7 @@ impl<'input> Variant<'input> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub(crate) struct MemberOffset(usize);
 
 impl MemberOffset {
@@ -917,15 +1259,42 @@ impl Default for MemberOffset {
     }
 }
 
+/// The accessibility of a member of a struct, union or class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Accessibility {
+    /// `public`
+    Public,
+    /// `protected`
+    Protected,
+    /// `private`
+    Private,
+}
+
+impl Accessibility {
+    /// The keyword for this accessibility.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Accessibility::Public => "public",
+            Accessibility::Protected => "protected",
+            Accessibility::Private => "private",
+        }
+    }
+}
+
 /// A member of a struct or union.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Member<'input> {
     pub(crate) offset: MemberOffset,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) ty: TypeOffset,
     // Defaults to 0, so always present.
     pub(crate) bit_offset: u64,
     pub(crate) bit_size: Size,
+    pub(crate) alignment: Size,
+    pub(crate) accessibility: Option<Accessibility>,
+    pub(crate) artificial: bool,
+    pub(crate) is_static: bool,
 }
 
 impl<'input> Member<'input> {
@@ -961,6 +1330,39 @@ impl<'input> Member<'input> {
         }
     }
 
+    /// The alignment in bytes specified by `DW_AT_alignment`, if any.
+    #[inline]
+    pub fn alignment(&self) -> Option<u64> {
+        self.alignment.get()
+    }
+
+    /// The alignment in bytes of the type of this member.
+    pub fn natural_alignment(&self, hash: &FileHash) -> Option<u64> {
+        self.ty(hash).and_then(|v| v.alignment(hash))
+    }
+
+    /// The accessibility of this member, if specified.
+    #[inline]
+    pub fn accessibility(&self) -> Option<Accessibility> {
+        self.accessibility
+    }
+
+    /// Return true if this member was generated by the compiler.
+    ///
+    /// This includes pointers to virtual function tables.
+    #[inline]
+    pub fn is_artificial(&self) -> bool {
+        self.artificial
+    }
+
+    /// Return true if this is a static data member.
+    ///
+    /// Static data members are not part of the layout of the type.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.is_static
+    }
+
     /// Return true if this member defines an inline type.
     pub fn is_inline(&self, hash: &FileHash) -> bool {
         match self.name() {
@@ -980,11 +1382,13 @@ impl<'input> Member<'input> {
 }
 
 /// An inherited type of a struct or union.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Inherit {
     pub(crate) ty: TypeOffset,
     // Defaults to 0, so always present.
     pub(crate) bit_offset: u64,
+    pub(crate) accessibility: Option<Accessibility>,
+    pub(crate) is_virtual: bool,
 }
 
 impl Inherit {
@@ -1000,11 +1404,27 @@ impl Inherit {
     }
 
     /// The offset in bits of the inherited type within the struct.
+    ///
+    /// This is not meaningful for virtual base classes.
     #[inline]
     pub fn bit_offset(&self) -> u64 {
         self.bit_offset
     }
 
+    /// The accessibility of the inherited type, if specified.
+    #[inline]
+    pub fn accessibility(&self) -> Option<Accessibility> {
+        self.accessibility
+    }
+
+    /// Return true if this is a virtual base class.
+    ///
+    /// Virtual base classes are not part of the layout of the type.
+    #[inline]
+    pub fn is_virtual(&self) -> bool {
+        self.is_virtual
+    }
+
     /// The size in bits of the inherited type.
     pub fn bit_size(&self, hash: &FileHash) -> Option<u64> {
         self.ty(hash).and_then(|v| v.byte_size(hash).map(|v| v * 8))
@@ -1032,8 +1452,8 @@ pub enum LayoutItem<'input, 'item> {
     Padding,
     /// A member.
     Member(&'item Member<'input>),
-    /// A variant part.
-    VariantPart(&'item VariantPart<'input>),
+    /// A variant part, and its discriminant member if known.
+    VariantPart(&'item VariantPart<'input>, Option<&'item Member<'input>>),
     /// An inherited type.
     Inherit(&'item Inherit),
 }
@@ -1046,23 +1466,30 @@ fn layout<'input, 'item>(
     bit_size: Option<u64>,
     hash: &FileHash,
 ) -> Vec<Layout<'input, 'item>> {
+    let all_members = members;
     let mut members: Vec<_> = members
         .iter()
+        .filter(|member| !member.is_static())
         .map(|member| Layout {
             bit_offset: member.bit_offset() - base_bit_offset,
             bit_size: member.bit_size(hash).into(),
             item: LayoutItem::Member(member),
         })
         .collect();
-    members.extend(inherits.iter().map(|inherit| Layout {
-        bit_offset: inherit.bit_offset() - base_bit_offset,
-        bit_size: inherit.bit_size(hash).into(),
-        item: LayoutItem::Inherit(inherit),
-    }));
+    members.extend(
+        inherits
+            .iter()
+            .filter(|x| !x.is_virtual())
+            .map(|inherit| Layout {
+                bit_offset: inherit.bit_offset() - base_bit_offset,
+                bit_size: inherit.bit_size(hash).into(),
+                item: LayoutItem::Inherit(inherit),
+            }),
+    );
     members.extend(variant_parts.iter().map(|variant_part| Layout {
         bit_offset: variant_part.bit_offset() - base_bit_offset,
         bit_size: variant_part.bit_size(hash).into(),
-        item: LayoutItem::VariantPart(variant_part),
+        item: LayoutItem::VariantPart(variant_part, variant_part.discriminant(all_members)),
     }));
     members.sort_by(|a, b| {
         a.bit_offset
@@ -1100,12 +1527,35 @@ fn layout<'input, 'item>(
     layout
 }
 
+// Sort a layout into declaration order, and remove the padding.
+fn declaration_order<'input, 'item>(
+    mut layout: Vec<Layout<'input, 'item>>,
+    members: &[Member<'input>],
+    inherits: &[Inherit],
+    variant_parts: &[VariantPart<'input>],
+) -> Vec<Layout<'input, 'item>> {
+    layout.retain(|x| !matches!(x.item, LayoutItem::Padding));
+    layout.sort_by_key(|x| match x.item {
+        LayoutItem::Padding => (0, None),
+        LayoutItem::Inherit(inherit) => (0, inherits.iter().position(|x| ptr::eq(x, inherit))),
+        LayoutItem::Member(member) => (1, members.iter().position(|x| ptr::eq(x, member))),
+        LayoutItem::VariantPart(variant_part, _) => (
+            2,
+            variant_parts.iter().position(|x| ptr::eq(x, variant_part)),
+        ),
+    });
+    layout
+}
+
 /// An enumeration type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct EnumerationType<'input> {
     pub(crate) offset: TypeOffset,
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
     pub(crate) declaration: bool,
     pub(crate) ty: TypeOffset,
@@ -1113,6 +1563,12 @@ pub struct EnumerationType<'input> {
 }
 
 impl<'input> EnumerationType<'input> {
+    /// The debuginfo offset of the type.
+    #[inline]
+    pub fn offset(&self) -> TypeOffset {
+        self.offset
+    }
+
     /// The namespace of the type.
     pub fn namespace(&self) -> Option<&Namespace> {
         self.namespace.as_ref().map(|x| &**x)
@@ -1189,10 +1645,10 @@ impl<'input> Enumerator<'input> {
 }
 
 /// A type for an array of elements.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ArrayType<'input> {
     pub(crate) ty: TypeOffset,
-    pub(crate) count: Size,
+    pub(crate) counts: Vec<Size>,
     pub(crate) byte_size: Size,
     pub(crate) phantom: marker::PhantomData<&'input str>,
 }
@@ -1207,28 +1663,45 @@ impl<'input> ArrayType<'input> {
     pub fn byte_size(&self, hash: &FileHash) -> Option<u64> {
         if self.byte_size.is_some() {
             self.byte_size.get()
-        } else if let (Some(ty), Some(count)) = (self.element_type(hash), self.count.get()) {
-            ty.byte_size(hash).map(|v| v * count)
+        } else if let (Some(ty), Some(count)) = (self.element_type(hash), self.dimensions_count()) {
+            ty.byte_size(hash).and_then(|v| v.checked_mul(count))
         } else {
             None
         }
     }
 
-    /// The number of elements in the array.
+    /// The total number of elements in the array.
+    ///
+    /// For multi-dimensional arrays, this is the product of the dimensions.
     pub fn count(&self, hash: &FileHash) -> Option<u64> {
-        if self.count.is_some() {
-            self.count.get()
-        } else if let (Some(ty), Some(byte_size)) = (self.element_type(hash), self.byte_size.get())
-        {
-            ty.byte_size(hash).map(|v| byte_size / v)
-        } else {
-            None
+        if let (Some(ty), Some(byte_size)) = (self.element_type(hash), self.byte_size.get()) {
+            if let Some(count) = ty.byte_size(hash).and_then(|v| byte_size.checked_div(v)) {
+                return Some(count);
+            }
         }
+        self.dimensions_count()
+    }
+
+    /// The number of elements in each dimension of the array.
+    ///
+    /// The outermost dimension is first. A dimension is `None` if its length
+    /// is unknown, such as for a flexible array member.
+    pub fn counts(&self) -> impl Iterator<Item = Option<u64>> + '_ {
+        self.counts.iter().map(|count| count.get())
+    }
+
+    fn dimensions_count(&self) -> Option<u64> {
+        if self.counts.is_empty() {
+            return None;
+        }
+        self.counts
+            .iter()
+            .try_fold(1u64, |total, count| total.checked_mul(count.get()?))
     }
 
     /// Compare the identifying information of two types.
     ///
-    /// Array types are considered equal if the element identifiers and counts are equal.
+    /// Array types are considered equal if the element identifiers and dimensions are equal.
     ///
     /// This can be used to sort, and to determine if two types refer to the same definition
     /// (even if there are differences in the definitions).
@@ -1253,13 +1726,14 @@ impl<'input> ArrayType<'input> {
             }
             (None, None) => {}
         }
-        a.count.cmp(&b.count)
+        a.counts.cmp(&b.counts)
     }
 }
 
 /// A subrange of another type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct SubrangeType<'input> {
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) ty: TypeOffset,
     pub(crate) lower: Option<u64>,
@@ -1333,9 +1807,11 @@ impl<'input> SubrangeType<'input> {
 }
 
 /// A function type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct FunctionType<'input> {
+    #[serde(borrow)]
     pub(crate) parameters: Vec<ParameterType<'input>>,
+    pub(crate) variadic: bool,
     pub(crate) return_type: TypeOffset,
     pub(crate) byte_size: Size,
 }
@@ -1347,6 +1823,12 @@ impl<'input> FunctionType<'input> {
         &self.parameters
     }
 
+    /// Return true if the function accepts a variable number of arguments.
+    #[inline]
+    pub fn is_variadic(&self) -> bool {
+        self.variadic
+    }
+
     /// The return type of the function.
     #[inline]
     pub fn return_type<'a>(&self, hash: &'a FileHash<'input>) -> Option<Cow<'a, Type<'input>>> {
@@ -1361,8 +1843,8 @@ impl<'input> FunctionType<'input> {
 
     /// Compare the identifying information of two types.
     ///
-    /// Function types are considered equal if they have the same parameter types and
-    /// return types. Parameter names are ignored.
+    /// Function types are considered equal if they have the same parameter types,
+    /// variadic-ness, and return types. Parameter names are ignored.
     ///
     /// This can be used to sort, and to determine if two types refer to the same definition
     /// (even if there are differences in the definitions).
@@ -1384,6 +1866,11 @@ impl<'input> FunctionType<'input> {
             return ord;
         }
 
+        let ord = a.variadic.cmp(&b.variadic);
+        if ord != cmp::Ordering::Equal {
+            return ord;
+        }
+
         match (a.return_type(hash_a), b.return_type(hash_b)) {
             (Some(ref ty_a), Some(ref ty_b)) => {
                 let ord = Type::cmp_id(hash_a, ty_a, hash_b, ty_b);
@@ -1405,9 +1892,10 @@ impl<'input> FunctionType<'input> {
 }
 
 /// The type of a function parameter.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct ParameterType<'input> {
     pub(crate) offset: ParameterOffset,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
     pub(crate) ty: TypeOffset,
 }
@@ -1445,9 +1933,11 @@ impl<'input> ParameterType<'input> {
 }
 
 /// An unspecified type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct UnspecifiedType<'input> {
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
 }
 
@@ -1475,7 +1965,7 @@ impl<'input> UnspecifiedType<'input> {
 }
 
 /// A type for a pointer to a member of a containing type.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct PointerToMemberType {
     pub(crate) ty: TypeOffset,
     pub(crate) containing_ty: TypeOffset,
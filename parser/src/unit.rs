@@ -2,26 +2,37 @@ use std::borrow::Cow;
 use std::ops::Deref;
 
 use gimli;
+use serde::{Deserialize, Serialize};
 
 use crate::file::FileHash;
 use crate::function::Function;
-use crate::range::RangeList;
+use crate::range::{Range, RangeList};
 use crate::types::Type;
 use crate::variable::Variable;
 use crate::Id;
 
 /// A compilation unit.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Unit<'input> {
+    // Ids are assigned by the user, so they aren't cached.
+    #[serde(skip)]
     pub(crate) id: Id,
+    #[serde(borrow)]
     pub(crate) dir: Option<Cow<'input, str>>,
+    #[serde(borrow)]
     pub(crate) name: Option<Cow<'input, str>>,
+    #[serde(borrow)]
+    pub(crate) producer: Option<Cow<'input, str>>,
+    #[serde(with = "serde_language")]
     pub(crate) language: Option<gimli::DwLang>,
     pub(crate) address_size: Option<u64>,
     pub(crate) low_pc: Option<u64>,
     pub(crate) ranges: RangeList,
+    #[serde(borrow)]
     pub(crate) types: Vec<Type<'input>>,
+    #[serde(borrow)]
     pub(crate) functions: Vec<Function<'input>>,
+    #[serde(borrow)]
     pub(crate) variables: Vec<Variable<'input>>,
 }
 
@@ -48,6 +59,11 @@ impl<'input> Unit<'input> {
         self.name.as_ref().map(Cow::deref)
     }
 
+    /// The name and flags of the compiler that produced the unit.
+    pub fn producer(&self) -> Option<&str> {
+        self.producer.as_deref()
+    }
+
     /// The source language.
     // TODO: avoid gimli dependency.
     #[inline]
@@ -67,7 +83,7 @@ impl<'input> Unit<'input> {
     pub fn ranges(&self, hash: &FileHash) -> RangeList {
         let mut ranges = RangeList::default();
         for function in &self.functions {
-            if let Some(range) = function.range() {
+            for range in function.ranges() {
                 ranges.push(range);
             }
         }
@@ -91,6 +107,27 @@ impl<'input> Unit<'input> {
         ranges.subtract(&self.ranges(hash))
     }
 
+    /// The range from the lowest to the highest address covered by the unit.
+    ///
+    /// This may include gaps that are covered by other units.
+    pub fn address_span(&self) -> Option<Range> {
+        let begin = self.ranges.list().iter().map(|range| range.begin).min()?;
+        let end = self.ranges.list().iter().map(|range| range.end).max()?;
+        Some(Range { begin, end })
+    }
+
+    /// The proportion of the address span that is covered by the unit.
+    ///
+    /// A low density means that the unit's code is scattered, such as due to
+    /// identical code folding or inlining.
+    pub fn address_density(&self) -> Option<f64> {
+        let span = self.address_span()?.size();
+        if span == 0 {
+            return None;
+        }
+        Some(self.ranges.size() as f64 / span as f64)
+    }
+
     /// The total size of all functions and variables.
     pub fn size(&self, hash: &FileHash) -> u64 {
         // TODO: account for padding and overlap between functions and variables?
@@ -101,7 +138,7 @@ impl<'input> Unit<'input> {
     pub fn function_size(&self) -> u64 {
         let mut ranges = RangeList::default();
         for function in &self.functions {
-            if let Some(range) = function.range() {
+            for range in function.ranges() {
                 ranges.push(range);
             }
         }
@@ -139,3 +176,22 @@ impl<'input> Unit<'input> {
         &self.variables
     }
 }
+
+// `gimli::DwLang` doesn't implement the serde traits, so use its raw value.
+mod serde_language {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(language: &Option<gimli::DwLang>, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        language.map(|language| language.0).serialize(s)
+    }
+
+    pub(super) fn deserialize<'de, D>(d: D) -> Result<Option<gimli::DwLang>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<u16>::deserialize(d)?.map(gimli::DwLang))
+    }
+}
@@ -3,6 +3,8 @@ use std::cmp;
 use std::sync::Arc;
 use std::usize;
 
+use serde::{Deserialize, Serialize};
+
 use crate::file::FileHash;
 use crate::location::{self, FrameLocation, Location, Piece, Register};
 use crate::namespace::Namespace;
@@ -14,7 +16,7 @@ use crate::{Address, Id, Size};
 /// The debuginfo offset of a variable.
 ///
 /// This is unique for all variables in a file.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct VariableOffset(usize);
 
 impl VariableOffset {
@@ -28,6 +30,17 @@ impl VariableOffset {
     pub(crate) fn none() -> VariableOffset {
         VariableOffset(usize::MAX)
     }
+
+    /// The offset within the debuginfo section, or `None` if the variable
+    /// was not created from debuginfo.
+    #[inline]
+    pub fn get(self) -> Option<usize> {
+        if self == Self::none() {
+            None
+        } else {
+            Some(self.0)
+        }
+    }
 }
 
 impl Default for VariableOffset {
@@ -38,19 +51,27 @@ impl Default for VariableOffset {
 }
 
 /// A global variable.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Variable<'input> {
+    #[serde(skip)]
     pub(crate) id: Id,
     pub(crate) offset: VariableOffset,
+    #[serde(borrow)]
     pub(crate) namespace: Option<Arc<Namespace<'input>>>,
+    #[serde(borrow)]
     pub(crate) name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) linkage_name: Option<&'input str>,
+    #[serde(borrow)]
     pub(crate) symbol_name: Option<&'input str>,
     pub(crate) ty: TypeOffset,
+    #[serde(borrow)]
     pub(crate) source: Source<'input>,
     pub(crate) address: Address,
     pub(crate) size: Size,
     pub(crate) declaration: bool,
+    #[serde(borrow)]
+    pub(crate) members: Vec<Variable<'input>>,
 }
 
 impl<'input> Variable<'input> {
@@ -66,6 +87,12 @@ impl<'input> Variable<'input> {
         self.id.set(id)
     }
 
+    /// The debuginfo offset of this variable.
+    #[inline]
+    pub fn offset(&self) -> VariableOffset {
+        self.offset
+    }
+
     /// The namespace of the variable.
     pub fn namespace(&self) -> Option<&Namespace> {
         self.namespace.as_ref().map(|x| &**x)
@@ -112,14 +139,32 @@ impl<'input> Variable<'input> {
     }
 
     /// The size in bytes of the variable.
+    ///
+    /// The size of a common block is the extent of its members.
     pub fn byte_size(&self, hash: &FileHash) -> Option<u64> {
         if self.size.is_some() {
             self.size.get()
+        } else if !self.members.is_empty() {
+            let begin = self.address()?;
+            let mut end = begin;
+            for member in &self.members {
+                let member_end = member.address()? + member.byte_size(hash)?;
+                end = cmp::max(end, member_end);
+            }
+            Some(end - begin)
         } else {
             self.ty(hash).and_then(|t| t.byte_size(hash))
         }
     }
 
+    /// The variables in a Fortran common block, in order of address.
+    ///
+    /// This is empty if the variable is not a common block.
+    #[inline]
+    pub fn members(&self) -> &[Variable<'input>] {
+        &self.members
+    }
+
     /// The address range of the variable.
     pub fn range(&self, hash: &FileHash) -> Option<Range> {
         match (self.address(), self.byte_size(hash)) {
@@ -127,7 +172,7 @@ impl<'input> Variable<'input> {
                 if size != 0 {
                     Some(Range {
                         begin,
-                        end: begin + size,
+                        end: begin.saturating_add(size),
                     })
                 } else {
                     None
@@ -0,0 +1,82 @@
+// The test files are shared with the ddbug crate, and are built by its Makefile.
+
+#[test]
+fn parse_error() {
+    let error = |path: &str| ddbug_parser::File::parse(path.into()).err().unwrap();
+    let e = error("../main/tests/bin/missing");
+    assert!(matches!(e, ddbug_parser::Error::Open(_)), "{:?}", e);
+    assert!(e.to_string().starts_with("open failed: "), "{}", e);
+    let e = error("../main/tests/src/diff.c");
+    assert!(
+        matches!(e, ddbug_parser::Error::UnknownFormat(_)),
+        "{:?}",
+        e
+    );
+    assert!(e.to_string().starts_with("object error: "), "{}", e);
+}
+
+#[test]
+fn unresolved_types() {
+    let unresolved = |path: &str| {
+        let file = ddbug_parser::File::parse(path.into()).unwrap();
+        let hash = ddbug_parser::FileHash::new(file.file());
+        let offsets: Vec<_> = file
+            .file()
+            .unresolved_types(&hash)
+            .iter()
+            .filter_map(|offset| offset.get())
+            .collect();
+        (offsets, file.file().warnings().to_vec())
+    };
+
+    for path in &["../main/tests/bin/diff1", "../main/tests/bin/diff2"] {
+        let (offsets, warnings) = unresolved(path);
+        assert_eq!(offsets, Vec::new());
+        assert!(!warnings
+            .iter()
+            .any(|warning| warning.starts_with("unresolved type")));
+    }
+
+    // The typedef refers to a variable instead of a type.
+    let (offsets, warnings) = unresolved("../main/tests/bin/unresolved");
+    assert_eq!(offsets, [0x21]);
+    assert_eq!(warnings, ["unresolved type at offset 0x21"]);
+}
+
+#[test]
+fn dwarf5_forms() {
+    use object::{Object, ObjectSection};
+
+    // Check that the fixture uses the forms that the ddbug tests rely on.
+    let data = std::fs::read("../main/tests/bin/dwarf5").unwrap();
+    let object = object::File::parse(&*data).unwrap();
+    let dwarf = gimli::Dwarf::load(|id| -> Result<_, gimli::Error> {
+        let data = object
+            .section_by_name(id.name())
+            .and_then(|section| section.data().ok())
+            .unwrap_or(&[]);
+        Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+    })
+    .unwrap();
+
+    let mut forms = Vec::new();
+    let mut units = dwarf.units();
+    while let Some(header) = units.next().unwrap() {
+        assert_eq!(header.version(), 5);
+        let unit = dwarf.unit(header).unwrap();
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs().unwrap() {
+            let abbrev = unit.abbreviations.get(entry.code()).unwrap();
+            forms.extend(abbrev.attributes().iter().map(|spec| spec.form()));
+        }
+    }
+    for form in &[
+        gimli::DW_FORM_strx1,
+        gimli::DW_FORM_strx,
+        gimli::DW_FORM_addrx,
+        gimli::DW_FORM_rnglistx,
+        gimli::DW_FORM_loclistx,
+    ] {
+        assert!(forms.contains(form), "missing {}", form);
+    }
+}